@@ -28,7 +28,7 @@ pub enum DragItem {
     FromPlayground(usize),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Item {
     id: usize,
     image: ImageData,
@@ -37,7 +37,7 @@ pub struct Item {
     combinations: HashMap<usize, usize>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum Message {
     Place(Item, (f32, f32)),
     MovePlaygroundItem(usize, (f32, f32)),
@@ -9,7 +9,7 @@ struct Counter {
 }
 
 // The message type that will be used in our `Counter` component.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 enum Message {
     UpPressed,
     DownPressed,
@@ -13,7 +13,7 @@ struct DownloadState {
     pub size: usize,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 enum Message {
     UrlChanged(String),
     DownloadPressed,
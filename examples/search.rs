@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use winit::window::WindowBuilder;
+
+use pixel_widgets::prelude::*;
+
+const FRUITS: &[&str] = &[
+    "apple",
+    "apricot",
+    "banana",
+    "blackberry",
+    "blueberry",
+    "cherry",
+    "grape",
+    "grapefruit",
+    "lemon",
+    "lime",
+    "mango",
+    "orange",
+    "peach",
+    "pear",
+    "pineapple",
+    "plum",
+    "raspberry",
+    "strawberry",
+];
+
+#[derive(Default)]
+struct Search;
+
+#[derive(Default)]
+struct SearchState {
+    query: String,
+    // incremented on every change, so only the last delayed search is performed.
+    generation: usize,
+    results: Vec<&'static str>,
+}
+
+#[derive(Clone, Debug)]
+enum Message {
+    QueryChanged(String),
+    Search(usize),
+}
+
+impl Component for Search {
+    type Message = Message;
+    type State = SearchState;
+    type Output = ();
+
+    fn mount(&self, _: &mut Runtime<Message>) -> SearchState {
+        SearchState {
+            results: FRUITS.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn view<'a>(&'a self, state: &'a SearchState) -> Node<'a, Message> {
+        view! {
+            Column => {
+                Input {
+                    placeholder: "search fruits",
+                    val: state.query.as_str(),
+                    on_change: Message::QueryChanged,
+                },
+                Column => {
+                    [for result in state.results.iter()]
+                    Text { val: *result, key: *result }
+                }
+            }
+        }
+    }
+
+    fn update(
+        &self,
+        message: Message,
+        mut state: DetectMut<SearchState>,
+        runtime: &mut Runtime<Message>,
+        _: &mut Context<()>,
+    ) {
+        match message {
+            Message::QueryChanged(query) => {
+                state.query = query;
+                state.generation += 1;
+                // wait until the user stops typing before searching.
+                runtime.delay(Duration::from_millis(300), Message::Search(state.generation));
+            }
+            Message::Search(generation) if generation == state.generation => {
+                let query = state.query.to_lowercase();
+                state.results = FRUITS.iter().copied().filter(|fruit| fruit.contains(&query)).collect();
+            }
+            Message::Search(_) => (),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    Sandbox::new(
+        Search,
+        StyleBuilder::default(),
+        WindowBuilder::new()
+            .with_title("Search")
+            .with_inner_size(winit::dpi::LogicalSize::new(240, 480)),
+    )
+    .await
+    .unwrap()
+    .run()
+    .await;
+}
@@ -19,6 +19,7 @@ mod login_window;
 use dummy_window::DummyWindow;
 use login_window::LoginWindow;
 
+#[derive(Debug)]
 pub enum Message {
     LoginPressed,
     ShowContext(f32, f32),
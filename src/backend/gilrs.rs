@@ -0,0 +1,69 @@
+use crate::event::{Event, Key, NavigateDirection};
+
+use gilrs::{Axis, Button, EventType};
+
+/// How far a stick has to be pushed before it counts as a navigation input.
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// Converts gilrs gamepad events to pixel-widgets events, if such a conversion is available.
+/// Requires the "gilrs" feature.
+///
+/// The d-pad and the left stick generate [`Event::Navigate`](../../event/enum.Event.html#variant.Navigate) events,
+/// which move the cursor between focusable widgets.
+/// The south button (A on xbox controllers) clicks the selected widget and the east button (B) acts as escape.
+///
+/// A stick only navigates once each time it crosses the threshold, so the state of the sticks is tracked by this
+/// struct. Use one `GamepadNavigation` for all events from gilrs.
+#[derive(Default)]
+pub struct GamepadNavigation {
+    stick: (f32, f32),
+}
+
+impl GamepadNavigation {
+    /// Construct a new `GamepadNavigation`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts a gilrs event to a pixel-widgets event.
+    pub fn convert_event(&mut self, event: &gilrs::Event) -> Option<Event> {
+        match event.event {
+            EventType::ButtonPressed(button, _) => match button {
+                Button::DPadUp => Some(Event::Navigate(NavigateDirection::Up)),
+                Button::DPadDown => Some(Event::Navigate(NavigateDirection::Down)),
+                Button::DPadLeft => Some(Event::Navigate(NavigateDirection::Left)),
+                Button::DPadRight => Some(Event::Navigate(NavigateDirection::Right)),
+                Button::South => Some(Event::Press(Key::LeftMouseButton)),
+                Button::East => Some(Event::Press(Key::Escape)),
+                _ => None,
+            },
+            EventType::ButtonReleased(button, _) => match button {
+                Button::South => Some(Event::Release(Key::LeftMouseButton)),
+                Button::East => Some(Event::Release(Key::Escape)),
+                _ => None,
+            },
+            EventType::AxisChanged(Axis::LeftStickX, value, _) => {
+                let previous = std::mem::replace(&mut self.stick.0, value);
+                if value >= STICK_THRESHOLD && previous < STICK_THRESHOLD {
+                    Some(Event::Navigate(NavigateDirection::Right))
+                } else if value <= -STICK_THRESHOLD && previous > -STICK_THRESHOLD {
+                    Some(Event::Navigate(NavigateDirection::Left))
+                } else {
+                    None
+                }
+            }
+            EventType::AxisChanged(Axis::LeftStickY, value, _) => {
+                // gilrs reports up as positive
+                let previous = std::mem::replace(&mut self.stick.1, value);
+                if value >= STICK_THRESHOLD && previous < STICK_THRESHOLD {
+                    Some(Event::Navigate(NavigateDirection::Up))
+                } else if value <= -STICK_THRESHOLD && previous > -STICK_THRESHOLD {
+                    Some(Event::Navigate(NavigateDirection::Down))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
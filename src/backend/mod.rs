@@ -1,3 +1,8 @@
+/// gilrs gamepad event conversion
+#[cfg(feature = "gilrs")]
+pub mod gilrs;
+/// Software renderer that renders to an image on the cpu
+pub mod software;
 /// wgpu-rs based renderer
 #[cfg(feature = "wgpu")]
 pub mod wgpu;
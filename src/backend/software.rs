@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+
+use image::RgbaImage;
+
+use crate::draw::{Command, DrawList, Update, Vertex};
+use crate::layout::Rectangle;
+use crate::style::Style;
+use crate::Component;
+
+/// Renders [`DrawList`s](../../draw/struct.DrawList.html) to an in-memory `image::RgbaImage` on the cpu.
+///
+/// The triangles of the draw list are rasterized the same way the wgpu backend renders them, but without a gpu, which
+/// makes the renderer useful for headless and deterministic snapshot tests. It favours simplicity over speed, so it's
+/// not meant for rendering interactive uis. Pixels are sampled at their centers without anti-aliasing, and images are
+/// sampled using nearest neighbour filtering. Like the wgpu backend, colors are blended using premultiplied alpha,
+/// so the pixels of the rendered image are premultiplied.
+#[derive(Default)]
+pub struct Renderer {
+    textures: HashMap<usize, RgbaImage>,
+}
+
+/// Wrapper for [`Ui`](../../struct.Ui.html) that renders to an `image::RgbaImage` using a
+/// [`Renderer`](struct.Renderer.html).
+///
+/// ```
+/// # use pixel_widgets::prelude::*;
+/// # use pixel_widgets::draw::{Color, Primitive};
+/// # use pixel_widgets::backend::software::Ui;
+/// # struct Red;
+/// # impl Component for Red {
+/// #     type State = ();
+/// #     type Message = ();
+/// #     type Output = ();
+/// #     fn mount(&self, _: &mut Runtime<()>) {}
+/// #     fn view(&self, _: &()) -> Node<()> {
+/// #         view! {
+/// #             Canvas {
+/// #                 draw: |rect| vec![Primitive::DrawRect(rect, Color::red())],
+/// #                 measure: || (64.0, 32.0),
+/// #             }
+/// #         }
+/// #     }
+/// # }
+/// let mut ui = Ui::new(Red, Rectangle::from_wh(64.0, 32.0), 1.0, StyleBuilder::default()).unwrap();
+/// let image = ui.draw();
+/// assert_eq!(image.dimensions(), (64, 32));
+/// assert_eq!(image.get_pixel(32, 16).0, [255, 0, 0, 255]);
+/// ```
+pub struct Ui<C: 'static + Component> {
+    inner: crate::Ui<C>,
+    renderer: Renderer,
+    target: RgbaImage,
+}
+
+impl Renderer {
+    /// Constructs a new `Renderer` without any textures.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies the texture updates of the `draw_list`, and then draws it's commands on top of the current contents of
+    /// `target`. The draw list is mapped to the whole `target`, which should have the size of the viewport in
+    /// physical pixels.
+    pub fn render(&mut self, draw_list: DrawList, target: &mut RgbaImage) {
+        for update in draw_list.updates {
+            match update {
+                Update::Texture { id, size, data, .. } => {
+                    let texture = if data.is_empty() {
+                        RgbaImage::new(size[0], size[1])
+                    } else {
+                        RgbaImage::from_raw(size[0], size[1], data).expect("texture data doesn't match it's size")
+                    };
+                    self.textures.insert(id, texture);
+                }
+                Update::TextureSubresource { id, offset, size, data } => {
+                    let texture = self.textures.get_mut(&id).expect("non existing texture is updated");
+                    let stride = texture.width() as usize * 4;
+                    let texture: &mut [u8] = texture;
+                    let row_len = size[0] as usize * 4;
+                    for (y, row) in data.chunks_exact(row_len).enumerate() {
+                        let start = (offset[1] as usize + y) * stride + offset[0] as usize * 4;
+                        texture[start..start + row_len].copy_from_slice(row);
+                    }
+                }
+                Update::DropTexture { id } => {
+                    self.textures.remove(&id);
+                }
+            }
+        }
+
+        let full = Rectangle::from_wh(target.width() as f32, target.height() as f32);
+        let mut scissor = full;
+        for command in draw_list.commands {
+            match command {
+                Command::Clip { scissor: rect } => {
+                    scissor = rect.round().intersect(&full).unwrap_or_else(Rectangle::zero);
+                }
+                Command::Colored { offset, count } => {
+                    let vertices = &draw_list.vertices[offset..offset + count];
+                    rasterize(vertices, None, scissor, target);
+                }
+                Command::Textured { texture, offset, count } => {
+                    let vertices = &draw_list.vertices[offset..offset + count];
+                    rasterize(vertices, self.textures.get(&texture), scissor, target);
+                }
+                Command::Nop => (),
+            }
+        }
+    }
+}
+
+impl<C: Component> Ui<C> {
+    /// Constructs a new `Ui`, that renders to an image with the size of the `viewport`.
+    /// Returns an error if the style fails to load.
+    pub fn new<S, E>(root_component: C, viewport: Rectangle, hidpi_scale: f32, style: S) -> anyhow::Result<Self>
+    where
+        S: TryInto<Style, Error = E>,
+        anyhow::Error: From<E>,
+    {
+        Ok(Self {
+            inner: crate::Ui::new(root_component, viewport, hidpi_scale, style)?,
+            renderer: Renderer::new(),
+            target: target_for(viewport),
+        })
+    }
+
+    /// Resizes the viewport and the rendered image.
+    /// This forces the view to be rerendered, but only if the size actually changed.
+    pub fn resize(&mut self, viewport: Rectangle, hidpi_scale: f32) {
+        self.inner.resize(viewport, hidpi_scale);
+        let target = target_for(viewport);
+        if target.dimensions() != self.target.dimensions() {
+            self.target = target;
+        }
+    }
+
+    /// Draws the ui to the image and returns it. The image is cleared to transparent black before drawing.
+    /// If the ui doesn't need to be redrawn the image of the previous draw is returned.
+    /// If the ui is animating but doesn't need to be redrawn, it's animated first.
+    pub fn draw(&mut self) -> &RgbaImage {
+        if !self.inner.needs_redraw() {
+            self.inner.animate();
+        }
+
+        if self.inner.needs_redraw() {
+            let draw_list = self.inner.draw();
+            self.target.fill(0);
+            self.renderer.render(draw_list, &mut self.target);
+        }
+
+        &self.target
+    }
+
+    /// Returns the image of the last [`draw`](#method.draw).
+    pub fn image(&self) -> &RgbaImage {
+        &self.target
+    }
+}
+
+impl<C: Component> Deref for Ui<C> {
+    type Target = crate::Ui<C>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<C: Component> DerefMut for Ui<C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+fn target_for(viewport: Rectangle) -> RgbaImage {
+    RgbaImage::new(viewport.width().round() as u32, viewport.height().round() as u32)
+}
+
+/// Rasterizes a triangle list within the scissor rect, using the same shading as `wgpu.wgsl`.
+fn rasterize(vertices: &[Vertex], texture: Option<&RgbaImage>, scissor: Rectangle, target: &mut RgbaImage) {
+    let (width, height) = (target.width() as f32, target.height() as f32);
+    let position = |vertex: &Vertex| {
+        let [x, y] = vertex.pos;
+        [(x + 1.0) * 0.5 * width, (y + 1.0) * 0.5 * height]
+    };
+
+    for triangle in vertices.chunks_exact(3) {
+        let mut vtx = [triangle[0], triangle[1], triangle[2]];
+        let mut pos = [position(&vtx[0]), position(&vtx[1]), position(&vtx[2])];
+        let mut area = edge(pos[0], pos[1], pos[2]);
+        if area == 0.0 {
+            continue;
+        } else if area < 0.0 {
+            vtx.swap(1, 2);
+            pos.swap(1, 2);
+            area = -area;
+        }
+
+        let min = |i: usize| pos.iter().map(|p| p[i]).fold(f32::INFINITY, f32::min).floor();
+        let max = |i: usize| pos.iter().map(|p| p[i]).fold(f32::NEG_INFINITY, f32::max).ceil();
+        let left = min(0).max(scissor.left) as u32;
+        let top = min(1).max(scissor.top) as u32;
+        let right = max(0).min(scissor.right).max(0.0) as u32;
+        let bottom = max(1).min(scissor.bottom).max(0.0) as u32;
+
+        let extras = vtx[0].extras;
+        for y in top..bottom {
+            for x in left..right {
+                let center = [x as f32 + 0.5, y as f32 + 0.5];
+                let weights = [
+                    edge(pos[1], pos[2], center),
+                    edge(pos[2], pos[0], center),
+                    edge(pos[0], pos[1], center),
+                ];
+                let inside = weights.iter().enumerate().all(|(i, &w)| {
+                    // pixels exactly on an edge belong to only one of the triangles that share the edge
+                    w > 0.0 || (w == 0.0 && owns_edge(pos[(i + 1) % 3], pos[(i + 2) % 3]))
+                });
+                if !inside {
+                    continue;
+                }
+
+                let weights = weights.map(|w| w / area);
+                let uv = interpolate(weights, vtx.map(|v| v.uv));
+                let color = interpolate(weights, vtx.map(|v| v.color));
+                let src = shade(extras, uv, color, texture);
+                let src_alpha = src[3];
+
+                // premultiplied alpha blending
+                let pixel = target.get_pixel_mut(x, y);
+                for (dst, src) in pixel.0.iter_mut().zip(src) {
+                    let blended = src + (*dst as f32 / 255.0) * (1.0 - src_alpha);
+                    *dst = (blended.clamp(0.0, 1.0) * 255.0).round() as u8;
+                }
+            }
+        }
+    }
+}
+
+/// Returns twice the signed area of the triangle `a`, `b`, `c`.
+fn edge(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Decides which of the two triangles that share an edge owns the pixels exactly on the edge.
+/// The triangles traverse the shared edge in opposite directions, so exactly one of them owns it.
+fn owns_edge(a: [f32; 2], b: [f32; 2]) -> bool {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    dy > 0.0 || (dy == 0.0 && dx > 0.0)
+}
+
+fn interpolate<const N: usize>(weights: [f32; 3], values: [[f32; N]; 3]) -> [f32; N] {
+    let mut result = [0.0; N];
+    for (weight, value) in weights.iter().zip(values.iter()) {
+        for (result, value) in result.iter_mut().zip(value.iter()) {
+            *result += weight * value;
+        }
+    }
+    result
+}
+
+fn mix(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn premultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [r * a, g * a, b * a, a]
+}
+
+/// Returns the premultiplied color of a pixel, like the fragment shader of `wgpu.wgsl`.
+fn shade(extras: [f32; 4], uv: [f32; 2], color: [f32; 4], texture: Option<&RgbaImage>) -> [f32; 4] {
+    match extras[0] as u32 {
+        1 => premultiply(color),
+        2 => {
+            let [r, g, b, _] = sample_linear(texture, uv);
+            let sd = r.min(g).max(r.max(g).min(b));
+            let (range, border) = (extras[1], extras[2]);
+            let outside = (range * (sd - 0.5 + border) + 0.5).clamp(0.0, 1.0);
+            let inside = (range * (sd - 0.5) + 0.5).clamp(0.0, 1.0);
+            let color = premultiply(color);
+            if border > 0.0 {
+                mix([0.0, 0.0, 0.0, outside], color, inside)
+            } else {
+                color.map(|c| c * inside)
+            }
+        }
+        3 => sample_linear(texture, uv).map(|c| c * color[3]),
+        _ => {
+            let texel = sample_nearest(texture, uv);
+            let color = premultiply(color);
+            [0, 1, 2, 3].map(|i| color[i] * texel[i])
+        }
+    }
+}
+
+fn texel(texture: &RgbaImage, x: i64, y: i64) -> [f32; 4] {
+    let x = x.clamp(0, texture.width() as i64 - 1) as u32;
+    let y = y.clamp(0, texture.height() as i64 - 1) as u32;
+    texture.get_pixel(x, y).0.map(|c| c as f32 / 255.0)
+}
+
+fn sample_nearest(texture: Option<&RgbaImage>, [u, v]: [f32; 2]) -> [f32; 4] {
+    match texture {
+        Some(texture) if texture.width() > 0 && texture.height() > 0 => texel(
+            texture,
+            (u * texture.width() as f32).floor() as i64,
+            (v * texture.height() as f32).floor() as i64,
+        ),
+        _ => [1.0; 4],
+    }
+}
+
+fn sample_linear(texture: Option<&RgbaImage>, [u, v]: [f32; 2]) -> [f32; 4] {
+    match texture {
+        Some(texture) if texture.width() > 0 && texture.height() > 0 => {
+            let x = u * texture.width() as f32 - 0.5;
+            let y = v * texture.height() as f32 - 0.5;
+            let (x0, y0) = (x.floor(), y.floor());
+            let (tx, ty) = (x - x0, y - y0);
+            let (x0, y0) = (x0 as i64, y0 as i64);
+            let top = mix(texel(texture, x0, y0), texel(texture, x0 + 1, y0), tx);
+            let bottom = mix(texel(texture, x0, y0 + 1), texel(texture, x0 + 1, y0 + 1), tx);
+            mix(top, bottom, ty)
+        }
+        _ => [1.0; 4],
+    }
+}
@@ -22,6 +22,7 @@ pub struct Ui<C: 'static + Component> {
     linear_sampler: Sampler,
     textures: HashMap<usize, TextureEntry>,
     vertex_buffer: Option<Buffer>,
+    vertex_count: usize,
     draw_commands: Vec<DrawCommand>,
 }
 
@@ -132,7 +133,7 @@ impl<C: Component> Ui<C> {
                 entry_point: "fs_main",
                 targets: &[wgpu::ColorTargetState {
                     format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 }],
             }),
@@ -177,6 +178,7 @@ impl<C: Component> Ui<C> {
             linear_sampler,
             textures: HashMap::new(),
             vertex_buffer: None,
+            vertex_count: 0,
             draw_commands: Vec::new(),
         }
     }
@@ -184,15 +186,20 @@ impl<C: Component> Ui<C> {
     /// Draw the ui to a `RenderPass`.
     /// The `device` must be the same as the one passed to [`new()`](#method.new).
     /// The `render_pass` render target must be compatible with the `texture_format` passed to [`new`](#method.new).
+    /// If the ui is animating but doesn't need to be redrawn, it's animated first.
     pub fn draw<'a>(&'a mut self, device: &Device, queue: &Queue, render_pass: &mut RenderPass<'a>) {
+        if !self.inner.needs_redraw() {
+            self.inner.animate();
+        }
+
         if self.inner.needs_redraw() {
             let DrawList {
                 updates,
                 vertices,
                 commands,
+                changed,
             } = self.inner.draw();
 
-            self.vertex_buffer.take();
             self.draw_commands = commands;
 
             if !updates.is_empty() {
@@ -298,6 +305,9 @@ impl<C: Component> Ui<C> {
                                         },
                                     );
                                 }
+                                Update::DropTexture { id } => {
+                                    self.textures.remove(&id);
+                                }
                             }
                             cmd
                         })
@@ -305,13 +315,25 @@ impl<C: Component> Ui<C> {
                 ));
             }
 
-            if !vertices.is_empty() {
-                self.vertex_buffer
-                    .replace(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                        label: None,
-                        contents: vertices.as_bytes(),
-                        usage: wgpu::BufferUsages::VERTEX,
-                    }));
+            match (changed, self.vertex_buffer.as_ref()) {
+                (Some(changed), Some(vertex_buffer)) if self.vertex_count == vertices.len() => {
+                    let stride = std::mem::size_of::<Vertex>();
+                    for range in changed {
+                        queue.write_buffer(vertex_buffer, (range.start * stride) as u64, vertices[range].as_bytes());
+                    }
+                }
+                _ => {
+                    self.vertex_buffer.take();
+                    self.vertex_count = vertices.len();
+                    if !vertices.is_empty() {
+                        self.vertex_buffer
+                            .replace(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                                label: None,
+                                contents: vertices.as_bytes(),
+                                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                            }));
+                    }
+                }
             }
         }
 
@@ -324,12 +346,9 @@ impl<C: Component> Ui<C> {
         for command in self.draw_commands.iter() {
             match command {
                 DrawCommand::Clip { scissor } => {
-                    render_pass.set_scissor_rect(
-                        scissor.left as u32,
-                        scissor.top as u32,
-                        scissor.width() as u32,
-                        scissor.height() as u32,
-                    );
+                    let (left, top) = (scissor.left.round(), scissor.top.round());
+                    let (right, bottom) = (scissor.right.round(), scissor.bottom.round());
+                    render_pass.set_scissor_rect(left as u32, top as u32, (right - left) as u32, (bottom - top) as u32);
                 }
                 &DrawCommand::Colored { offset, count } => {
                     render_pass.draw(offset as u32..(offset + count) as u32, 0..1);
@@ -27,13 +27,19 @@ var u_sampler: sampler;
 [[group(0), binding(2)]]
 var u_linear_sampler: sampler;
 
+// Colors are blended using premultiplied alpha. Vertex colors use straight alpha, so they are premultiplied before
+// they're combined. Textures are premultiplied on upload, except for the distance fields of fonts.
+fn premultiply(color: vec4<f32>) -> vec4<f32> {
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
+
 [[stage(fragment)]]
 fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
     var tex: vec4<f32> = textureSample(u_color_texture, u_sampler, in.uv);
     var font: vec4<f32> = textureSample(u_color_texture, u_linear_sampler, in.uv);
     switch (u32(in.mode.x)) {
         case 1: {
-            return in.color;
+            return premultiply(in.color);
         }
         case 2: {
             let border = in.mode.z;
@@ -46,15 +52,18 @@ fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
             if (border > 0.0) {
                 return mix(
                     vec4<f32>(0.0, 0.0, 0.0, outside_distance), 
-                    vec4<f32>(in.color), 
+                    premultiply(in.color), 
                     inside_distance
                 );
             } else {
-                return vec4<f32>(in.color.rgb, in.color.a * inside_distance);
+                return premultiply(in.color) * inside_distance;
             }
         }
+        case 3: {
+            return font * in.color.a;
+        }
         default: {
-            return in.color * tex;
+            return premultiply(in.color) * tex;
         }
     }
 }
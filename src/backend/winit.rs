@@ -1,10 +1,23 @@
-use crate::event::{Event, Key, Modifiers};
+use crate::event::{CursorIcon, Event, Key, Modifiers};
 
 use winit::event::{DeviceEvent, ElementState, KeyboardInput, MouseButton, MouseScrollDelta, WindowEvent};
 
+/// The number of logical pixels that one line of a mouse wheel scrolls.
+const LINE_HEIGHT: f32 = 20.0;
+
 /// Converts a winit event to a pixel-widgets event, if such a conversion is available.
+/// Mouse wheel lines are converted for a hidpi scale of `1.0`, use
+/// [`convert_event_with_scale`](fn.convert_event_with_scale.html) to scroll the same distance on hidpi screens.
 /// Requires the "winit" feature.
 pub fn convert_event<T>(ev: winit::event::Event<T>) -> Option<Event> {
+    convert_event_with_scale(ev, 1.0)
+}
+
+/// Converts a winit event to a pixel-widgets event, if such a conversion is available.
+/// Lines of a mouse wheel scroll a fixed distance in logical pixels, so they are converted to physical pixels using
+/// the `hidpi_scale` of the window, which should be the same scale that the `Ui` uses.
+/// Requires the "winit" feature.
+pub fn convert_event_with_scale<T>(ev: winit::event::Event<T>, hidpi_scale: f32) -> Option<Event> {
     match ev {
         winit::event::Event::WindowEvent { event, .. } => match event {
             WindowEvent::Resized(size) => Some(Event::Resize(size.width as f32, size.height as f32)),
@@ -47,8 +60,11 @@ pub fn convert_event<T>(ev: winit::event::Event<T>) -> Option<Event> {
             },
             WindowEvent::CursorMoved { position, .. } => Some(Event::Cursor(position.x as f32, position.y as f32)),
             WindowEvent::MouseWheel { delta, .. } => match delta {
-                MouseScrollDelta::LineDelta(dx, dy) => Some(Event::Scroll(dx * 20.0, dy * 20.0)),
-
+                // mouse wheels scroll by lines, which are converted to physical pixels using a fixed line height.
+                MouseScrollDelta::LineDelta(dx, dy) => {
+                    let line = LINE_HEIGHT * hidpi_scale;
+                    Some(Event::Scroll(dx * line, dy * line))
+                }
                 MouseScrollDelta::PixelDelta(delta) => Some(Event::Scroll(delta.x as f32, delta.y as f32)),
             },
             _ => None,
@@ -61,6 +77,25 @@ pub fn convert_event<T>(ev: winit::event::Event<T>) -> Option<Event> {
     }
 }
 
+/// Converts a pixel-widgets cursor icon to a winit cursor icon, so it can be applied using
+/// `Window::set_cursor_icon`.
+/// Requires the "winit" feature.
+pub fn convert_cursor_icon(icon: CursorIcon) -> winit::window::CursorIcon {
+    match icon {
+        CursorIcon::Default => winit::window::CursorIcon::Default,
+        CursorIcon::Pointer => winit::window::CursorIcon::Hand,
+        CursorIcon::Text => winit::window::CursorIcon::Text,
+        CursorIcon::Grab => winit::window::CursorIcon::Grab,
+        CursorIcon::Grabbing => winit::window::CursorIcon::Grabbing,
+        CursorIcon::Move => winit::window::CursorIcon::Move,
+        CursorIcon::ResizeHorizontal => winit::window::CursorIcon::EwResize,
+        CursorIcon::ResizeVertical => winit::window::CursorIcon::NsResize,
+        CursorIcon::ResizeDiagonal => winit::window::CursorIcon::NwseResize,
+        CursorIcon::ResizeAntiDiagonal => winit::window::CursorIcon::NeswResize,
+        CursorIcon::NotAllowed => winit::window::CursorIcon::NotAllowed,
+    }
+}
+
 fn convert_mods(x: winit::event::ModifiersState) -> Modifiers {
     Modifiers {
         ctrl: x.ctrl(),
@@ -133,6 +168,7 @@ fn convert_key(key: winit::event::VirtualKeyCode) -> Option<Key> {
         Vk::Space => Some(Key::Space),
         Vk::Return => Some(Key::Enter),
         Vk::Back => Some(Key::Backspace),
+        Vk::Delete => Some(Key::Delete),
         Vk::Escape => Some(Key::Escape),
         Vk::Left => Some(Key::Left),
         Vk::Right => Some(Key::Right),
@@ -140,6 +176,8 @@ fn convert_key(key: winit::event::VirtualKeyCode) -> Option<Key> {
         Vk::Down => Some(Key::Down),
         Vk::Home => Some(Key::Home),
         Vk::End => Some(Key::End),
+        Vk::PageUp => Some(Key::PageUp),
+        Vk::PageDown => Some(Key::PageDown),
         Vk::Minus => Some(Key::Minus),
         Vk::Plus => Some(Key::Plus),
         Vk::LBracket => Some(Key::BracketOpen),
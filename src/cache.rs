@@ -1,4 +1,8 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 
 use anyhow::*;
@@ -6,17 +10,35 @@ use image::{Pixel, RgbaImage};
 use smallvec::SmallVec;
 
 use crate::atlas::*;
+use crate::color_glyphs::add_color_glyphs;
 use crate::draw::*;
 use crate::layout::Rectangle;
-use crate::text::Font;
+use crate::text::{Font, FontData};
+
+/// Texture ids are unique between all caches, so textures of different styles can be used side by side.
+static NEXT_TEXTURE_ID: AtomicUsize = AtomicUsize::new(0);
+
+fn next_texture_id() -> usize {
+    NEXT_TEXTURE_ID.fetch_add(1, Ordering::Relaxed)
+}
 
 /// A cache for textures and text
+///
+/// Images are packed into a texture atlas when they fit, or get a texture of their own when they don't.
+/// Loading the same image again returns the already loaded image, so the cache keeps images around after they are no
+/// longer referenced, until the memory budget is exceeded. At that point the least recently loaded images that are not
+/// referenced by any [`ImageData`](../draw/struct.ImageData.html) are evicted. Images that are still referenced are
+/// never evicted, even if that means the budget is exceeded. Fonts hold on to their atlas image, so glyphs stay loaded
+/// for as long as the font is in use.
 pub struct Cache {
-    #[allow(unused)]
     size: usize,
-    textures: Vec<TextureSlot>,
+    budget: usize,
+    textures: Vec<(usize, TextureSlot)>,
+    entries: HashMap<u64, Entry>,
+    texels: usize,
     updates: Vec<Update>,
     image_id_counter: usize,
+    clock: usize,
 }
 
 enum TextureSlot {
@@ -24,46 +46,108 @@ enum TextureSlot {
     Big,
 }
 
+/// An image loaded in the cache. The cache holds one reference to the image id, so it is unreferenced when that is the
+/// only remaining reference.
+struct Entry {
+    image_id: Arc<usize>,
+    texture: usize,
+    texcoords: Rectangle,
+    offset: [u32; 2],
+    size: [u32; 2],
+    texels: usize,
+    last_used: usize,
+    /// Whether the entry was returned for more than one load, so it's pixels can't be replaced.
+    shared: bool,
+}
+
 impl Cache {
     /// Create a new cache. Size is the width and height of textures in pixels.
-    /// Offset is the offset to apply to texture ids
+    /// The memory budget is the number of texels in one texture, see [`with_budget`](#method.with_budget).
     pub fn new(size: usize) -> Cache {
+        Self::with_budget(size, size * size)
+    }
+
+    /// Create a new cache with a memory budget. Size is the width and height of textures in pixels, the budget is the
+    /// total number of texels of loaded images that the cache aims to stay within.
+    pub fn with_budget(size: usize, budget: usize) -> Cache {
         let atlas = Atlas::new(size);
+        let glyph_id = next_texture_id();
+        let atlas_id = next_texture_id();
 
         Cache {
             size,
+            budget,
             textures: vec![
                 // glyph cache
-                TextureSlot::Big,
+                (glyph_id, TextureSlot::Big),
                 // atlas for textures
-                TextureSlot::Atlas(atlas),
+                (atlas_id, TextureSlot::Atlas(atlas)),
             ],
+            entries: HashMap::new(),
+            texels: 0,
             updates: vec![
                 // glyph cache
                 Update::Texture {
-                    id: 0,
+                    id: glyph_id,
                     size: [size as u32, size as u32],
                     data: Vec::new(),
                     atlas: true,
                 },
                 // atlas for textures
                 Update::Texture {
-                    id: 1,
+                    id: atlas_id,
                     size: [size as u32, size as u32],
                     data: Vec::new(),
                     atlas: true,
                 },
             ],
             image_id_counter: 1,
+            clock: 0,
         }
     }
 
+    /// The total number of texels of the images in the cache, including images that are no longer referenced.
+    pub fn texels(&self) -> usize {
+        self.texels
+    }
+
+    /// The memory budget of the cache, in texels.
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
     /// Take updates for the texture system from the cache
     pub fn take_updates(&mut self) -> Vec<Update> {
         mem::take(&mut self.updates)
     }
 
-    pub(crate) fn load_image(&mut self, image: RgbaImage) -> ImageData {
+    pub(crate) fn has_updates(&self) -> bool {
+        !self.updates.is_empty()
+    }
+
+    pub(crate) fn load_image(&mut self, mut image: RgbaImage) -> ImageData {
+        premultiply(&mut image);
+        self.load_premultiplied(image)
+    }
+
+    /// Loads an image in a slot of it's own, even if an image with the same pixels is already loaded, so it's pixels can
+    /// be replaced using [`update_image`](#method.update_image) without affecting other images.
+    pub(crate) fn load_unique_image(&mut self, mut image: RgbaImage) -> ImageData {
+        premultiply(&mut image);
+        let mut hasher = DefaultHasher::new();
+        "unique".hash(&mut hasher);
+        self.image_id_counter.hash(&mut hasher);
+        let size = Rectangle::from_wh(image.width() as f32, image.height() as f32);
+        let (texture, cache_id, texcoords) = self.insert_keyed(hasher.finish(), image);
+        ImageData {
+            texture,
+            _cache_id: cache_id,
+            texcoords,
+            size,
+        }
+    }
+
+    fn load_premultiplied(&mut self, image: RgbaImage) -> ImageData {
         let size = Rectangle {
             left: 0.0,
             top: 0.0,
@@ -79,6 +163,47 @@ impl Cache {
         }
     }
 
+    /// Replaces the pixels of a loaded image, keeping it's place in the texture.
+    pub(crate) fn update_image(&mut self, image: &ImageData, mut data: Vec<u8>) -> Result<()> {
+        let key = self
+            .entries
+            .iter()
+            .find(|(_, entry)| Arc::ptr_eq(&entry.image_id, &image._cache_id))
+            .map(|(&key, _)| key)
+            .ok_or_else(|| anyhow!("the image was not loaded by this cache"))?;
+
+        let [width, height] = self.entries[&key].size;
+        let len = width as usize * height as usize * 4;
+        ensure!(
+            data.len() == len,
+            "expected {len} bytes of rgba data for a {width}x{height} image"
+        );
+
+        ensure!(
+            !self.entries[&key].shared,
+            "the image shares it's pixels with another loaded image, use `load_image_from_rgba` for images that are updated"
+        );
+
+        let mut entry = self.entries.remove(&key).unwrap();
+
+        premultiply(&mut data);
+        self.updates.push(Update::TextureSubresource {
+            id: entry.texture,
+            offset: entry.offset,
+            size: entry.size,
+            data,
+        });
+
+        // the pixels no longer match the key, so the entry gets a key of it's own that loaded images can't match.
+        let mut hasher = DefaultHasher::new();
+        "updated".hash(&mut hasher);
+        entry.image_id.hash(&mut hasher);
+        self.clock += 1;
+        entry.last_used = self.clock;
+        self.entries.insert(hasher.finish(), entry);
+        Ok(())
+    }
+
     pub(crate) fn load_patch(&mut self, mut image: RgbaImage) -> Patch {
         let mut h_stretch = SmallVec::<[(f32, f32); 2]>::new();
         let mut h_content = (1.0, 0.0);
@@ -135,7 +260,8 @@ impl Cache {
         // strip stretch and content bars from the image
         let patch_width = image.width() - 2;
         let patch_height = image.height() - 2;
-        let image = image::imageops::crop(&mut image, 1, 1, patch_width, patch_height).to_image();
+        let mut image = image::imageops::crop(&mut image, 1, 1, patch_width, patch_height).to_image();
+        premultiply(&mut image);
         let size = Rectangle {
             left: 0.0,
             top: 0.0,
@@ -158,38 +284,97 @@ impl Cache {
         }
     }
 
-    pub(crate) fn load_font<D: AsRef<[u8]>>(&mut self, data: D, image: RgbaImage) -> Result<crate::text::Font> {
-        let atlas = self.load_image(image);
-        Font::from_data(data, atlas)
+    pub(crate) fn load_font<D: AsRef<[u8]>>(
+        &mut self,
+        data: D,
+        mut image: RgbaImage,
+        source: Option<&[u8]>,
+    ) -> Result<crate::text::Font> {
+        let mut data: FontData = serde_json::from_slice(data.as_ref())?;
+        if let Some(source) = source {
+            image = add_color_glyphs(&mut data, image, source)?;
+        }
+        // the alpha of a distance field is a distance instead of a coverage, so only color glyphs are premultiplied.
+        for bounds in data.color_glyph_bounds() {
+            let (left, top) = (bounds.left.floor().max(0.0) as u32, bounds.top.floor().max(0.0) as u32);
+            let right = (bounds.right.ceil() as u32).min(image.width());
+            let bottom = (bounds.bottom.ceil() as u32).min(image.height());
+            for y in top..bottom {
+                for x in left..right {
+                    premultiply(image.get_pixel_mut(x, y).channels_mut());
+                }
+            }
+        }
+        let atlas = self.load_premultiplied(image);
+        Ok(Font::from_font_data(data, atlas))
     }
 
-    fn insert_image(&mut self, image: image::RgbaImage) -> (usize, Arc<usize>, Rectangle) {
-        for slot in self.textures.iter_mut() {
-            if let TextureSlot::Atlas(atlas) = slot {
-                atlas.remove_expired();
+    /// Loads an image that is identified by `key` instead of it's pixels, so `load` is only called if the image is
+    /// not in the cache yet.
+    #[cfg(feature = "svg")]
+    pub(crate) fn load_keyed(&mut self, key: u64, load: impl FnOnce() -> Result<RgbaImage>) -> Result<ImageData> {
+        self.clock += 1;
+        let (texture, cache_id, texcoords, [width, height]) = match self.entries.get_mut(&key) {
+            Some(entry) => {
+                entry.last_used = self.clock;
+                (entry.texture, entry.image_id.clone(), entry.texcoords, entry.size)
+            }
+            None => {
+                let mut image = load()?;
+                premultiply(&mut image);
+                let size = [image.width(), image.height()];
+                let (texture, cache_id, texcoords) = self.insert_keyed(key, image);
+                (texture, cache_id, texcoords, size)
             }
+        };
+        Ok(ImageData {
+            texture,
+            _cache_id: cache_id,
+            texcoords,
+            size: Rectangle::from_wh(width as f32, height as f32),
+        })
+    }
+
+    fn insert_image(&mut self, image: image::RgbaImage) -> (usize, Arc<usize>, Rectangle) {
+        let mut hasher = DefaultHasher::new();
+        image.dimensions().hash(&mut hasher);
+        image.as_raw().hash(&mut hasher);
+        self.insert_keyed(hasher.finish(), image)
+    }
+
+    fn insert_keyed(&mut self, key: u64, image: image::RgbaImage) -> (usize, Arc<usize>, Rectangle) {
+        self.clock += 1;
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.last_used = self.clock;
+            entry.shared = true;
+            return (entry.texture, entry.image_id.clone(), entry.texcoords);
         }
 
+        let texels = (image.width() * image.height()) as usize;
+        while self.texels + texels > self.budget && self.evict(|_| true) {}
+
         let image_id = Arc::new(self.image_id_counter);
         self.image_id_counter += 1;
 
-        let slot = self
-            .textures
-            .iter_mut()
-            .enumerate()
-            .filter_map(|(index, slot)| match slot {
-                TextureSlot::Atlas(atlas) => {
-                    let image_size = image.width().max(image.height()) as usize;
-                    atlas
-                        .insert(Arc::downgrade(&image_id), image_size)
-                        .ok()
-                        .map(|area| (area, atlas.size() as f32, index))
-                }
-                TextureSlot::Big => None,
-            })
-            .next();
+        let image_size = image.width().max(image.height()) as usize;
+        let slot = loop {
+            self.remove_expired();
+            if let Some(slot) = self.insert_atlas(&image_id, image_size) {
+                break Some(slot);
+            }
+            // make room in the atlas before resorting to a texture of it's own
+            let atlases = self
+                .textures
+                .iter()
+                .filter(|(_, slot)| matches!(slot, TextureSlot::Atlas(_)))
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            if image_size > self.size || !self.evict(|entry| atlases.contains(&entry.texture)) {
+                break None;
+            }
+        };
 
-        if let Some((mut area, atlas_size, tex_id)) = slot {
+        let (tex_id, texcoords, offset) = if let Some((mut area, atlas_size, tex_id)) = slot {
             area.right = area.left + image.width() as usize;
             area.bottom = area.top + image.height() as usize;
 
@@ -203,16 +388,16 @@ impl Cache {
 
             (
                 tex_id,
-                image_id,
                 Rectangle {
                     left: area.left as f32 / atlas_size,
                     top: area.top as f32 / atlas_size,
                     right: area.right as f32 / atlas_size,
                     bottom: area.bottom as f32 / atlas_size,
                 },
+                [area.left as u32, area.top as u32],
             )
         } else {
-            let tex_id = self.textures.len();
+            let tex_id = next_texture_id();
 
             let update = Update::Texture {
                 id: tex_id,
@@ -222,9 +407,84 @@ impl Cache {
             };
 
             self.updates.push(update);
-            self.textures.push(TextureSlot::Big);
+            self.textures.push((tex_id, TextureSlot::Big));
+
+            (tex_id, Rectangle::from_wh(1.0, 1.0), [0, 0])
+        };
+
+        self.entries.insert(
+            key,
+            Entry {
+                image_id: image_id.clone(),
+                texture: tex_id,
+                texcoords,
+                offset,
+                size: [image.width(), image.height()],
+                texels,
+                last_used: self.clock,
+                shared: false,
+            },
+        );
+        self.texels += texels;
+
+        (tex_id, image_id, texcoords)
+    }
+
+    fn insert_atlas(&mut self, image_id: &Arc<usize>, image_size: usize) -> Option<(Area, f32, usize)> {
+        self.textures
+            .iter_mut()
+            .filter_map(|(id, slot)| match slot {
+                TextureSlot::Atlas(atlas) => atlas
+                    .insert(Arc::downgrade(image_id), image_size)
+                    .ok()
+                    .map(|area| (area, atlas.size() as f32, *id)),
+                TextureSlot::Big => None,
+            })
+            .next()
+    }
+
+    fn remove_expired(&mut self) {
+        for (_, slot) in self.textures.iter_mut() {
+            if let TextureSlot::Atlas(atlas) = slot {
+                atlas.remove_expired();
+            }
+        }
+    }
+
+    /// Evicts the least recently used entry that is no longer referenced and matches `filter`.
+    /// Textures of evicted images that don't live in an atlas are released.
+    /// Returns `false` if there was no entry to evict.
+    fn evict(&mut self, filter: impl Fn(&Entry) -> bool) -> bool {
+        let key = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| Arc::strong_count(&entry.image_id) == 1 && filter(entry))
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(&key, _)| key);
+
+        if let Some(entry) = key.and_then(|key| self.entries.remove(&key)) {
+            self.texels -= entry.texels;
+            let big = self
+                .textures
+                .iter()
+                .position(|(id, slot)| *id == entry.texture && matches!(slot, TextureSlot::Big));
+            if let Some(index) = big {
+                self.textures.remove(index);
+                self.updates.push(Update::DropTexture { id: entry.texture });
+            }
+            true
+        } else {
+            false
+        }
+    }
+}
 
-            (tex_id, image_id, Rectangle::from_wh(1.0, 1.0))
+/// Premultiplies rgba texel data, since the textures of a cache are blended using premultiplied alpha.
+fn premultiply(data: &mut [u8]) {
+    for texel in data.chunks_exact_mut(4) {
+        let alpha = texel[3] as u32;
+        for channel in &mut texel[..3] {
+            *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
         }
     }
 }
@@ -0,0 +1,290 @@
+use anyhow::*;
+use image::{ImageFormat, Rgba, RgbaImage};
+use ttf_parser::colr::Painter;
+use ttf_parser::{Face, GlyphId, OutlineBuilder, RasterImageFormat, RgbaColor};
+
+use crate::layout::Rectangle;
+use crate::text::FontData;
+
+/// Transparent pixels around each color glyph in the atlas, so neighbouring glyphs don't bleed into each other.
+const PADDING: u32 = 1;
+/// The number of line segments that bezier curves are flattened to.
+const CURVE_STEPS: usize = 8;
+/// The number of scanlines that are sampled per row of pixels when rasterizing outlines.
+const SCANLINES: usize = 4;
+
+/// Replaces the glyphs of `data` that have color data in the `source` font file by full color bitmaps.
+/// Bitmaps are taken from the `CBDT` or `sbix` tables, and layered outlines from the `COLR` table are rasterized at the
+/// size of the atlas. The bitmaps are packed into rows that are appended to the bottom of the atlas.
+/// Only glyphs that are already in the font data are replaced, so the character set is chosen when the atlas is
+/// generated.
+pub(crate) fn add_color_glyphs(data: &mut FontData, atlas: RgbaImage, source: &[u8]) -> Result<RgbaImage> {
+    let face = Face::parse(source, 0)?;
+    let size = data.atlas.size;
+
+    let mut unicodes = data.glyphs.keys().copied().collect::<Vec<_>>();
+    unicodes.sort_unstable();
+    let bitmaps = unicodes
+        .into_iter()
+        .filter_map(|unicode| {
+            let id = face.glyph_index(char::from_u32(unicode)?)?;
+            let (bitmap, plane) = raster_glyph(&face, id, size).or_else(|| layered_glyph(&face, id, size))?;
+            Some((unicode, bitmap, plane))
+        })
+        .collect::<Vec<_>>();
+    if bitmaps.is_empty() {
+        return Ok(atlas);
+    }
+
+    // the atlas is extended at the bottom, so bounds that are measured from the bottom have to be flipped.
+    if data.atlas.y_origin == "bottom" {
+        let height = data.atlas.height as f32;
+        for glyph in data.glyphs.values_mut().chain(std::iter::once(&mut data.default_glyph)) {
+            glyph.atlas_bounds = glyph.atlas_bounds.map(|b| Rectangle {
+                top: height - b.top,
+                bottom: height - b.bottom,
+                ..b
+            });
+        }
+        data.atlas.y_origin = "top".into();
+    }
+
+    // pack the bitmaps in shelves below the existing atlas
+    let width = bitmaps
+        .iter()
+        .map(|(_, bitmap, _)| bitmap.width() + PADDING * 2)
+        .fold(atlas.width(), u32::max);
+    let mut positions = Vec::with_capacity(bitmaps.len());
+    let (mut x, mut y, mut shelf) = (0, atlas.height(), 0);
+    for (_, bitmap, _) in bitmaps.iter() {
+        if x + bitmap.width() + PADDING * 2 > width {
+            x = 0;
+            y += shelf;
+            shelf = 0;
+        }
+        positions.push((x + PADDING, y + PADDING));
+        x += bitmap.width() + PADDING * 2;
+        shelf = shelf.max(bitmap.height() + PADDING * 2);
+    }
+
+    let mut result = RgbaImage::new(width, y + shelf);
+    image::imageops::replace(&mut result, &atlas, 0, 0);
+    for ((unicode, bitmap, plane), (x, y)) in bitmaps.into_iter().zip(positions) {
+        image::imageops::replace(&mut result, &bitmap, x, y);
+        let glyph = data.glyphs.get_mut(&unicode).unwrap();
+        glyph.atlas_bounds = Some(Rectangle {
+            left: x as f32,
+            top: y as f32,
+            right: (x + bitmap.width()) as f32,
+            bottom: (y + bitmap.height()) as f32,
+        });
+        glyph.plane_bounds = Some(plane);
+        glyph.color = true;
+    }
+    data.atlas.width = result.width();
+    data.atlas.height = result.height();
+
+    Ok(result)
+}
+
+/// Decodes the png bitmap of a glyph from the `CBDT` or `sbix` table, using the strike that's closest to `size`.
+/// Returns the bitmap and it's plane bounds in em units.
+fn raster_glyph(face: &Face, id: GlyphId, size: f32) -> Option<(RgbaImage, Rectangle)> {
+    let raster = face.glyph_raster_image(id, size.round() as u16)?;
+    if raster.format != RasterImageFormat::PNG {
+        return None;
+    }
+    let bitmap = image::load_from_memory_with_format(raster.data, ImageFormat::Png)
+        .ok()?
+        .into_rgba8();
+    let ppem = raster.pixels_per_em as f32;
+    let (x, y) = (raster.x as f32, raster.y as f32);
+    let plane = Rectangle {
+        left: x / ppem,
+        top: (y + bitmap.height() as f32) / ppem,
+        right: (x + bitmap.width() as f32) / ppem,
+        bottom: y / ppem,
+    };
+    Some((bitmap, plane))
+}
+
+/// Rasterizes the layers of a glyph in the `COLR` table at `size` pixels per em, using the first palette.
+/// Returns the bitmap and it's plane bounds in em units.
+fn layered_glyph(face: &Face, id: GlyphId, size: f32) -> Option<(RgbaImage, Rectangle)> {
+    if !face.is_color_glyph(id) {
+        return None;
+    }
+    let mut painter = LayerPainter {
+        face,
+        outline: Outline::default(),
+        layers: Vec::new(),
+    };
+    face.paint_color_glyph(id, 0, &mut painter)?;
+
+    let scale = size / face.units_per_em() as f32;
+    let points = painter
+        .layers
+        .iter()
+        .flat_map(|(outline, _)| outline.edges.iter().flatten());
+    let (left, top, right, bottom) = points.fold(
+        (f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY, f32::INFINITY),
+        |(left, top, right, bottom), &(x, y)| (left.min(x), top.max(y), right.max(x), bottom.min(y)),
+    );
+    let (left, top) = ((left * scale).floor(), (top * scale).ceil());
+    let (right, bottom) = ((right * scale).ceil(), (bottom * scale).floor());
+    if !(right > left && top > bottom) {
+        return None;
+    }
+
+    let (width, height) = ((right - left) as u32, (top - bottom) as u32);
+    let mut bitmap = RgbaImage::new(width, height);
+    for (outline, color) in painter.layers {
+        let edges = outline
+            .edges
+            .iter()
+            .map(|edge| edge.map(|(x, y)| (x * scale - left, top - y * scale)))
+            .collect::<Vec<_>>();
+        for (pixel, coverage) in bitmap.pixels_mut().zip(coverage(&edges, width, height)) {
+            blend(pixel, color, coverage.min(1.0));
+        }
+    }
+
+    let plane = Rectangle {
+        left: left / size,
+        top: top / size,
+        right: right / size,
+        bottom: bottom / size,
+    };
+    Some((bitmap, plane))
+}
+
+/// Blends `color` with the given coverage over a straight alpha pixel.
+fn blend(pixel: &mut Rgba<u8>, color: RgbaColor, coverage: f32) {
+    let src = color.alpha as f32 / 255.0 * coverage;
+    if src <= 0.0 {
+        return;
+    }
+    let dst = pixel[3] as f32 / 255.0 * (1.0 - src);
+    let alpha = src + dst;
+    let channel = |s: u8, d: u8| ((s as f32 * src + d as f32 * dst) / alpha).round() as u8;
+    *pixel = Rgba([
+        channel(color.red, pixel[0]),
+        channel(color.green, pixel[1]),
+        channel(color.blue, pixel[2]),
+        (alpha * 255.0).round() as u8,
+    ]);
+}
+
+/// Returns the coverage of each pixel by the nonzero winding fill of `edges`.
+fn coverage(edges: &[[(f32, f32); 2]], width: u32, height: u32) -> Vec<f32> {
+    let mut coverage = vec![0.0f32; (width * height) as usize];
+    let mut crossings = Vec::new();
+    for row in 0..height as usize {
+        for scanline in 0..SCANLINES {
+            let y = row as f32 + (scanline as f32 + 0.5) / SCANLINES as f32;
+            crossings.clear();
+            crossings.extend(edges.iter().filter_map(|&[(x0, y0), (x1, y1)]| {
+                if (y0 <= y) != (y1 <= y) {
+                    let x = x0 + (y - y0) * (x1 - x0) / (y1 - y0);
+                    Some((x, if y1 > y0 { 1 } else { -1 }))
+                } else {
+                    None
+                }
+            }));
+            crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+            let mut winding = 0;
+            for span in crossings.windows(2) {
+                winding += span[0].1;
+                if winding == 0 {
+                    continue;
+                }
+                let (start, end) = (span[0].0.max(0.0), span[1].0.min(width as f32));
+                if start >= end {
+                    continue;
+                }
+                for x in start.floor() as usize..end.ceil() as usize {
+                    let overlap = end.min(x as f32 + 1.0) - start.max(x as f32);
+                    coverage[row * width as usize + x] += overlap / SCANLINES as f32;
+                }
+            }
+        }
+    }
+    coverage
+}
+
+/// Collects the outlines of the layers of a `COLR` glyph, together with their color.
+struct LayerPainter<'a, 'b> {
+    face: &'a Face<'b>,
+    outline: Outline,
+    layers: Vec<(Outline, RgbaColor)>,
+}
+
+impl<'a, 'b> Painter for LayerPainter<'a, 'b> {
+    fn outline(&mut self, glyph_id: GlyphId) {
+        self.outline = Outline::default();
+        self.face.outline_glyph(glyph_id, &mut self.outline);
+        self.outline.close();
+    }
+
+    fn paint_foreground(&mut self) {
+        // color glyphs are not multiplied by the text color, so the foreground is drawn black.
+        self.paint_color(RgbaColor::new(0, 0, 0, 255));
+    }
+
+    fn paint_color(&mut self, color: RgbaColor) {
+        self.layers.push((std::mem::take(&mut self.outline), color));
+    }
+}
+
+/// A glyph outline in font units, flattened to line segments.
+#[derive(Default)]
+struct Outline {
+    edges: Vec<[(f32, f32); 2]>,
+    start: (f32, f32),
+    last: (f32, f32),
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.start = (x, y);
+        self.last = (x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.edges.push([self.last, (x, y)]);
+        self.last = (x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last;
+        for step in 1..=CURVE_STEPS {
+            let t = step as f32 / CURVE_STEPS as f32;
+            let u = 1.0 - t;
+            self.line_to(
+                u * u * x0 + 2.0 * u * t * x1 + t * t * x,
+                u * u * y0 + 2.0 * u * t * y1 + t * t * y,
+            );
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last;
+        for step in 1..=CURVE_STEPS {
+            let t = step as f32 / CURVE_STEPS as f32;
+            let u = 1.0 - t;
+            self.line_to(
+                u * u * u * x0 + 3.0 * u * u * t * x1 + 3.0 * u * t * t * x2 + t * t * t * x,
+                u * u * u * y0 + 3.0 * u * u * t * y1 + 3.0 * u * t * t * y2 + t * t * t * y,
+            );
+        }
+    }
+
+    fn close(&mut self) {
+        if self.last != self.start {
+            self.edges.push([self.last, self.start]);
+            self.last = self.start;
+        }
+    }
+}
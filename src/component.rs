@@ -1,6 +1,8 @@
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 use crate::node::component_node::{DetectMut, Runtime};
 use crate::node::Node;
@@ -17,10 +19,10 @@ pub trait Component: Sized {
     type State: 'static + Any + Send + Sync;
 
     /// The message type this `Component` will receive from it's view.
-    type Message: 'static;
+    type Message: 'static + Traceable;
 
     /// The message type this `Component` submits to its parent.
-    type Output: 'static;
+    type Output: 'static + Traceable;
 
     /// Create a new `State` for the `Component`.
     /// This will be called only once when the `Component` is first created.
@@ -32,6 +34,18 @@ pub trait Component: Sized {
     ///  the view will be invalidated and the runtime will have to call this function again.
     fn view<'a>(&'a self, state: &'a Self::State) -> Node<'a, Self::Message>;
 
+    /// Generate the view for the `Component`, with access to the [`Environment`](struct.Environment.html) provided
+    ///  by ancestor components using [`Runtime::provide`](../prelude/struct.Runtime.html#method.provide).
+    /// The default implementation ignores the environment and calls [`view`](#tymethod.view).
+    /// When the environment changes, the view will be generated again.
+    fn view_with_environment<'a>(
+        &'a self,
+        state: &'a Self::State,
+        _environment: &Environment,
+    ) -> Node<'a, Self::Message> {
+        self.view(state)
+    }
+
     /// Update the `Component` state in response to the `message`.
     /// Asynchronous operations can be submitted to the `context`,
     ///  which will result in more `update` calls in the future.
@@ -77,6 +91,16 @@ pub trait Component: Sized {
         node
     }
 
+    /// Converts the component into a `Node` and sets multiple style classes to it.
+    fn classes<'a>(self, classes: &[&str]) -> Node<'a, Self::Output>
+    where
+        Self: 'a + Sized,
+    {
+        let mut node = self.into_node();
+        node.set_classes(classes);
+        node
+    }
+
     /// Converts the component into a `Node` and sets a custom key to it.
     fn key<'a, K>(self, key: K) -> Node<'a, Self::Output>
     where
@@ -91,10 +115,43 @@ pub trait Component: Sized {
     }
 }
 
+/// Bound on the [`Message`](trait.Component.html#associatedtype.Message) and
+/// [`Output`](trait.Component.html#associatedtype.Output) types of components.
+///
+/// With the `tracing` feature enabled, every message dispatched to
+/// [`Component::update`](trait.Component.html#tymethod.update), every output submitted to the parent and every redraw
+/// requested by a widget or an update is logged at the `debug` level. Updates are logged within an `update` span that
+/// has the type name of the component in it's `component` field. Events are logged within a `handle_event` span, and
+/// within an `event` span for every widget they pass through, that has the name of the widget in it's `widget` field.
+/// Messages passed to [`Ui::update`](../struct.Ui.html#method.update) are logged within a `ui_update` span.
+/// Messages and outputs then have to implement `Debug`, so this trait is implemented for all `Debug` types.
+/// Logs of a single component can be selected with a filter like
+/// `pixel_widgets[update{component=my_crate::Counter}]=debug`.
+///
+/// Without the `tracing` feature this trait is implemented for all types, and nothing is logged.
+pub use traceable::Traceable;
+
+// the trait is documented where it's re-exported, so both variants share their documentation.
+#[cfg(feature = "tracing")]
+#[allow(missing_docs)]
+mod traceable {
+    pub trait Traceable: std::fmt::Debug {}
+
+    impl<T: std::fmt::Debug> Traceable for T {}
+}
+
+#[cfg(not(feature = "tracing"))]
+#[allow(missing_docs)]
+mod traceable {
+    pub trait Traceable {}
+
+    impl<T> Traceable for T {}
+}
+
 /// Utility methods for components
 pub trait ComponentExt: Component + Sized {
     /// Maps the output message of a component to a different type.
-    fn map_message<T: 'static, F: Fn(Self::Output) -> T>(self, map_fn: F) -> MapComponent<Self, T, F> {
+    fn map_message<T: 'static + Traceable, F: Fn(Self::Output) -> T>(self, map_fn: F) -> MapComponent<Self, T, F> {
         MapComponent {
             component: self,
             map_fn,
@@ -105,12 +162,12 @@ pub trait ComponentExt: Component + Sized {
 impl<T: Component + Sized> ComponentExt for T {}
 
 /// The value returned by [`ComponentExt::map_message`](trait.ComponentExt.html#method.map_message).
-pub struct MapComponent<C: Component, T: 'static, F: Fn(C::Output) -> T> {
+pub struct MapComponent<C: Component, T: 'static + Traceable, F: Fn(C::Output) -> T> {
     component: C,
     map_fn: F,
 }
 
-impl<C: Component, T: 'static, F: Fn(C::Output) -> T> Component for MapComponent<C, T, F> {
+impl<C: Component, T: 'static + Traceable, F: Fn(C::Output) -> T> Component for MapComponent<C, T, F> {
     type State = C::State;
 
     type Message = C::Message;
@@ -125,6 +182,14 @@ impl<C: Component, T: 'static, F: Fn(C::Output) -> T> Component for MapComponent
         self.component.view(state)
     }
 
+    fn view_with_environment<'a>(
+        &'a self,
+        state: &'a Self::State,
+        environment: &Environment,
+    ) -> Node<'a, Self::Message> {
+        self.component.view_with_environment(state, environment)
+    }
+
     fn update(
         &self,
         message: C::Message,
@@ -137,6 +202,9 @@ impl<C: Component, T: 'static, F: Fn(C::Output) -> T> Component for MapComponent
         if sub_context.redraw_requested() {
             context.redraw();
         }
+        if sub_context.animation_requested() {
+            context.animate();
+        }
         context.extend(sub_context.into_iter().map(|m| (self.map_fn)(m)));
     }
 
@@ -148,3 +216,59 @@ impl<C: Component, T: 'static, F: Fn(C::Output) -> T> Component for MapComponent
         C::style_scope()
     }
 }
+
+/// A set of values, keyed by their type, that components provide to all of their descendants.
+/// Values are provided using [`Runtime::provide`](../prelude/struct.Runtime.html#method.provide) and
+///  can be read in [`Component::view_with_environment`](trait.Component.html#method.view_with_environment).
+/// This is useful for shared values like themes, locales or services that would otherwise
+///  have to be passed through the props of every component.
+#[derive(Clone, Default)]
+pub struct Environment {
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Environment {
+    /// Construct a new, empty `Environment`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value of type `T`, if one was provided.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+    }
+
+    /// Returns `true` if a value of type `T` was provided.
+    pub fn contains<T: Any>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Sets the value of type `T`, replacing the previous value of that type.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+        self.values.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Sets the value of type `T` and returns the resulting `Environment`.
+    pub fn with<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Returns `true` if the environment has no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns a new environment with the values of `other` overriding the values of `self`.
+    pub(crate) fn merge(&self, other: &Environment) -> Environment {
+        if other.is_empty() {
+            self.clone()
+        } else {
+            let mut values = self.values.clone();
+            values.extend(other.values.iter().map(|(k, v)| (*k, v.clone())));
+            Environment { values }
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use crate::layout::{Rectangle, Size};
+use crate::layout::{LayoutSnapshot, Rectangle, Size};
 use crate::text::Text;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
+use std::ops::Range;
 use std::sync::Arc;
 use zerocopy::AsBytes;
 
@@ -14,13 +17,31 @@ pub enum Primitive<'a> {
     /// Pops a clipping rectangle from a clipping rectangle stack. All [`PushClip`s](#variant.PushClip) should have
     /// a matching `PopClip`.
     PopClip,
+    /// Pushes a transform on a transform stack. The positions and sizes of all following primitives are multiplied by
+    /// the scale and then offset by the translation, after which the transforms below it on the stack are applied.
+    /// Clipping rectangles are transformed the same way. Text is rendered at it's transformed size, so it stays crisp
+    /// when zoomed in.
+    PushTransform(f32, (f32, f32)),
+    /// Pops a transform from the transform stack. All [`PushTransform`s](#variant.PushTransform) should have a
+    /// matching `PopTransform`.
+    PopTransform,
     /// Move following commands one layer up. Higher layers always draw in front of lower layers.
     LayerUp,
     /// Move following commands one layer down. Higher layers always draw in front of lower layers.
     LayerDown,
+    /// Draw the primitives after all other primitives, on a layer above all other layers.
+    /// The primitives are not clipped by any clipping rectangle that is active when the portal is encountered.
+    Portal(Vec<Primitive<'a>>),
     /// Draw a rectangle filled with a color.
     DrawRect(Rectangle, Color),
+    /// Draw a rectangle with rounded corners filled with a color.
+    /// The radii are clamped to half of the smallest dimension of the rectangle.
+    DrawRoundedRect(Rectangle, Corners, Color),
     /// Draw a triangle filled with a color.
+    /// The points are in the same coordinate space as the layout rectangles, so custom widgets can return triangles
+    /// from their [`draw`](../widget/trait.Widget.html#tymethod.draw) method like any other primitive. They are
+    /// clipped and transformed like the other primitives. Use [`polygon`](#method.polygon) to draw other convex
+    /// shapes.
     DrawTriangle([[f32; 2]; 3], Color),
     /// Draw some text within the bounds of a rectangle.
     /// See [`Text`](../text/struct.Text.html) for more information.
@@ -31,8 +52,72 @@ pub enum Primitive<'a> {
     DrawImage(ImageData, Rectangle, Color),
 }
 
+impl<'a> Primitive<'a> {
+    /// Fills a convex polygon with a color, by fanning it out into [`DrawTriangle`s](#variant.DrawTriangle) from the
+    /// first point. The points should be ordered around the polygon. Less than three points result in no primitives.
+    ///
+    /// A slice of a pie chart can be drawn by passing the center followed by points on the arc:
+    /// ```
+    /// use pixel_widgets::draw::{Color, Primitive};
+    ///
+    /// let (cx, cy, radius) = (50.0, 50.0, 40.0);
+    /// let arc = (0..=16).map(|i| {
+    ///     let angle = i as f32 / 16.0 * std::f32::consts::FRAC_PI_2;
+    ///     [cx + angle.cos() * radius, cy + angle.sin() * radius]
+    /// });
+    /// let points: Vec<[f32; 2]> = std::iter::once([cx, cy]).chain(arc).collect();
+    ///
+    /// let slice = Primitive::polygon(&points, Color::red());
+    /// assert_eq!(slice.len(), 16);
+    /// ```
+    pub fn polygon(points: &[[f32; 2]], color: Color) -> Vec<Primitive<'a>> {
+        match points.split_first() {
+            Some((&first, rest)) => rest
+                .windows(2)
+                .map(|pair| Primitive::DrawTriangle([first, pair[0], pair[1]], color))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Wraps primitives in [`LayerUp`](#variant.LayerUp) or [`LayerDown`](#variant.LayerDown) steps, so they are
+    /// drawn `z_index` layers above or below the surrounding primitives.
+    pub(crate) fn layered(primitives: Vec<Primitive<'a>>, z_index: i32) -> Vec<Primitive<'a>> {
+        let (up, down) = match z_index {
+            0 => return primitives,
+            z if z > 0 => (Primitive::LayerUp, Primitive::LayerDown),
+            _ => (Primitive::LayerDown, Primitive::LayerUp),
+        };
+        let steps = z_index.unsigned_abs() as usize;
+        let mut result = Vec::with_capacity(primitives.len() + steps * 2);
+        result.extend(std::iter::repeat_with(|| up.clone()).take(steps));
+        result.extend(primitives);
+        result.extend(std::iter::repeat_with(|| down.clone()).take(steps));
+        result
+    }
+
+    /// Convert a borrowed primitive to an owned primitive.
+    pub fn to_owned(&self) -> Primitive<'static> {
+        match self {
+            Primitive::PushClip(rect) => Primitive::PushClip(*rect),
+            Primitive::PopClip => Primitive::PopClip,
+            Primitive::PushTransform(scale, translate) => Primitive::PushTransform(*scale, *translate),
+            Primitive::PopTransform => Primitive::PopTransform,
+            Primitive::LayerUp => Primitive::LayerUp,
+            Primitive::LayerDown => Primitive::LayerDown,
+            Primitive::Portal(primitives) => Primitive::Portal(primitives.iter().map(Primitive::to_owned).collect()),
+            Primitive::DrawRect(rect, color) => Primitive::DrawRect(*rect, *color),
+            Primitive::DrawRoundedRect(rect, corners, color) => Primitive::DrawRoundedRect(*rect, *corners, *color),
+            Primitive::DrawTriangle(points, color) => Primitive::DrawTriangle(*points, *color),
+            Primitive::DrawText(text, rect) => Primitive::DrawText(text.to_owned(), *rect),
+            Primitive::Draw9(patch, rect, color) => Primitive::Draw9(patch.clone(), *rect, *color),
+            Primitive::DrawImage(image, rect, color) => Primitive::DrawImage(image.clone(), *rect, *color),
+        }
+    }
+}
+
 /// A color with red, green, blue and alpha components.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Color {
     /// The red component in `[0.0-1.0]` range.
     pub r: f32,
@@ -90,12 +175,24 @@ pub enum Background {
     None,
     /// Draw a solid color
     Color(Color),
+    /// Draw a solid color with rounded corners
+    RoundedColor(Color, Corners),
     /// Draw a stretched image multiplied by a color
     Image(ImageData, Color),
     /// Draw a 9 patch image multiplied by a color
     Patch(Patch, Color),
 }
 
+/// The radii of the four corners of a rounded rectangle.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Corners {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
 /// A collection of data needed to render the ui.
 pub struct DrawList {
     /// A list of texture updates that need to be uploaded before rendering.
@@ -104,10 +201,164 @@ pub struct DrawList {
     pub vertices: Vec<Vertex>,
     /// A list of draw commands that use the `vertices` buffer.
     pub commands: Vec<Command>,
+    /// The ranges of `vertices` that changed since the previous `DrawList`, if vertex diffing is enabled using
+    /// [`Ui::set_vertex_diffing`](../struct.Ui.html#method.set_vertex_diffing).
+    /// When this is `None` the vertex buffer should be rebuilt completely, which is also the case when the number
+    /// of vertices changed. When it is `Some`, all vertices outside of the ranges are the same as in the previous
+    /// `DrawList`, so backends can update only the changed ranges of a persistent vertex buffer.
+    pub changed: Option<Vec<Range<usize>>>,
+}
+
+impl DrawList {
+    /// Returns the number of vertices that need to be uploaded for this `DrawList`,
+    /// taking [`changed`](#structfield.changed) into account.
+    pub fn upload_len(&self) -> usize {
+        self.changed.as_ref().map_or(self.vertices.len(), |changed| {
+            changed.iter().map(|range| range.end - range.start).sum()
+        })
+    }
+
+    /// Returns a [`DrawSnapshot`](struct.DrawSnapshot.html) of the commands, for a render target of `width` by
+    /// `height` physical pixels.
+    pub fn snapshot(&self, width: f32, height: f32) -> DrawSnapshot {
+        let round = LayoutSnapshot::round;
+        let summarize = |offset: usize, count: usize| {
+            let mut bounds: Option<Rectangle> = None;
+            let mut colors = Vec::new();
+            for vertex in &self.vertices[offset..offset + count] {
+                let [x, y] = vertex.pos;
+                let (x, y) = (round((x + 1.0) * 0.5 * width), round((y + 1.0) * 0.5 * height));
+                let point = Rectangle {
+                    left: x,
+                    top: y,
+                    right: x,
+                    bottom: y,
+                };
+                bounds = Some(bounds.map_or(point, |bounds| bounds.union(point)));
+
+                let [r, g, b, a] = vertex.color;
+                let color = Color::rgba(r, g, b, a).to_rgba8();
+                if !colors.contains(&color) {
+                    colors.push(color);
+                }
+            }
+            (bounds.unwrap_or_else(Rectangle::zero), colors)
+        };
+
+        let commands = self
+            .commands
+            .iter()
+            .filter_map(|command| match *command {
+                Command::Nop => None,
+                Command::Clip { scissor } => Some(CommandSnapshot::Clip {
+                    scissor: Rectangle {
+                        left: round(scissor.left),
+                        top: round(scissor.top),
+                        right: round(scissor.right),
+                        bottom: round(scissor.bottom),
+                    },
+                }),
+                Command::Colored { offset, count } => {
+                    let (bounds, colors) = summarize(offset, count);
+                    Some(CommandSnapshot::Colored {
+                        vertices: count,
+                        bounds,
+                        colors,
+                    })
+                }
+                Command::Textured { texture, offset, count } => {
+                    let (bounds, colors) = summarize(offset, count);
+                    Some(CommandSnapshot::Textured {
+                        texture,
+                        vertices: count,
+                        bounds,
+                        colors,
+                    })
+                }
+            })
+            .collect();
+
+        DrawSnapshot { commands }
+    }
+
+    /// Computes the ranges of `vertices` that differ from `previous`.
+    /// Ranges that are less than `merge_gap` vertices apart are merged into a single range.
+    /// Returns `None` if the number of vertices is different.
+    pub(crate) fn diff(previous: &[Vertex], vertices: &[Vertex], merge_gap: usize) -> Option<Vec<Range<usize>>> {
+        if previous.len() != vertices.len() {
+            return None;
+        }
+
+        let mut changed: Vec<Range<usize>> = Vec::new();
+        for (i, (a, b)) in previous.iter().zip(vertices.iter()).enumerate() {
+            if a.as_bytes() != b.as_bytes() {
+                match changed.last_mut() {
+                    Some(range) if i <= range.end + merge_gap => range.end = i + 1,
+                    _ => changed.push(i..i + 1),
+                }
+            }
+        }
+        Some(changed)
+    }
+}
+
+/// A deterministic summary of the commands of a [`DrawList`](struct.DrawList.html), which can be compared against a
+/// stored snapshot to test for rendering regressions without a gpu.
+/// See [`Ui::draw_snapshot`](../struct.Ui.html#method.draw_snapshot).
+///
+/// Positions are in physical pixels and rounded to hundredths of a pixel, and colors are converted to 8 bit components,
+/// so tiny floating point differences don't change the snapshot.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DrawSnapshot {
+    /// Snapshots of the commands, in order. `Command::Nop`s are left out.
+    pub commands: Vec<CommandSnapshot>,
+}
+
+/// Snapshot of a single draw [`Command`](enum.Command.html).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CommandSnapshot {
+    /// Sets a new scissor rect.
+    Clip {
+        /// The scissor rectangle
+        scissor: Rectangle,
+    },
+    /// Draws vertices without an active texture.
+    Colored {
+        /// The number of vertices that are drawn
+        vertices: usize,
+        /// The bounding rectangle of the vertices
+        bounds: Rectangle,
+        /// The distinct colors of the vertices, in order of appearance
+        colors: Vec<[u8; 4]>,
+    },
+    /// Draws vertices with an active texture.
+    Textured {
+        /// Texture id to be used
+        texture: usize,
+        /// The number of vertices that are drawn
+        vertices: usize,
+        /// The bounding rectangle of the vertices
+        bounds: Rectangle,
+        /// The distinct colors of the vertices, in order of appearance
+        colors: Vec<[u8; 4]>,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl DrawSnapshot {
+    /// Serializes the snapshot to pretty printed json.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("draw snapshots can always be serialized")
+    }
 }
 
 /// An update of the available texture data. The backend is responsible for uploading the provided
 /// data to the GPU.
+///
+/// Texel data uses premultiplied alpha, so linear filtering doesn't bleed the color of transparent texels into their
+/// neighbours. The distance fields of fonts are the exception, since their alpha channel stores a distance.
 pub enum Update {
     /// An existing texture is updated.
     TextureSubresource {
@@ -131,13 +382,20 @@ pub enum Update {
         data: Vec<u8>,
         /// Whether the texture will be used as atlas. `true` means the texture might be updated
         /// later with [`TextureSubresource`](#variant.TextureSubresource), while `false` means the texture is
-        /// immutable.
+        /// only updated when the pixels of it's image are replaced using
+        /// [`Graphics::update_image`](../graphics/struct.Graphics.html#method.update_image).
         atlas: bool,
     },
+    /// A texture is no longer used and can be released.
+    DropTexture {
+        /// The id of the texture to release
+        id: usize,
+    },
 }
 
 /// The `Vertex` type passed to the vertex shader.
 #[derive(Debug, Clone, Copy, AsBytes)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(packed)]
 pub struct Vertex {
     /// The position of the vertex within device coordinates.
@@ -148,6 +406,7 @@ pub struct Vertex {
     pub uv: [f32; 2],
     /// A color associated with the `Vertex`.
     /// The color is multiplied by the end result of the fragment shader.
+    /// Colors use straight alpha, backends that blend using premultiplied alpha have to premultiply them.
     /// When `mode` is not 1, the default value is white ([1.0; 4])
     pub color: [f32; 4],
     /// Extra arguments for the fragment shader.
@@ -155,6 +414,7 @@ pub struct Vertex {
     /// `0` for rendering an image.
     /// `1` for rendering non-textured 2D geometry.
     /// `2` for rendering msdf text.
+    /// `3` for rendering full color glyphs, which only use the alpha of `color`.
     /// If any other value is given, the fragment shader will treat it as mode 0.
     ///
     /// The second value is the pixel range for msdf text.
@@ -165,6 +425,7 @@ pub struct Vertex {
 
 /// A draw `Command` that is to be translated to a draw command specific to the backend
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Command {
     /// Do nothing. Appending a `Nop` to another command will flush the other command.
     Nop,
@@ -258,14 +519,176 @@ impl Color {
         self
     }
 
-    /// Modifies a color by blending it towards another color
-    pub fn blend(mut self, other: Color, factor: f32) -> Self {
-        let inverse = 1.0 - factor;
-        self.r = self.r * inverse + other.r * factor;
-        self.g = self.g * inverse + other.g * factor;
-        self.b = self.b * inverse + other.b * factor;
-        self.a = self.a * inverse + other.a * factor;
-        self
+    /// Modifies a color by blending it towards another color, the same way the default style derives it's hover and
+    /// pressed colors. `factor` is clamped to `[0.0-1.0]`, where `0.0` returns this color and `1.0` returns `other`.
+    ///
+    /// The red, green and blue components are blended in linear space, which avoids the dark and muddy colors that
+    /// blending the sRGB components directly produces. The alpha component is blended linearly.
+    ///
+    /// ```
+    /// # use pixel_widgets::draw::Color;
+    /// let grey = Color::black().blend(Color::white(), 0.5);
+    /// assert_eq!(grey.to_rgba8(), [188, 188, 188, 255]);
+    /// assert_eq!(Color::red().blend(Color::blue(), 2.0).to_rgba8(), Color::blue().to_rgba8());
+    /// ```
+    pub fn blend(self, other: Color, factor: f32) -> Self {
+        let factor = factor.clamp(0.0, 1.0);
+        let blend = |a: f32, b: f32| srgb_from_linear(lerp(srgb_to_linear(a), srgb_to_linear(b), factor));
+        Color {
+            r: blend(self.r, other.r),
+            g: blend(self.g, other.g),
+            b: blend(self.b, other.b),
+            a: lerp(self.a, other.a, factor),
+        }
+    }
+
+    /// Linearly interpolates all components of this color towards another color, without converting to linear space.
+    /// `t` is clamped to `[0.0-1.0]`, where `0.0` returns this color and `1.0` returns `other`.
+    /// Use [`blend`](#method.blend) for blending colors that are shown to the user side by side.
+    ///
+    /// ```
+    /// # use pixel_widgets::draw::Color;
+    /// let grey = Color::black().lerp(Color::white(), 0.5);
+    /// assert_eq!(grey.to_rgba8(), [128, 128, 128, 255]);
+    /// ```
+    pub fn lerp(self, other: Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        Color {
+            r: lerp(self.r, other.r, t),
+            g: lerp(self.g, other.g, t),
+            b: lerp(self.b, other.b, t),
+            a: lerp(self.a, other.a, t),
+        }
+    }
+
+    /// Returns a color from 8 bit (r, g, b, a) components, where `255` maps to `1.0`.
+    pub fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::rgba(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0)
+    }
+
+    /// Returns the 8 bit `[r, g, b, a]` components of the color.
+    /// Components are clamped to `[0.0-1.0]` and rounded to the nearest value, so `1.0` maps to `255`.
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        let convert = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [convert(self.r), convert(self.g), convert(self.b), convert(self.a)]
+    }
+
+    /// Returns a color from a packed `0xRRGGBBAA` value, with red in the most significant byte.
+    ///
+    /// ```
+    /// # use pixel_widgets::draw::Color;
+    /// let color = Color::from_u32(0xff800040);
+    /// assert_eq!(color.to_rgba8(), [0xff, 0x80, 0x00, 0x40]);
+    /// assert_eq!(color.to_u32(), 0xff800040);
+    /// ```
+    pub fn from_u32(rgba: u32) -> Self {
+        let [r, g, b, a] = rgba.to_be_bytes();
+        Self::from_rgba8(r, g, b, a)
+    }
+
+    /// Parses a hex color, like the colors in a stylesheet. The hex digits must match one of the following patterns,
+    /// with an optional leading `#`: `rgb`, `rgba`, `rrggbb` or `rrggbbaa`.
+    /// Returns `None` if the string is not a valid hex color.
+    ///
+    /// ```
+    /// # use pixel_widgets::draw::Color;
+    /// assert_eq!(Color::from_hex("#ff8000").unwrap().to_u32(), 0xff8000ff);
+    /// assert_eq!(Color::from_hex("f80c").unwrap().to_u32(), 0xff8800cc);
+    /// assert!(Color::from_hex("#ff80").is_some());
+    /// assert!(Color::from_hex("#ff800").is_none());
+    /// ```
+    #[allow(clippy::identity_op)] // to keep the code clean and consistent
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let int = u32::from_str_radix(hex, 16).ok()?;
+        match hex.len() {
+            3 => Some(Color {
+                r: ((int & 0xf00) >> 8) as f32 / 15.0,
+                g: ((int & 0x0f0) >> 4) as f32 / 15.0,
+                b: ((int & 0x00f) >> 0) as f32 / 15.0,
+                a: 1.0,
+            }),
+            4 => Some(Color {
+                r: ((int & 0xf000) >> 12) as f32 / 15.0,
+                g: ((int & 0x0f00) >> 8) as f32 / 15.0,
+                b: ((int & 0x00f0) >> 4) as f32 / 15.0,
+                a: ((int & 0x000f) >> 0) as f32 / 15.0,
+            }),
+            6 => Some(Color {
+                r: ((int & 0xff0000) >> 16) as f32 / 255.0,
+                g: ((int & 0x00ff00) >> 8) as f32 / 255.0,
+                b: ((int & 0x0000ff) >> 0) as f32 / 255.0,
+                a: 1.0,
+            }),
+            8 => Some(Color {
+                r: ((int & 0xff000000) >> 24) as f32 / 255.0,
+                g: ((int & 0x00ff0000) >> 16) as f32 / 255.0,
+                b: ((int & 0x0000ff00) >> 8) as f32 / 255.0,
+                a: ((int & 0x000000ff) >> 0) as f32 / 255.0,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the color packed as `0xRRGGBBAA`, with red in the most significant byte.
+    pub fn to_u32(&self) -> u32 {
+        u32::from_be_bytes(self.to_rgba8())
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn srgb_from_linear(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+impl From<[u8; 4]> for Color {
+    fn from([r, g, b, a]: [u8; 4]) -> Self {
+        Self::from_rgba8(r, g, b, a)
+    }
+}
+
+impl From<Color> for [u8; 4] {
+    fn from(color: Color) -> Self {
+        color.to_rgba8()
+    }
+}
+
+impl ImageData {
+    /// Returns an image that covers a sub region of this image. The region is defined in pixels, relative to the
+    /// left top corner of this image. The returned image shares the texture of this image,
+    /// so no extra texture data is uploaded.
+    pub fn sub(&self, region: Rectangle) -> ImageData {
+        let scale_x = self.texcoords.width() / self.size.width();
+        let scale_y = self.texcoords.height() / self.size.height();
+        ImageData {
+            texture: self.texture,
+            _cache_id: self._cache_id.clone(),
+            texcoords: Rectangle {
+                left: self.texcoords.left + region.left * scale_x,
+                top: self.texcoords.top + region.top * scale_y,
+                right: self.texcoords.left + region.right * scale_x,
+                bottom: self.texcoords.top + region.bottom * scale_y,
+            },
+            size: region.size(),
+        }
     }
 }
 
@@ -383,6 +806,34 @@ impl Patch {
     }
 }
 
+impl Corners {
+    /// Construct `Corners` with the same radius for all corners.
+    pub fn all(radius: f32) -> Self {
+        Self {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+
+    /// Returns whether all corners are sharp.
+    pub fn is_zero(&self) -> bool {
+        self.top_left <= 0.0 && self.top_right <= 0.0 && self.bottom_right <= 0.0 && self.bottom_left <= 0.0
+    }
+
+    /// Clamps the radii to half of the smallest dimension of `rect`, so opposing corners never overlap.
+    pub fn clamp(self, rect: Rectangle) -> Self {
+        let max = (rect.width().min(rect.height()) * 0.5).max(0.0);
+        Self {
+            top_left: self.top_left.clamp(0.0, max),
+            top_right: self.top_right.clamp(0.0, max),
+            bottom_right: self.bottom_right.clamp(0.0, max),
+            bottom_left: self.bottom_left.clamp(0.0, max),
+        }
+    }
+}
+
 impl Background {
     /// Content rect for a given size and padding
     pub fn content_rect(&self, layout: Rectangle, padding: Rectangle) -> Rectangle {
@@ -401,29 +852,35 @@ impl Background {
         }
     }
 
-    /// Resolve the size of a widget when taking this background and padding into account
+    /// Resolve the size of a widget when taking this background and padding into account.
+    ///
+    /// If the widget wants to [`Shrink`](../layout/enum.Size.html#variant.Shrink) but the content is not
+    /// `Exact`, the content would depend on the size of the widget and the widget would depend on the size
+    /// of the content. To break this cycle, the content resolves to it's minimum size in that case.
+    /// ```
+    /// # use pixel_widgets::draw::Background;
+    /// # use pixel_widgets::layout::{Rectangle, Size};
+    /// let padding = Rectangle { left: 2.0, top: 2.0, right: 2.0, bottom: 2.0 };
+    /// let (width, height) = Background::None.resolve_size(
+    ///     (Size::Shrink, Size::Shrink),
+    ///     (Size::Fill(1), Size::Exact(10.0)),
+    ///     padding,
+    /// );
+    /// assert!(matches!(width, Size::Exact(w) if w == 4.0));
+    /// assert!(matches!(height, Size::Exact(h) if h == 14.0));
+    /// ```
     pub fn resolve_size(&self, widget: (Size, Size), content: (Size, Size), padding: Rectangle) -> (Size, Size) {
-        let (width, height) = match (widget, content) {
-            ((Size::Shrink, Size::Shrink), (Size::Exact(width), Size::Exact(height))) => {
-                let rect = self.layout_rect(Rectangle::from_wh(width, height), padding);
-                (Size::Exact(rect.width()), Size::Exact(rect.height()))
-            }
-            ((Size::Shrink, other), (Size::Exact(width), _)) => {
-                let rect = self.layout_rect(Rectangle::from_wh(width, 0.0), padding);
-                (Size::Exact(rect.width()), other)
-            }
-            ((other, Size::Shrink), (_, Size::Exact(height))) => {
-                let rect = self.layout_rect(Rectangle::from_wh(0.0, height), padding);
-                (other, Size::Exact(rect.height()))
-            }
-            (other, _) => other,
+        let content = Rectangle::from_wh(content.0.min_size(), content.1.min_size());
+        let rect = self.layout_rect(content, padding);
+        let width = match widget.0 {
+            Size::Shrink => Size::Exact(rect.width()),
+            other => other,
         };
-        match (width, height) {
-            (Size::Shrink, Size::Shrink) => (Size::Exact(0.0), Size::Exact(0.0)),
-            (Size::Shrink, other) => (Size::Exact(0.0), other),
-            (other, Size::Shrink) => (other, Size::Exact(0.0)),
+        let height = match widget.1 {
+            Size::Shrink => Size::Exact(rect.height()),
             other => other,
-        }
+        };
+        (width, height)
     }
 
     /// Size of the background if the content rect is zero sized
@@ -453,6 +910,7 @@ impl Background {
     pub fn render(&self, rectangle: Rectangle) -> Option<Primitive<'static>> {
         match self {
             Background::Color(color) => Some(Primitive::DrawRect(rectangle, *color)),
+            Background::RoundedColor(color, corners) => Some(Primitive::DrawRoundedRect(rectangle, *corners, *color)),
             Background::Image(image, color) => Some(Primitive::DrawImage(image.clone(), rectangle, *color)),
             Background::Patch(patch, color) => Some(Primitive::Draw9(patch.clone(), rectangle, *color)),
             Background::None => None,
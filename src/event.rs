@@ -64,9 +64,12 @@ pub enum Key {
     Space,
     Enter,
     Backspace,
+    Delete,
     Escape,
     Home,
     End,
+    PageUp,
+    PageDown,
     Minus,
     Plus,
     BracketOpen,
@@ -161,7 +164,7 @@ impl Modifiers {
 }
 
 /// A user input event.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Event {
     /// A button on some input device was pressed.
     Press(Key),
@@ -176,13 +179,68 @@ pub enum Event {
     /// The mouse cursor was moved to a location.
     Cursor(f32, f32),
     /// The mouse wheel or touchpad scroll gesture sent us some scroll event.
+    /// Like [`Cursor`](#variant.Cursor), the horizontal and vertical delta are in physical pixels when they are passed to
+    /// the ui, and converted to logical pixels before the widgets receive them. Positive values scroll towards the left
+    /// and the top of the content, like turning the mouse wheel away from the user.
     Scroll(f32, f32),
     /// Text input was received, usually via the keyboard.
     Text(char),
+    /// A block of text should be inserted at once, for example when pasting or when an input method commits it's
+    /// composition. Widgets that edit text treat the whole block as a single edit.
+    InsertText(String),
     /// The window was focused or lost focus.
     Focus(bool),
     /// The application exited it's main event loop
     Exit,
     /// The ui was redrawn, maybe you want to do it again?
+    /// This is sent after every [`Ui::draw`](../struct.Ui.html#method.draw), and by
+    /// [`Ui::animate`](../struct.Ui.html#method.animate) on frames in between while a widget requests animation
+    /// frames using [`Context::animate`](../widget/struct.Context.html#method.animate).
     Animate,
+    /// Move the cursor to the nearest focusable widget in a direction, for example using the d-pad of a gamepad.
+    /// Focusable widgets are the widgets that were visible during the last draw and that return `true` from
+    /// [`Widget::focusable`](../widget/trait.Widget.html#method.focusable).
+    /// Widgets receive the resulting cursor move as an [`Event::Cursor`](#variant.Cursor), so clicking the selected
+    /// widget is done by sending a left mouse button press and release.
+    Navigate(NavigateDirection),
+}
+
+/// A direction for [`Event::Navigate`](enum.Event.html#variant.Navigate).
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavigateDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// The icon of the mouse cursor, as requested by the widget under the cursor.
+/// Widgets request an icon using [`Context::set_cursor_icon`](../widget/struct.Context.html#method.set_cursor_icon),
+/// and the host can read it using [`Ui::cursor_icon`](../struct.Ui.html#method.cursor_icon).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CursorIcon {
+    /// The default arrow cursor.
+    #[default]
+    Default,
+    /// A pointing hand, for clickable widgets.
+    Pointer,
+    /// An I-beam, for editable or selectable text.
+    Text,
+    /// An open hand, for widgets that can be dragged.
+    Grab,
+    /// A closed hand, for widgets that are being dragged.
+    Grabbing,
+    /// Arrows in all directions, for widgets that can be moved.
+    Move,
+    /// A horizontal resize cursor.
+    ResizeHorizontal,
+    /// A vertical resize cursor.
+    ResizeVertical,
+    /// A diagonal resize cursor from the top left to the bottom right.
+    ResizeDiagonal,
+    /// A diagonal resize cursor from the bottom left to the top right.
+    ResizeAntiDiagonal,
+    /// Indicates that the widget under the cursor can't be interacted with.
+    NotAllowed,
 }
@@ -1,23 +1,206 @@
+use std::future::Future;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::*;
+use futures::channel::oneshot;
+use once_cell::sync::OnceCell;
 
 use crate::cache::Cache;
 use crate::draw::{ImageData, Patch};
+use crate::layout::Rectangle;
 
 /// Cloneable image loader
 pub struct Graphics {
     pub(crate) cache: Arc<Mutex<Cache>>,
 }
 
+/// Handle to an image that is loaded asynchronously using
+/// [`Graphics::load_image_async`](struct.Graphics.html#method.load_image_async).
+/// Clones of the handle refer to the same image. The handle can be displayed using
+/// [`Image::handle`](../widget/image/struct.Image.html#method.handle).
+#[derive(Clone, Default)]
+pub struct ImageHandle {
+    // `None` when loading the image failed.
+    image: Arc<OnceCell<Option<ImageData>>>,
+}
+
+/// A set of animation frames that are cut from a single image.
+/// All frames share the texture of the source image, so no extra texture data is uploaded.
+///
+/// Frames are played at a fixed rate, unless the sprite sheet has [`delays`](#method.with_delays) for each frame,
+/// like the animations loaded using [`Graphics::load_animation`](struct.Graphics.html#method.load_animation).
+#[derive(Clone, Debug)]
+pub struct SpriteSheet {
+    frames: Vec<ImageData>,
+    delays: Vec<Duration>,
+}
+
+/// How an animation continues after the last frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Playback {
+    /// Stop at the last frame
+    Once,
+    /// Start over at the first frame
+    Loop,
+    /// Play the frames in reverse back to the first frame, then start over
+    PingPong,
+}
+
 impl Graphics {
-    /// Loads an image
+    /// Returns the total number of texels of the images in the texture cache, and the memory budget of the cache.
+    /// Text doesn't add to the cache, since glyphs are drawn from the atlas of their font.
+    /// See [`Cache`](../cache/struct.Cache.html) for when images are evicted.
+    pub fn cache_usage(&self) -> (usize, usize) {
+        let cache = self.cache.lock().unwrap();
+        (cache.texels(), cache.budget())
+    }
+
+    /// Loads an image. Png images are always supported, while gif and jpeg images require the "gif" and "jpeg"
+    /// features.
     pub fn load_image<B: AsRef<[u8]>>(&self, bytes: B) -> Result<ImageData> {
         let image = image::load_from_memory(bytes.as_ref())?;
         let image = self.cache.lock().unwrap().load_image(image.into_rgba8());
         Ok(image)
     }
 
+    /// Loads an image from bytes that are produced by a future, for example an image that is downloaded.
+    /// Returns an [`ImageHandle`](struct.ImageHandle.html) that is empty until the image is loaded, together with a
+    /// future that loads the image into the handle once the bytes are available. The image is decoded on a separate
+    /// thread, so large images don't block the ui.
+    ///
+    /// The future should be submitted to the [`Runtime`](../prelude/struct.Runtime.html) of a component using
+    /// [`wait`](../prelude/struct.Runtime.html#method.wait), so it's driven by the [`Ui`](../struct.Ui.html) task.
+    /// The component is updated with the resulting message when loading finished, at which point it should mutate it's
+    /// state so the view is rebuilt with the loaded image.
+    pub fn load_image_async<F>(&self, bytes: F) -> (ImageHandle, impl Future<Output = Result<()>> + Send + Sync)
+    where
+        F: 'static + Future<Output = Result<Vec<u8>>> + Send + Sync,
+    {
+        let handle = ImageHandle::default();
+        let image = handle.image.clone();
+        let graphics = self.clone();
+        let load = async move {
+            let decoded = async move {
+                let bytes = bytes.await?;
+                let (sender, receiver) = oneshot::channel();
+                std::thread::Builder::new()
+                    .name("pixel-widgets image decoder".into())
+                    .spawn(move || {
+                        sender
+                            .send(image::load_from_memory(&bytes).map(|image| image.into_rgba8()))
+                            .ok();
+                    })?;
+                Ok(receiver.await??)
+            };
+
+            let loaded = decoded
+                .await
+                .map(|decoded| graphics.cache.lock().unwrap().load_image(decoded));
+            image.set(loaded.as_ref().ok().cloned()).ok();
+            loaded.map(|_| ())
+        };
+        (handle, load)
+    }
+
+    /// Loads an image from raw pixels, without decoding it. `data` must contain the rgba pixels of the image row by
+    /// row, with 4 bytes per pixel.
+    ///
+    /// The image always gets a place in the texture of it's own, so it can be updated using
+    /// [`update_image`](#method.update_image). Images loaded in other ways share their place with images that have the
+    /// same pixels, and can only be updated if no other image with the same pixels was loaded.
+    pub fn load_image_from_rgba(&self, width: u32, height: u32, data: Vec<u8>) -> Result<ImageData> {
+        let len = width as usize * height as usize * 4;
+        let image = image::RgbaImage::from_raw(width, height, data)
+            .ok_or_else(|| anyhow!("expected {len} bytes of rgba data for a {width}x{height} image"))?;
+        Ok(self.cache.lock().unwrap().load_unique_image(image))
+    }
+
+    /// Loads an svg image and rasterizes it to `width` by `height` pixels. The svg is scaled to fill the whole image,
+    /// so the size should have the aspect ratio of the svg. Use the size in physical pixels, so the image stays crisp
+    /// on hidpi displays. Text in the svg is not rendered.
+    ///
+    /// The rasterized image is cached by the svg data and the size, so loading the same svg at the same size again
+    /// doesn't rasterize it again. Requires the "svg" feature.
+    #[cfg(feature = "svg")]
+    pub fn load_svg<B: AsRef<[u8]>>(&self, bytes: B, width: u32, height: u32) -> Result<ImageData> {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = DefaultHasher::new();
+        "svg".hash(&mut hasher);
+        bytes.as_ref().hash(&mut hasher);
+        (width, height).hash(&mut hasher);
+
+        self.cache
+            .lock()
+            .unwrap()
+            .load_keyed(hasher.finish(), || rasterize_svg(bytes.as_ref(), width, height))
+    }
+
+    /// Replaces the pixels of an image that was loaded by this `Graphics`, for example to stream video frames into an
+    /// [`Image`](../widget/image/struct.Image.html) widget. `data` must contain the rgba pixels row by row, with the
+    /// same size as the image. The image keeps it's place in the texture, so all `ImageData` that refer to it,
+    /// including [`sub`](../draw/struct.ImageData.html#method.sub) images, show the new pixels once the ui is
+    /// redrawn. The ui needs a redraw as long as there are texture updates that weren't drawn yet.
+    /// Returns an error if the image shares it's place with another image, see
+    /// [`load_image_from_rgba`](#method.load_image_from_rgba).
+    pub fn update_image(&self, image: &ImageData, data: Vec<u8>) -> Result<()> {
+        self.cache.lock().unwrap().update_image(image, data)
+    }
+
+    /// Loads an image and cuts it into a [`SpriteSheet`](struct.SpriteSheet.html).
+    /// See [`SpriteSheet::new`](struct.SpriteSheet.html#method.new) for the meaning of the parameters.
+    pub fn load_sprite_sheet<B: AsRef<[u8]>>(
+        &self,
+        bytes: B,
+        columns: usize,
+        rows: usize,
+        frame_count: usize,
+    ) -> Result<SpriteSheet> {
+        Ok(SpriteSheet::new(&self.load_image(bytes)?, columns, rows, frame_count))
+    }
+
+    /// Loads an animated png (apng) or gif image as a [`SpriteSheet`](struct.SpriteSheet.html), with the delays of the
+    /// frames, that can be played using a [`SpriteAnimation`](../widget/sprite_animation/struct.SpriteAnimation.html).
+    /// Each frame is loaded as a separate image. Images that are not animated are loaded as a single frame.
+    ///
+    /// Decoding gifs requires the "gif" feature.
+    pub fn load_animation<B: AsRef<[u8]>>(&self, bytes: B) -> Result<SpriteSheet> {
+        use image::AnimationDecoder;
+
+        let bytes = bytes.as_ref();
+        let frames = match image::guess_format(bytes)? {
+            #[cfg(feature = "gif")]
+            image::ImageFormat::Gif => image::codecs::gif::GifDecoder::new(bytes)?
+                .into_frames()
+                .collect_frames()?,
+            image::ImageFormat::Png => {
+                let decoder = image::codecs::png::PngDecoder::new(bytes)?;
+                if !decoder.is_apng() {
+                    return Ok(SpriteSheet::from_frames(vec![self.load_image(bytes)?]));
+                }
+                decoder.apng().into_frames().collect_frames()?
+            }
+            _ => return Ok(SpriteSheet::from_frames(vec![self.load_image(bytes)?])),
+        };
+
+        let mut cache = self.cache.lock().unwrap();
+        let mut images = Vec::with_capacity(frames.len());
+        let mut delays = Vec::with_capacity(frames.len());
+        for frame in frames {
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
+            let delay = Duration::from_secs_f64(numerator as f64 / denominator as f64 / 1000.0);
+            delays.push(if delay <= Duration::from_millis(10) {
+                DEFAULT_DELAY
+            } else {
+                delay
+            });
+            images.push(cache.load_image(frame.into_buffer()));
+        }
+        Ok(SpriteSheet::from_frames(images).with_delays(delays))
+    }
+
     /// Loads a 9 patch.
     pub fn load_patch<B: AsRef<[u8]>>(&self, bytes: B) -> Result<Patch> {
         let image = image::load_from_memory(bytes.as_ref())?;
@@ -26,6 +209,201 @@ impl Graphics {
     }
 }
 
+impl SpriteSheet {
+    /// Cuts an image into a grid of `columns` by `rows` equally sized frames.
+    /// Frames are ordered from left to right, then from top to bottom.
+    /// Only the first `frame_count` frames are used, which is useful if the last row is not completely filled.
+    pub fn new(image: &ImageData, columns: usize, rows: usize, frame_count: usize) -> Self {
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+        let width = image.size.width() / columns as f32;
+        let height = image.size.height() / rows as f32;
+        let frames = (0..rows)
+            .flat_map(|y| (0..columns).map(move |x| (x, y)))
+            .take(frame_count)
+            .map(|(x, y)| image.sub(Rectangle::from_xywh(x as f32 * width, y as f32 * height, width, height)))
+            .collect();
+        Self {
+            frames,
+            delays: Vec::new(),
+        }
+    }
+
+    /// Constructs a `SpriteSheet` from frames that were loaded separately.
+    pub fn from_frames(frames: Vec<ImageData>) -> Self {
+        Self {
+            frames,
+            delays: Vec::new(),
+        }
+    }
+
+    /// Sets how long each frame is displayed, which overrides the frame rate of the animation.
+    /// If there are less delays than frames, the last delay is used for the remaining frames.
+    pub fn with_delays(mut self, mut delays: Vec<Duration>) -> Self {
+        let last = delays.last().copied().unwrap_or(DEFAULT_DELAY);
+        delays.resize(self.frames.len(), last);
+        self.delays = delays;
+        self
+    }
+
+    /// The delays of the frames, or an empty slice if the frames are played at a fixed rate.
+    pub fn delays(&self) -> &[Duration] {
+        self.delays.as_slice()
+    }
+
+    /// All frames in the sprite sheet.
+    pub fn frames(&self) -> &[ImageData] {
+        self.frames.as_slice()
+    }
+
+    /// Returns a single frame. Panics if the index is out of bounds.
+    pub fn frame(&self, index: usize) -> &ImageData {
+        &self.frames[index]
+    }
+
+    /// The number of frames in the sprite sheet.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if the sprite sheet has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Iterator over all frames in the sprite sheet.
+    pub fn iter(&self) -> impl Iterator<Item = &ImageData> {
+        self.frames.iter()
+    }
+
+    /// Returns the index of the frame that should be displayed after `elapsed` time,
+    /// when playing at `fps` frames per second.
+    pub fn frame_index(&self, elapsed: Duration, fps: f32, playback: Playback) -> usize {
+        playback.frame_index(self.frames_played(elapsed, fps, playback), self.len())
+    }
+
+    /// Returns the number of frames that have been played after `elapsed` time. When the sprite sheet has
+    /// [`delays`](#method.with_delays), they are used instead of `fps`.
+    pub fn frames_played(&self, elapsed: Duration, fps: f32, playback: Playback) -> usize {
+        let len = self.len();
+        if self.delays.is_empty() {
+            return (elapsed.as_secs_f32() * fps) as usize;
+        }
+
+        // skip the whole cycles of looping animations, so long running animations don't have to count every frame.
+        let period = match playback {
+            Playback::PingPong if len > 1 => (len - 1) * 2,
+            _ => len,
+        };
+        let cycle: Duration = (0..period).map(|i| self.delays[playback.frame_index(i, len)]).sum();
+        if cycle.is_zero() {
+            return 0;
+        }
+        let (mut played, mut remaining) = match playback {
+            Playback::Once => (0, elapsed),
+            _ => {
+                let cycles = (elapsed.as_nanos() / cycle.as_nanos()) as u32;
+                (cycles as usize * period, elapsed - cycle * cycles)
+            }
+        };
+
+        loop {
+            let delay = self.delays[playback.frame_index(played, len)];
+            if remaining < delay || playback.finished(played, len) {
+                return played;
+            }
+            remaining -= delay;
+            played += 1;
+        }
+    }
+}
+
+/// Browsers display gif frames with very short delays for this long, and so does
+/// [`Graphics::load_animation`](struct.Graphics.html#method.load_animation).
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+impl Playback {
+    /// Maps the number of frames that have been played to the index of the frame to display.
+    /// ```
+    /// # use pixel_widgets::graphics::Playback;
+    /// let frames: Vec<usize> = (0..8).map(|played| Playback::PingPong.frame_index(played, 4)).collect();
+    /// assert_eq!(frames, vec![0, 1, 2, 3, 2, 1, 0, 1]);
+    /// assert_eq!(Playback::Once.frame_index(7, 4), 3);
+    /// assert_eq!(Playback::Loop.frame_index(7, 4), 3);
+    /// ```
+    pub fn frame_index(self, played: usize, frame_count: usize) -> usize {
+        if frame_count < 2 {
+            return 0;
+        }
+        match self {
+            Playback::Once => played.min(frame_count - 1),
+            Playback::Loop => played % frame_count,
+            Playback::PingPong => {
+                let period = (frame_count - 1) * 2;
+                let position = played % period;
+                if position < frame_count {
+                    position
+                } else {
+                    period - position
+                }
+            }
+        }
+    }
+
+    /// Returns whether the animation has finished after playing `played` frames.
+    /// Only `Once` animations can finish.
+    pub fn finished(self, played: usize, frame_count: usize) -> bool {
+        self == Playback::Once && played + 1 >= frame_count
+    }
+}
+
+#[cfg(feature = "svg")]
+fn rasterize_svg(bytes: &[u8], width: u32, height: u32) -> Result<image::RgbaImage> {
+    use resvg::{tiny_skia, usvg};
+
+    let tree = usvg::Tree::from_data(bytes, &usvg::Options::default())?;
+    let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or_else(|| anyhow!("invalid svg size"))?;
+    let scale = (width as f32 / tree.size().width(), height as f32 / tree.size().height());
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale.0, scale.1),
+        &mut pixmap.as_mut(),
+    );
+
+    // tiny-skia uses premultiplied alpha, while images use straight alpha.
+    let data = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    Ok(image::RgbaImage::from_raw(width, height, data).unwrap())
+}
+
+impl ImageHandle {
+    /// Returns the image, or `None` if it's not loaded yet or failed to load.
+    pub fn get(&self) -> Option<&ImageData> {
+        self.image.get().and_then(Option::as_ref)
+    }
+
+    /// Returns the image, or `placeholder` if it's not loaded yet or failed to load.
+    pub fn get_or<'a>(&'a self, placeholder: &'a ImageData) -> &'a ImageData {
+        self.get().unwrap_or(placeholder)
+    }
+
+    /// Returns true if the image is loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.get().is_some()
+    }
+
+    /// Returns true if loading the image failed.
+    pub fn is_failed(&self) -> bool {
+        matches!(self.image.get(), Some(None))
+    }
+}
+
 impl Clone for Graphics {
     fn clone(&self) -> Self {
         Self {
@@ -1,7 +1,14 @@
 use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
 
 /// A sizing request
-#[derive(Debug, Clone, Copy)]
+///
+/// A `Fill` size inside a `Shrink` parent can't be resolved, since the parent wants to fit the child while the child
+/// wants to fill the parent. In this case the child is resolved to it's minimum size, which is `0.0` for non `Exact`
+/// sizes, and the parent will shrink to fit it's other content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum Size {
     /// Try to fit all children exactly
     Shrink,
@@ -10,6 +17,11 @@ pub enum Size {
     /// Fill the available space using a weight in units.
     /// The available space is divided between `Fill` sizes according to their weight.
     Fill(u32),
+    /// A percentage of the size of the parent. Unlike `Fill`, this doesn't depend on the space taken by siblings.
+    Percent(f32),
+    /// A percentage of the size of the parent plus a number of units, as written with `calc(100% - 20)` in .pwss
+    /// files. Like `Percent`, this doesn't depend on the space taken by siblings. Never resolves to a negative size.
+    Calc(f32, f32),
 }
 
 /// Alignment
@@ -34,6 +46,7 @@ pub enum Direction {
 /// A rectangle
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Rectangle {
     pub left: f32,
     pub top: f32,
@@ -41,13 +54,141 @@ pub struct Rectangle {
     pub bottom: f32,
 }
 
+/// The computed layout of a widget and all of it's descendants, as returned by
+/// [`Ui::layout_snapshot`](../struct.Ui.html#method.layout_snapshot).
+///
+/// Snapshots only contain the structure and layout of the ui, so they are stable between runs and can be compared
+/// against a stored snapshot to catch layout regressions without rendering. Components are transparent: their view
+/// shows up in place of the component. Sizes and positions are rounded to hundredths of a unit, like in a
+/// [`DrawSnapshot`](../draw/struct.DrawSnapshot.html).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct LayoutSnapshot {
+    /// The widget name, as used in selectors.
+    pub widget: String,
+    /// The class of the widget, if it has one.
+    pub class: Option<String>,
+    /// The path of the widget in the tree, made of the widget names and child indices from the root down,
+    /// like `column/row[0]/button[2]`. Unlike keys, paths don't depend on hashing so they are stable between releases.
+    pub path: String,
+    /// The size requested by the widget, including margins.
+    pub size: (Size, Size),
+    /// The layout rect of the widget, excluding margins. `None` if the widget wasn't drawn, for example because it
+    /// is the content of a closed dropdown.
+    pub layout: Option<Rectangle>,
+    /// Snapshots of the child widgets, in order.
+    pub children: Vec<LayoutSnapshot>,
+}
+
+#[cfg(feature = "serde")]
+impl LayoutSnapshot {
+    /// Serializes the snapshot to pretty printed json.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("layout snapshots can always be serialized")
+    }
+}
+
+impl LayoutSnapshot {
+    /// Rounds a value to hundredths of a unit, and gets rid of negative zeroes so they serialize the same as zeroes.
+    pub(crate) fn round(x: f32) -> f32 {
+        (x * 100.0).round() / 100.0 + 0.0
+    }
+
+    pub(crate) fn round_size(size: Size) -> Size {
+        match size {
+            Size::Exact(x) => Size::Exact(Self::round(x)),
+            Size::Percent(x) => Size::Percent(Self::round(x)),
+            Size::Calc(percent, offset) => Size::Calc(Self::round(percent), Self::round(offset)),
+            size => size,
+        }
+    }
+
+    pub(crate) fn round_rect(rect: Rectangle) -> Rectangle {
+        Rectangle {
+            left: Self::round(rect.left),
+            top: Self::round(rect.top),
+            right: Self::round(rect.right),
+            bottom: Self::round(rect.bottom),
+        }
+    }
+}
+
 impl Size {
-    /// Resolve the `Size` to an actual size
+    /// Resolve the `Size` to an actual size.
+    /// `Fill` sizes never resolve to a negative size, even if there is no space available.
     pub fn resolve(self, available_space: f32, available_parts: u32) -> f32 {
         match self {
             Size::Shrink => 0.0,
             Size::Exact(wanted) => wanted,
-            Size::Fill(parts) => (available_space * parts as f32) / available_parts as f32,
+            Size::Fill(parts) => (available_space.max(0.0) * parts as f32) / available_parts as f32,
+            Size::Percent(percent) => available_space.max(0.0) * percent / 100.0,
+            Size::Calc(percent, offset) => (available_space.max(0.0) * percent / 100.0 + offset).max(0.0),
+        }
+    }
+
+    /// Resolve the `Size` along the axis on which a container stacks it's children, such as the vertical axis of a
+    /// `Column`. `Percent` and `Calc` sizes are relative to the `container` size, while `Fill` sizes divide the
+    /// `remaining` space that isn't [reserved](#method.reserved_size) by their siblings.
+    pub fn resolve_stacked(self, container: f32, remaining: f32, available_parts: u32) -> f32 {
+        match self {
+            Size::Percent(_) | Size::Calc(..) => self.resolve(container, 0),
+            other => other.resolve(remaining, available_parts),
+        }
+    }
+
+    /// Get the space that this `Size` takes from a container of size `container` before `Fill` sizes are resolved,
+    /// which is 0 for `Shrink` and `Fill` sizes.
+    pub fn reserved_size(&self, container: f32) -> f32 {
+        match self {
+            Size::Exact(wanted) => *wanted,
+            Size::Percent(_) | Size::Calc(..) => self.resolve(container, 0),
+            _ => 0.0,
+        }
+    }
+
+    /// Resolve the sizes of children that are stacked along an axis of a container, such as the heights of the children
+    /// of a `Column`. Each child is given as it's `Size` with the `(min, max)` limits of that size.
+    /// `Percent` and `Calc` sizes are relative to the `container` size, while `Fill` sizes divide the `space` that's
+    /// left after the other sizes are resolved. Sizes are clamped to their limits before the space is divided, so space
+    /// that a clamped `Fill` size can't take is redistributed over the other `Fill` sizes.
+    pub fn resolve_stacked_limited(children: &[(Size, (f32, f32))], container: f32, space: f32) -> Vec<f32> {
+        let clamp = |size: f32, (min, max): (f32, f32)| size.min(max).max(min);
+        let mut resolved = children
+            .iter()
+            .map(|&(size, limits)| match size {
+                Size::Fill(_) => None,
+                other => Some(clamp(other.resolve_stacked(container, 0.0, 0), limits)),
+            })
+            .collect::<Vec<_>>();
+
+        // every pass either freezes at least one `Fill` size at it's limit, or resolves all of them.
+        loop {
+            let remaining = space - resolved.iter().flatten().sum::<f32>();
+            let parts = children
+                .iter()
+                .zip(resolved.iter())
+                .filter(|(_, resolved)| resolved.is_none())
+                .map(|((size, _), _)| size.parts())
+                .sum();
+
+            let mut clamped = false;
+            for ((size, limits), resolved) in children.iter().zip(resolved.iter_mut()) {
+                if resolved.is_none() {
+                    let wanted = size.resolve(remaining, parts);
+                    if clamp(wanted, *limits) != wanted {
+                        *resolved = Some(clamp(wanted, *limits));
+                        clamped = true;
+                    }
+                }
+            }
+
+            if !clamped {
+                return children
+                    .iter()
+                    .zip(resolved)
+                    .map(|((size, _), resolved)| resolved.unwrap_or_else(|| size.resolve(remaining, parts)))
+                    .collect();
+            }
         }
     }
 
@@ -135,6 +276,21 @@ impl Rectangle {
         x >= self.left && x < self.right && y >= self.top && y < self.bottom
     }
 
+    /// Returns how close the queried point is to the rectangle, ranging from `1.0` when the point is inside the
+    /// rectangle to `0.0` when the point is `radius` or further away from it.
+    pub fn proximity(&self, x: f32, y: f32, radius: f32) -> f32 {
+        let dx = (self.left - x).max(x - self.right).max(0.0);
+        let dy = (self.top - y).max(y - self.bottom).max(0.0);
+        let distance = (dx * dx + dy * dy).sqrt();
+        if distance <= 0.0 {
+            1.0
+        } else if distance >= radius {
+            0.0
+        } else {
+            1.0 - distance / radius
+        }
+    }
+
     /// Returns the rectangle that is covered both by `self` and `other`.
     /// `None` is returned if the rectangles do not overlap.
     pub fn intersect(&self, other: &Rectangle) -> Option<Rectangle> {
@@ -188,6 +344,16 @@ impl Rectangle {
         }
     }
 
+    /// Scale the rectangle relative to the origin
+    pub fn scale(&self, scale: f32) -> Rectangle {
+        Rectangle {
+            left: self.left * scale,
+            top: self.top * scale,
+            right: self.right * scale,
+            bottom: self.bottom * scale,
+        }
+    }
+
     /// Increase the size of the rectangle on the right and bottom side.
     pub fn grow(&self, w: f32, h: f32) -> Rectangle {
         Rectangle {
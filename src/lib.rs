@@ -1,24 +1,28 @@
 #![doc = include_str!("../README.md")]
 #![deny(missing_docs)]
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::VecDeque;
 use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
 use std::sync::{Arc, Mutex, MutexGuard};
 
 use futures::future::poll_fn;
 use graphics::Graphics;
 use node::GenericNode;
 use owning_ref::{MutexGuardRef, MutexGuardRefMut};
-use widget::Context;
+use widget::{Context, CursorResponse};
 
+use crate::cache::Cache;
 use crate::component::Component;
-use crate::draw::DrawList;
-use crate::event::Event;
-use crate::layout::Rectangle;
+use crate::draw::{DrawList, Update};
+use crate::event::{CursorIcon, Event, Key, NavigateDirection};
+use crate::layout::{LayoutSnapshot, Rectangle};
 use crate::node::component_node::ComponentNode;
 use crate::style::tree::Query;
-use crate::style::Style;
+use crate::style::{Style, StyleInspection};
 use crate::tracker::ManagedState;
 
 mod atlas;
@@ -27,6 +31,7 @@ pub mod backend;
 mod bitset;
 /// Texture cache for styles and text
 pub mod cache;
+mod color_glyphs;
 /// The component trait.
 pub mod component;
 /// Primitives used for drawing
@@ -50,6 +55,7 @@ pub mod sandbox;
 pub mod style;
 /// Primitives for rendering text
 pub mod text;
+mod timer;
 /// Utility for tracking state conveniently.
 pub mod tracker;
 /// User interface widgets
@@ -61,12 +67,14 @@ pub mod widget;
 /// [`DrawList`](draw/struct.DrawList.html) that can be rendered using your own renderer implementation.
 /// Alternatively, you can use one of the following included wrappers:
 /// - [`wgpu::Ui`](backend/wgpu/struct.Ui.html) Renders using [wgpu](https://github.com/gfx-rs/wgpu).
+/// - [`software::Ui`](backend/software/struct.Ui.html) Renders to an image on the cpu, for headless testing.
 ///
 /// # Async support
-/// Components can submit futures to the [`Context`](component/struct.Context.html) using
-/// [`wait()`](component/struct.Context.html#method.wait) and
-/// [`stream()`](component/struct.Context.html#method.stream). These futures will update
-/// those components when they complete or yield messages.
+/// Components can submit futures to the [`Runtime`](prelude/struct.Runtime.html) using
+/// [`wait()`](prelude/struct.Runtime.html#method.wait) and
+/// [`stream()`](prelude/struct.Runtime.html#method.stream). These futures will update
+/// those components when they complete or yield messages. Messages that should arrive after a timeout can be
+/// submitted using [`delay()`](prelude/struct.Runtime.html#method.delay).
 /// To support this, you must make sure that the poll method on `Ui` is called appropriately
 /// since the `Ui` can't be submitted to a typical executor.
 ///
@@ -75,20 +83,37 @@ pub mod widget;
 pub struct Ui<C: 'static + Component> {
     data: Arc<Mutex<Data<C>>>,
     style: Arc<Style>,
+    retired_updates: Vec<Update>,
+    retired_caches: Vec<Arc<Mutex<Cache>>>,
     task_created: bool,
     viewport: Rectangle,
     hidpi_scale: f32,
 }
 
+/// Changed vertex ranges that are less than this many vertices apart are uploaded as a single range.
+const VERTEX_DIFF_MERGE_GAP: usize = 24;
+
 struct Data<C: 'static + Component> {
     #[allow(unused)]
     state: ManagedState,
     root_node: ComponentNode<'static, C>,
     viewport: Rectangle,
     redraw: bool,
+    animating: bool,
+    transitions: bool,
     cursor: (f32, f32),
+    hovered: Option<Vec<usize>>,
+    capturing: bool,
+    tracking_cursor: bool,
+    portals: bool,
+    cursor_icon: CursorIcon,
+    buttons_held: usize,
     hidpi_scale: f32,
     output: VecDeque<C::Output>,
+    subscribers: Vec<Subscriber<C::Output>>,
+    previous_vertices: Option<Vec<draw::Vertex>>,
+    focusable: Vec<Rectangle>,
+    regions: Vec<(Rectangle, CursorResponse)>,
 }
 
 impl<C: 'static + Component> Ui<C> {
@@ -117,11 +142,25 @@ impl<C: 'static + Component> Ui<C> {
                     bottom: viewport.bottom / hidpi_scale,
                 },
                 redraw: true,
+                animating: false,
+                transitions: false,
                 cursor: (0.0, 0.0),
+                hovered: None,
+                capturing: false,
+                tracking_cursor: false,
+                portals: false,
+                cursor_icon: CursorIcon::Default,
+                buttons_held: 0,
                 hidpi_scale,
                 output: Default::default(),
+                subscribers: Vec::new(),
+                previous_vertices: None,
+                focusable: Vec::new(),
+                regions: Vec::new(),
             })),
             style,
+            retired_updates: Vec::new(),
+            retired_caches: Vec::new(),
             task_created: false,
             viewport: Rectangle {
                 left: viewport.left / hidpi_scale,
@@ -138,6 +177,32 @@ impl<C: 'static + Component> Ui<C> {
         self.style.graphics()
     }
 
+    /// Replaces the style of the ui, for example to edit a theme while the ui is running.
+    /// Returns an error if the style fails to load, in which case the current style is kept.
+    ///
+    /// All widgets are restyled and the ui is redrawn. Textures of the previous style are kept by the backend, so
+    /// images that were loaded through it remain valid. `Graphics` loaders of the previous style keep working, their
+    /// texture updates are passed to the backend for as long as they are alive. New images should preferably be loaded
+    /// using a `Graphics` loader from [`graphics()`](#method.graphics) after the style was replaced.
+    /// [`PrimitiveCache`](widget/primitive_cache/struct.PrimitiveCache.html) widgets keep their primitives until their
+    /// version changes.
+    pub fn set_style<S, E>(&mut self, style: S) -> anyhow::Result<()>
+    where
+        S: TryInto<Style, Error = E>,
+        anyhow::Error: From<E>,
+    {
+        let style = Arc::new(style.try_into()?);
+        let previous = std::mem::replace(&mut self.style, style.clone());
+        // `Graphics` loaders of the previous style can still queue uploads, which have to reach the backend.
+        self.retired_caches.push(previous.cache());
+
+        let mut data = self.data.lock().unwrap();
+        data.root_node.set_dirty();
+        data.root_node.style(&mut Query::from_style(style), (0, 1));
+        data.redraw = true;
+        Ok(())
+    }
+
     /// Create a task that will drive all ui futures.
     /// Takes an `on_redraw` closure that will be called to wake up the main thread for redrawing the ui when required.
     /// This method will panic if it's called a second time.
@@ -153,11 +218,14 @@ impl<C: 'static + Component> Ui<C> {
                 if context.redraw_requested() {
                     (on_redraw)();
                     data.redraw = true;
+                } else if context.animation_requested() {
+                    (on_redraw)();
                 }
                 if context.rebuild_requested() {
                     data.root_node.set_dirty();
                 }
-                data.output.extend(context);
+                data.animating |= context.animation_requested();
+                data.push_output(context);
 
                 std::task::Poll::Pending
             } else {
@@ -168,6 +236,8 @@ impl<C: 'static + Component> Ui<C> {
 
     /// Updates the root component with a message.
     pub fn update(&mut self, message: C::Message) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("ui_update").entered();
         let mut data = self.data.lock().unwrap();
         let mut context = Context::new(data.redraw, false, data.cursor);
         data.root_node.update(message, &mut context);
@@ -175,7 +245,15 @@ impl<C: 'static + Component> Ui<C> {
             data.root_node.set_dirty();
         }
         data.redraw |= context.redraw_requested();
-        data.output.extend(context);
+        data.animating |= context.animation_requested();
+        data.push_output(context);
+    }
+
+    /// Inserts a block of text into the focused widget, replacing it's selection.
+    /// This is a shorthand for handling an [`Event::InsertText`](event/enum.Event.html#variant.InsertText), which is
+    /// a lot faster than sending every character as a separate [`Event::Text`](event/enum.Event.html#variant.Text).
+    pub fn insert_text(&mut self, text: impl Into<String>) -> bool {
+        self.handle_event(Event::InsertText(text.into()))
     }
 
     /// Handles a ui [`Event`](event/struct.Event.html).
@@ -183,14 +261,77 @@ impl<C: 'static + Component> Ui<C> {
     /// It's up to the user to make sure that the `waker` will schedule a call to [`poll()`](#method.poll) on this `Ui`.
     ///
     /// Returns `true` if the event was handled in a way that it's captured by the ui.
+    ///
+    /// Cursor moves that don't enter or leave any widget are not dispatched to the widgets, as long as every widget
+    /// under the cursor only [responds to hovering](widget/trait.Widget.html#method.cursor_response). The hovered
+    /// widgets are looked up in the layouts of the last draw, so the view doesn't have to be traversed. Moves are
+    /// always dispatched while a mouse button is held, while a widget has exclusive focus, while a widget is
+    /// [tracking the cursor](widget/struct.Context.html#method.track_cursor) or while a redraw is pending, so drags,
+    /// capturing widgets and widgets that react to a nearby cursor always receive all cursor updates.
     pub fn handle_event(&mut self, mut event: Event) -> bool {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("handle_event", ?event).entered();
         let mut data = self.data.lock().unwrap();
 
-        if let Event::Cursor(x, y) = event {
-            event = Event::Cursor(x / data.hidpi_scale, y / data.hidpi_scale);
-            data.cursor = (x / data.hidpi_scale, y / data.hidpi_scale);
+        if let Event::Navigate(direction) = event {
+            match navigate_target(&data.focusable, data.cursor, direction) {
+                Some((x, y)) => event = Event::Cursor(x * data.hidpi_scale, y * data.hidpi_scale),
+                None => return false,
+            }
         }
 
+        match event {
+            Event::Cursor(x, y) => {
+                let (x, y) = (x / data.hidpi_scale, y / data.hidpi_scale);
+                event = Event::Cursor(x, y);
+                data.cursor = (x, y);
+
+                let hovered = data
+                    .regions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (rect, _))| rect.point_inside(x, y))
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+                let tracked = hovered
+                    .iter()
+                    .any(|&index| data.regions[index].1 == CursorResponse::Track);
+                let unchanged = data.hovered.as_ref() == Some(&hovered);
+                data.hovered = Some(hovered);
+                if unchanged
+                    && !tracked
+                    && !data.capturing
+                    && !data.tracking_cursor
+                    && data.buttons_held == 0
+                    && !data.redraw
+                    && !data.root_node.dirty()
+                {
+                    return false;
+                }
+            }
+            Event::Scroll(x, y) => {
+                event = Event::Scroll(x / data.hidpi_scale, y / data.hidpi_scale);
+            }
+            Event::Press(Key::LeftMouseButton | Key::MiddleMouseButton | Key::RightMouseButton) => {
+                data.buttons_held += 1;
+            }
+            Event::Release(Key::LeftMouseButton | Key::MiddleMouseButton | Key::RightMouseButton) => {
+                data.buttons_held = data.buttons_held.saturating_sub(1);
+            }
+            // style transitions are advanced for all widgets, since not every widget passes events to it's children.
+            // the view is only walked while a transition is running.
+            Event::Animate
+                if (std::mem::take(&mut data.transitions) | self.style.take_transition_started())
+                    && data.root_node.view().animate_transitions() =>
+            {
+                data.transitions = true;
+                data.redraw = true;
+                data.animating = true;
+            }
+            _ => (),
+        }
+
+        let cursor_moved = matches!(event, Event::Cursor(_, _));
         let mut context = Context::new(data.redraw, false, data.cursor);
 
         let result = {
@@ -200,11 +341,29 @@ impl<C: 'static + Component> Ui<C> {
                 w.resolve(data.viewport.width(), w.parts()),
                 h.resolve(data.viewport.height(), h.parts()),
             );
+            // portals are drawn on top of everything, so their content gets the event first. The widgets below don't see
+            // the cursor while it's over the content of a portal.
+            if data.portals {
+                view.portal_event(event.clone(), &mut context);
+            }
+            let event = match event {
+                Event::Cursor(_, _) if context.portal_hovered() => {
+                    context.set_cursor((f32::INFINITY, f32::INFINITY));
+                    Event::Cursor(f32::INFINITY, f32::INFINITY)
+                }
+                other => other,
+            };
             view.event(layout, data.viewport, event, &mut context);
             view.focused()
         };
+        data.capturing = result;
 
         data.redraw |= context.redraw_requested();
+        data.animating |= context.animation_requested();
+        if cursor_moved {
+            data.tracking_cursor = context.cursor_tracking_requested();
+            data.cursor_icon = context.cursor_icon_requested().unwrap_or_default();
+        }
 
         let mut outer_context = Context::new(data.redraw, context.rebuild_requested(), data.cursor);
 
@@ -212,16 +371,37 @@ impl<C: 'static + Component> Ui<C> {
             data.root_node.update(message, &mut outer_context);
         }
 
-        if outer_context.rebuild_requested() {
+        if outer_context.rebuild_requested() || self.style.take_layout_changed() {
             data.root_node.set_dirty();
         }
 
         data.redraw |= outer_context.redraw_requested();
-        data.output.extend(outer_context);
+        data.animating |= outer_context.animation_requested();
+        data.push_output(outer_context);
 
         result
     }
 
+    /// Moves the cursor to the nearest focusable widget in a direction.
+    /// This is a shorthand for handling an [`Event::Navigate`](event/enum.Event.html#variant.Navigate).
+    /// Only widgets that were visible during the last [`draw()`](#method.draw) can be selected.
+    ///
+    /// Returns `true` if a widget was found in the given direction.
+    pub fn navigate(&mut self, direction: NavigateDirection) -> bool {
+        let target = {
+            let data = self.data.lock().unwrap();
+            navigate_target(&data.focusable, data.cursor, direction)
+                .map(|(x, y)| (x * data.hidpi_scale, y * data.hidpi_scale))
+        };
+        match target {
+            Some((x, y)) => {
+                self.handle_event(Event::Cursor(x, y));
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Resizes the viewport.
     /// This forces the view to be rerendered, but only if the size actually changed.
     pub fn resize(&mut self, viewport: Rectangle, hidpi_scale: f32) {
@@ -250,9 +430,34 @@ impl<C: 'static + Component> Ui<C> {
     }
 
     /// Perform a hitdetect on the root component,
-    ///  to see if a future pointer event would be handled
+    ///  to see if a future pointer event would be handled.
+    /// Like [`Event::Cursor`](event/enum.Event.html#variant.Cursor), `x` and `y` are in physical pixels, so they are
+    /// divided by the hidpi scale before they are compared to the layout of the widgets, which is in logical pixels.
+    ///
+    /// ```
+    /// # use pixel_widgets::prelude::*;
+    /// # struct Small;
+    /// # impl Component for Small {
+    /// #     type State = ();
+    /// #     type Message = ();
+    /// #     type Output = ();
+    /// #     fn mount(&self, _: &mut Runtime<()>) {}
+    /// #     fn view(&self, _: &()) -> Node<()> {
+    /// #         Button::new(Spacer).into_node()
+    /// #     }
+    /// # }
+    /// for scale in [1.25, 1.5] {
+    ///     let button = RuleBuilder::new("button").width(10.0).height(10.0).margin_all(0.0);
+    ///     let style = StyleBuilder::default().rule(button);
+    ///     let ui = Ui::new(Small, Rectangle::from_wh(100.0 * scale, 100.0 * scale), scale, style).unwrap();
+    ///     // the button is 10 logical pixels wide, so it ends at 10 * scale physical pixels.
+    ///     assert!(ui.hit(10.0 * scale - 0.5, 10.0 * scale - 0.5));
+    ///     assert!(!ui.hit(10.0 * scale + 0.5, 10.0 * scale + 0.5));
+    /// }
+    /// ```
     pub fn hit(&self, x: f32, y: f32) -> bool {
         let data = self.data.lock().unwrap();
+        let (x, y) = (x / data.hidpi_scale, y / data.hidpi_scale);
         let view = data.root_node.view();
         let (w, h) = view.size();
         let layout = Rectangle::from_wh(
@@ -262,6 +467,81 @@ impl<C: 'static + Component> Ui<C> {
         view.hit(layout, data.viewport, x, y, true)
     }
 
+    /// Inspect the style that was resolved for the widget with the given key.
+    /// The key must be the same as the one that was passed to [`IntoNode::key`](node/trait.IntoNode.html#method.key).
+    /// Nested components are searched as well. The key of a component inspects the root widget of it's view.
+    ///
+    /// Returns the stylesheet that the widget uses for drawing, along with all the rules that matched it.
+    /// This is intended for debugging styles. If no widget with the key is found, `None` is returned.
+    pub fn inspect_style<K: Hash>(&self, key: K) -> Option<StyleInspection> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let data = self.data.lock().unwrap();
+        let mut view = data.root_node.view();
+        view.inspect_style(hasher.finish())
+    }
+
+    /// Lays out the view and returns a [`LayoutSnapshot`](layout/struct.LayoutSnapshot.html) of the whole widget tree.
+    /// With the `serde` feature enabled, the snapshot can be serialized using
+    /// [`to_json`](layout/struct.LayoutSnapshot.html#method.to_json) and compared against a stored snapshot, to test
+    /// for layout regressions without rendering.
+    ///
+    /// The view is drawn to compute the layout, but the result is discarded.
+    pub fn layout_snapshot(&mut self) -> LayoutSnapshot {
+        let data = self.data.lock().unwrap();
+        let mut view = data.root_node.view();
+        let (w, h) = view.size();
+        let layout = Rectangle::from_wh(
+            w.resolve(data.viewport.width(), w.parts()),
+            h.resolve(data.viewport.height(), h.parts()),
+        );
+        view.draw(layout, data.viewport);
+        view.layout_snapshot(None)
+    }
+
+    /// Draws the view and returns a [`DrawSnapshot`](draw/struct.DrawSnapshot.html) of the resulting draw commands.
+    /// The snapshot can be compared against a stored snapshot, to test for rendering regressions without a gpu. With
+    /// the `serde` feature enabled, it can be serialized using [`to_json`](draw/struct.DrawSnapshot.html#method.to_json).
+    ///
+    /// Like [`draw_primitives`](#method.draw_primitives), the texture updates are kept until the next `DrawList` is
+    /// generated, and the snapshot doesn't affect [vertex diffing](#method.set_vertex_diffing).
+    ///
+    /// ```
+    /// # use pixel_widgets::prelude::*;
+    /// # use pixel_widgets::draw::{Color, CommandSnapshot, Primitive};
+    /// # struct Red;
+    /// # impl Component for Red {
+    /// #     type State = ();
+    /// #     type Message = ();
+    /// #     type Output = ();
+    /// #     fn mount(&self, _: &mut Runtime<()>) {}
+    /// #     fn view(&self, _: &()) -> Node<()> {
+    /// #         view! {
+    /// #             Canvas {
+    /// #                 draw: |rect| vec![Primitive::DrawRect(rect, Color::red())],
+    /// #                 measure: || (20.0, 10.0),
+    /// #             }
+    /// #         }
+    /// #     }
+    /// # }
+    /// let mut ui = Ui::new(Red, Rectangle::from_wh(200.0, 100.0), 2.0, StyleBuilder::default()).unwrap();
+    /// let snapshot = ui.draw_snapshot();
+    /// assert!(snapshot.commands.iter().any(|command| match command {
+    ///     CommandSnapshot::Colored { bounds, colors, .. } => {
+    ///         *bounds == Rectangle::from_wh(40.0, 20.0) && colors == &[[255, 0, 0, 255]]
+    ///     }
+    ///     _ => false,
+    /// }));
+    /// ```
+    pub fn draw_snapshot(&mut self) -> draw::DrawSnapshot {
+        let viewport = self.data.lock().unwrap().viewport;
+        let scale = self.hidpi_scale;
+        let target = (viewport.width() * scale, viewport.height() * scale);
+        let mut list = self.draw_lists(&[(viewport, target)], false).pop().unwrap();
+        self.retired_updates = std::mem::take(&mut list.updates);
+        list.snapshot(target.0, target.1)
+    }
+
     /// Return an immutable reference to the root component
     pub fn props(&self) -> impl '_ + Deref<Target = C> {
         MutexGuardRef::new(self.data.lock().unwrap()).map(|d| d.root_node.props())
@@ -275,400 +555,949 @@ impl<C: 'static + Component> Ui<C> {
     }
 
     /// Returns an iterator over the output messages produced by the root component.
+    /// Messages that were delivered to a [`subscribe`](#method.subscribe)r are not returned here.
     pub fn output(&mut self) -> impl '_ + Iterator<Item = C::Output> {
         Output(self.data.lock().unwrap())
     }
 
+    /// Subscribes to the output messages produced by the root component for which `filter` returns `true`.
+    /// Every subscriber receives it's own copy of the matching messages, in the order they were produced.
+    /// Messages that don't match any subscriber are still available through [`output()`](#method.output).
+    ///
+    /// Dropping the returned [`Subscription`](struct.Subscription.html) unsubscribes it.
+    ///
+    /// ```
+    /// # use pixel_widgets::prelude::*;
+    /// # struct Echo;
+    /// # impl Component for Echo {
+    /// #     type State = ();
+    /// #     type Message = u32;
+    /// #     type Output = u32;
+    /// #     fn mount(&self, _: &mut Runtime<u32>) {}
+    /// #     fn view(&self, _: &()) -> Node<u32> {
+    /// #         Spacer.into_node()
+    /// #     }
+    /// #     fn update(&self, message: u32, _: DetectMut<()>, _: &mut Runtime<u32>, context: &mut Context<u32>) {
+    /// #         context.push(message);
+    /// #     }
+    /// # }
+    /// let mut ui = Ui::new(Echo, Rectangle::from_wh(100.0, 100.0), 1.0, StyleBuilder::default()).unwrap();
+    /// let mut even = ui.subscribe(|output| output % 2 == 0);
+    /// let mut all = ui.subscribe(|_| true);
+    /// for message in 1..=4 {
+    ///     ui.update(message);
+    /// }
+    /// assert_eq!(even.try_iter().collect::<Vec<_>>(), vec![2, 4]);
+    /// assert_eq!(all.try_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    ///
+    /// drop(all);
+    /// ui.update(5);
+    /// ui.update(6);
+    /// assert_eq!(even.try_iter().collect::<Vec<_>>(), vec![6]);
+    /// assert_eq!(ui.output().collect::<Vec<_>>(), vec![5]);
+    /// ```
+    pub fn subscribe<F>(&mut self, filter: F) -> Subscription<C::Output>
+    where
+        C::Output: Clone + Send,
+        F: 'static + Send + Fn(&C::Output) -> bool,
+    {
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        let mut data = self.data.lock().unwrap();
+        data.subscribers.push(Box::new(move |output| {
+            if sender.is_closed() {
+                Delivery::Closed
+            } else if filter(output) {
+                sender
+                    .unbounded_send(output.clone())
+                    .map_or(Delivery::Closed, |_| Delivery::Delivered)
+            } else {
+                Delivery::Skipped
+            }
+        }));
+        Subscription { receiver }
+    }
+
+    /// Enables or disables vertex diffing. When enabled, the [`DrawList`](draw/struct.DrawList.html) returned by
+    /// [`draw()`](#method.draw) contains the ranges of vertices that changed since the previous call,
+    /// so that backends can update only those parts of a persistent vertex buffer.
+    /// This requires keeping a copy of the previous vertices around, so it's disabled by default.
+    pub fn set_vertex_diffing(&mut self, enabled: bool) {
+        let mut data = self.data.lock().unwrap();
+        if enabled != data.previous_vertices.is_some() {
+            data.previous_vertices = if enabled { Some(Vec::new()) } else { None };
+        }
+    }
+
+    /// Returns the icon that the mouse cursor should have, as requested by the widgets under the cursor at the last
+    /// cursor move. The host should apply this icon to the OS cursor after handling events, for example using
+    /// [`convert_cursor_icon`](backend/winit/fn.convert_cursor_icon.html) when using winit.
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.data.lock().unwrap().cursor_icon
+    }
+
     /// Returns true if the ui needs to be redrawn. If the ui doesn't need to be redrawn the
     /// [`Command`s](draw/struct.Command.html) from the last [`draw`](#method.draw) may be used again.
+    /// Pending texture updates, for example from
+    /// [`Graphics::update_image`](graphics/struct.Graphics.html#method.update_image), also require a redraw.
     pub fn needs_redraw(&self) -> bool {
         let data = self.data.lock().unwrap();
-        data.redraw || data.root_node.dirty()
+        data.redraw
+            || data.root_node.dirty()
+            || !self.retired_updates.is_empty()
+            || self.style.cache().lock().unwrap().has_updates()
+            || self
+                .retired_caches
+                .iter()
+                .any(|cache| cache.lock().unwrap().has_updates())
+    }
+
+    /// Returns true if a widget requested another animation frame using
+    /// [`Context::animate`](widget/struct.Context.html#method.animate).
+    /// While the ui is animating, [`animate()`](#method.animate) or [`draw()`](#method.draw) should be called every
+    /// frame, even if the ui doesn't [need to be redrawn](#method.needs_redraw).
+    pub fn is_animating(&self) -> bool {
+        self.data.lock().unwrap().animating
+    }
+
+    /// Sends an [`Event::Animate`](event/enum.Event.html#variant.Animate) to the widgets if the ui
+    /// [is animating](#method.is_animating), without drawing the ui.
+    /// Use this on frames where the ui doesn't need to be redrawn. If it needs to be redrawn afterwards, the animation
+    /// changed what the widgets look like.
+    pub fn animate(&mut self) {
+        if std::mem::replace(&mut self.data.lock().unwrap().animating, false) {
+            self.handle_event(Event::Animate);
+        }
     }
 
     /// Generate a [`DrawList`](draw/struct.DrawList.html) for the view.
     pub fn draw(&mut self) -> DrawList {
-        use self::draw::*;
+        let (viewport, scale) = {
+            let data = self.data.lock().unwrap();
+            (data.viewport, data.hidpi_scale)
+        };
+        let target = (viewport.width() * scale, viewport.height() * scale);
+        self.draw_lists(&[(viewport, target)], true).pop().unwrap()
+    }
+
+    /// Generate a [`DrawList`](draw/struct.DrawList.html) for a region of the view, for example to show a zoomed in
+    /// part of the ui in an inspector next to the main view.
+    /// The `region` is in physical pixels, like the viewport. It is mapped to the whole render target of the
+    /// `DrawList`, which is `target_size` physical pixels large. The scissor rects of the clip commands are in the
+    /// physical pixels of the render target, so they stay correct when the region is zoomed in or out.
+    /// The view is always laid out for the viewport of the `Ui`, so drawing a region doesn't change the layout.
+    ///
+    /// Cursor events in a region can be routed to the ui using [`handle_region_event`](#method.handle_region_event).
+    /// Use [`draw_regions`](#method.draw_regions) to draw multiple regions in the same frame.
+    pub fn draw_region(&mut self, region: Rectangle, target_size: (f32, f32)) -> DrawList {
+        self.draw_regions(&[(region, target_size)]).pop().unwrap()
+    }
+
+    /// Generate a [`DrawList`](draw/struct.DrawList.html) for each of the `(region, target_size)` pairs, like
+    /// [`draw_region`](#method.draw_region). The view is drawn once for all of the regions, so they always show the
+    /// same state. Texture updates are only included in the first `DrawList`, and must be applied before rendering any
+    /// of them.
+    pub fn draw_regions(&mut self, regions: &[(Rectangle, (f32, f32))]) -> Vec<DrawList> {
+        let scale = self.hidpi_scale;
+        let regions = regions
+            .iter()
+            .map(|&(region, target_size)| {
+                let region = Rectangle {
+                    left: region.left / scale,
+                    top: region.top / scale,
+                    right: region.right / scale,
+                    bottom: region.bottom / scale,
+                };
+                (region, target_size)
+            })
+            .collect::<Vec<_>>();
+        self.draw_lists(&regions, false)
+    }
+
+    /// Handles a ui [`Event`](event/struct.Event.html) that happened in a region drawn using
+    /// [`draw_region`](#method.draw_region). The coordinates of cursor events are in the physical pixels of the
+    /// render target, relative to it's top left corner, and are mapped back to the region the same way the region was
+    /// mapped to the render target. Other events are handled like in [`handle_event`](#method.handle_event).
+    pub fn handle_region_event(&mut self, region: Rectangle, target_size: (f32, f32), event: Event) -> bool {
+        match event {
+            Event::Cursor(x, y) => self.handle_event(Event::Cursor(
+                region.left + x * region.width() / target_size.0,
+                region.top + y * region.height() / target_size.1,
+            )),
+            event => self.handle_event(event),
+        }
+    }
 
+    /// Generate the primitives for the view, for renderers that don't use the vertices of a
+    /// [`DrawList`](draw/struct.DrawList.html), like terminal or remote renderers.
+    ///
+    /// These are the same primitives that [`draw`](#method.draw) generates it's vertices from, including the clip and
+    /// layer primitives, in logical pixels. Portals and transforms are already resolved, so the primitives never
+    /// contain `Primitive::Portal`, `Primitive::PushTransform` or `Primitive::PopTransform`. Underlines and
+    /// strikethroughs of text are drawn using a `Primitive::DrawRect` after the `Primitive::DrawText`.
+    /// Texture updates of the style are kept until the next `DrawList` is generated, so the textures that
+    /// the primitives refer to are uploaded when the ui is drawn using the gpu again.
+    ///
+    /// ```
+    /// # use pixel_widgets::prelude::*;
+    /// # use pixel_widgets::draw::Primitive;
+    /// # struct Small;
+    /// # impl Component for Small {
+    /// #     type State = ();
+    /// #     type Message = ();
+    /// #     type Output = ();
+    /// #     fn mount(&self, _: &mut Runtime<()>) {}
+    /// #     fn view(&self, _: &()) -> Node<()> {
+    /// #         Text::new("hello").into_node()
+    /// #     }
+    /// # }
+    /// let mut ui = Ui::new(Small, Rectangle::from_wh(200.0, 100.0), 2.0, StyleBuilder::default()).unwrap();
+    /// let primitives = ui.draw_primitives();
+    /// // the viewport is 100 logical pixels wide
+    /// assert!(primitives.iter().any(|primitive| match primitive {
+    ///     Primitive::DrawText(text, rect) => text.text == "hello" && rect.right <= 100.0,
+    ///     _ => false,
+    /// }));
+    /// ```
+    pub fn draw_primitives(&mut self) -> Vec<draw::Primitive<'static>> {
+        let primitives = self.draw_view();
+        self.finish_draw(&mut []);
+        primitives
+    }
+
+    /// Draws the view once and generates a `DrawList` for each region, which is in logical pixels, and the size of it's
+    /// render target, which is in physical pixels.
+    fn draw_lists(&mut self, regions: &[(Rectangle, (f32, f32))], diff: bool) -> Vec<DrawList> {
+        let mut primitives = self.draw_view();
         let mut data = self.data.lock().unwrap();
 
-        let viewport = data.viewport;
-        let viewport_center = (
-            (viewport.left + viewport.right) * 0.5,
-            (viewport.top + viewport.bottom) * 0.5,
-        );
-        let viewport_inverse_size = (
-            ((viewport.right - viewport.left) * 0.5).recip(),
-            ((viewport.top - viewport.bottom) * -0.5).recip(),
-        );
+        let mut lists = Vec::with_capacity(regions.len());
+        for (i, &(region, target_size)) in regions.iter().enumerate() {
+            let primitives = if i + 1 < regions.len() {
+                primitives.clone()
+            } else {
+                std::mem::take(&mut primitives)
+            };
+            let (vertices, commands) = tessellate(primitives, region, target_size);
+
+            let changed = data.previous_vertices.as_mut().filter(|_| diff).and_then(|previous| {
+                let changed = DrawList::diff(previous.as_slice(), vertices.as_slice(), VERTEX_DIFF_MERGE_GAP);
+                previous.clear();
+                previous.extend_from_slice(vertices.as_slice());
+                changed
+            });
+
+            lists.push(DrawList {
+                updates: Vec::new(),
+                vertices,
+                commands,
+                changed,
+            });
+        }
+        drop(data);
+
+        self.finish_draw(&mut lists);
+        lists
+    }
+
+    /// Draws the view and resolves the portals, transforms and text decorations of the primitives, in logical pixels.
+    fn draw_view(&mut self) -> Vec<draw::Primitive<'static>> {
+        let mut data = self.data.lock().unwrap();
 
-        let primitives = {
+        let viewport = data.viewport;
+        let (primitives, portals) = {
             let mut view = data.root_node.view();
             let (w, h) = view.size();
             let layout = Rectangle::from_wh(
                 w.resolve(viewport.width(), w.parts()),
                 h.resolve(viewport.height(), h.parts()),
             );
-            view.draw(layout, viewport)
+            let primitives = view.draw(layout, viewport);
+            let portals = primitives
+                .iter()
+                .any(|primitive| matches!(primitive, draw::Primitive::Portal(_)));
+            let primitives = resolve_transforms(primitives, (1.0, (0.0, 0.0)));
+            (resolve_decorations(resolve_portals(primitives, viewport)), portals)
         };
         data.redraw = false;
+        data.portals = portals;
+
+        let mut focusable = std::mem::take(&mut data.focusable);
+        let mut regions = std::mem::take(&mut data.regions);
+        focusable.clear();
+        regions.clear();
+        data.root_node.view().drawn_rects(&mut focusable, &mut regions);
+        data.focusable = focusable;
+        data.regions = regions;
+        data.hovered = None;
+
+        primitives
+    }
+
+    /// Animates the widgets after the view was drawn, and gives the texture updates to the first `DrawList`.
+    /// The updates are kept for the next draw if there are no `DrawList`s.
+    fn finish_draw(&mut self, lists: &mut [DrawList]) {
+        self.data.lock().unwrap().animating = false;
+        self.handle_event(Event::Animate);
 
-        struct Layer {
-            vtx: Vec<Vertex>,
-            cmd: Vec<Command>,
+        let mut updates = std::mem::take(&mut self.retired_updates);
+        for cache in self.retired_caches.iter() {
+            updates.extend(cache.lock().unwrap().take_updates());
         }
+        // caches that are no longer referenced by a style or a `Graphics` loader can't queue any more updates.
+        self.retired_caches.retain(|cache| Arc::strong_count(cache) > 1);
+        updates.extend(self.style.cache().lock().unwrap().take_updates());
+        match lists.first_mut() {
+            Some(first) => first.updates = updates,
+            None => self.retired_updates = updates,
+        }
+    }
+}
 
-        impl Layer {
-            fn append(&mut self, command: Command) {
-                if let Some(next) = self.cmd.last_mut().unwrap().append(command) {
-                    self.cmd.push(next);
-                }
+/// Generates the vertices and draw commands for a region of the view. The region is mapped to the whole render target,
+/// and scissor rects are in the physical pixels of the render target.
+fn tessellate(
+    primitives: Vec<draw::Primitive>,
+    region: Rectangle,
+    target_size: (f32, f32),
+) -> (Vec<draw::Vertex>, Vec<draw::Command>) {
+    use self::draw::*;
+
+    // physical pixels of the render target per logical pixel of the region.
+    let scale = (target_size.0 / region.width(), target_size.1 / region.height());
+    let hidpi_scale = scale.0.min(scale.1);
+
+    let viewport_center = ((region.left + region.right) * 0.5, (region.top + region.bottom) * 0.5);
+    let viewport_inverse_size = (
+        ((region.right - region.left) * 0.5).recip(),
+        ((region.top - region.bottom) * -0.5).recip(),
+    );
+
+    struct Layer {
+        vtx: Vec<Vertex>,
+        cmd: Vec<Command>,
+    }
+
+    impl Layer {
+        fn append(&mut self, command: Command) {
+            if let Some(next) = self.cmd.last_mut().unwrap().append(command) {
+                self.cmd.push(next);
             }
         }
+    }
 
-        let mut layers = vec![Layer {
-            vtx: Vec::new(),
-            cmd: vec![Command::Nop],
-        }];
-        let mut layer: usize = 0;
-
-        let mut scissors = vec![viewport];
-
-        let scale = data.hidpi_scale;
-        let validate_clip = move |clip: Rectangle| {
-            let v = Rectangle {
-                left: clip.left.max(0.0).min(viewport.right) * scale,
-                top: clip.top.max(0.0).min(viewport.bottom) * scale,
-                right: clip.right.max(0.0).min(viewport.right) * scale,
-                bottom: clip.bottom.max(0.0).min(viewport.bottom) * scale,
-            };
-            if v.right as u32 - v.left as u32 > 0 && v.bottom as u32 - v.top as u32 > 0 {
-                Some(v)
-            } else {
-                None
-            }
+    let mut layers = vec![Layer {
+        vtx: Vec::new(),
+        cmd: vec![Command::Nop],
+    }];
+    let mut layer: usize = 0;
+
+    let mut scissors = vec![region];
+
+    let validate_clip = move |clip: Rectangle| {
+        let v = Rectangle {
+            left: (clip.left.max(region.left).min(region.right) - region.left) * scale.0,
+            top: (clip.top.max(region.top).min(region.bottom) - region.top) * scale.1,
+            right: (clip.right.max(region.left).min(region.right) - region.left) * scale.0,
+            bottom: (clip.bottom.max(region.top).min(region.bottom) - region.top) * scale.1,
         };
+        // scissor rects are rounded to whole pixels by the backend, so empty rounded rects are skipped here.
+        if v.right.round() > v.left.round() && v.bottom.round() > v.top.round() {
+            Some(v)
+        } else {
+            None
+        }
+    };
 
-        let mut draw_enabled = true;
+    let mut draw_enabled = true;
 
-        for primitive in primitives.into_iter() {
-            match primitive {
-                Primitive::PushClip(scissor) => {
-                    scissors.push(scissor);
+    for primitive in primitives.into_iter() {
+        match primitive {
+            Primitive::PushClip(scissor) => {
+                scissors.push(scissor);
 
-                    draw_enabled = validate_clip(scissor).map_or(false, |s| {
-                        layers[layer].append(Command::Clip { scissor: s });
-                        true
-                    });
-                }
+                draw_enabled = validate_clip(scissor).map_or(false, |s| {
+                    layers[layer].append(Command::Clip { scissor: s });
+                    true
+                });
+            }
+
+            Primitive::PopClip => {
+                scissors.pop();
+                let scissor = scissors[scissors.len() - 1];
 
-                Primitive::PopClip => {
-                    scissors.pop();
-                    let scissor = scissors[scissors.len() - 1];
+                draw_enabled = validate_clip(scissor).map_or(false, |s| {
+                    layers[layer].append(Command::Clip { scissor: s });
+                    true
+                });
+            }
 
-                    draw_enabled = validate_clip(scissor).map_or(false, |s| {
-                        layers[layer].append(Command::Clip { scissor: s });
-                        true
+            Primitive::LayerUp => {
+                layer += 1;
+                while layer >= layers.len() {
+                    layers.push(Layer {
+                        vtx: Vec::new(),
+                        cmd: vec![Command::Nop],
                     });
                 }
+            }
 
-                Primitive::LayerUp => {
-                    layer += 1;
-                    while layer >= layers.len() {
-                        layers.push(Layer {
+            Primitive::LayerDown => {
+                if layer == 0 {
+                    // below everything that was drawn so far, so a new bottom layer is needed.
+                    layers.insert(
+                        0,
+                        Layer {
                             vtx: Vec::new(),
                             cmd: vec![Command::Nop],
-                        });
+                        },
+                    );
+                } else {
+                    layer -= 1;
+                }
+            }
+
+            Primitive::Portal(_) => unreachable!("portals are resolved before generating vertices"),
+
+            Primitive::PushTransform(_, _) | Primitive::PopTransform => {
+                unreachable!("transforms are resolved before generating vertices")
+            }
+
+            Primitive::DrawRect(r, color) => {
+                if draw_enabled {
+                    let r = r.to_device_coordinates(region);
+                    let color = [color.r, color.g, color.b, color.a];
+                    let extras = [1.0, 0.0, 0.0, 0.0];
+                    let offset = layers[layer].vtx.len();
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.left, r.top],
+                        uv: [0.0; 2],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.right, r.top],
+                        uv: [0.0; 2],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.right, r.bottom],
+                        uv: [0.0; 2],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.left, r.top],
+                        uv: [0.0; 2],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.right, r.bottom],
+                        uv: [0.0; 2],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.left, r.bottom],
+                        uv: [0.0; 2],
+                        color,
+                        extras,
+                    });
+                    layers[layer].append(Command::Colored { offset, count: 6 });
+                }
+            }
+
+            Primitive::DrawRoundedRect(r, corners, color) => {
+                if draw_enabled {
+                    let color = [color.r, color.g, color.b, color.a];
+                    let extras = [1.0, 0.0, 0.0, 0.0];
+                    let to_device = |[x, y]: [f32; 2]| {
+                        [
+                            (x - viewport_center.0) * viewport_inverse_size.0,
+                            (y - viewport_center.1) * viewport_inverse_size.1,
+                        ]
+                    };
+                    let center = to_device([(r.left + r.right) * 0.5, (r.top + r.bottom) * 0.5]);
+                    let outline = rounded_rect_outline(r, corners.clamp(r), hidpi_scale);
+                    let offset = layers[layer].vtx.len();
+                    // the outline is convex, so it can be drawn as a fan around the center.
+                    for (i, &a) in outline.iter().enumerate() {
+                        let b = outline[(i + 1) % outline.len()];
+                        for pos in [center, to_device(a), to_device(b)] {
+                            layers[layer].vtx.push(Vertex {
+                                pos,
+                                uv: [0.0; 2],
+                                color,
+                                extras,
+                            });
+                        }
                     }
+                    layers[layer].append(Command::Colored {
+                        offset,
+                        count: outline.len() * 3,
+                    });
                 }
+            }
 
-                Primitive::LayerDown => {
-                    layer -= 1;
+            Primitive::DrawTriangle(vtx, color) => {
+                if draw_enabled {
+                    let color = [color.r, color.g, color.b, color.a];
+                    let extras = [1.0, 0.0, 0.0, 0.0];
+                    let offset = layers[layer].vtx.len();
+                    layers[layer].vtx.extend(vtx.map(|[x, y]| Vertex {
+                        pos: [
+                            (x - viewport_center.0) * viewport_inverse_size.0,
+                            (y - viewport_center.1) * viewport_inverse_size.1,
+                        ],
+                        uv: [0.0; 2],
+                        color,
+                        extras,
+                    }));
+                    layers[layer].append(Command::Colored { offset, count: 3 });
                 }
+            }
 
-                Primitive::DrawRect(r, color) => {
-                    if draw_enabled {
-                        let r = r.to_device_coordinates(viewport);
+            Primitive::DrawText(text, rect) => {
+                if draw_enabled {
+                    let msdf_extras = [
+                        2.0,
+                        ((text.size * hidpi_scale) / text.font.atlas.size) * text.font.atlas.distance_range,
+                        text.border,
+                        0.0,
+                    ];
+                    let color_extras = [3.0, 0.0, 0.0, 0.0];
+                    let offset = layers[layer].vtx.len();
+
+                    text.draw(rect, |uv, pos, color_glyph, color| {
                         let color = [color.r, color.g, color.b, color.a];
-                        let extras = [1.0, 0.0, 0.0, 0.0];
-                        let offset = layers[layer].vtx.len();
+                        let extras = if color_glyph { color_extras } else { msdf_extras };
+                        let rc = Rectangle {
+                            left: pos.left,
+                            top: pos.top,
+                            right: pos.right,
+                            bottom: pos.bottom,
+                        }
+                        .to_device_coordinates(region);
+
                         layers[layer].vtx.push(Vertex {
-                            pos: [r.left, r.top],
-                            uv: [0.0; 2],
+                            pos: [rc.left, rc.top],
+                            uv: uv.pt(0.0, 0.0),
                             color,
                             extras,
                         });
                         layers[layer].vtx.push(Vertex {
-                            pos: [r.right, r.top],
-                            uv: [0.0; 2],
+                            pos: [rc.right, rc.top],
+                            uv: uv.pt(1.0, 0.0),
                             color,
                             extras,
                         });
                         layers[layer].vtx.push(Vertex {
-                            pos: [r.right, r.bottom],
-                            uv: [0.0; 2],
+                            pos: [rc.right, rc.bottom],
+                            uv: uv.pt(1.0, 1.0),
                             color,
                             extras,
                         });
                         layers[layer].vtx.push(Vertex {
-                            pos: [r.left, r.top],
-                            uv: [0.0; 2],
+                            pos: [rc.left, rc.top],
+                            uv: uv.pt(0.0, 0.0),
                             color,
                             extras,
                         });
                         layers[layer].vtx.push(Vertex {
-                            pos: [r.right, r.bottom],
-                            uv: [0.0; 2],
+                            pos: [rc.right, rc.bottom],
+                            uv: uv.pt(1.0, 1.0),
                             color,
                             extras,
                         });
                         layers[layer].vtx.push(Vertex {
-                            pos: [r.left, r.bottom],
-                            uv: [0.0; 2],
+                            pos: [rc.left, rc.bottom],
+                            uv: uv.pt(0.0, 1.0),
                             color,
                             extras,
                         });
-                        layers[layer].append(Command::Colored { offset, count: 6 });
-                    }
-                }
+                    });
 
-                Primitive::DrawTriangle(vtx, color) => {
-                    if draw_enabled {
-                        let color = [color.r, color.g, color.b, color.a];
-                        let extras = [1.0, 0.0, 0.0, 0.0];
-                        let offset = layers[layer].vtx.len();
-                        layers[layer].vtx.extend(vtx.map(|[x, y]| Vertex {
-                            pos: [
-                                (x - viewport_center.0) * viewport_inverse_size.0,
-                                (y - viewport_center.1) * viewport_inverse_size.1,
-                            ],
-                            uv: [0.0; 2],
-                            color,
-                            extras,
-                        }));
-                        layers[layer].append(Command::Colored { offset, count: 3 });
-                    }
+                    let count = layers[layer].vtx.len() - offset;
+                    layers[layer].append(Command::Textured {
+                        texture: text.font.texture(),
+                        offset,
+                        count,
+                    });
                 }
+            }
+
+            Primitive::Draw9(patch, rect, color) => {
+                if draw_enabled {
+                    let uv = patch.image.texcoords;
+                    let color = [color.r, color.g, color.b, color.a];
+                    let extras = [0.0; 4];
+                    let offset = layers[layer].vtx.len();
 
-                Primitive::DrawText(text, rect) => {
-                    if draw_enabled {
-                        let color = [text.color.r, text.color.g, text.color.b, text.color.a];
-                        let extras = [
-                            2.0,
-                            ((text.size * data.hidpi_scale) / text.font.atlas.size) * text.font.atlas.distance_range,
-                            text.border,
-                            0.0,
-                        ];
-                        let offset = layers[layer].vtx.len();
-
-                        text.draw(rect, |uv, pos| {
+                    patch.iterate_sections(false, rect.width(), |x, u| {
+                        patch.iterate_sections(true, rect.height(), |y, v| {
                             let rc = Rectangle {
-                                left: pos.left,
-                                top: pos.top,
-                                right: pos.right,
-                                bottom: pos.bottom,
+                                left: x.0 + rect.left,
+                                right: x.1 + rect.left,
+                                top: y.0 + rect.top,
+                                bottom: y.1 + rect.top,
                             }
-                            .to_device_coordinates(viewport);
+                            .to_device_coordinates(region);
 
                             layers[layer].vtx.push(Vertex {
                                 pos: [rc.left, rc.top],
-                                uv: uv.pt(0.0, 0.0),
+                                uv: uv.pt(u.0, v.0),
                                 color,
                                 extras,
                             });
                             layers[layer].vtx.push(Vertex {
                                 pos: [rc.right, rc.top],
-                                uv: uv.pt(1.0, 0.0),
+                                uv: uv.pt(u.1, v.0),
                                 color,
                                 extras,
                             });
                             layers[layer].vtx.push(Vertex {
                                 pos: [rc.right, rc.bottom],
-                                uv: uv.pt(1.0, 1.0),
+                                uv: uv.pt(u.1, v.1),
                                 color,
                                 extras,
                             });
                             layers[layer].vtx.push(Vertex {
                                 pos: [rc.left, rc.top],
-                                uv: uv.pt(0.0, 0.0),
+                                uv: uv.pt(u.0, v.0),
                                 color,
                                 extras,
                             });
                             layers[layer].vtx.push(Vertex {
                                 pos: [rc.right, rc.bottom],
-                                uv: uv.pt(1.0, 1.0),
+                                uv: uv.pt(u.1, v.1),
                                 color,
                                 extras,
                             });
                             layers[layer].vtx.push(Vertex {
                                 pos: [rc.left, rc.bottom],
-                                uv: uv.pt(0.0, 1.0),
+                                uv: uv.pt(u.0, v.1),
                                 color,
                                 extras,
                             });
                         });
+                    });
 
-                        let count = layers[layer].vtx.len() - offset;
-                        layers[layer].append(Command::Textured {
-                            texture: text.font.texture(),
-                            offset,
-                            count,
-                        });
-                    }
+                    let count = layers[layer].vtx.len() - offset;
+                    layers[layer].append(Command::Textured {
+                        texture: patch.image.texture,
+                        offset,
+                        count,
+                    });
                 }
+            }
 
-                Primitive::Draw9(patch, rect, color) => {
-                    if draw_enabled {
-                        let uv = patch.image.texcoords;
-                        let color = [color.r, color.g, color.b, color.a];
-                        let extras = [0.0; 4];
-                        let offset = layers[layer].vtx.len();
-
-                        patch.iterate_sections(false, rect.width(), |x, u| {
-                            patch.iterate_sections(true, rect.height(), |y, v| {
-                                let rc = Rectangle {
-                                    left: x.0 + rect.left,
-                                    right: x.1 + rect.left,
-                                    top: y.0 + rect.top,
-                                    bottom: y.1 + rect.top,
-                                }
-                                .to_device_coordinates(viewport);
-
-                                layers[layer].vtx.push(Vertex {
-                                    pos: [rc.left, rc.top],
-                                    uv: uv.pt(u.0, v.0),
-                                    color,
-                                    extras,
-                                });
-                                layers[layer].vtx.push(Vertex {
-                                    pos: [rc.right, rc.top],
-                                    uv: uv.pt(u.1, v.0),
-                                    color,
-                                    extras,
-                                });
-                                layers[layer].vtx.push(Vertex {
-                                    pos: [rc.right, rc.bottom],
-                                    uv: uv.pt(u.1, v.1),
-                                    color,
-                                    extras,
-                                });
-                                layers[layer].vtx.push(Vertex {
-                                    pos: [rc.left, rc.top],
-                                    uv: uv.pt(u.0, v.0),
-                                    color,
-                                    extras,
-                                });
-                                layers[layer].vtx.push(Vertex {
-                                    pos: [rc.right, rc.bottom],
-                                    uv: uv.pt(u.1, v.1),
-                                    color,
-                                    extras,
-                                });
-                                layers[layer].vtx.push(Vertex {
-                                    pos: [rc.left, rc.bottom],
-                                    uv: uv.pt(u.0, v.1),
-                                    color,
-                                    extras,
-                                });
-                            });
-                        });
+            Primitive::DrawImage(image, r, color) => {
+                if draw_enabled {
+                    let r = r.to_device_coordinates(region);
+                    let uv = image.texcoords;
+                    let color = [color.r, color.g, color.b, color.a];
+                    let extras = [0.0; 4];
+                    let offset = layers[layer].vtx.len();
+
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.left, r.top],
+                        uv: [uv.left, uv.top],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.right, r.top],
+                        uv: [uv.right, uv.top],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.right, r.bottom],
+                        uv: [uv.right, uv.bottom],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.left, r.top],
+                        uv: [uv.left, uv.top],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.right, r.bottom],
+                        uv: [uv.right, uv.bottom],
+                        color,
+                        extras,
+                    });
+                    layers[layer].vtx.push(Vertex {
+                        pos: [r.left, r.bottom],
+                        uv: [uv.left, uv.bottom],
+                        color,
+                        extras,
+                    });
 
-                        let count = layers[layer].vtx.len() - offset;
-                        layers[layer].append(Command::Textured {
-                            texture: patch.image.texture,
-                            offset,
-                            count,
-                        });
-                    }
+                    layers[layer].append(Command::Textured {
+                        texture: image.texture,
+                        offset,
+                        count: 6,
+                    });
                 }
+            }
+        }
+    }
 
-                Primitive::DrawImage(image, r, color) => {
-                    if draw_enabled {
-                        let r = r.to_device_coordinates(viewport);
-                        let uv = image.texcoords;
-                        let color = [color.r, color.g, color.b, color.a];
-                        let extras = [0.0; 4];
-                        let offset = layers[layer].vtx.len();
+    layers
+        .into_iter()
+        .fold((Vec::new(), Vec::new()), |(mut vtx, mut cmd), mut layer| {
+            let layer_offset = vtx.len();
+            vtx.append(&mut layer.vtx);
+            cmd.extend(layer.cmd.into_iter().map(|command| match command {
+                Command::Textured { texture, offset, count } => Command::Textured {
+                    texture,
+                    offset: offset + layer_offset,
+                    count,
+                },
+                Command::Colored { offset, count } => Command::Colored {
+                    offset: offset + layer_offset,
+                    count,
+                },
+                other => other,
+            }));
+            (vtx, cmd)
+        })
+}
 
-                        layers[layer].vtx.push(Vertex {
-                            pos: [r.left, r.top],
-                            uv: [uv.left, uv.top],
-                            color,
-                            extras,
-                        });
-                        layers[layer].vtx.push(Vertex {
-                            pos: [r.right, r.top],
-                            uv: [uv.right, uv.top],
-                            color,
-                            extras,
-                        });
-                        layers[layer].vtx.push(Vertex {
-                            pos: [r.right, r.bottom],
-                            uv: [uv.right, uv.bottom],
-                            color,
-                            extras,
-                        });
-                        layers[layer].vtx.push(Vertex {
-                            pos: [r.left, r.top],
-                            uv: [uv.left, uv.top],
-                            color,
-                            extras,
-                        });
-                        layers[layer].vtx.push(Vertex {
-                            pos: [r.right, r.bottom],
-                            uv: [uv.right, uv.bottom],
-                            color,
-                            extras,
-                        });
-                        layers[layer].vtx.push(Vertex {
-                            pos: [r.left, r.bottom],
-                            uv: [uv.left, uv.bottom],
-                            color,
-                            extras,
-                        });
+/// Returns the outline of a rounded rectangle in clockwise order, starting at the left side of the top left corner.
+/// Corners are approximated with more segments as their radius in physical pixels grows.
+fn rounded_rect_outline(rect: Rectangle, corners: draw::Corners, hidpi_scale: f32) -> Vec<[f32; 2]> {
+    use std::f32::consts::FRAC_PI_2;
+
+    let (c, l, t, r, b) = (corners, rect.left, rect.top, rect.right, rect.bottom);
+    let arcs = [
+        (c.top_left, l + c.top_left, t + c.top_left),
+        (c.top_right, r - c.top_right, t + c.top_right),
+        (c.bottom_right, r - c.bottom_right, b - c.bottom_right),
+        (c.bottom_left, l + c.bottom_left, b - c.bottom_left),
+    ];
+
+    let mut outline = Vec::new();
+    for (index, (radius, x, y)) in arcs.into_iter().enumerate() {
+        if radius <= 0.0 {
+            outline.push([x, y]);
+            continue;
+        }
+        let segments = ((radius * hidpi_scale).sqrt() * 2.0).ceil().clamp(1.0, 16.0) as usize;
+        // the top left arc starts pointing left, every next corner is a quarter turn further.
+        let start = (index as f32 + 2.0) * FRAC_PI_2;
+        for step in 0..=segments {
+            let angle = start + FRAC_PI_2 * step as f32 / segments as f32;
+            outline.push([x + angle.cos() * radius, y + angle.sin() * radius]);
+        }
+    }
+    outline
+}
 
-                        layers[layer].append(Command::Textured {
-                            texture: image.texture,
-                            offset,
-                            count: 6,
-                        });
-                    }
-                }
+/// Finds the center of the focusable rect that is closest to the cursor in a direction.
+/// The search starts from the focusable rect that contains the cursor, or from the cursor itself if there is none.
+fn navigate_target(focusable: &[Rectangle], cursor: (f32, f32), direction: NavigateDirection) -> Option<(f32, f32)> {
+    let center = |r: &Rectangle| ((r.left + r.right) * 0.5, (r.top + r.bottom) * 0.5);
+    let origin = focusable
+        .iter()
+        .find(|r| r.point_inside(cursor.0, cursor.1))
+        .copied()
+        .unwrap_or(Rectangle {
+            left: cursor.0,
+            top: cursor.1,
+            right: cursor.0,
+            bottom: cursor.1,
+        });
+    let (ox, oy) = center(&origin);
+
+    // distance along the navigation axis and the gap perpendicular to it, or None if the rect isn't in the direction.
+    let score = |r: &Rectangle| {
+        let (cx, cy) = center(r);
+        let gap = |a_min: f32, a_max: f32, b_min: f32, b_max: f32| (b_min - a_max).max(a_min - b_max).max(0.0);
+        let horizontal_gap = gap(origin.left, origin.right, r.left, r.right);
+        let vertical_gap = gap(origin.top, origin.bottom, r.top, r.bottom);
+        let (ahead, primary, secondary) = match direction {
+            NavigateDirection::Up => (cy < oy, origin.top - r.bottom, horizontal_gap),
+            NavigateDirection::Down => (cy > oy, r.top - origin.bottom, horizontal_gap),
+            NavigateDirection::Left => (cx < ox, origin.left - r.right, vertical_gap),
+            NavigateDirection::Right => (cx > ox, r.left - origin.right, vertical_gap),
+        };
+        let distance = (cx - ox) * (cx - ox) + (cy - oy) * (cy - oy);
+        ahead.then(|| (primary.max(0.0) + secondary * 2.0, distance))
+    };
+
+    focusable
+        .iter()
+        .filter(|r| **r != origin)
+        .filter_map(|r| score(r).map(|s| (s, r)))
+        .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(_, r)| center(r))
+}
+
+/// Applies all `Primitive::PushTransform`s to the primitives they affect, so that no transforms are left.
+/// `transform` is the transform that is active for the primitives, as a scale followed by a translation.
+fn resolve_transforms(primitives: Vec<draw::Primitive>, mut transform: (f32, (f32, f32))) -> Vec<draw::Primitive> {
+    use self::draw::{Corners, Primitive};
+
+    let mut result = Vec::with_capacity(primitives.len());
+    let mut stack = Vec::new();
+
+    for primitive in primitives {
+        let (scale, (x, y)) = transform;
+        let rect = |r: Rectangle| r.scale(scale).translate(x, y);
+
+        result.push(match primitive {
+            Primitive::PushTransform(s, (tx, ty)) => {
+                stack.push(transform);
+                transform = (scale * s, (tx * scale + x, ty * scale + y));
+                continue;
             }
+            Primitive::PopTransform => {
+                transform = stack.pop().unwrap_or(transform);
+                continue;
+            }
+            Primitive::PushClip(r) => Primitive::PushClip(rect(r)),
+            Primitive::Portal(content) => Primitive::Portal(resolve_transforms(content, transform)),
+            Primitive::DrawRect(r, color) => Primitive::DrawRect(rect(r), color),
+            Primitive::DrawRoundedRect(r, corners, color) => Primitive::DrawRoundedRect(
+                rect(r),
+                Corners {
+                    top_left: corners.top_left * scale,
+                    top_right: corners.top_right * scale,
+                    bottom_right: corners.bottom_right * scale,
+                    bottom_left: corners.bottom_left * scale,
+                },
+                color,
+            ),
+            Primitive::DrawTriangle(points, color) => {
+                Primitive::DrawTriangle(points.map(|[px, py]| [px * scale + x, py * scale + y]), color)
+            }
+            Primitive::DrawText(mut text, r) => {
+                // scaling the font size instead of the glyphs makes the text render crisp at the effective size.
+                text.size *= scale;
+                text.border *= scale;
+                Primitive::DrawText(text, rect(r))
+            }
+            Primitive::Draw9(patch, r, color) => Primitive::Draw9(patch, rect(r), color),
+            Primitive::DrawImage(image, r, color) => Primitive::DrawImage(image, rect(r), color),
+            other => other,
+        });
+    }
+
+    result
+}
+
+/// Moves the content of all `Primitive::Portal`s to the end of the primitive list,
+/// on a layer above all other layers and with the clipping rectangle reset to the viewport.
+fn resolve_portals(primitives: Vec<draw::Primitive>, viewport: Rectangle) -> Vec<draw::Primitive> {
+    use self::draw::Primitive;
+
+    let mut result = Vec::with_capacity(primitives.len());
+    let mut portals = Vec::new();
+    let mut depth = 0usize;
+    let mut max_depth = 0usize;
+
+    for primitive in primitives {
+        match primitive {
+            Primitive::Portal(content) => portals.extend(content),
+            Primitive::LayerUp => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+                result.push(Primitive::LayerUp);
+            }
+            Primitive::LayerDown => {
+                depth = depth.saturating_sub(1);
+                result.push(Primitive::LayerDown);
+            }
+            other => result.push(other),
         }
+    }
+
+    if !portals.is_empty() {
+        result.extend((0..=max_depth).map(|_| Primitive::LayerUp));
+        result.push(Primitive::PushClip(viewport));
+        result.extend(resolve_portals(portals, viewport));
+        result.push(Primitive::PopClip);
+        result.extend((0..=max_depth).map(|_| Primitive::LayerDown));
+    }
+
+    result
+}
+
+/// Adds a `Primitive::DrawRect` after each `Primitive::DrawText` for every underline or strikethrough of the text.
+fn resolve_decorations(primitives: Vec<draw::Primitive>) -> Vec<draw::Primitive> {
+    use self::draw::Primitive;
 
-        let (vertices, commands) =
-            layers
+    let mut result = Vec::with_capacity(primitives.len());
+    for primitive in primitives {
+        let decorations = match &primitive {
+            Primitive::DrawText(text, rect) => text.decorations(*rect),
+            _ => Vec::new(),
+        };
+        result.push(primitive);
+        result.extend(
+            decorations
                 .into_iter()
-                .fold((Vec::new(), Vec::new()), |(mut vtx, mut cmd), mut layer| {
-                    let layer_offset = vtx.len();
-                    vtx.append(&mut layer.vtx);
-                    cmd.extend(layer.cmd.into_iter().map(|command| match command {
-                        Command::Textured { texture, offset, count } => Command::Textured {
-                            texture,
-                            offset: offset + layer_offset,
-                            count,
-                        },
-                        Command::Colored { offset, count } => Command::Colored {
-                            offset: offset + layer_offset,
-                            count,
-                        },
-                        other => other,
-                    }));
-                    (vtx, cmd)
-                });
+                .map(|(rect, color)| Primitive::DrawRect(rect, color)),
+        );
+    }
 
-        drop(data);
-        self.handle_event(Event::Animate);
+    result
+}
 
-        DrawList {
-            updates: self.style.cache().lock().unwrap().take_updates(),
-            vertices,
-            commands,
+impl<C: 'static + Component> Data<C> {
+    fn push_output(&mut self, output: impl IntoIterator<Item = C::Output>) {
+        for output in output {
+            let mut delivered = false;
+            self.subscribers.retain_mut(|subscriber| match subscriber(&output) {
+                Delivery::Delivered => {
+                    delivered = true;
+                    true
+                }
+                Delivery::Skipped => true,
+                Delivery::Closed => false,
+            });
+            if !delivered {
+                self.output.push_back(output);
+            }
         }
     }
 }
 
+type Subscriber<T> = Box<dyn FnMut(&T) -> Delivery + Send>;
+
+enum Delivery {
+    Delivered,
+    Skipped,
+    Closed,
+}
+
+/// Output messages of a [`Ui`](struct.Ui.html) that were selected using [`Ui::subscribe`](struct.Ui.html#method.subscribe).
+/// The messages can be read using [`try_iter()`](#method.try_iter), or asynchronously since `Subscription`
+/// implements `Stream`.
+pub struct Subscription<T> {
+    receiver: futures::channel::mpsc::UnboundedReceiver<T>,
+}
+
+impl<T> Subscription<T> {
+    /// Returns an iterator over the messages that are currently available, without waiting for new ones.
+    pub fn try_iter(&mut self) -> impl '_ + Iterator<Item = T> {
+        std::iter::from_fn(move || self.receiver.try_recv().ok())
+    }
+}
+
+impl<T> futures::Stream for Subscription<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut std::task::Context) -> std::task::Poll<Option<T>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
 struct Output<'a, C: 'static + Component>(MutexGuard<'a, Data<C>>);
 
 impl<'a, C: 'static + Component> Iterator for Output<'a, C> {
@@ -9,6 +9,7 @@ macro_rules! view {
         inner $widget:ident
             $({$($modifier:ident: $value:expr),*})?
             $(=>{$(
+                $([spread $($s:tt)*])?
                 $([match $e:expr][case $p:pat])?
                 $([for $x:pat in $i:expr])?
                 $([if $(let $y:pat =)? $yc:expr])?
@@ -21,6 +22,7 @@ macro_rules! view {
         Some($widget::default()
             $($(.extend(view!{
                 inner
+                $([spread $($s)*])?
                 $([match $e][case $p])?
                 $([for $x in $i])?
                 $([if $(let $y =)? $yc])?
@@ -34,6 +36,13 @@ macro_rules! view {
         )
     };
 
+    {
+        inner
+        [spread]
+        $nodes:ident
+    } => {
+        $nodes
+    };
     {
         inner 
         [for $x:pat in $i:expr] 
@@ -51,14 +60,16 @@ macro_rules! view {
             $(=>$c1:tt)?
         $([else if $(let $y:pat =)? $yc:expr] 
         $w2:ident
-            $({($m2:ident: $v2:expr),*})?
+            $({$($m2:ident: $v2:expr),*})?
             $(=>$c2:tt)?)*
     } => {
         if $(let $x =)? $xc {
             view!{ inner $w1 $({$($m1: $v1),*})? $(=>$c1)?}
+                .map(|node| $crate::node::branch_key(node, ("if", stringify!($xc))))
         }
         $(else if $(let $y =)? $yc {
             view!{ inner $w2 $({$($m2: $v2),*})? $(=>$c2)?}
+                .map(|node| $crate::node::branch_key(node, ("else if", stringify!($yc))))
         })*
         else {
             None
@@ -80,12 +91,15 @@ macro_rules! view {
     } => {
         if $(let $x =)? $xc {
             view!{ inner $w1 $({$($m1: $v1),*})? $(=>$c1)? }
+                .map(|node| $crate::node::branch_key(node, ("if", stringify!($xc))))
         }
         $(else if $(let $y =)? $yc {
             view!{ inner $w2 $({$($m2: $v2),*})? $(=>$c2)? }
+                .map(|node| $crate::node::branch_key(node, ("else if", stringify!($yc))))
         })*
         else {
             view!{ inner $w3 $({$($m3: $v3),*})? $(=>$c3)? }
+                .map(|node| $crate::node::branch_key(node, ("else", stringify!($xc))))
         }
     };
     {
@@ -6,29 +6,36 @@ use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
 use std::ptr::null_mut;
 use std::task::Poll;
+use std::time::Duration;
 
 use futures::{FutureExt, Stream, StreamExt};
 
 use crate::bitset::BitSet;
-use crate::component::Component;
+use crate::component::{Component, Environment};
 use crate::draw::Primitive;
 use crate::event::Event;
-use crate::layout::{Rectangle, Size};
+use crate::layout::{LayoutSnapshot, Rectangle, Size};
 use crate::node::{GenericNode, Node};
 use crate::style::tree::Query;
-use crate::tracker::{ManagedState, ManagedStateTracker};
-use crate::widget::Context;
+use crate::style::StyleInspection;
+use crate::timer::Delay;
+use crate::tracker::{ManagedState, ManagedStateTracker, StateToken};
+use crate::widget::{Context, CursorResponse};
 
 pub struct ComponentNode<'a, C: 'a + Component> {
     props: Box<C>,
     state: RefCell<Option<&'a mut ManagedState>>,
+    state_token: StateToken,
     view: RefCell<Option<Node<'a, C::Message>>>,
     component_state: Cell<*mut (C::State, Runtime<C::Message>)>,
+    component_state_token: RefCell<StateToken>,
     style_query: Option<Query>,
     style_position: (usize, usize),
     style_matches: BitSet,
     key: u64,
+    retained: bool,
     waker: Option<std::task::Waker>,
+    environment: Environment,
 }
 
 /// Runtime for submitting future messages to [`Component::update`](../component/trait.Component.html#method.update).
@@ -36,6 +43,8 @@ pub struct Runtime<Message> {
     futures: Vec<Pin<Box<dyn Future<Output = Message> + Send + Sync>>>,
     streams: Vec<Pin<Box<dyn Stream<Item = Message> + Send + Sync>>>,
     waker: Option<std::task::Waker>,
+    provided: Environment,
+    provided_changed: bool,
 }
 
 /// Mutable state accessor.
@@ -52,13 +61,17 @@ impl<'a, C: 'a + Component> ComponentNode<'a, C> {
         Self {
             props: Box::new(props),
             state: RefCell::new(None),
+            state_token: StateToken::default(),
             view: RefCell::new(None),
             component_state: Cell::new(null_mut()),
+            component_state_token: RefCell::new(StateToken::default()),
             style_query: None,
             style_position: (0, 1),
             style_matches: BitSet::new(),
             key: hasher.finish(),
+            retained: false,
             waker: None,
+            environment: Environment::default(),
         }
     }
 
@@ -82,6 +95,7 @@ impl<'a, C: 'a + Component> ComponentNode<'a, C> {
     pub fn update(&mut self, message: C::Message, context: &mut Context<C::Output>) {
         let mut dirty = false;
 
+        self.state_token.assert_alive(std::any::type_name::<C>());
         let mut component_state = self.component_state.get();
         if component_state.is_null() {
             let mut tracker = unsafe {
@@ -100,15 +114,28 @@ impl<'a, C: 'a + Component> ComponentNode<'a, C> {
                     futures: Vec::new(),
                     streams: Vec::new(),
                     waker: self.waker.clone(),
+                    provided: Environment::default(),
+                    provided_changed: false,
                 };
                 let state = self.props.mount(&mut runtime);
                 (state, runtime)
             }) as *mut _;
             self.component_state.set(component_state);
+            self.component_state_token.replace(tracker.token());
         }
+        self.component_state_token
+            .borrow()
+            .assert_alive(std::any::type_name::<C>());
 
         let (state, runtime) = unsafe { component_state.as_mut().unwrap() };
 
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("update", component = std::any::type_name::<C>()).entered();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(?message, "message");
+        #[cfg(feature = "tracing")]
+        let outputs = context.messages().len();
+
         self.props.update(
             message,
             DetectMut {
@@ -119,6 +146,15 @@ impl<'a, C: 'a + Component> ComponentNode<'a, C> {
             context,
         );
 
+        #[cfg(feature = "tracing")]
+        for output in &context.messages()[outputs..] {
+            tracing::debug!(?output, "output");
+        }
+
+        if std::mem::take(&mut runtime.provided_changed) {
+            dirty = true;
+        }
+
         if dirty {
             self.set_dirty();
             context.redraw();
@@ -127,6 +163,7 @@ impl<'a, C: 'a + Component> ComponentNode<'a, C> {
 
     pub fn view(&self) -> RefMut<Node<'a, C::Message>> {
         if self.dirty() {
+            self.state_token.assert_alive(std::any::type_name::<C>());
             let mut tracker = unsafe {
                 self.state
                     .borrow_mut()
@@ -143,13 +180,20 @@ impl<'a, C: 'a + Component> ComponentNode<'a, C> {
                     futures: Vec::new(),
                     streams: Vec::new(),
                     waker: self.waker.clone(),
+                    provided: Environment::default(),
+                    provided_changed: false,
                 };
                 let state = self.props.mount(&mut runtime);
                 (state, runtime)
             });
             self.component_state.set(state as *mut _);
+            self.component_state_token.replace(tracker.token());
 
-            let mut root = unsafe { (self.props.as_ref() as *const C).as_ref().unwrap() }.view(&state.0);
+            state.1.provided_changed = false;
+            let environment = self.environment.merge(&state.1.provided);
+
+            let mut root = unsafe { (self.props.as_ref() as *const C).as_ref().unwrap() }
+                .view_with_environment(&state.0, &environment);
             let mut query = self.style_query.clone().unwrap();
             root.acquire_state(&mut tracker);
             root.style(&mut query, self.style_position);
@@ -158,10 +202,48 @@ impl<'a, C: 'a + Component> ComponentNode<'a, C> {
                 root.acquire_waker(waker);
             }
 
+            root.acquire_environment(&environment);
+
             self.view.replace(Some(root));
         }
         RefMut::map(self.view.borrow_mut(), |b| b.as_mut().unwrap())
     }
+
+    /// Passes an event to the view using a sub context, and handles the messages and requests that result from it.
+    fn dispatch(
+        &mut self,
+        context: &mut Context<C::Output>,
+        event: impl FnOnce(&mut Node<'a, C::Message>, &mut Context<C::Message>),
+    ) {
+        let mut sub_context = context.sub_context();
+        event(&mut *self.view(), &mut sub_context);
+
+        if sub_context.redraw_requested() {
+            context.redraw();
+        }
+        if sub_context.animation_requested() {
+            context.animate();
+        }
+        if sub_context.cursor_tracking_requested() {
+            context.track_cursor();
+        }
+        if let Some(icon) = sub_context.cursor_icon_requested() {
+            context.set_cursor_icon(icon);
+        }
+        if sub_context.scroll_consumed() {
+            context.consume_scroll();
+        }
+        if sub_context.portal_hovered() {
+            context.hover_portal();
+        }
+        if sub_context.rebuild_requested() {
+            self.set_dirty();
+        }
+
+        for message in sub_context {
+            self.update(message, context);
+        }
+    }
 }
 
 impl<'a, C: 'a + Component> GenericNode<'a, C::Output> for ComponentNode<'a, C> {
@@ -173,11 +255,28 @@ impl<'a, C: 'a + Component> GenericNode<'a, C::Output> for ComponentNode<'a, C>
         self.key = key;
     }
 
+    fn set_retained(&mut self, retained: bool) {
+        self.retained = retained;
+    }
+
     fn set_class(&mut self, _: &'a str) {}
 
+    fn set_classes(&mut self, _: &[&str]) {}
+
     fn acquire_state(&mut self, tracker: &mut ManagedStateTracker<'a>) {
-        self.state
-            .replace(Some(tracker.begin::<ManagedState, _>(self.key, ManagedState::default)));
+        let state = tracker.begin::<ManagedState, _>(self.key, ManagedState::default);
+        self.state_token = tracker.token();
+        if self.retained {
+            tracker.retain();
+        }
+        let state_ptr = state as *mut ManagedState;
+        if let Some(previous) = self.state.replace(Some(state)) {
+            if !std::ptr::eq(previous, state_ptr) {
+                // the component state lives inside of the managed state, so it has to be acquired again.
+                self.component_state.set(null_mut());
+                self.set_dirty();
+            }
+        }
         tracker.end();
     }
 
@@ -185,6 +284,10 @@ impl<'a, C: 'a + Component> GenericNode<'a, C::Output> for ComponentNode<'a, C>
         self.view().size()
     }
 
+    fn size_limits(&self) -> ((f32, f32), (f32, f32)) {
+        self.view().size_limits()
+    }
+
     fn hit(&self, layout: Rectangle, clip: Rectangle, x: f32, y: f32, recursive: bool) -> bool {
         self.view().hit(layout, clip, x, y, recursive)
     }
@@ -193,6 +296,18 @@ impl<'a, C: 'a + Component> GenericNode<'a, C::Output> for ComponentNode<'a, C>
         self.view().focused()
     }
 
+    fn z_index(&self) -> i32 {
+        self.view().z_index()
+    }
+
+    fn drawn_rects(&mut self, focusable: &mut Vec<Rectangle>, regions: &mut Vec<(Rectangle, CursorResponse)>) {
+        self.view().drawn_rects(focusable, regions)
+    }
+
+    fn layout_snapshot(&mut self, parent: Option<(&str, usize)>) -> LayoutSnapshot {
+        self.view().layout_snapshot(parent)
+    }
+
     fn draw(&mut self, layout: Rectangle, clip: Rectangle) -> Vec<Primitive<'a>> {
         self.view().draw(layout, clip)
     }
@@ -258,6 +373,20 @@ impl<'a, C: 'a + Component> GenericNode<'a, C::Output> for ComponentNode<'a, C>
         query.siblings.push(query.ancestors.pop().unwrap());
     }
 
+    fn animate_transitions(&mut self) -> bool {
+        self.view().animate_transitions()
+    }
+
+    fn inspect_style(&mut self, key: u64) -> Option<StyleInspection> {
+        // a component is styled through the root widget of it's view, so it's key inspects that widget.
+        let mut view = self.view();
+        if self.key == key {
+            let root = view.get_key();
+            return view.inspect_style(root);
+        }
+        view.inspect_style(key)
+    }
+
     fn event(
         &mut self,
         layout: Rectangle,
@@ -265,25 +394,24 @@ impl<'a, C: 'a + Component> GenericNode<'a, C::Output> for ComponentNode<'a, C>
         event: Event,
         context: &mut Context<<C as Component>::Output>,
     ) {
-        let mut sub_context = context.sub_context();
-        self.view().event(layout, clip, event, &mut sub_context);
-
-        if sub_context.redraw_requested() {
-            context.redraw();
-        }
-        if sub_context.rebuild_requested() {
-            self.set_dirty();
-        }
+        self.dispatch(context, |view, sub_context| {
+            view.event(layout, clip, event, sub_context)
+        });
+    }
 
-        for message in sub_context {
-            self.update(message, context);
-        }
+    fn portal_event(&mut self, event: Event, context: &mut Context<<C as Component>::Output>) {
+        self.dispatch(context, |view, sub_context| view.portal_event(event, sub_context));
     }
 
     fn acquire_waker(&mut self, waker: &std::task::Waker) {
         self.waker = Some(waker.clone());
     }
 
+    fn acquire_environment(&mut self, environment: &Environment) {
+        self.environment = environment.clone();
+        self.set_dirty();
+    }
+
     fn poll(&mut self, context: &mut Context<<C as Component>::Output>, task_context: &mut std::task::Context) {
         self.waker = Some(task_context.waker().clone());
 
@@ -293,6 +421,9 @@ impl<'a, C: 'a + Component> GenericNode<'a, C::Output> for ComponentNode<'a, C>
         if sub_context.redraw_requested() {
             context.redraw();
         }
+        if sub_context.animation_requested() {
+            context.animate();
+        }
         if sub_context.rebuild_requested() {
             self.set_dirty();
         }
@@ -301,6 +432,9 @@ impl<'a, C: 'a + Component> GenericNode<'a, C::Output> for ComponentNode<'a, C>
             self.update(message, context);
         }
 
+        self.component_state_token
+            .borrow()
+            .assert_alive(std::any::type_name::<C>());
         let (_, runtime) = unsafe { self.component_state.get().as_mut().unwrap() };
 
         for message in runtime.poll(task_context) {
@@ -334,6 +468,27 @@ impl<Message> Runtime<Message> {
         }
     }
 
+    /// Submits a message to the component after `duration` has passed.
+    /// The timer doesn't depend on an async runtime, so it works with any executor that polls the `Ui`. All delays
+    /// share a single timer thread.
+    ///
+    /// Delays can't be cancelled, but they can be used to debounce messages by tagging them with a counter that's
+    /// stored in the state, and ignoring messages that carry an outdated counter.
+    pub fn delay(&mut self, duration: Duration, message: Message)
+    where
+        Message: 'static + Send + Sync,
+    {
+        self.wait(Delay::new(duration).map(move |_| message));
+    }
+
+    /// Provides a value to the [`Environment`](../component/struct.Environment.html) of all descendants of the
+    /// component. A previously provided value of the same type is replaced.
+    /// Descendants will be rebuilt with the new value.
+    pub fn provide<T: 'static + Send + Sync>(&mut self, value: T) {
+        self.provided.insert(value);
+        self.provided_changed = true;
+    }
+
     pub(crate) fn poll(&mut self, cx: &mut std::task::Context) -> Vec<Message> {
         self.waker = Some(cx.waker().clone());
 
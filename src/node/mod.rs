@@ -2,12 +2,14 @@ use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 
+use crate::component::Environment;
 use crate::draw::Primitive;
 use crate::event::Event;
-use crate::layout::{Rectangle, Size};
+use crate::layout::{LayoutSnapshot, Rectangle, Size};
 use crate::style::tree::Query;
+use crate::style::StyleInspection;
 use crate::tracker::ManagedStateTracker;
-use crate::widget::{Context, Widget};
+use crate::widget::{Context, CursorResponse, Widget};
 use crate::Component;
 
 pub(crate) mod component_node;
@@ -22,16 +24,28 @@ pub trait GenericNode<'a, Message>: Send {
 
     fn set_key(&mut self, key: u64);
 
+    fn set_retained(&mut self, retained: bool);
+
     fn set_class(&mut self, class: &'a str);
 
+    fn set_classes(&mut self, classes: &[&str]);
+
     fn acquire_state(&mut self, tracker: &mut ManagedStateTracker<'a>);
 
     fn size(&self) -> (Size, Size);
 
+    fn size_limits(&self) -> ((f32, f32), (f32, f32));
+
     fn hit(&self, layout: Rectangle, clip: Rectangle, x: f32, y: f32, recursive: bool) -> bool;
 
     fn focused(&self) -> bool;
 
+    fn z_index(&self) -> i32;
+
+    fn drawn_rects(&mut self, focusable: &mut Vec<Rectangle>, regions: &mut Vec<(Rectangle, CursorResponse)>);
+
+    fn layout_snapshot(&mut self, parent: Option<(&str, usize)>) -> LayoutSnapshot;
+
     fn draw(&mut self, layout: Rectangle, clip: Rectangle) -> Vec<Primitive<'a>>;
 
     fn style(&mut self, query: &mut Query, position: (usize, usize));
@@ -40,10 +54,18 @@ pub trait GenericNode<'a, Message>: Send {
 
     fn remove_matches(&mut self, query: &mut Query);
 
+    fn animate_transitions(&mut self) -> bool;
+
+    fn inspect_style(&mut self, key: u64) -> Option<StyleInspection>;
+
     fn event(&mut self, layout: Rectangle, clip: Rectangle, event: Event, context: &mut Context<Message>);
 
+    fn portal_event(&mut self, event: Event, context: &mut Context<Message>);
+
     fn acquire_waker(&mut self, waker: &std::task::Waker);
 
+    fn acquire_environment(&mut self, environment: &Environment);
+
     fn poll(&mut self, context: &mut Context<Message>, task_context: &mut std::task::Context);
 }
 
@@ -61,6 +83,14 @@ pub trait IntoNode<'a, Message: 'a>: 'a + Sized {
         node
     }
 
+    /// Convenience function that converts to a node and then adds multiple style classes to the resulting
+    /// [`Node`](struct.Node.html). The node will match the selectors of each of the classes.
+    fn classes(self, classes: &[&str]) -> Node<'a, Message> {
+        let mut node = self.into_node();
+        node.set_classes(classes);
+        node
+    }
+
     /// Convenience function that converts to a node and then sets a custom id to the resulting [`Node`](struct.Node.html).
     fn key<K: Hash>(self, key: K) -> Node<'a, Message> {
         let mut hasher = DefaultHasher::new();
@@ -71,6 +101,19 @@ pub trait IntoNode<'a, Message: 'a>: 'a + Sized {
     }
 }
 
+/// Mixes the branch of a conditional in the `view!` macro into the key of the node it produced, so different branches
+/// never share state, and a branch that appears or disappears doesn't shift the state of the nodes after it.
+/// The state of the node is retained while the branch is not rendered, so it's still there when the branch comes back.
+#[doc(hidden)]
+pub fn branch_key<'a, Message, B: Hash>(mut node: Node<'a, Message>, branch: B) -> Node<'a, Message> {
+    let mut hasher = DefaultHasher::new();
+    node.get_key().hash(&mut hasher);
+    branch.hash(&mut hasher);
+    node.set_key(hasher.finish());
+    node.set_retained(true);
+    node
+}
+
 impl<'a, Message: 'a> Node<'a, Message> {
     /// Create a new [`Node`](struct.Node.html) from a [`Widget`](../widget/trait.Widget.html).
     pub fn from_widget<W: 'a + Widget<'a, Message>>(widget: W) -> Self {
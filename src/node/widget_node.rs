@@ -1,44 +1,69 @@
+use std::borrow::Cow;
 use std::cell::Cell;
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Instant;
 
 use smallvec::SmallVec;
 
 use crate::bitset::BitSet;
+use crate::component::Environment;
 use crate::draw::Primitive;
 use crate::event::Event;
-use crate::layout::{Rectangle, Size};
+use crate::layout::{LayoutSnapshot, Rectangle, Size};
 use crate::node::GenericNode;
 use crate::prelude::{StateVec, Style, Widget};
 use crate::style::tree::Query;
-use crate::style::Stylesheet;
-use crate::tracker::ManagedStateTracker;
-use crate::widget::Context;
+use crate::style::{StyleInspection, Stylesheet};
+use crate::tracker::{ManagedStateTracker, StateToken};
+use crate::widget::{Context, CursorResponse};
 
 /// Generic ui widget.
 pub struct WidgetNode<'a, Message, W: Widget<'a, Message>> {
     widget: W,
     key: u64,
+    retained: bool,
     widget_state: Option<&'a mut W::State>,
+    transition: Option<&'a mut StyleTransition>,
+    state_token: StateToken,
     size: Cell<Option<(Size, Size)>>,
     focused: Cell<Option<bool>>,
+    drawn: Option<(Rectangle, Rectangle)>,
     position: (usize, usize),
     style: Option<Arc<Style>>,
     selector_matches: BitSet,
     stylesheet: Option<Arc<Stylesheet>>,
-    class: Option<&'a str>,
+    class: Option<Cow<'a, str>>,
     state: StateVec,
 }
 
+/// The managed state of a widget node. The style transition is kept next to the state of the widget, so transitions
+/// continue when the view is rebuilt.
+struct NodeState<S> {
+    widget: S,
+    transition: StyleTransition,
+}
+
+/// Keeps track of the stylesheet transition of a widget.
+#[derive(Default)]
+struct StyleTransition {
+    target: Option<Arc<Stylesheet>>,
+    from: Option<(Arc<Stylesheet>, Instant)>,
+}
+
 impl<'a, Message, W: Widget<'a, Message>> WidgetNode<'a, Message, W> {
     pub fn new(widget: W) -> Self {
         let key = widget.key();
         Self {
             widget,
             key,
+            retained: false,
             widget_state: None,
+            transition: None,
+            state_token: StateToken::default(),
             size: Cell::new(None),
             focused: Cell::new(None),
+            drawn: None,
             position: (0, 1),
             style: None,
             selector_matches: BitSet::new(),
@@ -47,6 +72,75 @@ impl<'a, Message, W: Widget<'a, Message>> WidgetNode<'a, Message, W> {
             state: SmallVec::new(),
         }
     }
+
+    /// Sets the stylesheet that was resolved for the widget. If the new stylesheet has transitions, the displayed
+    /// stylesheet animates from the previously displayed stylesheet.
+    fn set_stylesheet(&mut self, stylesheet: Arc<Stylesheet>) {
+        let transition = self.transition.as_mut().unwrap();
+        let stylesheet = transition.set_target(stylesheet);
+        if transition.animating() {
+            self.style.as_ref().unwrap().start_transition();
+        }
+        self.display_stylesheet(stylesheet);
+    }
+
+    /// Replaces the displayed stylesheet. When the size of the widget depends on the changed properties, the cached size
+    /// is dropped and the ui is laid out again.
+    fn display_stylesheet(&mut self, stylesheet: Arc<Stylesheet>) {
+        if let Some(previous) = self.stylesheet.replace(stylesheet) {
+            if previous.layout_differs(self.stylesheet.as_ref().unwrap()) {
+                self.size.set(None);
+                self.style.as_ref().unwrap().change_layout();
+            }
+        }
+    }
+}
+
+impl StyleTransition {
+    /// Sets the stylesheet to transition to, and returns the stylesheet to display right now.
+    fn set_target(&mut self, target: Arc<Stylesheet>) -> Arc<Stylesheet> {
+        if let Some(previous) = self.target.as_ref() {
+            if !Arc::ptr_eq(previous, &target) {
+                self.from = if target.transitions.is_empty() {
+                    None
+                } else {
+                    Some((self.current(), Instant::now()))
+                };
+            }
+        }
+        self.target = Some(target);
+        self.current()
+    }
+
+    /// Returns the stylesheet to display right now, ending the transition when it's complete.
+    fn current(&mut self) -> Arc<Stylesheet> {
+        let target = self.target.clone().unwrap();
+        if let Some((from, start)) = self.from.as_ref() {
+            match target.transition_from(from, start.elapsed()) {
+                Some(stylesheet) => return Arc::new(stylesheet),
+                None => self.from = None,
+            }
+        }
+        target
+    }
+
+    fn animating(&self) -> bool {
+        self.from.is_some()
+    }
+}
+
+/// Applies the margin and the min/max size constraints of the stylesheet to the layout rect assigned by the parent.
+/// Parents that divide their space over their children already honor the constraints using
+/// [`size_limits`](trait.GenericNode.html#tymethod.size_limits). Rects of other parents that exceed the constraints are
+/// shrunk or grown from the top left corner.
+fn widget_layout(layout: Rectangle, stylesheet: &Stylesheet) -> Rectangle {
+    let layout = layout.after_padding(stylesheet.margin);
+    Rectangle::from_xywh(
+        layout.left,
+        layout.top,
+        layout.width().min(stylesheet.max_width).max(stylesheet.min_width),
+        layout.height().min(stylesheet.max_height).max(stylesheet.min_height),
+    )
 }
 
 impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNode<'a, Message, W> {
@@ -58,12 +152,29 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
         self.key = key;
     }
 
+    fn set_retained(&mut self, retained: bool) {
+        self.retained = retained;
+    }
+
     fn set_class(&mut self, class: &'a str) {
-        self.class = Some(class);
+        self.class = Some(Cow::Borrowed(class));
+    }
+
+    fn set_classes(&mut self, classes: &[&str]) {
+        self.class = Some(Cow::Owned(classes.join(" ")));
     }
 
     fn acquire_state(&mut self, tracker: &mut ManagedStateTracker<'a>) {
-        self.widget_state = Some(tracker.begin(self.key, || self.widget.mount()));
+        let state = tracker.begin(self.key, || NodeState {
+            widget: self.widget.mount(),
+            transition: StyleTransition::default(),
+        });
+        self.widget_state = Some(&mut state.widget);
+        self.transition = Some(&mut state.transition);
+        self.state_token = tracker.token();
+        if self.retained {
+            tracker.retain();
+        }
         self.widget.visit_children(&mut |child| {
             child.acquire_state(&mut *tracker);
         });
@@ -76,11 +187,15 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
             let style = self.stylesheet.as_ref().unwrap().deref();
             let mut size = self.widget.size(&**state, style);
             size.0 = match size.0 {
-                Size::Exact(size) => Size::Exact(size + style.margin.left + style.margin.right),
+                Size::Exact(size) => {
+                    Size::Exact(size.min(style.max_width).max(style.min_width) + style.margin.left + style.margin.right)
+                }
                 other => other,
             };
             size.1 = match size.1 {
-                Size::Exact(size) => Size::Exact(size + style.margin.top + style.margin.bottom),
+                Size::Exact(size) => Size::Exact(
+                    size.min(style.max_height).max(style.min_height) + style.margin.top + style.margin.bottom,
+                ),
                 other => other,
             };
             self.size.replace(Some(size));
@@ -88,10 +203,20 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
         self.size.get().unwrap()
     }
 
+    fn size_limits(&self) -> ((f32, f32), (f32, f32)) {
+        let style = self.stylesheet.as_ref().unwrap().deref();
+        let horizontal = style.margin.left + style.margin.right;
+        let vertical = style.margin.top + style.margin.bottom;
+        (
+            (style.min_width + horizontal, style.max_width + horizontal),
+            (style.min_height + vertical, style.max_height + vertical),
+        )
+    }
+
     fn hit(&self, layout: Rectangle, clip: Rectangle, x: f32, y: f32, recursive: bool) -> bool {
         let state = self.widget_state.as_ref().unwrap();
         let stylesheet = self.stylesheet.as_ref().unwrap().deref();
-        let layout = layout.after_padding(stylesheet.margin);
+        let layout = widget_layout(layout, stylesheet);
         self.widget.hit(&**state, layout, clip, stylesheet, x, y, recursive)
     }
 
@@ -103,12 +228,61 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
         self.focused.get().unwrap()
     }
 
+    fn z_index(&self) -> i32 {
+        let style = self.stylesheet.as_ref().map_or(0, |stylesheet| stylesheet.z_index);
+        let widget = self
+            .widget_state
+            .as_ref()
+            .map_or(0, |state| self.widget.z_index(&**state));
+        style + widget
+    }
+
+    fn drawn_rects(&mut self, focusable: &mut Vec<Rectangle>, regions: &mut Vec<(Rectangle, CursorResponse)>) {
+        if let Some((layout, clip)) = self.drawn.take() {
+            let state = &**self.widget_state.as_ref().unwrap();
+            if self.widget.focusable(state) {
+                focusable.extend(clip.intersect(&layout));
+            }
+            match self.widget.cursor_response(state) {
+                CursorResponse::Ignore => (),
+                response => regions.extend(clip.intersect(&layout).map(|rect| (rect, response))),
+            }
+        }
+        let state = &mut **self.widget_state.as_mut().unwrap();
+        self.widget.drawn_rects(state, focusable, regions);
+    }
+
+    fn layout_snapshot(&mut self, parent: Option<(&str, usize)>) -> LayoutSnapshot {
+        let widget = self.widget.widget();
+        let path = match parent {
+            Some((parent, index)) => format!("{}/{}[{}]", parent, widget, index),
+            None => widget.to_string(),
+        };
+        let mut children = Vec::new();
+        self.widget.visit_children(&mut |child| {
+            let index = children.len();
+            children.push(child.layout_snapshot(Some((&path, index))));
+        });
+        let (width, height) = self.size();
+        LayoutSnapshot {
+            widget: widget.to_string(),
+            class: self.class.as_deref().map(str::to_string),
+            path,
+            size: (LayoutSnapshot::round_size(width), LayoutSnapshot::round_size(height)),
+            layout: self.drawn.take().map(|(layout, _)| LayoutSnapshot::round_rect(layout)),
+            children,
+        }
+    }
+
     fn draw(&mut self, layout: Rectangle, clip: Rectangle) -> Vec<Primitive<'a>> {
+        self.state_token.assert_alive(self.widget.widget());
         let state = self.widget_state.as_mut().unwrap();
         let stylesheet = self.stylesheet.as_ref().unwrap().deref();
-        let layout = layout.after_padding(stylesheet.margin);
+        let layout = widget_layout(layout, stylesheet);
+        self.drawn = Some((layout, clip));
 
-        self.widget.draw(&mut **state, layout, clip, stylesheet)
+        let primitives = self.widget.draw(&mut **state, layout, clip, stylesheet);
+        Primitive::layered(primitives, stylesheet.z_index + self.widget.z_index(&**state))
     }
 
     fn style(&mut self, query: &mut Query, position: (usize, usize)) {
@@ -121,12 +295,12 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
         self.state = self.widget.state(&**self.widget_state.as_ref().unwrap());
         self.selector_matches = query.match_widget(
             self.widget.widget(),
-            self.class.unwrap_or(""),
+            self.class.as_deref().unwrap_or(""),
             self.state.as_slice(),
             self.position.0,
             self.position.1,
         );
-        self.stylesheet.replace(query.style.get(&self.selector_matches));
+        self.set_stylesheet(query.style.get(&self.selector_matches));
 
         // resolve children style
         query.ancestors.push(self.selector_matches.clone());
@@ -144,17 +318,28 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
     fn add_matches(&mut self, query: &mut Query) {
         let additions = query.match_widget(
             self.widget.widget(),
-            self.class.unwrap_or(""),
+            self.class.as_deref().unwrap_or(""),
+            self.state.as_slice(),
+            self.position.0,
+            self.position.1,
+        );
+
+        // the added rules may set flags, which changes the rules matched through flag selectors.
+        let mut new_style = self.selector_matches.union(&additions);
+        query.style.rule_tree().match_flags(
+            &mut new_style,
             self.state.as_slice(),
+            self.class.as_deref().unwrap_or(""),
             self.position.0,
             self.position.1,
         );
+        let additions = additions
+            .intersection(&new_style)
+            .union(&new_style.difference(&self.selector_matches));
 
-        let new_style = self.selector_matches.union(&additions);
         if new_style != self.selector_matches {
             self.selector_matches = new_style;
-            self.stylesheet
-                .replace(self.style.as_ref().unwrap().get(&self.selector_matches));
+            self.set_stylesheet(self.style.as_ref().unwrap().get(&self.selector_matches));
         }
 
         query.ancestors.push(additions);
@@ -167,17 +352,28 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
     fn remove_matches(&mut self, query: &mut Query) {
         let removals = query.match_widget(
             self.widget.widget(),
-            self.class.unwrap_or(""),
+            self.class.as_deref().unwrap_or(""),
+            self.state.as_slice(),
+            self.position.0,
+            self.position.1,
+        );
+
+        // the removed rules may have set flags, which changes the rules matched through flag selectors.
+        let mut new_style = self.selector_matches.difference(&removals);
+        query.style.rule_tree().match_flags(
+            &mut new_style,
             self.state.as_slice(),
+            self.class.as_deref().unwrap_or(""),
             self.position.0,
             self.position.1,
         );
+        let removals = removals
+            .difference(&new_style)
+            .union(&self.selector_matches.difference(&new_style));
 
-        let new_style = self.selector_matches.difference(&removals);
         if new_style != self.selector_matches {
             self.selector_matches = new_style;
-            self.stylesheet
-                .replace(self.style.as_ref().unwrap().get(&self.selector_matches));
+            self.set_stylesheet(self.style.as_ref().unwrap().get(&self.selector_matches));
         }
 
         query.ancestors.push(removals);
@@ -188,11 +384,43 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
         query.siblings.push(query.ancestors.pop().unwrap());
     }
 
+    fn animate_transitions(&mut self) -> bool {
+        let transition = self.transition.as_mut().unwrap();
+        let mut animated = transition.animating();
+        if animated {
+            let stylesheet = transition.current();
+            self.display_stylesheet(stylesheet);
+        }
+        self.widget
+            .visit_children(&mut |child| animated |= child.animate_transitions());
+        animated
+    }
+
+    fn inspect_style(&mut self, key: u64) -> Option<StyleInspection> {
+        if self.key == key {
+            return Some(StyleInspection {
+                stylesheet: self.stylesheet.as_deref().unwrap().clone(),
+                rules: self.style.as_ref().unwrap().matched_rules(&self.selector_matches),
+            });
+        }
+
+        let mut result = None;
+        self.widget.visit_children(&mut |child| {
+            if result.is_none() {
+                result = child.inspect_style(key);
+            }
+        });
+        result
+    }
+
     fn event(&mut self, layout: Rectangle, clip: Rectangle, event: Event, context: &mut Context<Message>) {
+        self.state_token.assert_alive(self.widget.widget());
         let state = self.widget_state.as_mut().unwrap();
         let stylesheet = self.stylesheet.as_ref().unwrap().deref();
-        let layout = layout.after_padding(stylesheet.margin);
+        let layout = widget_layout(layout, stylesheet);
 
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("event", widget = self.widget.widget()).entered();
         self.widget
             .event(&mut **state, layout, clip, stylesheet, event, context);
 
@@ -204,7 +432,7 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
             let new_style = self.style.as_ref().unwrap().rule_tree().rematch(
                 &self.selector_matches,
                 self.state.as_slice(),
-                self.class.unwrap_or(""),
+                self.class.as_deref().unwrap_or(""),
                 self.position.0,
                 self.position.1,
             );
@@ -237,8 +465,10 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
                 }
 
                 self.selector_matches = new_style;
-                self.stylesheet
-                    .replace(self.style.as_ref().unwrap().get(&self.selector_matches));
+                self.set_stylesheet(self.style.as_ref().unwrap().get(&self.selector_matches));
+
+                // the transitions that were started are advanced on the next animation frame.
+                context.animate();
             }
         }
 
@@ -246,10 +476,20 @@ impl<'a, Message, W: Widget<'a, Message>> GenericNode<'a, Message> for WidgetNod
             .replace(Some(self.widget.focused(&**self.widget_state.as_ref().unwrap())));
     }
 
+    fn portal_event(&mut self, event: Event, context: &mut Context<Message>) {
+        let state = self.widget_state.as_mut().unwrap();
+        self.widget.portal_event(&mut **state, event, context);
+    }
+
     fn acquire_waker(&mut self, waker: &std::task::Waker) {
         self.widget.visit_children(&mut |child| child.acquire_waker(waker));
     }
 
+    fn acquire_environment(&mut self, environment: &Environment) {
+        self.widget
+            .visit_children(&mut |child| child.acquire_environment(environment));
+    }
+
     fn poll(&mut self, context: &mut Context<Message>, task_context: &mut std::task::Context) {
         self.widget
             .visit_children(&mut |child| child.poll(context, task_context));
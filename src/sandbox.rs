@@ -161,13 +161,16 @@ where
                     ..
                 } => *control_flow = ControlFlow::Exit,
                 other => {
-                    if let Some(event) = crate::backend::winit::convert_event(other) {
+                    let scale = self.window.scale_factor() as f32;
+                    if let Some(event) = crate::backend::winit::convert_event_with_scale(other, scale) {
                         self.ui.handle_event(event);
+                        self.window
+                            .set_cursor_icon(crate::backend::winit::convert_cursor_icon(self.ui.cursor_icon()));
                     }
                 }
             }
 
-            if self.ui.needs_redraw() {
+            if self.ui.needs_redraw() || self.ui.is_animating() {
                 self.window.request_redraw();
             }
         });
@@ -13,8 +13,10 @@ type DataFuture = Pin<Box<dyn Future<Output = Result<Vec<u8>>>>>;
 pub struct StyleBuilder {
     pub(crate) images: HashMap<String, RgbaImageFuture>,
     pub(crate) patches: HashMap<String, RgbaImageFuture>,
-    pub(crate) fonts: HashMap<String, (RgbaImageFuture, DataFuture)>,
+    pub(crate) fonts: HashMap<String, (RgbaImageFuture, DataFuture, Option<DataFuture>)>,
     pub(crate) rule_tree: tree::RuleTreeBuilder,
+    pub(crate) cache_budget: Option<usize>,
+    pub(crate) variables: HashMap<String, Vec<Token>>,
 }
 
 /// Handle to an image in a `StyleBuilder`.
@@ -29,8 +31,9 @@ pub struct FontId(pub(crate) String);
 
 /// Builder that adds style declarations to a selected rule.
 pub struct RuleBuilder {
-    selector: Vec<Selector>,
+    selector: Vec<Vec<Selector>>,
     declarations: Vec<Declaration<ImageId, PatchId, FontId>>,
+    important: Vec<Declaration<ImageId, PatchId, FontId>>,
 }
 
 impl StyleBuilder {
@@ -41,10 +44,21 @@ impl StyleBuilder {
                 RuleBuilder::new("button")
                     .padding_all(5.0)
                     .margin_all(5.0)
-                    .background_color(background),
+                    .background_color(background)
+                    .transition(TransitionProperty::Background, std::time::Duration::from_millis(150)),
             )
             .rule(RuleBuilder::new("button:hover").background_color(background.blend(primary, 0.5)))
             .rule(RuleBuilder::new("button:pressed").background_color(primary))
+            .rule(
+                RuleBuilder::new("checkbox")
+                    .width(16.0)
+                    .height(16.0)
+                    .padding_all(2.0)
+                    .margin_all(5.0)
+                    .background_color(Color::white())
+                    .color(Color::black()),
+            )
+            .rule(RuleBuilder::new("checkbox:hover").background_color(Color::white().blend(primary, 0.25)))
             .rule(
                 RuleBuilder::new("dropdown")
                     .background_color(background)
@@ -52,6 +66,18 @@ impl StyleBuilder {
                     .padding_all(5.0)
                     .margin_all(5.0),
             )
+            .rule(
+                RuleBuilder::new("image > placeholder")
+                    .width(16.0)
+                    .height(16.0)
+                    .background_color(background),
+            )
+            .rule(
+                RuleBuilder::new("image > error")
+                    .width(16.0)
+                    .height(16.0)
+                    .background_color(Color::red()),
+            )
             .rule(
                 RuleBuilder::new("input")
                     .width(300.0)
@@ -60,6 +86,7 @@ impl StyleBuilder {
                     .padding_all(5.0)
                     .margin_all(5.0),
             )
+            .rule(RuleBuilder::new("item:disabled > *").color(foreground.with_alpha(0.5)))
             .rule(RuleBuilder::new("layers").fill_width().fill_height())
             .rule(
                 RuleBuilder::new("menu")
@@ -67,18 +94,74 @@ impl StyleBuilder {
                     .color(background.blend(primary, 0.5))
                     .padding_all(5.0),
             )
+            .rule(
+                RuleBuilder::new("menu > separator")
+                    .height(1.0)
+                    .background_color(background.blend(primary, 0.5)),
+            )
+            .rule(RuleBuilder::new("progress").background_color(background))
+            .rule(RuleBuilder::new("progress > bar").background_color(primary))
+            .rule(
+                RuleBuilder::new("radio")
+                    .width(16.0)
+                    .height(16.0)
+                    .padding_all(4.0)
+                    .margin_all(5.0)
+                    .border_radius_all(8.0)
+                    .background_color(Color::white())
+                    .color(Color::black()),
+            )
+            .rule(RuleBuilder::new("radio:hover").background_color(Color::white().blend(primary, 0.25)))
+            .rule(RuleBuilder::new("scroll > scrollbar-horizontal").background_color(background.blend(primary, 0.5)))
+            .rule(RuleBuilder::new("scroll > scrollbar-vertical").background_color(background.blend(primary, 0.5)))
+            .rule(RuleBuilder::new("scroll:pressed > scrollbar-horizontal").background_color(primary))
+            .rule(RuleBuilder::new("scroll:pressed > scrollbar-vertical").background_color(primary))
+            .rule(RuleBuilder::new("scrollbar").background_color(background))
+            .rule(RuleBuilder::new("scrollbar > thumb").background_color(background.blend(primary, 0.5)))
+            .rule(RuleBuilder::new("scrollbar:pressed > thumb").background_color(primary))
             .rule(RuleBuilder::new("spacer").fill_width().fill_height())
+            .rule(
+                RuleBuilder::new("toggle")
+                    .width(36.0)
+                    .height(20.0)
+                    .padding_all(2.0)
+                    .margin_all(5.0)
+                    .border_radius_all(10.0)
+                    .background_color(background.blend(foreground, 0.2)),
+            )
+            .rule(RuleBuilder::new("toggle:checked").background_color(primary))
+            .rule(
+                RuleBuilder::new("toggle > knob")
+                    .width(16.0)
+                    .height(16.0)
+                    .border_radius_all(8.0)
+                    .background_color(Color::white()),
+            )
+            .rule(
+                RuleBuilder::new("tooltip")
+                    .background_color(background.blend(foreground, 0.2))
+                    .padding_all(4.0),
+            )
             .rule(
                 RuleBuilder::new("window")
                     .background_color(background.blend(foreground, 0.2))
                     .padding_all(2.0),
             )
             .rule(RuleBuilder::new("window > *:nth-child(0)").background_color(background.blend(primary, 0.2)))
+            .rule(
+                RuleBuilder::new("window > close")
+                    .width(16.0)
+                    .height(16.0)
+                    .background_color(background.blend(primary, 0.5)),
+            )
     }
 
     /// Add a rule defined in a [`RuleBuilder`](struct.RuleBuilder.html) to the `StyleBuilder`.
     pub fn rule(mut self, builder: RuleBuilder) -> Self {
-        self.rule_tree.insert(builder.selector.as_slice(), builder.declarations);
+        for selector in builder.selector {
+            self.rule_tree
+                .insert(selector, builder.declarations.clone(), builder.important.clone());
+        }
         self
     }
 
@@ -96,15 +179,33 @@ impl StyleBuilder {
     }
 
     /// Merge with another `StyleBuilder`.
+    ///
+    /// Declarations from `builder` are applied after the declarations of this `StyleBuilder` with the same selector,
+    /// so they take precedence. Declarations marked [`important`](struct.RuleBuilder.html#method.important) in either
+    /// `StyleBuilder` still take precedence over all regular declarations.
     pub fn merge(mut self, builder: StyleBuilder) -> Self {
         self.images.extend(builder.images);
         self.patches.extend(builder.patches);
         self.fonts.extend(builder.fonts);
         self.rule_tree.merge(builder.rule_tree);
+        self.cache_budget = builder.cache_budget.or(self.cache_budget);
+        self.variables.extend(builder.variables);
+        self
+    }
+
+    /// Sets the memory budget of the texture cache, in texels. When loading an image would exceed the budget, images
+    /// that are no longer referenced are evicted from the cache. See [`Cache`](../../cache/struct.Cache.html).
+    /// Defaults to the number of texels in one texture atlas.
+    pub fn cache_budget(mut self, texels: usize) -> Self {
+        self.cache_budget = Some(texels);
         self
     }
 
     /// Include the scoped style of a `Component` in this `StyleBuilder`.
+    ///
+    /// The rules of the component are scoped to the component's widget, which makes them more specific than unscoped
+    /// rules, so they take precedence over those. To override a component's style from the application style, mark the
+    /// declarations [`important`](struct.RuleBuilder.html#method.important).
     pub fn component<C: Component>(mut self) -> Self {
         let mut builder = C::style();
         self.images.extend(builder.images);
@@ -126,7 +227,7 @@ impl StyleBuilder {
         R: ReadFn,
     {
         let text = String::from_utf8(read.read(path.as_ref()).await?).unwrap();
-        Ok(parse(tokenize(text)?, read).await?)
+        parse_text(text, read, Self::default()).await
     }
 
     /// Synchronously load a stylesheet from a .pwss file. See the [style module documentation](../index.html) on how to write
@@ -135,16 +236,89 @@ impl StyleBuilder {
     where
         P: AsRef<Path>,
     {
-        let mut fut = Self::from_read_fn(path, |path: &Path| std::future::ready(std::fs::read(path)));
-        // this is safe because we are using a noop_waker
-        unsafe {
-            match Pin::new_unchecked(&mut fut)
-                .poll(&mut std::task::Context::from_waker(futures::task::noop_waker_ref()))
-            {
-                std::task::Poll::Ready(result) => result,
-                std::task::Poll::Pending => unreachable!(),
-            }
-        }
+        poll_ready(Self::from_read_fn(path, |path: &Path| {
+            std::future::ready(std::fs::read(path))
+        }))
+    }
+
+    /// Asynchronously parse a stylesheet from .pwss text, for example a theme that was embedded using `include_str!`.
+    /// Images, patches and fonts referenced by the stylesheet are loaded using `read`.
+    /// See the [style module documentation](../index.html) on how to write .pwss files.
+    pub async fn from_string_read_fn<R: ReadFn>(text: &str, read: R) -> anyhow::Result<Self> {
+        parse_text(text.to_string(), read, Self::default()).await
+    }
+
+    /// Synchronously parse a stylesheet from .pwss text, for example a theme that was embedded using `include_str!`.
+    /// Images, patches and fonts referenced by the stylesheet are loaded using `read`, which blocks until the futures
+    /// it returns are complete. See the [style module documentation](../index.html) on how to write .pwss files.
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use pixel_widgets::prelude::*;
+    ///
+    /// // serve the images of the theme from the binary instead of the file system.
+    /// let read = |path: &Path| {
+    ///     std::future::ready(match path.to_str() {
+    ///         Some("bar.png") => Ok(include_bytes!("../../examples/bar.png").to_vec()),
+    ///         _ => Err(std::io::Error::from(std::io::ErrorKind::NotFound)),
+    ///     })
+    /// };
+    /// let style = StyleBuilder::from_string_with("progress { background: \"bar.png\"; }", read).unwrap();
+    /// ```
+    pub fn from_string_with<R: ReadFn>(text: &str, read: R) -> anyhow::Result<Self> {
+        futures::executor::block_on(Self::from_string_read_fn(text, read))
+    }
+
+    /// Synchronously parse a stylesheet from .pwss text, for example a theme that was embedded using `include_str!`.
+    /// Images, patches and fonts referenced by the stylesheet are loaded from the file system, relative to the working
+    /// directory. See the [style module documentation](../index.html) on how to write .pwss files.
+    ///
+    /// ```rust
+    /// use pixel_widgets::prelude::*;
+    ///
+    /// let style = StyleBuilder::from_string("button { padding: 4; } button:hover { color: #f00; }").unwrap();
+    /// ```
+    pub fn from_string(text: &str) -> anyhow::Result<Self> {
+        Self::default().load_string(text)
+    }
+
+    /// Synchronously parse .pwss text and add the rules to this `StyleBuilder`, after the rules that are already in
+    /// it. Images, patches and fonts referenced by the stylesheet are loaded from the file system, relative to the
+    /// working directory. Variables set using [`variable`](#method.variable) override the variables defined in `text`.
+    pub fn load_string(self, text: &str) -> anyhow::Result<Self> {
+        poll_ready(parse_text(
+            text.to_string(),
+            |path: &Path| std::future::ready(std::fs::read(path)),
+            self,
+        ))
+    }
+
+    /// Synchronously load a .pwss file and add the rules to this `StyleBuilder`, after the rules that are already in
+    /// it. Variables set using [`variable`](#method.variable) override the variables defined in the file.
+    pub fn load_file<P: AsRef<Path>>(self, path: P) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        self.load_string(&text)
+    }
+
+    /// Sets a variable that can be used in .pwss files as `var(--name)`. The variable overrides the variable with the
+    /// same name that is defined in a `:root` block of .pwss files that are loaded afterwards using
+    /// [`load_file`](#method.load_file) or [`load_string`](#method.load_string), so a theme can be changed without
+    /// editing it. The leading `--` of the name is optional.
+    ///
+    /// Panics if the value can't be tokenized.
+    ///
+    /// ```rust
+    /// use pixel_widgets::prelude::*;
+    ///
+    /// let theme = ":root { --primary: #33aaff; } button { background: var(--primary); }";
+    /// let style = StyleBuilder::default().variable("primary", "#ff0000").load_string(theme).unwrap();
+    /// ```
+    pub fn variable(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        let name = name.strip_prefix("--").unwrap_or(name);
+        self.variables
+            .insert(name.to_string(), tokenize(value.as_ref().to_string()).unwrap());
+        self
     }
 
     /// Returns an `ImageId` for the `key`.
@@ -179,6 +353,25 @@ impl StyleBuilder {
         self.load_font_async(key, async move { load_rgba() }, async move { load_data() })
     }
 
+    /// Returns a `FontId` for the `key`, like [`load_font`](#method.load_font).
+    /// Glyphs that have color data in the `CBDT`, `sbix` or `COLR` tables of the source font file are replaced by full
+    /// color glyphs. The `load_source` closure must return the bytes of the .ttf or .otf file the atlas was generated
+    /// from.
+    pub fn load_color_font(
+        &mut self,
+        key: impl Into<String>,
+        load_rgba: impl FnOnce() -> Result<RgbaImage> + 'static,
+        load_data: impl FnOnce() -> Result<Vec<u8>> + 'static,
+        load_source: impl FnOnce() -> Result<Vec<u8>> + 'static,
+    ) -> FontId {
+        self.load_color_font_async(
+            key,
+            async move { load_rgba() },
+            async move { load_data() },
+            async move { load_source() },
+        )
+    }
+
     /// Returns an `ImageId` for the `key`.
     /// When the style is built, the image is loaded by awaiting the future.
     pub fn load_image_async(
@@ -218,7 +411,25 @@ impl StyleBuilder {
     ) -> FontId {
         let key = key.into();
         if let std::collections::hash_map::Entry::Vacant(v) = self.fonts.entry(key.clone()) {
-            v.insert((Box::pin(fut_rgba), Box::pin(fut_data)));
+            v.insert((Box::pin(fut_rgba), Box::pin(fut_data), None));
+        }
+        FontId(key)
+    }
+
+    /// Returns a `FontId` for the `key`, like [`load_font_async`](#method.load_font_async).
+    /// Glyphs that have color data in the `CBDT`, `sbix` or `COLR` tables of the source font file are replaced by full
+    /// color glyphs. The `fut_source` future must output the bytes of the .ttf or .otf file the atlas was generated
+    /// from.
+    pub fn load_color_font_async(
+        &mut self,
+        key: impl Into<String>,
+        fut_rgba: impl Future<Output = Result<RgbaImage>> + 'static,
+        fut_data: impl Future<Output = Result<Vec<u8>>> + 'static,
+        fut_source: impl Future<Output = Result<Vec<u8>>> + 'static,
+    ) -> FontId {
+        let key = key.into();
+        if let std::collections::hash_map::Entry::Vacant(v) = self.fonts.entry(key.clone()) {
+            v.insert((Box::pin(fut_rgba), Box::pin(fut_data), Some(Box::pin(fut_source))));
         }
         FontId(key)
     }
@@ -228,13 +439,16 @@ impl StyleBuilder {
     pub async fn build_async(mut self) -> Result<Style> {
         self = Self::base(Color::white(), Color::rgb(0.3, 0.3, 0.3), Color::blue()).merge(self);
 
-        let mut cache = Cache::new(2048);
+        let mut cache = match self.cache_budget {
+            Some(budget) => Cache::with_budget(2048, budget),
+            None => Cache::new(2048),
+        };
 
         let font_image = image::load_from_memory(include_bytes!("default_font.png"))
             .unwrap()
             .into_rgba8();
         let font = cache
-            .load_font(include_bytes!("default_font.json"), font_image)
+            .load_font(include_bytes!("default_font.json"), font_image, None)
             .unwrap();
 
         let mut images = HashMap::new();
@@ -262,8 +476,14 @@ impl StyleBuilder {
         }
 
         let mut fonts = HashMap::new();
-        for (key, (rgba, data)) in self.fonts {
-            let load = async { Result::<_, Error>::Ok(cache.load_font(data.await?, rgba.await?)?) };
+        for (key, (rgba, data, source)) in self.fonts {
+            let load = async {
+                let source = match source {
+                    Some(source) => Some(source.await?),
+                    None => None,
+                };
+                Result::<_, Error>::Ok(cache.load_font(data.await?, rgba.await?, source.as_deref())?)
+            };
             fonts.insert(
                 key.clone(),
                 load.await
@@ -274,8 +494,11 @@ impl StyleBuilder {
         Ok(Style {
             cache: Arc::new(Mutex::new(cache)),
             resolved: Default::default(),
+            transition_started: Default::default(),
+            layout_changed: Default::default(),
             default: Stylesheet {
                 background: Background::None,
+                border_radius: Corners::default(),
                 font,
                 color: Color::white(),
                 padding: Rectangle::zero(),
@@ -283,12 +506,22 @@ impl StyleBuilder {
                 text_size: 16.0,
                 text_border: 0.3,
                 text_wrap: TextWrap::NoWrap,
+                text_decoration: TextDecoration::default(),
                 width: Size::Shrink,
                 height: Size::Shrink,
+                min_width: 0.0,
+                max_width: f32::INFINITY,
+                min_height: 0.0,
+                max_height: f32::INFINITY,
                 direction: Direction::LeftToRight,
                 align_horizontal: Align::Begin,
                 align_vertical: Align::Begin,
+                spacing: 0.0,
+                z_index: 0,
                 flags: Vec::new(),
+                transitions: Vec::new(),
+                delay: None,
+                content: Content::None,
             },
             rule_tree: self.rule_tree.build(&images, &patches, &fonts),
         })
@@ -322,7 +555,8 @@ impl TryInto<Style> for StyleBuilder {
 
 impl RuleBuilder {
     /// Constructs a new `RuleBuilder` for the given selector.
-    /// The selector must follow the same syntax as the [.pwss file format](../index.html).
+    /// The selector must follow the same syntax as the [.pwss file format](../index.html),
+    /// including comma separated groups of selectors.
     ///
     /// Panics if the selector can't be parsed.
     ///
@@ -331,12 +565,33 @@ impl RuleBuilder {
     ///
     /// // Sets the background of the first direct child of any window widget
     /// RuleBuilder::new("window > * :nth-child(0)").background_color(Color::red());
+    ///
+    /// // Sets the text color of both buttons and inputs inside a column
+    /// RuleBuilder::new("column button, column input").color(Color::blue());
     /// ```
     pub fn new<S: AsRef<str>>(selector: S) -> Self {
         Self {
-            selector: parse_selectors(tokenize(selector.as_ref().to_string()).unwrap()).unwrap(),
+            selector: parse_selector_groups(tokenize(selector.as_ref().to_string()).unwrap()).unwrap(),
             declarations: Vec::new(),
+            important: Vec::new(),
+        }
+    }
+    /// Marks the previously added declaration as important, like `!important` in .pwss files.
+    /// Important declarations are applied after the regular declarations of all matching rules, so they win regardless
+    /// of the order in which rules were added or how specific their selectors are.
+    /// Between important declarations the regular order applies.
+    ///
+    /// ```rust
+    /// use pixel_widgets::prelude::*;
+    ///
+    /// // Buttons are red, even inside components that style their own buttons
+    /// RuleBuilder::new("button").background_color(Color::red()).important();
+    /// ```
+    pub fn important(mut self) -> Self {
+        if let Some(declaration) = self.declarations.pop() {
+            self.important.push(declaration);
         }
+        self
     }
     /// Clears the background
     pub fn background_none(mut self) -> Self {
@@ -363,98 +618,101 @@ impl RuleBuilder {
         self.declarations.push(Declaration::Font(value));
         self
     }
+    /// Sets the radii of the corners of a color background, in clockwise order starting at the top left corner.
+    pub fn border_radius(mut self, value: impl Into<Sides>) -> Self {
+        self.declarations.push(Declaration::BorderRadius(value.into()));
+        self
+    }
+    /// Sets the radii of all corners of a color background to the same value
+    pub fn border_radius_all(self, value: impl Into<Length>) -> Self {
+        self.border_radius(Sides::all(value.into()))
+    }
     /// Sets the foreground color
     pub fn color(mut self, value: Color) -> Self {
         self.declarations.push(Declaration::Color(value));
         self
     }
     /// Sets padding
-    pub fn padding(mut self, value: Rectangle) -> Self {
-        self.declarations.push(Declaration::Padding(value));
+    pub fn padding(mut self, value: impl Into<Sides>) -> Self {
+        self.declarations.push(Declaration::Padding(value.into()));
         self
     }
     /// Sets all padding values to the same value
-    pub fn padding_all(self, value: f32) -> Self {
-        self.padding(Rectangle {
-            left: value,
-            top: value,
-            right: value,
-            bottom: value,
-        })
+    pub fn padding_all(self, value: impl Into<Length>) -> Self {
+        self.padding(Sides::all(value.into()))
     }
     /// Sets horizontal padding values to the same value
-    pub fn padding_horizontal(self, value: f32) -> Self {
+    pub fn padding_horizontal(self, value: impl Into<Length>) -> Self {
+        let value = value.into();
         self.padding_left(value).padding_right(value)
     }
     /// Sets vertical padding values to the same value
-    pub fn padding_vertical(self, value: f32) -> Self {
+    pub fn padding_vertical(self, value: impl Into<Length>) -> Self {
+        let value = value.into();
         self.padding_top(value).padding_bottom(value)
     }
     /// Sets left padding
-    pub fn padding_left(mut self, value: f32) -> Self {
-        self.declarations.push(Declaration::PaddingLeft(value));
+    pub fn padding_left(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::PaddingLeft(value.into()));
         self
     }
     /// Sets right padding
-    pub fn padding_right(mut self, value: f32) -> Self {
-        self.declarations.push(Declaration::PaddingRight(value));
+    pub fn padding_right(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::PaddingRight(value.into()));
         self
     }
     /// Sets top padding
-    pub fn padding_top(mut self, value: f32) -> Self {
-        self.declarations.push(Declaration::PaddingTop(value));
+    pub fn padding_top(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::PaddingTop(value.into()));
         self
     }
     /// Sets bottom padding
-    pub fn padding_bottom(mut self, value: f32) -> Self {
-        self.declarations.push(Declaration::PaddingBottom(value));
+    pub fn padding_bottom(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::PaddingBottom(value.into()));
         self
     }
     /// Sets the margins
-    pub fn margin(mut self, value: Rectangle) -> Self {
-        self.declarations.push(Declaration::Margin(value));
+    pub fn margin(mut self, value: impl Into<Sides>) -> Self {
+        self.declarations.push(Declaration::Margin(value.into()));
         self
     }
     /// Sets all margin values to the same value
-    pub fn margin_all(self, value: f32) -> Self {
-        self.margin(Rectangle {
-            left: value,
-            top: value,
-            right: value,
-            bottom: value,
-        })
+    pub fn margin_all(self, value: impl Into<Length>) -> Self {
+        self.margin(Sides::all(value.into()))
     }
     /// Sets horizontal margin values to the same value
-    pub fn margin_horizontal(self, value: f32) -> Self {
+    pub fn margin_horizontal(self, value: impl Into<Length>) -> Self {
+        let value = value.into();
         self.margin_left(value).margin_right(value)
     }
     /// Sets vertical margin values to the same value
-    pub fn margin_vertical(self, value: f32) -> Self {
+    pub fn margin_vertical(self, value: impl Into<Length>) -> Self {
+        let value = value.into();
         self.margin_top(value).margin_bottom(value)
     }
     /// Sets the left margin
-    pub fn margin_left(mut self, value: f32) -> Self {
-        self.declarations.push(Declaration::MarginLeft(value));
+    pub fn margin_left(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::MarginLeft(value.into()));
         self
     }
     /// Sets the right margin
-    pub fn margin_right(mut self, value: f32) -> Self {
-        self.declarations.push(Declaration::MarginRight(value));
+    pub fn margin_right(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::MarginRight(value.into()));
         self
     }
     /// Sets the top margin
-    pub fn margin_top(mut self, value: f32) -> Self {
-        self.declarations.push(Declaration::MarginTop(value));
+    pub fn margin_top(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::MarginTop(value.into()));
         self
     }
     /// Sets the bottom margin
-    pub fn margin_bottom(mut self, value: f32) -> Self {
-        self.declarations.push(Declaration::MarginBottom(value));
+    pub fn margin_bottom(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::MarginBottom(value.into()));
         self
     }
     /// Sets the text size
-    pub fn text_size(mut self, value: f32) -> Self {
-        self.declarations.push(Declaration::TextSize(value));
+    pub fn text_size(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::TextSize(value.into()));
         self
     }
     /// Sets the way text wraps
@@ -462,6 +720,29 @@ impl RuleBuilder {
         self.declarations.push(Declaration::TextWrap(value));
         self
     }
+    /// Sets the lines that are drawn along text
+    pub fn text_decoration(mut self, value: TextDecoration) -> Self {
+        self.declarations.push(Declaration::TextDecoration(value));
+        self
+    }
+    /// Adds a transition for a property, which animates the property from it's previous value when the rule starts or
+    /// stops applying to a widget.
+    pub fn transition(mut self, property: TransitionProperty, duration: std::time::Duration) -> Self {
+        let transition = Transition { property, duration };
+        let existing = self
+            .declarations
+            .iter_mut()
+            .rev()
+            .find_map(|declaration| match declaration {
+                Declaration::Transition(transitions) => Some(transitions),
+                _ => None,
+            });
+        match existing {
+            Some(transitions) => transitions.push(transition),
+            None => self.declarations.push(Declaration::Transition(vec![transition])),
+        }
+        self
+    }
     /// Sets the preferred width
     pub fn width(mut self, value: impl Into<Size>) -> Self {
         self.declarations.push(Declaration::Width(value.into()));
@@ -482,6 +763,26 @@ impl RuleBuilder {
         self.declarations.push(Declaration::Height(Size::Fill(1)));
         self
     }
+    /// Sets the minimum width
+    pub fn min_width(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::MinWidth(value.into()));
+        self
+    }
+    /// Sets the maximum width
+    pub fn max_width(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::MaxWidth(value.into()));
+        self
+    }
+    /// Sets the minimum height
+    pub fn min_height(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::MinHeight(value.into()));
+        self
+    }
+    /// Sets the maximum height
+    pub fn max_height(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::MaxHeight(value.into()));
+        self
+    }
     /// Sets the direction for layouting
     pub fn layout_direction(mut self, value: Direction) -> Self {
         self.declarations.push(Declaration::LayoutDirection(value));
@@ -497,6 +798,36 @@ impl RuleBuilder {
         self.declarations.push(Declaration::AlignVertical(value));
         self
     }
+    /// Sets the gap between the children of a `Column` or `Row`
+    pub fn spacing(mut self, value: impl Into<Length>) -> Self {
+        self.declarations.push(Declaration::Spacing(value.into()));
+        self
+    }
+    /// Sets the layer to draw on, relative to the layer of the parent
+    pub fn z_index(mut self, value: i32) -> Self {
+        self.declarations.push(Declaration::ZIndex(value));
+        self
+    }
+    /// Sets the time before a widget reacts to the cursor resting on it, such as the time before a tooltip appears
+    pub fn delay(mut self, value: std::time::Duration) -> Self {
+        self.declarations.push(Declaration::Delay(value));
+        self
+    }
+    /// Clears the content of a pseudo element
+    pub fn content_none(mut self) -> Self {
+        self.declarations.push(Declaration::ContentNone);
+        self
+    }
+    /// Sets the content of a pseudo element to a text
+    pub fn content_text(mut self, value: impl Into<String>) -> Self {
+        self.declarations.push(Declaration::ContentText(value.into()));
+        self
+    }
+    /// Sets the content of a pseudo element to an image
+    pub fn content_image(mut self, value: ImageId) -> Self {
+        self.declarations.push(Declaration::ContentImage(value));
+        self
+    }
     /// Adds a flag to the stylesheet
     pub fn add_flag(mut self, value: String) -> Self {
         self.declarations.push(Declaration::AddFlag(value));
@@ -508,3 +839,14 @@ impl RuleBuilder {
         self
     }
 }
+
+/// Polls a future that completes without waiting, such as one that only reads files synchronously.
+fn poll_ready<T>(mut fut: impl Future<Output = T>) -> T {
+    // this is safe because the future is never moved after being pinned
+    unsafe {
+        match Pin::new_unchecked(&mut fut).poll(&mut std::task::Context::from_waker(futures::task::noop_waker_ref())) {
+            std::task::Poll::Ready(result) => result,
+            std::task::Poll::Pending => unreachable!(),
+        }
+    }
+}
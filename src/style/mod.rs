@@ -1,12 +1,13 @@
 #![doc = include_str!("../../style.md")]
 use std::collections::HashMap;
 use std::iter::Peekable;
+use std::time::Duration;
 
 use crate::bitset::BitSet;
 use crate::cache::Cache;
-use crate::draw::{Background, Color, ImageData, Patch};
+use crate::draw::{Background, Color, Corners, ImageData, Patch};
 use crate::layout::{Align, Direction, Rectangle, Size};
-use crate::text::{Font, TextWrap};
+use crate::text::{Font, TextDecoration, TextWrap};
 
 /// Style building tools
 pub mod builder;
@@ -20,6 +21,7 @@ use futures::FutureExt;
 use parse::*;
 use std::future::Future;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tokenize::*;
 
@@ -28,8 +30,8 @@ use builder::*;
 /// Errors that can be encountered while loading a stylesheet
 #[derive(Debug)]
 pub enum Error {
-    /// Syntax error
-    Syntax(String, TokenPos),
+    /// Syntax error, with the position of the error and the text of the line that contains it, if it's known.
+    Syntax(String, TokenPos, Option<String>),
     /// Unexpected end of file error
     Eof,
     /// Image loading error
@@ -42,6 +44,10 @@ pub enum Error {
 pub struct Style {
     cache: Arc<Mutex<Cache>>,
     resolved: Mutex<HashMap<BitSet, Arc<Stylesheet>>>,
+    // set when a widget starts a transition, so the `Ui` knows it has to advance the transitions of the widgets.
+    transition_started: AtomicBool,
+    // set when the layout properties of a widget change after it was laid out, so the `Ui` knows to rebuild.
+    layout_changed: AtomicBool,
     default: Stylesheet,
     rule_tree: tree::RuleTree,
 }
@@ -75,8 +81,18 @@ pub struct Stylesheet {
     pub width: Size,
     /// Widget height
     pub height: Size,
+    /// Minimum width of the layout rect of the widget
+    pub min_width: f32,
+    /// Maximum width of the layout rect of the widget
+    pub max_width: f32,
+    /// Minimum height of the layout rect of the widget
+    pub min_height: f32,
+    /// Maximum height of the layout rect of the widget
+    pub max_height: f32,
     /// Background for the widget that full covers the layout rect
     pub background: Background,
+    /// Radii of the corners of color backgrounds
+    pub border_radius: Corners,
     /// Amount of padding to use on each side of the content
     pub padding: Rectangle,
     /// Size of the margin on each side of the widget
@@ -91,18 +107,150 @@ pub struct Stylesheet {
     pub text_border: f32,
     /// Wrapping strategy for text
     pub text_wrap: TextWrap,
+    /// Lines to draw along text
+    pub text_decoration: TextDecoration,
     /// Layout direction for widgets that support it (atm not text unfortunately..)
     pub direction: Direction,
     /// How to align children horizontally
     pub align_horizontal: Align,
     /// How to align children vertically
     pub align_vertical: Align,
+    /// Gap between the children of widgets that lay out a list of children, such as `Column` and `Row`
+    pub spacing: f32,
+    /// Layer to draw the widget and it's descendants on, relative to the layer of it's parent.
+    /// Higher layers are drawn in front of lower layers.
+    pub z_index: i32,
     /// Flags
     pub flags: Vec<String>,
+    /// Content of pseudo elements, such as `::before` and `::after`
+    pub content: Content,
+    /// Properties that animate from their previous value when the stylesheet of the widget changes
+    pub transitions: Vec<Transition>,
+    /// Time before a widget reacts to the cursor resting on it, such as the time before a `Tooltip` appears.
+    /// `None` leaves it up to the widget.
+    pub delay: Option<Duration>,
+}
+
+/// Content that is injected by pseudo elements, such as `::before` and `::after`.
+#[derive(Clone, Debug)]
+pub enum Content {
+    /// No content
+    None,
+    /// A short text, such as a check mark or an icon from an icon font
+    Text(String),
+    /// An image
+    Image(ImageData),
+}
+
+/// A transition of a style property, declared using `transition: <property> <duration>`.
+/// When the stylesheet of a widget changes, for example because it became hovered, the properties that have a
+/// transition in the new stylesheet are interpolated from their previous value over the duration of the transition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    /// The property that is animated
+    pub property: TransitionProperty,
+    /// The time it takes to animate the property to it's new value
+    pub duration: Duration,
+}
+
+/// A style property that can be animated using a [`Transition`](struct.Transition.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionProperty {
+    /// All properties that support transitions
+    All,
+    /// The color of color backgrounds, and the color that image and patch backgrounds are multiplied with
+    Background,
+    /// The foreground color
+    Color,
+    /// The padding on each side of the content
+    Padding,
+}
+
+/// A length in a style declaration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    /// A length in pixels. Numbers without a unit are also pixels.
+    Px(f32),
+    /// A length relative to the resolved `text-size` of the widget.
+    Em(f32),
+}
+
+/// Lengths for the four sides of a rectangle, used by the `padding` and `margin` declarations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sides {
+    /// Top side
+    pub top: Length,
+    /// Right side
+    pub right: Length,
+    /// Bottom side
+    pub bottom: Length,
+    /// Left side
+    pub left: Length,
+}
+
+impl Length {
+    /// Resolve the length to pixels, using `em` as the size of `1em`.
+    pub fn resolve(self, em: f32) -> f32 {
+        match self {
+            Length::Px(x) => x,
+            Length::Em(x) => x * em,
+        }
+    }
+}
+
+impl From<f32> for Length {
+    fn from(value: f32) -> Self {
+        Length::Px(value)
+    }
+}
+
+impl Sides {
+    /// Construct `Sides` with the same length on all sides.
+    pub fn all(value: Length) -> Self {
+        Self {
+            top: value,
+            right: value,
+            bottom: value,
+            left: value,
+        }
+    }
+
+    /// Resolve the lengths to a `Rectangle` in pixels, using `em` as the size of `1em`.
+    pub fn resolve(self, em: f32) -> Rectangle {
+        Rectangle {
+            left: self.left.resolve(em),
+            top: self.top.resolve(em),
+            right: self.right.resolve(em),
+            bottom: self.bottom.resolve(em),
+        }
+    }
+
+    /// Resolve the lengths to `Corners` in pixels, using `em` as the size of `1em`.
+    /// The sides map to the corners in clockwise order: `top` is the top left corner, `right` the top right corner,
+    /// `bottom` the bottom right corner and `left` the bottom left corner.
+    pub fn resolve_corners(self, em: f32) -> Corners {
+        Corners {
+            top_left: self.top.resolve(em),
+            top_right: self.right.resolve(em),
+            bottom_right: self.bottom.resolve(em),
+            bottom_left: self.left.resolve(em),
+        }
+    }
+}
+
+impl From<Rectangle> for Sides {
+    fn from(value: Rectangle) -> Self {
+        Self {
+            top: Length::Px(value.top),
+            right: Length::Px(value.right),
+            bottom: Length::Px(value.bottom),
+            left: Length::Px(value.left),
+        }
+    }
 }
 
 /// A style property and it's value
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Declaration<I = ImageId, P = PatchId, F = FontId> {
     /// no background
     BackgroundNone,
@@ -112,46 +260,72 @@ pub enum Declaration<I = ImageId, P = PatchId, F = FontId> {
     BackgroundImage(I, Color),
     /// background patch
     BackgroundPatch(P, Color),
+    /// border-radius, in clockwise order starting at the top left corner
+    BorderRadius(Sides),
     /// font
     Font(F),
     /// color
     Color(Color),
     /// padding
-    Padding(Rectangle),
+    Padding(Sides),
     /// padding left
-    PaddingLeft(f32),
+    PaddingLeft(Length),
     /// Padding right
-    PaddingRight(f32),
+    PaddingRight(Length),
     /// Padding top
-    PaddingTop(f32),
+    PaddingTop(Length),
     /// Padding bottom
-    PaddingBottom(f32),
+    PaddingBottom(Length),
     /// margin
-    Margin(Rectangle),
+    Margin(Sides),
     /// padding left
-    MarginLeft(f32),
+    MarginLeft(Length),
     /// Padding right
-    MarginRight(f32),
+    MarginRight(Length),
     /// Padding top
-    MarginTop(f32),
+    MarginTop(Length),
     /// Padding bottom
-    MarginBottom(f32),
+    MarginBottom(Length),
     /// text-size
-    TextSize(f32),
+    TextSize(Length),
     /// text-border
-    TextBorder(f32),
+    TextBorder(Length),
     /// text-wrap
     TextWrap(TextWrap),
+    /// text-decoration
+    TextDecoration(TextDecoration),
     /// width
     Width(Size),
     /// height
     Height(Size),
+    /// min-width
+    MinWidth(Length),
+    /// max-width
+    MaxWidth(Length),
+    /// min-height
+    MinHeight(Length),
+    /// max-height
+    MaxHeight(Length),
     /// layout-direction
     LayoutDirection(Direction),
     /// align-horizontal
     AlignHorizontal(Align),
     /// align-vertical
     AlignVertical(Align),
+    /// spacing
+    Spacing(Length),
+    /// z-index
+    ZIndex(i32),
+    /// transition
+    Transition(Vec<Transition>),
+    /// delay
+    Delay(Duration),
+    /// content: none;
+    ContentNone,
+    /// content: "text";
+    ContentText(String),
+    /// content: image("url");
+    ContentImage(I),
     /// flag: true;
     AddFlag(String),
     /// flag: false;
@@ -179,16 +353,44 @@ pub enum Selector {
     Nth(usize),
     /// Match the nth child widget counted from the last child widget
     NthLast(usize),
+    /// Match child widgets at an index of `step * k + offset` for any `k >= 0`
+    NthStep(isize, isize),
+    /// Match child widgets at an index of `step * k + offset` for any `k >= 0`, counted from the last child widget
+    NthLastStep(isize, isize),
     /// Match widgets that are the only child of their parent
     OnlyChild,
     /// Match widgets that have a class
     Class(String),
+    /// Match widgets that have a flag, set by the declarations of other rules that match the widget
+    Flag(String),
     /// Match widgets that are in a state
     State(StyleState<String>),
     /// Invert the nested selector
     Not(Box<Selector>),
 }
 
+/// Snapshot of the style that was resolved for a single widget, used for debugging styles.
+/// Obtained through [`Ui::inspect_style`](../struct.Ui.html#method.inspect_style).
+#[derive(Clone, Debug)]
+pub struct StyleInspection {
+    /// The stylesheet that the widget is currently drawn with. While a transition is running, this is the
+    /// interpolated stylesheet rather than the stylesheet that the matched rules resolve to.
+    pub stylesheet: Stylesheet,
+    /// All rules that matched the widget, in the order they were applied.
+    pub rules: Vec<MatchedRule>,
+}
+
+/// A rule from the style that matched a widget.
+#[derive(Clone, Debug)]
+pub struct MatchedRule {
+    /// The full selector path of the rule, starting at the root.
+    pub selectors: Vec<Selector>,
+    /// The declarations that were applied by this rule.
+    pub declarations: Vec<Declaration<ImageData, Patch, Font>>,
+    /// The important declarations that were applied by this rule, after the declarations of all rules.
+    pub important: Vec<Declaration<ImageData, Patch, Font>>,
+}
+
 /// Widget name as used in a `Selector`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SelectorWidget {
@@ -238,14 +440,36 @@ impl Style {
             return existing.clone();
         }
         let mut computed = self.default.clone();
+        // text-size is resolved first, so that `em` lengths are relative to the final text size.
+        // `em` in text-size itself is relative to the default text size, so later declarations replace earlier ones.
+        let default_em = computed.text_size;
         for rule in self.rule_tree.iter_declarations(style) {
-            rule.apply(&mut computed);
+            if let Declaration::TextSize(_) = rule {
+                rule.apply_em(&mut computed, default_em);
+            }
+        }
+        let em = computed.text_size;
+        for rule in self.rule_tree.iter_declarations(style) {
+            if !matches!(rule, Declaration::TextSize(_)) {
+                rule.apply_em(&mut computed, em);
+            }
         }
         let result = Arc::new(computed);
         resolved.insert(style.clone(), result.clone());
         result
     }
 
+    pub(crate) fn matched_rules(&self, style: &BitSet) -> Vec<MatchedRule> {
+        style
+            .iter()
+            .map(|rule| MatchedRule {
+                selectors: self.rule_tree.selectors(rule),
+                declarations: self.rule_tree.declarations(rule).to_vec(),
+                important: self.rule_tree.important_declarations(rule).to_vec(),
+            })
+            .collect()
+    }
+
     pub(crate) fn rule_tree(&self) -> &tree::RuleTree {
         &self.rule_tree
     }
@@ -254,6 +478,22 @@ impl Style {
         self.cache.clone()
     }
 
+    pub(crate) fn start_transition(&self) {
+        self.transition_started.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn take_transition_started(&self) -> bool {
+        self.transition_started.swap(false, Ordering::Relaxed)
+    }
+
+    pub(crate) fn change_layout(&self) {
+        self.layout_changed.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn take_layout_changed(&self) -> bool {
+        self.layout_changed.swap(false, Ordering::Relaxed)
+    }
+
     /// Retrieve a `Graphics` loader that can be used to load images
     pub fn graphics(&self) -> Graphics {
         Graphics { cache: self.cache() }
@@ -271,36 +511,121 @@ impl Stylesheet {
     pub fn contains(&self, flag: &str) -> bool {
         self.flags.binary_search_by_key(&flag, |s| s.as_str()).is_ok()
     }
+
+    /// Interpolates the properties that have a transition in this stylesheet from their values in `from`, `elapsed`
+    /// after the transition started. Returns `None` if all transitions are complete.
+    /// Returns whether the properties that affect the size of the widget differ from `other`.
+    pub(crate) fn layout_differs(&self, other: &Stylesheet) -> bool {
+        self.width != other.width
+            || self.height != other.height
+            || self.min_width != other.min_width
+            || self.max_width != other.max_width
+            || self.min_height != other.min_height
+            || self.max_height != other.max_height
+            || self.padding != other.padding
+            || self.margin != other.margin
+            || self.spacing != other.spacing
+            || self.text_size != other.text_size
+    }
+
+    pub(crate) fn transition_from(&self, from: &Stylesheet, elapsed: Duration) -> Option<Stylesheet> {
+        let mut result = self.clone();
+        let mut complete = true;
+        for transition in self.transitions.iter() {
+            let t = if transition.duration.is_zero() {
+                1.0
+            } else {
+                elapsed.as_secs_f32() / transition.duration.as_secs_f32()
+            };
+            if t >= 1.0 {
+                continue;
+            }
+            complete = false;
+
+            if transition.property.includes(TransitionProperty::Background) {
+                result.background = blend_background(&from.background, &self.background, t);
+            }
+            if transition.property.includes(TransitionProperty::Color) {
+                result.color = from.color.blend(self.color, t);
+            }
+            if transition.property.includes(TransitionProperty::Padding) {
+                let lerp = |a: f32, b: f32| a + (b - a) * t;
+                result.padding = Rectangle {
+                    left: lerp(from.padding.left, self.padding.left),
+                    top: lerp(from.padding.top, self.padding.top),
+                    right: lerp(from.padding.right, self.padding.right),
+                    bottom: lerp(from.padding.bottom, self.padding.bottom),
+                };
+            }
+        }
+
+        if complete {
+            None
+        } else {
+            Some(result)
+        }
+    }
+}
+
+impl TransitionProperty {
+    fn includes(self, property: TransitionProperty) -> bool {
+        self == TransitionProperty::All || self == property
+    }
 }
 
 impl Declaration<ImageData, Patch, Font> {
-    /// Apply values to a `Stylesheet`.
+    /// Apply values to a `Stylesheet`, resolving `em` lengths relative to the current text size of the stylesheet.
     pub fn apply(&self, stylesheet: &mut Stylesheet) {
+        self.apply_em(stylesheet, stylesheet.text_size)
+    }
+
+    /// Apply values to a `Stylesheet`, resolving `em` lengths relative to the `em` text size.
+    pub fn apply_em(&self, stylesheet: &mut Stylesheet, em: f32) {
         match self {
             Declaration::BackgroundNone => stylesheet.background = Background::None,
-            Declaration::BackgroundColor(x) => stylesheet.background = Background::Color(*x),
+            Declaration::BackgroundColor(x) => {
+                stylesheet.background = color_background(*x, stylesheet.border_radius);
+            }
             Declaration::BackgroundImage(x, y) => stylesheet.background = Background::Image(x.clone(), *y),
             Declaration::BackgroundPatch(x, y) => stylesheet.background = Background::Patch(x.clone(), *y),
+            Declaration::BorderRadius(x) => {
+                stylesheet.border_radius = x.resolve_corners(em);
+                if let Background::Color(color) | Background::RoundedColor(color, _) = stylesheet.background {
+                    stylesheet.background = color_background(color, stylesheet.border_radius);
+                }
+            }
             Declaration::Font(x) => stylesheet.font = x.clone(),
             Declaration::Color(x) => stylesheet.color = *x,
-            Declaration::Padding(x) => stylesheet.padding = *x,
-            Declaration::PaddingLeft(x) => stylesheet.padding.left = *x,
-            Declaration::PaddingRight(x) => stylesheet.padding.right = *x,
-            Declaration::PaddingTop(x) => stylesheet.padding.top = *x,
-            Declaration::PaddingBottom(x) => stylesheet.padding.bottom = *x,
-            Declaration::Margin(x) => stylesheet.margin = *x,
-            Declaration::MarginLeft(x) => stylesheet.margin.left = *x,
-            Declaration::MarginRight(x) => stylesheet.margin.right = *x,
-            Declaration::MarginTop(x) => stylesheet.margin.top = *x,
-            Declaration::MarginBottom(x) => stylesheet.margin.bottom = *x,
-            Declaration::TextSize(x) => stylesheet.text_size = *x,
-            Declaration::TextBorder(x) => stylesheet.text_border = *x,
+            Declaration::Padding(x) => stylesheet.padding = x.resolve(em),
+            Declaration::PaddingLeft(x) => stylesheet.padding.left = x.resolve(em),
+            Declaration::PaddingRight(x) => stylesheet.padding.right = x.resolve(em),
+            Declaration::PaddingTop(x) => stylesheet.padding.top = x.resolve(em),
+            Declaration::PaddingBottom(x) => stylesheet.padding.bottom = x.resolve(em),
+            Declaration::Margin(x) => stylesheet.margin = x.resolve(em),
+            Declaration::MarginLeft(x) => stylesheet.margin.left = x.resolve(em),
+            Declaration::MarginRight(x) => stylesheet.margin.right = x.resolve(em),
+            Declaration::MarginTop(x) => stylesheet.margin.top = x.resolve(em),
+            Declaration::MarginBottom(x) => stylesheet.margin.bottom = x.resolve(em),
+            Declaration::TextSize(x) => stylesheet.text_size = x.resolve(em),
+            Declaration::TextBorder(x) => stylesheet.text_border = x.resolve(em),
             Declaration::TextWrap(x) => stylesheet.text_wrap = *x,
+            Declaration::TextDecoration(x) => stylesheet.text_decoration = *x,
             Declaration::Width(x) => stylesheet.width = *x,
             Declaration::Height(x) => stylesheet.height = *x,
+            Declaration::MinWidth(x) => stylesheet.min_width = x.resolve(em),
+            Declaration::MaxWidth(x) => stylesheet.max_width = x.resolve(em),
+            Declaration::MinHeight(x) => stylesheet.min_height = x.resolve(em),
+            Declaration::MaxHeight(x) => stylesheet.max_height = x.resolve(em),
             Declaration::LayoutDirection(x) => stylesheet.direction = *x,
             Declaration::AlignHorizontal(x) => stylesheet.align_horizontal = *x,
             Declaration::AlignVertical(x) => stylesheet.align_vertical = *x,
+            Declaration::Spacing(x) => stylesheet.spacing = x.resolve(em),
+            Declaration::ZIndex(x) => stylesheet.z_index = *x,
+            Declaration::Transition(x) => stylesheet.transitions = x.clone(),
+            Declaration::Delay(x) => stylesheet.delay = Some(*x),
+            Declaration::ContentNone => stylesheet.content = Content::None,
+            Declaration::ContentText(x) => stylesheet.content = Content::Text(x.clone()),
+            Declaration::ContentImage(x) => stylesheet.content = Content::Image(x.clone()),
             Declaration::AddFlag(x) => {
                 if let Err(insert_at) = stylesheet.flags.binary_search(x) {
                     stylesheet.flags.insert(insert_at, x.clone());
@@ -336,6 +661,16 @@ impl Selector {
         }
     }
 
+    /// Match the flags of the widget matched by the current rule. `flags` must be sorted.
+    /// If this selector is not a flag selector `None` is returned.
+    pub fn match_flag(&self, flags: &[String]) -> Option<bool> {
+        match self {
+            Selector::Flag(ref flag) => Some(flags.binary_search(flag).is_ok()),
+            Selector::Not(ref selector) => selector.match_flag(flags).map(|b| !b),
+            _ => None,
+        }
+    }
+
     /// Match parameters of the widget matched by the current rule.
     /// If this selector is not a meta selector `None` is returned.
     pub fn match_meta<S: AsRef<str>>(
@@ -347,11 +682,13 @@ impl Selector {
     ) -> Option<bool> {
         match self {
             Selector::State(ref sel_state) => Some(state.iter().any(|state| state.eq(sel_state))),
-            Selector::Class(ref sel_class) => Some(sel_class == class),
+            Selector::Class(ref sel_class) => Some(class.split_whitespace().any(|class| class == sel_class)),
             Selector::Nth(num) => Some(n == *num),
             Selector::NthMod(num, den) => Some((n % *den) == *num),
             Selector::NthLast(num) => Some(len - 1 - n == *num),
             Selector::NthLastMod(num, den) => Some(((len - 1 - n) % *den) == *num),
+            Selector::NthStep(step, offset) => Some(matches_step(n, *step, *offset)),
+            Selector::NthLastStep(step, offset) => Some(matches_step(len - 1 - n, *step, *offset)),
             Selector::OnlyChild => Some(n == 0 && len == 1),
             Selector::Not(ref selector) => selector.match_meta(state, class, n, len).map(|b| !b),
             _ => None,
@@ -359,6 +696,15 @@ impl Selector {
     }
 }
 
+/// Returns whether `index` equals `step * k + offset` for some `k >= 0`
+fn matches_step(index: usize, step: isize, offset: isize) -> bool {
+    let difference = index as isize - offset;
+    match step {
+        0 => difference == 0,
+        step => difference % step == 0 && difference / step >= 0,
+    }
+}
+
 impl SelectorWidget {
     fn matches(&self, widget: &str) -> bool {
         match self {
@@ -388,6 +734,19 @@ impl<A: AsRef<str>, B: AsRef<str>> PartialEq<StyleState<B>> for StyleState<A> {
     }
 }
 
+impl Error {
+    /// Adds the line of `text` that contains a syntax error to the error, so it can be shown along with the error.
+    pub(crate) fn with_source(self, text: &str) -> Self {
+        match self {
+            Error::Syntax(error, pos, None) => {
+                let line = text.lines().nth(pos.line.saturating_sub(1)).map(str::to_string);
+                Error::Syntax(error, pos, line)
+            }
+            other => other,
+        }
+    }
+}
+
 impl From<image::ImageError> for Error {
     fn from(error: image::ImageError) -> Self {
         Error::Image(error)
@@ -397,7 +756,22 @@ impl From<image::ImageError> for Error {
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::Syntax(error, pos) => write!(f, "Syntax error: {} at line {}:{}", error, pos.line, pos.col_start),
+            Error::Syntax(error, pos, line) => {
+                write!(f, "Syntax error: {} at line {}:{}", error, pos.line, pos.col_start)?;
+                if let Some(line) = line {
+                    // point a caret at the offending token, keeping tabs so the caret lines up with the source.
+                    let number = pos.line.to_string();
+                    let indent = line
+                        .chars()
+                        .take(pos.col_start.saturating_sub(1))
+                        .map(|c| if c == '\t' { '\t' } else { ' ' })
+                        .collect::<String>();
+                    let gutter = " ".repeat(number.len());
+                    let caret = "^".repeat(pos.col_end + 1 - pos.col_start.min(pos.col_end));
+                    write!(f, "\n{} | {}\n{} | {}{}", number, line, gutter, indent, caret)?;
+                }
+                Ok(())
+            }
             Error::Eof => write!(f, "Unexpected end of file reached"),
             Error::Image(error) => write!(f, "Image decode error: {}", error),
             Error::Io(error) => write!(f, "I/O error: {}", error),
@@ -433,3 +807,43 @@ impl<'a> From<&'a str> for StyleState<String> {
         }
     }
 }
+
+/// A color background, which is rounded if any of the corners has a radius.
+fn color_background(color: Color, corners: Corners) -> Background {
+    if corners.is_zero() {
+        Background::Color(color)
+    } else {
+        Background::RoundedColor(color, corners)
+    }
+}
+
+/// Interpolates the color of a background. Backgrounds that don't share a color to interpolate switch immediately.
+fn blend_background(from: &Background, to: &Background, t: f32) -> Background {
+    match (from, to) {
+        (Background::Color(a) | Background::RoundedColor(a, _), Background::Color(b)) => {
+            Background::Color(a.blend(*b, t))
+        }
+        (Background::Color(a) | Background::RoundedColor(a, _), &Background::RoundedColor(b, corners)) => {
+            Background::RoundedColor(a.blend(b, t), corners)
+        }
+        (Background::None, &Background::Color(b)) => Background::Color(b.with_alpha(0.0).blend(b, t)),
+        (Background::None, &Background::RoundedColor(b, corners)) => {
+            Background::RoundedColor(b.with_alpha(0.0).blend(b, t), corners)
+        }
+        (&Background::Color(a), Background::None) => Background::Color(a.blend(a.with_alpha(0.0), t)),
+        (&Background::RoundedColor(a, corners), Background::None) => {
+            Background::RoundedColor(a.blend(a.with_alpha(0.0), t), corners)
+        }
+        (Background::Image(from_image, a), Background::Image(image, b))
+            if Arc::ptr_eq(&from_image._cache_id, &image._cache_id) =>
+        {
+            Background::Image(image.clone(), a.blend(*b, t))
+        }
+        (Background::Patch(from_patch, a), Background::Patch(patch, b))
+            if Arc::ptr_eq(&from_patch.image._cache_id, &patch.image._cache_id) =>
+        {
+            Background::Patch(patch.clone(), a.blend(*b, t))
+        }
+        (_, to) => to.clone(),
+    }
+}
@@ -1,7 +1,10 @@
 use super::tree::*;
+use super::Error;
 use super::*;
 use anyhow::*;
 
+const URL_CHARACTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~:/?#[]@!$&'()*+,;%=";
+
 struct LoadContext<'a, I: Iterator<Item = Token>, R: ReadFn> {
     loader: R,
     tokens: TokenProvider<I>,
@@ -22,24 +25,25 @@ impl<I: Iterator<Item = Token>> TokenProvider<I> {
     }
 
     pub fn take(&mut self, token: TokenValue) -> anyhow::Result<Token> {
-        let Token(value, pos) = self.tokens.next().ok_or_else(|| anyhow!("EOF"))?;
+        let Token(value, pos) = self.tokens.next().ok_or(Error::Eof)?;
         if token == value {
             Ok(Token(value, pos))
         } else {
-            Err(anyhow!("Expected '{:?}' at {}", token, pos))
+            Err(syntax_error(format!("Expected '{:?}'", token), pos))
         }
     }
 
     pub fn take_identifier(&mut self) -> anyhow::Result<(String, TokenPos)> {
-        match self.tokens.next().ok_or_else(|| anyhow!("EOF"))? {
+        match self.tokens.next().ok_or(Error::Eof)? {
             Token(TokenValue::Iden(id), pos) => Ok((id, pos)),
-            Token(_, pos) => Err(anyhow!("Expected 'Identifier' at {}", pos)),
+            Token(_, pos) => Err(syntax_error("Expected 'Identifier'", pos)),
         }
     }
 }
 
-pub async fn parse(tokens: Vec<Token>, loader: impl ReadFn) -> anyhow::Result<StyleBuilder> {
-    let mut builder = Style::builder();
+/// Parses the rules in `tokens` and adds them to `builder`.
+pub async fn parse(tokens: Vec<Token>, loader: impl ReadFn, mut builder: StyleBuilder) -> anyhow::Result<StyleBuilder> {
+    let tokens = substitute_variables(tokens, &builder.variables)?;
 
     let mut rule_tree = RuleTreeBuilder::new();
 
@@ -53,8 +57,10 @@ pub async fn parse(tokens: Vec<Token>, loader: impl ReadFn) -> anyhow::Result<St
         };
 
         while context.tokens.peek().is_some() {
-            let (selectors, rules) = parse_rule(&mut context).await?;
-            rule_tree.insert(selectors, rules);
+            let rule = parse_rule(&mut context).await?;
+            for selectors in rule.groups {
+                rule_tree.insert(selectors, rule.declarations.clone(), rule.important.clone());
+            }
         }
     }
 
@@ -63,6 +69,137 @@ pub async fn parse(tokens: Vec<Token>, loader: impl ReadFn) -> anyhow::Result<St
     Ok(builder)
 }
 
+/// Tokenizes and parses .pwss text and adds the rules to `builder`.
+/// Syntax errors include the line of `text` that contains the error.
+pub async fn parse_text(text: String, loader: impl ReadFn, builder: StyleBuilder) -> anyhow::Result<StyleBuilder> {
+    let tokens = tokenize(text.clone()).map_err(|error| error.with_source(&text))?;
+    parse(tokens, loader, builder).await.map_err(|error| {
+        error
+            .downcast::<Error>()
+            .map_or_else(|error| error, |error| error.with_source(&text).into())
+    })
+}
+
+/// Removes the `:root { --name: <value>; }` blocks that define variables from the tokens, and replaces every
+/// `var(--name)` with the tokens of the value. Variables in `overrides` take precedence over the variables that are
+/// defined in the tokens.
+fn substitute_variables(tokens: Vec<Token>, overrides: &HashMap<String, Vec<Token>>) -> anyhow::Result<Vec<Token>> {
+    let mut variables = HashMap::new();
+    let mut remaining = Vec::new();
+    let mut depth = 0usize;
+    let mut p = TokenProvider {
+        tokens: tokens.into_iter().peekable(),
+    };
+    while let Some(token) = p.next() {
+        let rule_start = depth == 0 && matches!(remaining.last(), None | Some(Token(TokenValue::BraceClose, _)));
+        match token {
+            Token(TokenValue::Colon, _)
+                if rule_start && matches!(p.peek(), Some(Token(TokenValue::Iden(root), _)) if root == "root") =>
+            {
+                p.next();
+                p.take(TokenValue::BraceOpen)?;
+                parse_variables(&mut p, &mut variables)?;
+            }
+            token => {
+                match token.0 {
+                    TokenValue::BraceOpen => depth += 1,
+                    TokenValue::BraceClose => depth = depth.saturating_sub(1),
+                    _ => (),
+                }
+                remaining.push(token);
+            }
+        }
+    }
+    variables.extend(overrides.iter().map(|(name, value)| (name.clone(), value.clone())));
+
+    let mut result = Vec::with_capacity(remaining.len());
+    substitute(remaining, &variables, &mut Vec::new(), None, &mut result)?;
+    Ok(result)
+}
+
+/// Parses the variables in a `:root` block, up to and including the closing brace.
+fn parse_variables<I: Iterator<Item = Token>>(
+    c: &mut TokenProvider<I>,
+    variables: &mut HashMap<String, Vec<Token>>,
+) -> anyhow::Result<()> {
+    loop {
+        match c.next().ok_or(Error::Eof)? {
+            Token(TokenValue::BraceClose, _) => return Ok(()),
+            Token(TokenValue::Iden(name), pos) if name.len() > 2 && name.starts_with("--") => {
+                c.take(TokenValue::Colon)?;
+                let mut value = Vec::new();
+                loop {
+                    match c.next().ok_or(Error::Eof)? {
+                        Token(TokenValue::Semi, _) => break,
+                        token => value.push(token),
+                    }
+                }
+                if value.is_empty() {
+                    return Err(syntax_error(format!("Expected a value for `{}`", name), pos));
+                }
+                variables.insert(name[2..].to_string(), value);
+            }
+            Token(_, pos) => return Err(syntax_error("Expected a variable, like `--name: <value>;`", pos)),
+        }
+    }
+}
+
+/// Appends `tokens` to `result`, replacing every `var(--name)` with the value of the variable.
+/// `stack` contains the variables that are being substituted, to catch variables that refer to themselves.
+/// The tokens of a variable take the position `at` of the `var()` they replace, so errors point at where the variable
+/// was used.
+fn substitute(
+    tokens: Vec<Token>,
+    variables: &HashMap<String, Vec<Token>>,
+    stack: &mut Vec<String>,
+    at: Option<TokenPos>,
+    result: &mut Vec<Token>,
+) -> anyhow::Result<()> {
+    let mut p = TokenProvider {
+        tokens: tokens.into_iter().peekable(),
+    };
+    while let Some(Token(value, pos)) = p.next() {
+        match value {
+            TokenValue::Iden(function)
+                if function == "var" && matches!(p.peek(), Some(Token(TokenValue::ParenOpen, _))) =>
+            {
+                p.next();
+                let (name, name_pos) = p.take_identifier()?;
+                p.take(TokenValue::ParenClose)?;
+                let pos = at.unwrap_or(name_pos);
+                let key = name
+                    .strip_prefix("--")
+                    .ok_or_else(|| syntax_error("Expected a variable name starting with `--`", pos))?;
+                if stack.iter().any(|variable| variable == key) {
+                    return Err(syntax_error(format!("Variable `{}` refers to itself", name), pos));
+                }
+                let value = variables
+                    .get(key)
+                    .ok_or_else(|| syntax_error(format!("Unknown variable `{}`", name), pos))?;
+                stack.push(key.to_string());
+                substitute(value.clone(), variables, stack, Some(pos), result)?;
+                stack.pop();
+            }
+            value => result.push(Token(value, at.unwrap_or(pos))),
+        }
+    }
+    Ok(())
+}
+
+/// Constructs a syntax error for the token at `pos`.
+fn syntax_error(message: impl Into<String>, pos: TokenPos) -> anyhow::Error {
+    Error::Syntax(message.into(), pos, None).into()
+}
+
+/// Checks that a quoted token used as a `<url>` only contains characters that are valid in an url.
+/// Quoted tokens are also used for `content` text, so the tokenizer itself accepts any character.
+fn parse_url(url: String, pos: TokenPos) -> anyhow::Result<String> {
+    match url.chars().find(|&ch| !URL_CHARACTERS.contains(ch)) {
+        Some(ch) => Err(syntax_error(format!("Unexpected character '{}' in <url>", ch), pos)),
+        None => Ok(url),
+    }
+}
+
 pub fn parse_selectors(tokens: Vec<Token>) -> anyhow::Result<Vec<Selector>> {
     let mut p = TokenProvider {
         tokens: tokens.into_iter().peekable(),
@@ -74,78 +211,257 @@ pub fn parse_selectors(tokens: Vec<Token>) -> anyhow::Result<Vec<Selector>> {
     Ok(result)
 }
 
-async fn parse_rule<I: Iterator<Item = Token>, L: ReadFn>(
-    c: &mut LoadContext<'_, I, L>,
-) -> anyhow::Result<(Vec<Selector>, Vec<Declaration<ImageId, PatchId, FontId>>)> {
+pub fn parse_selector_groups(tokens: Vec<Token>) -> anyhow::Result<Vec<Vec<Selector>>> {
+    let mut p = TokenProvider {
+        tokens: tokens.into_iter().peekable(),
+    };
+    let mut groups = Vec::new();
+    let mut selectors = Vec::new();
+    let mut last_comma = None;
+    while let Some(token) = p.peek() {
+        if let Token(TokenValue::Comma, pos) = token {
+            let pos = *pos;
+            p.next();
+            groups.push(take_group(&mut selectors, pos)?);
+            last_comma = Some(pos);
+        } else {
+            selectors.push(parse_selector(&mut p)?);
+        }
+    }
+    match last_comma {
+        Some(pos) if selectors.is_empty() => return Err(syntax_error("Expected `<selector>` after ','", pos)),
+        _ => (),
+    }
+    groups.push(selectors);
+    Ok(groups)
+}
+
+/// Takes the selectors of a group that ends at `pos`. Groups can't be empty.
+fn take_group(selectors: &mut Vec<Selector>, pos: TokenPos) -> anyhow::Result<Vec<Selector>> {
+    if selectors.is_empty() {
+        Err(syntax_error("Expected `<selector>`", pos))
+    } else {
+        Ok(std::mem::take(selectors))
+    }
+}
+
+/// A rule as it appears in a .pwss file: the selector groups and the declarations in the block.
+struct ParsedRule {
+    groups: Vec<Vec<Selector>>,
+    declarations: Vec<Declaration>,
+    important: Vec<Declaration>,
+}
+
+async fn parse_rule<I: Iterator<Item = Token>, L: ReadFn>(c: &mut LoadContext<'_, I, L>) -> anyhow::Result<ParsedRule> {
+    let mut groups = Vec::new();
     let mut selectors = Vec::new();
     let mut declarations = Vec::new();
+    let mut important = Vec::new();
     loop {
-        if let Token(TokenValue::BraceOpen, _) = c.tokens.peek().ok_or_else(|| anyhow!("EOF"))? {
-            c.tokens.next();
-            loop {
-                if let Some(&Token(TokenValue::BraceClose, _)) = c.tokens.peek() {
-                    break;
+        match c.tokens.peek().ok_or(Error::Eof)? {
+            Token(TokenValue::BraceOpen, pos) => {
+                let pos = *pos;
+                if !groups.is_empty() {
+                    groups.push(take_group(&mut selectors, pos)?);
                 } else {
-                    declarations.push(parse_declaration(c).await?);
+                    groups.push(std::mem::take(&mut selectors));
                 }
+                c.tokens.next();
+                loop {
+                    if let Some(&Token(TokenValue::BraceClose, _)) = c.tokens.peek() {
+                        break;
+                    } else {
+                        match parse_declaration(c).await? {
+                            (declaration, true) => important.push(declaration),
+                            (declaration, false) => declarations.push(declaration),
+                        }
+                    }
+                }
+                c.tokens.take(TokenValue::BraceClose)?;
+                return Ok(ParsedRule {
+                    groups,
+                    declarations,
+                    important,
+                });
             }
-            c.tokens.take(TokenValue::BraceClose)?;
-            return Ok((selectors, declarations));
-        } else {
-            selectors.push(parse_selector(&mut c.tokens)?);
+            Token(TokenValue::Comma, pos) => {
+                let pos = *pos;
+                c.tokens.next();
+                groups.push(take_group(&mut selectors, pos)?);
+            }
+            _ => selectors.push(parse_selector(&mut c.tokens)?),
         }
     }
 }
 
+/// The properties that are recognized by `parse_declaration`.
+const PROPERTIES: &[&str] = &[
+    "background",
+    "border-radius",
+    "font",
+    "color",
+    "padding",
+    "padding-left",
+    "padding-right",
+    "padding-top",
+    "padding-bottom",
+    "margin",
+    "margin-left",
+    "margin-right",
+    "margin-top",
+    "margin-bottom",
+    "text-size",
+    "text-border",
+    "text-wrap",
+    "text-decoration",
+    "width",
+    "height",
+    "min-width",
+    "max-width",
+    "min-height",
+    "max-height",
+    "layout-direction",
+    "align-horizontal",
+    "align-vertical",
+    "spacing",
+    "z-index",
+    "transition",
+    "delay",
+    "content",
+];
+
+/// Parses a single declaration, and whether it was marked `!important`.
 async fn parse_declaration<I: Iterator<Item = Token>, L: ReadFn>(
     c: &mut LoadContext<'_, I, L>,
-) -> anyhow::Result<Declaration> {
+) -> anyhow::Result<(Declaration, bool)> {
     let result = match c.tokens.next() {
-        Some(Token(TokenValue::Iden(key), _)) => {
+        Some(Token(TokenValue::BracketOpen, _)) => {
+            let (flag, _) = c.tokens.take_identifier()?;
+            c.tokens.take(TokenValue::BracketClose)?;
+            c.tokens.take(TokenValue::Colon)?;
+            parse_flag(&mut c.tokens, flag)
+        }
+        Some(Token(TokenValue::Iden(key), key_pos)) => {
             c.tokens.take(TokenValue::Colon)?;
             match key.as_str() {
                 "background" => Ok(parse_background(c).await?),
+                "border-radius" => Ok(Declaration::BorderRadius(parse_sides(&mut c.tokens)?)),
                 "font" => Ok(Declaration::Font(parse_font(c).await?)),
                 "color" => Ok(Declaration::Color(parse_color(&mut c.tokens)?)),
-                "padding" => Ok(Declaration::Padding(parse_rectangle(&mut c.tokens)?)),
-                "padding-left" => Ok(Declaration::PaddingLeft(parse_float(&mut c.tokens)?)),
-                "padding-right" => Ok(Declaration::PaddingRight(parse_float(&mut c.tokens)?)),
-                "padding-top" => Ok(Declaration::PaddingTop(parse_float(&mut c.tokens)?)),
-                "padding-bottom" => Ok(Declaration::PaddingBottom(parse_float(&mut c.tokens)?)),
-                "margin" => Ok(Declaration::Margin(parse_rectangle(&mut c.tokens)?)),
-                "margin-left" => Ok(Declaration::MarginLeft(parse_float(&mut c.tokens)?)),
-                "margin-right" => Ok(Declaration::MarginRight(parse_float(&mut c.tokens)?)),
-                "margin-top" => Ok(Declaration::MarginTop(parse_float(&mut c.tokens)?)),
-                "margin-bottom" => Ok(Declaration::MarginBottom(parse_float(&mut c.tokens)?)),
-                "text-size" => Ok(Declaration::TextSize(parse_float(&mut c.tokens)?)),
-                "text-border" => Ok(Declaration::TextBorder(parse_float(&mut c.tokens)?)),
+                "padding" => Ok(Declaration::Padding(parse_sides(&mut c.tokens)?)),
+                "padding-left" => Ok(Declaration::PaddingLeft(parse_length(&mut c.tokens)?)),
+                "padding-right" => Ok(Declaration::PaddingRight(parse_length(&mut c.tokens)?)),
+                "padding-top" => Ok(Declaration::PaddingTop(parse_length(&mut c.tokens)?)),
+                "padding-bottom" => Ok(Declaration::PaddingBottom(parse_length(&mut c.tokens)?)),
+                "margin" => Ok(Declaration::Margin(parse_sides(&mut c.tokens)?)),
+                "margin-left" => Ok(Declaration::MarginLeft(parse_length(&mut c.tokens)?)),
+                "margin-right" => Ok(Declaration::MarginRight(parse_length(&mut c.tokens)?)),
+                "margin-top" => Ok(Declaration::MarginTop(parse_length(&mut c.tokens)?)),
+                "margin-bottom" => Ok(Declaration::MarginBottom(parse_length(&mut c.tokens)?)),
+                "text-size" => Ok(Declaration::TextSize(parse_length(&mut c.tokens)?)),
+                "text-border" => Ok(Declaration::TextBorder(parse_length(&mut c.tokens)?)),
                 "text-wrap" => Ok(Declaration::TextWrap(parse_text_wrap(&mut c.tokens)?)),
+                "text-decoration" => Ok(Declaration::TextDecoration(parse_text_decoration(&mut c.tokens)?)),
                 "width" => Ok(Declaration::Width(parse_size(&mut c.tokens)?)),
                 "height" => Ok(Declaration::Height(parse_size(&mut c.tokens)?)),
+                "min-width" => Ok(Declaration::MinWidth(parse_length(&mut c.tokens)?)),
+                "max-width" => Ok(Declaration::MaxWidth(parse_length(&mut c.tokens)?)),
+                "min-height" => Ok(Declaration::MinHeight(parse_length(&mut c.tokens)?)),
+                "max-height" => Ok(Declaration::MaxHeight(parse_length(&mut c.tokens)?)),
                 "layout-direction" => Ok(Declaration::LayoutDirection(parse_direction(&mut c.tokens)?)),
                 "align-horizontal" => Ok(Declaration::AlignHorizontal(parse_align(&mut c.tokens)?)),
                 "align-vertical" => Ok(Declaration::AlignVertical(parse_align(&mut c.tokens)?)),
+                "spacing" => Ok(Declaration::Spacing(parse_length(&mut c.tokens)?)),
+                "z-index" => Ok(Declaration::ZIndex(parse_integer(&mut c.tokens)?)),
+                "transition" => Ok(Declaration::Transition(parse_transitions(&mut c.tokens)?)),
+                "delay" => Ok(Declaration::Delay(parse_duration(&mut c.tokens)?)),
+                "content" => Ok(parse_content(c)?),
                 flag => {
-                    let (id, pos) = c.tokens.take_identifier()?;
-                    match id.as_str() {
-                        "true" => Ok(Declaration::AddFlag(flag.to_string())),
-                        "false" => Ok(Declaration::RemoveFlag(flag.to_string())),
-                        _ => Err(anyhow!("Flag values must be either `true` or `false` at {}", pos)),
+                    // unknown properties are flags, unless they look like a misspelled property.
+                    if let Some(property) = similar_property(flag) {
+                        let message = format!("Unknown property `{}`, did you mean `{}`?", flag, property);
+                        return Err(syntax_error(message, key_pos));
+                    }
+                    match c.tokens.peek() {
+                        Some(Token(TokenValue::Iden(value), _)) if value == "true" || value == "false" => {
+                            parse_flag(&mut c.tokens, flag.to_string())
+                        }
+                        _ => Err(syntax_error(format!("Unknown property `{}`", flag), key_pos)),
                     }
                 }
             }
         }
-        Some(Token(_, pos)) => Err(anyhow!("Expected <property> at {}", pos)),
-        None => Err(anyhow!("EOF")),
+        Some(Token(_, pos)) => Err(syntax_error("Expected <property>", pos)),
+        None => Err(Error::Eof.into()),
     }?;
+    let important = parse_important(&mut c.tokens)?;
     c.tokens.take(TokenValue::Semi)?;
-    Ok(result)
+    Ok((result, important))
+}
+
+fn parse_flag<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>, flag: String) -> anyhow::Result<Declaration> {
+    match c.next().ok_or(Error::Eof)? {
+        Token(TokenValue::Iden(id), _) if id == "true" => Ok(Declaration::AddFlag(flag)),
+        Token(TokenValue::Iden(id), _) if id == "false" => Ok(Declaration::RemoveFlag(flag)),
+        Token(_, pos) => Err(syntax_error("Flag values must be either `true` or `false`", pos)),
+    }
+}
+
+/// Finds a property that is only a few typos away from `name`, to catch misspelled properties that would otherwise
+/// be parsed as flags.
+fn similar_property(name: &str) -> Option<&'static str> {
+    let max_distance = (name.chars().count() / 4).max(1);
+    PROPERTIES
+        .iter()
+        .map(|&property| (edit_distance(name, property), property))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, property)| property)
+}
+
+/// The number of single character insertions, deletions, substitutions or swaps of adjacent characters that are
+/// needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+fn parse_important<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> anyhow::Result<bool> {
+    if let Some(Token(TokenValue::Bang, _)) = c.peek() {
+        c.next();
+        match c.take_identifier()? {
+            (id, _) if id == "important" => Ok(true),
+            (_, pos) => Err(syntax_error("Expected `important` after `!`", pos)),
+        }
+    } else {
+        Ok(false)
+    }
 }
 
 async fn parse_background<I: Iterator<Item = Token>, L: ReadFn + 'static>(
     c: &mut LoadContext<'_, I, L>,
 ) -> anyhow::Result<Declaration> {
-    match c.tokens.peek().cloned().ok_or_else(|| anyhow!("EOF"))? {
+    match c.tokens.peek().cloned().ok_or(Error::Eof)? {
         Token(TokenValue::Iden(ty), pos) => {
             c.tokens.next();
             match ty.to_lowercase().as_str() {
@@ -154,7 +470,8 @@ async fn parse_background<I: Iterator<Item = Token>, L: ReadFn + 'static>(
                     c.tokens.take(TokenValue::ParenOpen)?;
                     let read = c.loader.clone();
                     let image = match c.tokens.next() {
-                        Some(Token(TokenValue::Path(url), _)) => {
+                        Some(Token(TokenValue::Path(path), pos)) => {
+                            let url = parse_url(path, pos)?;
                             Ok(c.builder.load_image_async(url.clone(), async move {
                                 Ok(
                                     image::load_from_memory(read.read(Path::new(url.as_str())).await?.as_ref())?
@@ -162,8 +479,8 @@ async fn parse_background<I: Iterator<Item = Token>, L: ReadFn + 'static>(
                                 )
                             }))
                         }
-                        Some(Token(_, pos)) => Err(anyhow!("Expected <url> at {}", pos)),
-                        None => Err(anyhow!("EOF")),
+                        Some(Token(_, pos)) => Err(syntax_error("Expected <url>", pos)),
+                        None => Err(Error::Eof.into()),
                     }?;
                     c.tokens.take(TokenValue::Comma)?;
                     let color = parse_color(&mut c.tokens)?;
@@ -174,7 +491,8 @@ async fn parse_background<I: Iterator<Item = Token>, L: ReadFn + 'static>(
                     c.tokens.take(TokenValue::ParenOpen)?;
                     let read = c.loader.clone();
                     let image = match c.tokens.next() {
-                        Some(Token(TokenValue::Path(url), _)) => {
+                        Some(Token(TokenValue::Path(path), pos)) => {
+                            let url = parse_url(path, pos)?;
                             Ok(c.builder.load_patch_async(url.clone(), async move {
                                 Ok(
                                     image::load_from_memory(read.read(Path::new(url.as_str())).await?.as_ref())?
@@ -182,20 +500,21 @@ async fn parse_background<I: Iterator<Item = Token>, L: ReadFn + 'static>(
                                 )
                             }))
                         }
-                        Some(Token(_, pos)) => Err(anyhow!("Expected url at {}", pos)),
-                        None => Err(anyhow!("EOF")),
+                        Some(Token(_, pos)) => Err(syntax_error("Expected url", pos)),
+                        None => Err(Error::Eof.into()),
                     }?;
                     c.tokens.take(TokenValue::Comma)?;
                     let color = parse_color(&mut c.tokens)?;
                     c.tokens.take(TokenValue::ParenClose)?;
                     Ok(Declaration::BackgroundPatch(image, color))
                 }
-                _ => Err(anyhow!("Expected `image`, `patch` or `none` at {}", pos)),
+                _ => Err(syntax_error("Expected `image`, `patch` or `none`", pos)),
             }
         }
         Token(TokenValue::Color(_), _) => Ok(Declaration::BackgroundColor(parse_color(&mut c.tokens)?)),
-        Token(TokenValue::Path(url), _) => {
+        Token(TokenValue::Path(path), pos) => {
             c.tokens.next();
+            let url = parse_url(path, pos)?;
             let read = c.loader.clone();
             if url.ends_with(".9.png") {
                 let patch = c.builder.load_patch_async(url.clone(), async move {
@@ -209,45 +528,97 @@ async fn parse_background<I: Iterator<Item = Token>, L: ReadFn + 'static>(
                 Ok(Declaration::BackgroundImage(image, Color::white()))
             }
         }
-        Token(_, pos) => Err(anyhow!(
-            "Expected `none`, `image(<url>, <color>)`, `patch(<url>, <color>)`, <color> or <url> at {}",
+        Token(_, pos) => Err(syntax_error(
+            "Expected `none`, `image(<url>, <color>)`, `patch(<url>, <color>)`, <color> or <url>",
             pos,
         )),
     }
 }
 
+fn parse_content<I: Iterator<Item = Token>, L: ReadFn>(c: &mut LoadContext<'_, I, L>) -> anyhow::Result<Declaration> {
+    match c.tokens.next().ok_or(Error::Eof)? {
+        Token(TokenValue::Path(text), _) => Ok(Declaration::ContentText(text)),
+        Token(TokenValue::Iden(ty), pos) => match ty.to_lowercase().as_str() {
+            "none" => Ok(Declaration::ContentNone),
+            "image" => {
+                c.tokens.take(TokenValue::ParenOpen)?;
+                let read = c.loader.clone();
+                let image = match c.tokens.next() {
+                    Some(Token(TokenValue::Path(path), pos)) => {
+                        let url = parse_url(path, pos)?;
+                        Ok(c.builder.load_image_async(url.clone(), async move {
+                            Ok(image::load_from_memory(read.read(Path::new(url.as_str())).await?.as_ref())?.to_rgba8())
+                        }))
+                    }
+                    Some(Token(_, pos)) => Err(syntax_error("Expected <url>", pos)),
+                    None => Err(Error::Eof.into()),
+                }?;
+                c.tokens.take(TokenValue::ParenClose)?;
+                Ok(Declaration::ContentImage(image))
+            }
+            _ => Err(syntax_error("Expected `none`, `image(<url>)` or <text>", pos)),
+        },
+        Token(_, pos) => Err(syntax_error("Expected `none`, `image(<url>)` or <text>", pos)),
+    }
+}
+
 async fn parse_font<I: Iterator<Item = Token>, L: ReadFn>(c: &mut LoadContext<'_, I, L>) -> anyhow::Result<FontId> {
     match c.tokens.next() {
-        Some(Token(TokenValue::Path(url), _)) => {
+        Some(Token(TokenValue::Path(path), pos)) => {
+            let url = parse_url(path, pos)?;
             let rgba_read = c.loader.clone();
             let json_read = c.loader.clone();
             let rgba_url = format!("{url}.png");
             let json_url = format!("{url}.json");
-            Ok(c.builder.load_font_async(
-                url.clone(),
-                async move {
-                    Ok(
-                        image::load_from_memory(rgba_read.read(Path::new(rgba_url.as_str())).await?.as_ref())?
-                            .to_rgba8(),
-                    )
-                },
-                async move { json_read.read(Path::new(json_url.as_str())).await },
-            ))
+            let rgba = async move {
+                Ok(image::load_from_memory(rgba_read.read(Path::new(rgba_url.as_str())).await?.as_ref())?.to_rgba8())
+            };
+            let json = async move { json_read.read(Path::new(json_url.as_str())).await };
+
+            // an optional second url points to the font file that the atlas was generated from
+            if matches!(c.tokens.peek(), Some(Token(TokenValue::Comma, _))) {
+                c.tokens.next();
+                let source_url = match c.tokens.next() {
+                    Some(Token(TokenValue::Path(path), pos)) => parse_url(path, pos)?,
+                    Some(Token(_, pos)) => return Err(syntax_error("Expected <url>", pos)),
+                    None => return Err(Error::Eof.into()),
+                };
+                let source_read = c.loader.clone();
+                Ok(c.builder
+                    .load_color_font_async(format!("{url}, {source_url}"), rgba, json, async move {
+                        source_read.read(Path::new(source_url.as_str())).await
+                    }))
+            } else {
+                Ok(c.builder.load_font_async(url, rgba, json))
+            }
         }
-        Some(Token(_, pos)) => Err(anyhow!("Expected <url> at {}", pos)),
-        None => Err(anyhow!("EOF")),
+        Some(Token(_, pos)) => Err(syntax_error("Expected <url>", pos)),
+        None => Err(Error::Eof.into()),
     }
 }
 
 fn parse_selector<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> anyhow::Result<Selector> {
-    match c.tokens.next().ok_or_else(|| anyhow!("EOF"))? {
+    match c.tokens.next().ok_or(Error::Eof)? {
         Token(TokenValue::Star, _) => Ok(Selector::Widget(SelectorWidget::Any)),
         Token(TokenValue::Dot, _) => Ok(Selector::Class(c.take_identifier()?.0)),
+        Token(TokenValue::BracketOpen, _) => {
+            let (flag, _) = c.take_identifier()?;
+            c.take(TokenValue::BracketClose)?;
+            Ok(Selector::Flag(flag))
+        }
         Token(TokenValue::Iden(widget), _) => Ok(Selector::Widget(SelectorWidget::Some(widget))),
         Token(TokenValue::Gt, _) => Ok(Selector::WidgetDirectChild(parse_widget(c)?)),
         Token(TokenValue::Plus, _) => Ok(Selector::WidgetDirectAfter(parse_widget(c)?)),
         Token(TokenValue::Tilde, _) => Ok(Selector::WidgetAfter(parse_widget(c)?)),
         Token(TokenValue::Colon, _) => {
+            if let Some(Token(TokenValue::Colon, _)) = c.peek() {
+                c.next();
+                let (id, pos) = c.take_identifier()?;
+                return match id.as_str() {
+                    "before" | "after" => Ok(Selector::WidgetDirectChild(SelectorWidget::Some(format!("::{}", id)))),
+                    _ => Err(syntax_error("Expected `before` or `after`", pos)),
+                };
+            }
             let (id, _pos) = c.take_identifier()?;
             match id.as_str() {
                 "nth-child-mod" => {
@@ -268,33 +639,25 @@ fn parse_selector<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> anyhow
                 }
                 "nth-child" => {
                     c.take(TokenValue::ParenOpen)?;
-                    let result = match c.tokens.next().ok_or_else(|| anyhow!("EOF"))? {
-                        Token(TokenValue::Iden(special), pos) => match special.as_str() {
-                            "odd" => Ok(Selector::NthMod(1, 2)),
-                            "even" => Ok(Selector::NthMod(0, 2)),
-                            _ => Err(anyhow!("Expected 'odd', 'even' or <number> at {}", pos)),
-                        },
-                        Token(TokenValue::Number(number), pos) => Ok(Selector::Nth(
-                            number.parse::<usize>().map_err(|err| anyhow!("{} at {}", err, pos))?,
-                        )),
-                        Token(_, pos) => Err(anyhow!("Expected 'odd', 'even' or <number> at {}", pos)),
-                    }?;
+                    let result = match parse_nth(c)? {
+                        (0, offset) if offset >= 0 => Selector::Nth(offset as usize),
+                        (step, offset) if (0..step).contains(&offset) => {
+                            Selector::NthMod(offset as usize, step as usize)
+                        }
+                        (step, offset) => Selector::NthStep(step, offset),
+                    };
                     c.take(TokenValue::ParenClose)?;
                     Ok(result)
                 }
                 "nth-last-child" => {
                     c.take(TokenValue::ParenOpen)?;
-                    let result = match c.tokens.next().ok_or_else(|| anyhow!("EOF"))? {
-                        Token(TokenValue::Iden(special), pos) => match special.as_str() {
-                            "odd" => Ok(Selector::NthLastMod(1, 2)),
-                            "even" => Ok(Selector::NthLastMod(0, 2)),
-                            _ => Err(anyhow!("Expected 'odd', 'even' or <number> at {}", pos)),
-                        },
-                        Token(TokenValue::Number(number), pos) => Ok(Selector::NthLast(
-                            number.parse::<usize>().map_err(|err| anyhow!("{} at {}", err, pos))?,
-                        )),
-                        Token(_, pos) => Err(anyhow!("Expected 'odd', 'even' or <number> at {}", pos)),
-                    }?;
+                    let result = match parse_nth(c)? {
+                        (0, offset) if offset >= 0 => Selector::NthLast(offset as usize),
+                        (step, offset) if (0..step).contains(&offset) => {
+                            Selector::NthLastMod(offset as usize, step as usize)
+                        }
+                        (step, offset) => Selector::NthLastStep(step, offset),
+                    };
                     c.take(TokenValue::ParenClose)?;
                     Ok(result)
                 }
@@ -319,70 +682,142 @@ fn parse_selector<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> anyhow
                 state => Ok(Selector::State(StyleState::Custom(state.to_string()))),
             }
         }
-        Token(_, pos) => Err(anyhow!("expected `<selector>` at {}", pos)),
+        Token(_, pos) => Err(syntax_error("expected `<selector>`", pos)),
+    }
+}
+
+/// Parses the argument of `:nth-child` or `:nth-last-child`, which can be `odd`, `even`, a number or an `an+b`
+/// expression, into the `(a, b)` of the expression.
+fn parse_nth<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<(isize, isize)> {
+    // expressions like `2n+1` or `-n-1` are split into several tokens, so they are joined back together first.
+    let mut expression = String::new();
+    let mut start = None;
+    while let Some(Token(value, pos)) = c.peek() {
+        match value {
+            TokenValue::Number(part) | TokenValue::Iden(part) => expression.push_str(part),
+            TokenValue::Plus => expression.push('+'),
+            _ => break,
+        }
+        start.get_or_insert(*pos).col_end = pos.col_end;
+        c.next();
+    }
+    let pos = match (start, c.peek()) {
+        (Some(pos), _) => pos,
+        (None, Some(Token(_, pos))) => return Err(syntax_error("Expected 'odd', 'even', <number> or 'an+b'", *pos)),
+        (None, None) => return Err(Error::Eof.into()),
+    };
+    let invalid = || syntax_error("Expected 'odd', 'even', <number> or 'an+b'", pos);
+
+    let parse_offset = |offset: &str| match offset {
+        "" => Ok(0),
+        offset => offset
+            .strip_prefix('+')
+            .unwrap_or(offset)
+            .parse::<isize>()
+            .map_err(|_| invalid()),
+    };
+    match expression.as_str() {
+        "odd" => Ok((2, 1)),
+        "even" => Ok((2, 0)),
+        expression => match expression.split_once('n') {
+            Some((step, offset)) => {
+                let step = match step {
+                    "" | "+" => 1,
+                    "-" => -1,
+                    step => step.parse::<isize>().map_err(|_| invalid())?,
+                };
+                if offset.starts_with(|c: char| c.is_ascii_digit()) {
+                    return Err(invalid());
+                }
+                Ok((step, parse_offset(offset)?))
+            }
+            None => Ok((0, parse_offset(expression)?)),
+        },
     }
 }
 
 fn parse_widget<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<SelectorWidget> {
-    match c.next().ok_or_else(|| anyhow!("EOF"))? {
+    match c.next().ok_or(Error::Eof)? {
         Token(TokenValue::Star, _) => Ok(SelectorWidget::Any),
         Token(TokenValue::Iden(widget), _) => Ok(SelectorWidget::Some(widget)),
-        Token(_, pos) => Err(anyhow!("Expected '*' or 'identifier' at {}", pos)),
+        Token(_, pos) => Err(syntax_error("Expected '*' or 'identifier'", pos)),
     }
 }
 
-fn parse_float<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<f32> {
+fn parse_usize<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<usize> {
     match c.next() {
-        Some(Token(TokenValue::Number(number), pos)) => {
-            number.parse::<f32>().map_err(|err| anyhow!("{} at {}", err, pos))
+        Some(Token(TokenValue::Number(number), pos)) => number
+            .parse::<usize>()
+            .map_err(|err| syntax_error(err.to_string(), pos)),
+        Some(Token(_, pos)) => Err(syntax_error("Expected <integer>", pos)),
+        None => Err(Error::Eof.into()),
+    }
+}
+
+fn parse_integer<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<i32> {
+    match c.next() {
+        // negative numbers start with a `-`, so they are tokenized as identifiers.
+        Some(Token(TokenValue::Number(number), pos)) | Some(Token(TokenValue::Iden(number), pos)) => {
+            number.parse::<i32>().map_err(|err| syntax_error(err.to_string(), pos))
         }
-        Some(Token(_, pos)) => Err(anyhow!("Expected <number> at {}", pos)),
-        None => Err(anyhow!("EOF")),
+        Some(Token(_, pos)) => Err(syntax_error("Expected <integer>", pos)),
+        None => Err(Error::Eof.into()),
     }
 }
 
-fn parse_usize<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<usize> {
+/// Splits a number token into the number and it's unit, which is empty if the number has no unit.
+fn split_unit(number: &str) -> (&str, &str) {
+    let unit_start = number
+        .find(|c: char| c.is_alphabetic() || c == '%')
+        .unwrap_or(number.len());
+    number.split_at(unit_start)
+}
+
+fn parse_length<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<Length> {
     match c.next() {
         Some(Token(TokenValue::Number(number), pos)) => {
-            number.parse::<usize>().map_err(|err| anyhow!("{} at {}", err, pos))
+            let (number, unit) = split_unit(number.as_str());
+            let number = number
+                .parse::<f32>()
+                .map_err(|err| syntax_error(err.to_string(), pos))?;
+            match unit {
+                "" | "px" => Ok(Length::Px(number)),
+                "em" => Ok(Length::Em(number)),
+                unit => Err(syntax_error(format!("Expected `px` or `em`, found `{}`", unit), pos)),
+            }
         }
-        Some(Token(_, pos)) => Err(anyhow!("Expected <integer> at {}", pos)),
-        None => Err(anyhow!("EOF")),
+        Some(Token(_, pos)) => Err(syntax_error("Expected <length>", pos)),
+        None => Err(Error::Eof.into()),
     }
 }
 
-fn parse_rectangle<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<Rectangle> {
-    let mut numbers = Vec::new();
+fn parse_sides<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<Sides> {
+    let mut lengths = Vec::new();
 
-    while let Token(TokenValue::Number(_), _) = c.peek().ok_or_else(|| anyhow!("EOF"))? {
-        numbers.push(parse_float(c)?);
+    while let Token(TokenValue::Number(_), _) = c.peek().ok_or(Error::Eof)? {
+        lengths.push(parse_length(c)?);
     }
 
-    match numbers.len() {
-        0 => Ok(Rectangle::zero()),
-        1 => Ok(Rectangle {
-            top: numbers[0],
-            right: numbers[0],
-            bottom: numbers[0],
-            left: numbers[0],
-        }),
-        2 => Ok(Rectangle {
-            top: numbers[0],
-            right: numbers[1],
-            bottom: numbers[0],
-            left: numbers[1],
+    match lengths.len() {
+        0 => Ok(Sides::all(Length::Px(0.0))),
+        1 => Ok(Sides::all(lengths[0])),
+        2 => Ok(Sides {
+            top: lengths[0],
+            right: lengths[1],
+            bottom: lengths[0],
+            left: lengths[1],
         }),
-        3 => Ok(Rectangle {
-            top: numbers[0],
-            right: numbers[1],
-            bottom: numbers[2],
-            left: numbers[1],
+        3 => Ok(Sides {
+            top: lengths[0],
+            right: lengths[1],
+            bottom: lengths[2],
+            left: lengths[1],
         }),
-        _ => Ok(Rectangle {
-            top: numbers[0],
-            right: numbers[1],
-            bottom: numbers[2],
-            left: numbers[3],
+        _ => Ok(Sides {
+            top: lengths[0],
+            right: lengths[1],
+            bottom: lengths[2],
+            left: lengths[3],
         }),
     }
 }
@@ -393,10 +828,73 @@ fn parse_text_wrap<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Resul
             "no-wrap" => Ok(TextWrap::NoWrap),
             "word-wrap" => Ok(TextWrap::WordWrap),
             "wrap" => Ok(TextWrap::Wrap),
-            _ => Err(anyhow!("Expected `no-wrap`, `word-wrap` or `wrap` at {}", pos)),
+            _ => Err(syntax_error("Expected `no-wrap`, `word-wrap` or `wrap`", pos)),
         },
-        Some(Token(_, pos)) => Err(anyhow!("Expected `no-wrap`, `word-wrap` or `wrap` at {}", pos)),
-        None => Err(anyhow!("EOF")),
+        Some(Token(_, pos)) => Err(syntax_error("Expected `no-wrap`, `word-wrap` or `wrap`", pos)),
+        None => Err(Error::Eof.into()),
+    }
+}
+
+fn parse_text_decoration<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<TextDecoration> {
+    let mut decoration = TextDecoration::default();
+    loop {
+        match c.next() {
+            Some(Token(TokenValue::Iden(ty), pos)) => match ty.to_lowercase().as_str() {
+                "none" => (),
+                "underline" => decoration.underline = true,
+                "line-through" => decoration.strikethrough = true,
+                _ => return Err(syntax_error("Expected `none`, `underline` or `line-through`", pos)),
+            },
+            Some(Token(_, pos)) => return Err(syntax_error("Expected `none`, `underline` or `line-through`", pos)),
+            None => return Err(Error::Eof.into()),
+        }
+        if !matches!(c.peek(), Some(Token(TokenValue::Iden(_), _))) {
+            return Ok(decoration);
+        }
+    }
+}
+
+fn parse_transitions<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<Vec<Transition>> {
+    let mut transitions = Vec::new();
+    loop {
+        let property = match c.next() {
+            Some(Token(TokenValue::Iden(ty), pos)) => match ty.to_lowercase().as_str() {
+                "none" if transitions.is_empty() => return Ok(transitions),
+                "all" => TransitionProperty::All,
+                "background" => TransitionProperty::Background,
+                "color" => TransitionProperty::Color,
+                "padding" => TransitionProperty::Padding,
+                _ => return Err(syntax_error("Expected `all`, `background`, `color` or `padding`", pos)),
+            },
+            Some(Token(_, pos)) => return Err(syntax_error("Expected `all`, `background`, `color` or `padding`", pos)),
+            None => return Err(Error::Eof.into()),
+        };
+        let duration = parse_duration(c)?;
+        transitions.push(Transition { property, duration });
+
+        if matches!(c.peek(), Some(Token(TokenValue::Comma, _))) {
+            c.next();
+        } else {
+            return Ok(transitions);
+        }
+    }
+}
+
+fn parse_duration<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<Duration> {
+    match c.next() {
+        Some(Token(TokenValue::Number(number), pos)) => {
+            let (number, unit) = split_unit(number.as_str());
+            let number = number
+                .parse::<f32>()
+                .map_err(|err| syntax_error(err.to_string(), pos))?;
+            match unit {
+                "ms" => Ok(Duration::from_secs_f32(number.max(0.0) / 1000.0)),
+                "s" => Ok(Duration::from_secs_f32(number.max(0.0))),
+                unit => Err(syntax_error(format!("Expected `ms` or `s`, found `{}`", unit), pos)),
+            }
+        }
+        Some(Token(_, pos)) => Err(syntax_error("Expected <duration>", pos)),
+        None => Err(Error::Eof.into()),
     }
 }
 
@@ -407,16 +905,16 @@ fn parse_direction<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Resul
             "left-to-right" => Ok(Direction::LeftToRight),
             "right-to-left" => Ok(Direction::RightToLeft),
             "bottom-to-top" => Ok(Direction::BottomToTop),
-            _ => Err(anyhow!(
-                "Expected `top-to-bottom`, `left-to-right`, `right-to-left` or `bottom-to-top` at {}",
+            _ => Err(syntax_error(
+                "Expected `top-to-bottom`, `left-to-right`, `right-to-left` or `bottom-to-top`",
                 pos,
             )),
         },
-        Some(Token(_, pos)) => Err(anyhow!(
-            "Expected `top-to-bottom`, `left-to-right`, `right-to-left` or `bottom-to-top` at {}",
+        Some(Token(_, pos)) => Err(syntax_error(
+            "Expected `top-to-bottom`, `left-to-right`, `right-to-left` or `bottom-to-top`",
             pos,
         )),
-        None => Err(anyhow!("EOF")),
+        None => Err(Error::Eof.into()),
     }
 }
 
@@ -426,10 +924,10 @@ fn parse_align<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<Al
             "begin" | "left" | "top" => Ok(Align::Begin),
             "center" => Ok(Align::Center),
             "end" | "right" | "bottom" => Ok(Align::End),
-            _ => Err(anyhow!("Expected `begin`, `center` or `end` at {}", pos)),
+            _ => Err(syntax_error("Expected `begin`, `center` or `end`", pos)),
         },
-        Some(Token(_, pos)) => Err(anyhow!("Expected `begin`, `center` or `end` at {}", pos)),
-        None => Err(anyhow!("EOF")),
+        Some(Token(_, pos)) => Err(syntax_error("Expected `begin`, `center` or `end`", pos)),
+        None => Err(Error::Eof.into()),
     }
 }
 
@@ -437,58 +935,131 @@ fn parse_size<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<Siz
     match c.next() {
         Some(Token(TokenValue::Iden(ty), pos)) => match ty.to_lowercase().as_str() {
             "shrink" => Ok(Size::Shrink),
+            "calc" => {
+                c.take(TokenValue::ParenOpen)?;
+                let result = parse_calc_sum(c)?;
+                c.take(TokenValue::ParenClose)?;
+                Ok(match result {
+                    (0.0, offset) => Size::Exact(offset),
+                    (percent, 0.0) => Size::Percent(percent),
+                    (percent, offset) => Size::Calc(percent, offset),
+                })
+            }
             "fill" => {
                 c.take(TokenValue::ParenOpen)?;
                 let size = parse_usize(c)?;
                 c.take(TokenValue::ParenClose)?;
                 Ok(Size::Fill(size as u32))
             }
-            _ => Err(anyhow!("Expected `shrink`, `fill(<integer>)` or <number> at {}", pos,)),
+            _ => Err(syntax_error(
+                "Expected `shrink`, `fill(<integer>)`, `calc(<expression>)` or <number>",
+                pos,
+            )),
         },
-        Some(Token(TokenValue::Number(num), pos)) => Ok(Size::Exact(
-            num.parse::<f32>().map_err(|err| anyhow!("{} at {}", err, pos))?,
+        Some(Token(TokenValue::Number(num), pos)) => {
+            let (num, unit) = split_unit(num.as_str());
+            let num = num.parse::<f32>().map_err(|err| syntax_error(err.to_string(), pos))?;
+            match unit {
+                "" | "px" => Ok(Size::Exact(num)),
+                "%" => Ok(Size::Percent(num)),
+                unit => Err(syntax_error(format!("Expected `px` or `%`, found `{}`", unit), pos)),
+            }
+        }
+        Some(Token(_, pos)) => Err(syntax_error(
+            "Expected `shrink`, `fill(<integer>)`, `calc(<expression>)` or <number>",
+            pos,
         )),
-        Some(Token(_, pos)) => Err(anyhow!("Expected `shrink`, `fill(<integer>)` or <number> at {}", pos,)),
-        None => Err(anyhow!("EOF")),
+        None => Err(Error::Eof.into()),
     }
 }
 
-#[allow(clippy::identity_op)] // to keep the code clean and consistent
-fn parse_color<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<Color> {
-    match c.next().ok_or_else(|| anyhow!("EOF"))? {
-        Token(TokenValue::Color(string), pos) => {
-            let int = u32::from_str_radix(string.as_str(), 16).map_err(|err| anyhow!("{} at {}", err, pos))?;
-            match string.len() {
-                3 => Ok(Color {
-                    r: ((int & 0xf00) >> 8) as f32 / 15.0,
-                    g: ((int & 0x0f0) >> 4) as f32 / 15.0,
-                    b: ((int & 0x00f) >> 0) as f32 / 15.0,
-                    a: 1.0,
-                }),
-                4 => Ok(Color {
-                    r: ((int & 0xf000) >> 12) as f32 / 15.0,
-                    g: ((int & 0x0f00) >> 8) as f32 / 15.0,
-                    b: ((int & 0x00f0) >> 4) as f32 / 15.0,
-                    a: ((int & 0x000f) >> 0) as f32 / 15.0,
-                }),
-                6 => Ok(Color {
-                    r: ((int & 0xff0000) >> 16) as f32 / 255.0,
-                    g: ((int & 0x00ff00) >> 8) as f32 / 255.0,
-                    b: ((int & 0x0000ff) >> 0) as f32 / 255.0,
-                    a: 1.0,
-                }),
-                8 => Ok(Color {
-                    r: ((int & 0xff000000) >> 24) as f32 / 255.0,
-                    g: ((int & 0x00ff0000) >> 16) as f32 / 255.0,
-                    b: ((int & 0x0000ff00) >> 8) as f32 / 255.0,
-                    a: ((int & 0x000000ff) >> 0) as f32 / 255.0,
-                }),
-                _ => Err(anyhow!(
-                    "Color values must match one of the following hex patterns: #rgb, #rgba, #rrggbb or #rrggbbaa at {}",
+/// Parses the `+` and `-` operations of a `calc()` expression. Expressions are evaluated while parsing, to a
+/// percentage and a number of units that are added together.
+fn parse_calc_sum<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<(f32, f32)> {
+    let mut result = parse_calc_product(c)?;
+    loop {
+        let sign = match c.peek() {
+            Some(Token(TokenValue::Plus, _)) => 1.0,
+            Some(Token(TokenValue::Iden(minus), _)) if minus == "-" => -1.0,
+            // `100%-20` is tokenized as `100%` followed by `-20`, which is a subtraction as well.
+            Some(Token(TokenValue::Iden(operand), _)) if operand.starts_with('-') => {
+                let operand = parse_calc_operand(c)?;
+                result = (result.0 + operand.0, result.1 + operand.1);
+                continue;
+            }
+            _ => return Ok(result),
+        };
+        c.next();
+        let operand = parse_calc_product(c)?;
+        result = (result.0 + sign * operand.0, result.1 + sign * operand.1);
+    }
+}
+
+/// Parses the `*` and `/` operations of a `calc()` expression. One side of each operation must be a plain number.
+fn parse_calc_product<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<(f32, f32)> {
+    let mut result = parse_calc_operand(c)?;
+    loop {
+        let (multiply, pos) = match c.peek() {
+            Some(Token(TokenValue::Star, pos)) => (true, *pos),
+            Some(Token(TokenValue::Slash, pos)) => (false, *pos),
+            _ => return Ok(result),
+        };
+        c.next();
+        let operand = parse_calc_operand(c)?;
+        result = match (multiply, result, operand) {
+            (true, (0.0, factor), (percent, offset)) | (true, (percent, offset), (0.0, factor)) => {
+                (percent * factor, offset * factor)
+            }
+            (false, (percent, offset), (0.0, divisor)) if divisor != 0.0 => (percent / divisor, offset / divisor),
+            (false, _, (0.0, _)) => return Err(syntax_error("Division by zero", pos)),
+            (true, _, _) => return Err(syntax_error("One side of `*` must be a number without a unit", pos)),
+            (false, _, _) => {
+                return Err(syntax_error(
+                    "The right side of `/` must be a number without a unit",
                     pos,
-                )),
+                ))
             }
+        };
+    }
+}
+
+/// Parses a number, a percentage or a parenthesized expression in a `calc()` expression.
+fn parse_calc_operand<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<(f32, f32)> {
+    match c.next().ok_or(Error::Eof)? {
+        Token(TokenValue::ParenOpen, _) => {
+            let result = parse_calc_sum(c)?;
+            c.take(TokenValue::ParenClose)?;
+            Ok(result)
+        }
+        Token(TokenValue::Number(number), pos) => parse_calc_number(number.as_str(), pos),
+        Token(TokenValue::Iden(number), pos) if number.starts_with('-') && number.len() > 1 => {
+            let (percent, offset) = parse_calc_number(&number[1..], pos)?;
+            Ok((-percent, -offset))
         }
-        Token(_, pos) => Err(anyhow!("Expected <color> at {}", pos)),
+        Token(_, pos) => Err(syntax_error("Expected <number>, <percentage> or `(`", pos)),
+    }
+}
+
+fn parse_calc_number(number: &str, pos: TokenPos) -> Result<(f32, f32)> {
+    let (number, unit) = split_unit(number);
+    let number = number
+        .parse::<f32>()
+        .map_err(|err| syntax_error(err.to_string(), pos))?;
+    match unit {
+        "" | "px" => Ok((0.0, number)),
+        "%" => Ok((number, 0.0)),
+        unit => Err(syntax_error(format!("Expected `px` or `%`, found `{}`", unit), pos)),
+    }
+}
+
+fn parse_color<I: Iterator<Item = Token>>(c: &mut TokenProvider<I>) -> Result<Color> {
+    match c.next().ok_or(Error::Eof)? {
+        Token(TokenValue::Color(string), pos) => Color::from_hex(string.as_str()).ok_or_else(|| {
+            syntax_error(
+                "Color values must match one of the following hex patterns: #rgb, #rgba, #rrggbb or #rrggbbaa",
+                pos,
+            )
+        }),
+        Token(_, pos) => Err(syntax_error("Expected <color>", pos)),
     }
 }
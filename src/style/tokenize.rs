@@ -1,6 +1,5 @@
 use super::Error;
 
-const URL_CHARACTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~:/?#[]@!$&'()*+,;%=";
 const NUMBER_CHARACTERS: &str = "0123456789.";
 
 #[derive(Debug, Clone, Copy)]
@@ -18,6 +17,8 @@ pub enum TokenValue {
     Number(String),
     ParenOpen,
     ParenClose,
+    BracketOpen,
+    BracketClose,
     BraceOpen,
     BraceClose,
     Colon,
@@ -28,6 +29,8 @@ pub enum TokenValue {
     Plus,
     Tilde,
     Star,
+    Slash,
+    Bang,
 }
 
 #[derive(Debug, Clone)]
@@ -75,6 +78,8 @@ pub fn tokenize(text: String) -> Result<Vec<Token>, Error> {
                     '"' => Some(Token(TokenValue::Path(String::new()), pos)),
                     '(' => Some(Token(TokenValue::ParenOpen, pos)),
                     ')' => Some(Token(TokenValue::ParenClose, pos)),
+                    '[' => Some(Token(TokenValue::BracketOpen, pos)),
+                    ']' => Some(Token(TokenValue::BracketClose, pos)),
                     '{' => Some(Token(TokenValue::BraceOpen, pos)),
                     '}' => Some(Token(TokenValue::BraceClose, pos)),
                     ':' => Some(Token(TokenValue::Colon, pos)),
@@ -85,8 +90,10 @@ pub fn tokenize(text: String) -> Result<Vec<Token>, Error> {
                     '+' => Some(Token(TokenValue::Plus, pos)),
                     '~' => Some(Token(TokenValue::Tilde, pos)),
                     '*' => Some(Token(TokenValue::Star, pos)),
+                    '/' => Some(Token(TokenValue::Slash, pos)),
+                    '!' => Some(Token(TokenValue::Bang, pos)),
                     chr => {
-                        return Err(Error::Syntax(format!("Unexpected character '{}'", chr), pos));
+                        return Err(Error::Syntax(format!("Unexpected character '{}'", chr), pos, None));
                     }
                 };
             }
@@ -126,18 +133,18 @@ impl Token {
                 }
             }
             Token(TokenValue::Path(ref mut p), ref mut pos) => {
-                if URL_CHARACTERS.chars().any(|c| c == ch) {
+                if ch == '"' {
+                    ExtendResult::Finished
+                } else if ch != '\n' {
                     pos.col_end += 1;
                     p.push(ch);
                     ExtendResult::Accepted
-                } else if ch == '"' {
-                    ExtendResult::Finished
                 } else {
                     ExtendResult::NotAccepted
                 }
             }
             Token(TokenValue::Number(ref mut n), ref mut pos) => {
-                if NUMBER_CHARACTERS.chars().any(|c| c == ch) {
+                if NUMBER_CHARACTERS.chars().any(|c| c == ch) || ch.is_alphabetic() || ch == '%' {
                     pos.col_end += 1;
                     n.push(ch);
                     ExtendResult::Accepted
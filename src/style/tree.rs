@@ -10,19 +10,23 @@ use std::sync::Arc;
 #[derive(Debug, Default)]
 pub(crate) struct RuleTree {
     rules: Vec<Rule>,
+    flag_selectors: bool,
 }
 
 #[derive(Debug)]
 pub(crate) struct Rule {
     selector: Selector,
     declarations: Vec<Declaration<ImageData, Patch, Font>>,
+    important: Vec<Declaration<ImageData, Patch, Font>>,
     children: Vec<usize>,
+    parent: Option<usize>,
 }
 
 #[derive(Debug)]
 pub(crate) struct RuleTreeBuilder {
     pub selector: Selector,
     pub declarations: Vec<Declaration<ImageId, PatchId, FontId>>,
+    pub important: Vec<Declaration<ImageId, PatchId, FontId>>,
     pub children: Vec<RuleTreeBuilder>,
 }
 
@@ -38,7 +42,35 @@ impl RuleTree {
         &'a self,
         style: &'a BitSet,
     ) -> impl Iterator<Item = &'a Declaration<ImageData, Patch, Font>> {
-        style.iter().flat_map(move |rule| self.rules[rule].declarations.iter())
+        // important declarations are applied after all regular declarations, so they override them.
+        style
+            .iter()
+            .flat_map(move |rule| self.rules[rule].declarations.iter())
+            .chain(style.iter().flat_map(move |rule| self.rules[rule].important.iter()))
+    }
+
+    /// The declarations of a single node from the rule tree.
+    pub fn declarations(&self, rule: usize) -> &[Declaration<ImageData, Patch, Font>] {
+        self.rules[rule].declarations.as_slice()
+    }
+
+    /// The important declarations of a single node from the rule tree.
+    pub fn important_declarations(&self, rule: usize) -> &[Declaration<ImageData, Patch, Font>] {
+        self.rules[rule].important.as_slice()
+    }
+
+    /// The selector path from the root of the rule tree to a node, excluding the root.
+    pub fn selectors(&self, rule: usize) -> Vec<Selector> {
+        let mut result = Vec::new();
+        let mut current = Some(rule);
+        while let Some(rule) = current {
+            if self.rules[rule].parent.is_some() {
+                result.push(self.rules[rule].selector.clone());
+            }
+            current = self.rules[rule].parent;
+        }
+        result.reverse();
+        result
     }
 
     /// Add a node from the rule tree to a bitset.
@@ -105,8 +137,87 @@ impl RuleTree {
             self.add_to_bitset(selector, state, class, n, len, &mut result);
         }
 
+        self.match_flags(&mut result, state, class, n, len);
         result
     }
+
+    /// Match the flag selectors that are children of the rules in the bitset against the flags set by those rules.
+    /// Rules that were previously matched through a flag selector are matched again, since the flags may have changed.
+    pub fn match_flags<S: AsRef<str>>(
+        &self,
+        style: &mut BitSet,
+        state: &[StyleState<S>],
+        class: &str,
+        n: usize,
+        len: usize,
+    ) {
+        if !self.flag_selectors {
+            return;
+        }
+
+        let derived = style
+            .iter()
+            .filter(|&rule| self.is_flag_derived(rule))
+            .collect::<Vec<_>>();
+        for rule in derived {
+            style.remove(rule);
+        }
+
+        // flag rules can set flags themselves, so keep matching until no new rules apply.
+        loop {
+            let flags = self.flags(style);
+            let matched = style
+                .iter()
+                .flat_map(|rule| self.rules[rule].children.iter().cloned())
+                .filter(|&child| {
+                    !style.contains(child) && self.rules[child].selector.match_flag(&flags).unwrap_or(false)
+                })
+                .collect::<Vec<_>>();
+            if matched.is_empty() {
+                break;
+            }
+            for rule in matched {
+                self.add_to_bitset(rule, state, class, n, len, style);
+            }
+        }
+    }
+
+    /// Whether a rule in the bitset of a widget was matched through a flag selector of the same widget.
+    fn is_flag_derived(&self, rule: usize) -> bool {
+        let mut current = Some(rule);
+        while let Some(rule) = current {
+            let selector = &self.rules[rule].selector;
+            if selector.match_flag(&[]).is_some() {
+                return true;
+            }
+            if selector.match_child(false, "").is_some() || selector.match_sibling(false, "").is_some() {
+                return false;
+            }
+            current = self.rules[rule].parent;
+        }
+        false
+    }
+
+    /// The flags that are set by the declarations of the rules in a bitset, in sorted order.
+    fn flags(&self, style: &BitSet) -> Vec<String> {
+        let mut flags = Vec::new();
+        for declaration in self.iter_declarations(style) {
+            match declaration {
+                Declaration::AddFlag(flag) => {
+                    if let Err(insert_at) = flags.binary_search(flag) {
+                        flags.insert(insert_at, flag.clone());
+                    }
+                }
+                Declaration::RemoveFlag(flag) => {
+                    if let Ok(exists) = flags.binary_search(flag) {
+                        flags.remove(exists);
+                    }
+                }
+                _ => (),
+            }
+        }
+        flags
+    }
 }
 
 impl RuleTreeBuilder {
@@ -114,13 +225,22 @@ impl RuleTreeBuilder {
         RuleTreeBuilder {
             selector: Selector::Root,
             declarations: Vec::new(),
+            important: Vec::new(),
             children: Vec::new(),
         }
     }
 
-    /// Recursively insert some rules at the selectors path
-    pub fn insert(&mut self, selectors: impl AsRef<[Selector]>, rules: Vec<Declaration<ImageId, PatchId, FontId>>) {
-        self.select(selectors).declarations.extend(rules);
+    /// Recursively insert some rules at the selectors path.
+    /// The `important` declarations are applied after the regular declarations of all matched rules.
+    pub fn insert(
+        &mut self,
+        selectors: impl AsRef<[Selector]>,
+        rules: Vec<Declaration<ImageId, PatchId, FontId>>,
+        important: Vec<Declaration<ImageId, PatchId, FontId>>,
+    ) {
+        let node = self.select(selectors);
+        node.declarations.extend(rules);
+        node.important.extend(important);
     }
 
     /// Get or create a `RuleTreeBuilder` at the selector path
@@ -141,6 +261,7 @@ impl RuleTreeBuilder {
                     self.children.push(RuleTreeBuilder {
                         selector: selector.clone(),
                         declarations: Vec::new(),
+                        important: Vec::new(),
                         children: Vec::new(),
                     });
                 }
@@ -157,6 +278,7 @@ impl RuleTreeBuilder {
 
         // merge the declarations
         self.declarations.append(&mut rule_tree.declarations);
+        self.important.append(&mut rule_tree.important);
 
         // merge the children
         for sub_tree in rule_tree.children {
@@ -174,6 +296,48 @@ impl RuleTreeBuilder {
         patches: &HashMap<String, Patch>,
         fonts: &HashMap<String, Font>,
     ) -> RuleTree {
+        let resolve = |declaration: Declaration<ImageId, PatchId, FontId>| match declaration {
+            Declaration::BackgroundNone => Declaration::BackgroundNone,
+            Declaration::BackgroundColor(x) => Declaration::BackgroundColor(x),
+            Declaration::BackgroundImage(ImageId(x), y) => Declaration::BackgroundImage(images[&x].clone(), y),
+            Declaration::BackgroundPatch(PatchId(x), y) => Declaration::BackgroundPatch(patches[&x].clone(), y),
+            Declaration::BorderRadius(x) => Declaration::BorderRadius(x),
+            Declaration::Font(FontId(x)) => Declaration::Font(fonts[&x].clone()),
+            Declaration::Color(x) => Declaration::Color(x),
+            Declaration::Padding(x) => Declaration::Padding(x),
+            Declaration::PaddingLeft(x) => Declaration::PaddingLeft(x),
+            Declaration::PaddingRight(x) => Declaration::PaddingRight(x),
+            Declaration::PaddingTop(x) => Declaration::PaddingTop(x),
+            Declaration::PaddingBottom(x) => Declaration::PaddingBottom(x),
+            Declaration::Margin(x) => Declaration::Margin(x),
+            Declaration::MarginLeft(x) => Declaration::MarginLeft(x),
+            Declaration::MarginRight(x) => Declaration::MarginRight(x),
+            Declaration::MarginTop(x) => Declaration::MarginTop(x),
+            Declaration::MarginBottom(x) => Declaration::MarginBottom(x),
+            Declaration::TextSize(x) => Declaration::TextSize(x),
+            Declaration::TextBorder(x) => Declaration::TextBorder(x),
+            Declaration::TextWrap(x) => Declaration::TextWrap(x),
+            Declaration::TextDecoration(x) => Declaration::TextDecoration(x),
+            Declaration::Width(x) => Declaration::Width(x),
+            Declaration::Height(x) => Declaration::Height(x),
+            Declaration::MinWidth(x) => Declaration::MinWidth(x),
+            Declaration::MaxWidth(x) => Declaration::MaxWidth(x),
+            Declaration::MinHeight(x) => Declaration::MinHeight(x),
+            Declaration::MaxHeight(x) => Declaration::MaxHeight(x),
+            Declaration::LayoutDirection(x) => Declaration::LayoutDirection(x),
+            Declaration::AlignHorizontal(x) => Declaration::AlignHorizontal(x),
+            Declaration::AlignVertical(x) => Declaration::AlignVertical(x),
+            Declaration::Spacing(x) => Declaration::Spacing(x),
+            Declaration::ZIndex(x) => Declaration::ZIndex(x),
+            Declaration::Transition(x) => Declaration::Transition(x),
+            Declaration::Delay(x) => Declaration::Delay(x),
+            Declaration::ContentNone => Declaration::ContentNone,
+            Declaration::ContentText(x) => Declaration::ContentText(x),
+            Declaration::ContentImage(ImageId(x)) => Declaration::ContentImage(images[&x].clone()),
+            Declaration::AddFlag(x) => Declaration::AddFlag(x),
+            Declaration::RemoveFlag(x) => Declaration::RemoveFlag(x),
+        };
+
         let mut rules = Vec::<Rule>::new();
 
         let mut queue = VecDeque::new();
@@ -191,47 +355,15 @@ impl RuleTreeBuilder {
 
             rules.push(Rule {
                 selector: rule.selector,
-                declarations: rule
-                    .declarations
-                    .into_iter()
-                    .map(|declaration| match declaration {
-                        Declaration::BackgroundNone => Declaration::BackgroundNone,
-                        Declaration::BackgroundColor(x) => Declaration::BackgroundColor(x),
-                        Declaration::BackgroundImage(ImageId(x), y) => {
-                            Declaration::BackgroundImage(images[&x].clone(), y)
-                        }
-                        Declaration::BackgroundPatch(PatchId(x), y) => {
-                            Declaration::BackgroundPatch(patches[&x].clone(), y)
-                        }
-                        Declaration::Font(FontId(x)) => Declaration::Font(fonts[&x].clone()),
-                        Declaration::Color(x) => Declaration::Color(x),
-                        Declaration::Padding(x) => Declaration::Padding(x),
-                        Declaration::PaddingLeft(x) => Declaration::PaddingLeft(x),
-                        Declaration::PaddingRight(x) => Declaration::PaddingRight(x),
-                        Declaration::PaddingTop(x) => Declaration::PaddingTop(x),
-                        Declaration::PaddingBottom(x) => Declaration::PaddingBottom(x),
-                        Declaration::Margin(x) => Declaration::Margin(x),
-                        Declaration::MarginLeft(x) => Declaration::MarginLeft(x),
-                        Declaration::MarginRight(x) => Declaration::MarginRight(x),
-                        Declaration::MarginTop(x) => Declaration::MarginTop(x),
-                        Declaration::MarginBottom(x) => Declaration::MarginBottom(x),
-                        Declaration::TextSize(x) => Declaration::TextSize(x),
-                        Declaration::TextBorder(x) => Declaration::TextBorder(x),
-                        Declaration::TextWrap(x) => Declaration::TextWrap(x),
-                        Declaration::Width(x) => Declaration::Width(x),
-                        Declaration::Height(x) => Declaration::Height(x),
-                        Declaration::LayoutDirection(x) => Declaration::LayoutDirection(x),
-                        Declaration::AlignHorizontal(x) => Declaration::AlignHorizontal(x),
-                        Declaration::AlignVertical(x) => Declaration::AlignVertical(x),
-                        Declaration::AddFlag(x) => Declaration::AddFlag(x),
-                        Declaration::RemoveFlag(x) => Declaration::RemoveFlag(x),
-                    })
-                    .collect(),
+                declarations: rule.declarations.into_iter().map(&resolve).collect(),
+                important: rule.important.into_iter().map(&resolve).collect(),
                 children: Vec::new(),
+                parent,
             });
         }
 
-        RuleTree { rules }
+        let flag_selectors = rules.iter().any(|rule| rule.selector.match_flag(&[]).is_some());
+        RuleTree { rules, flag_selectors }
     }
 }
 
@@ -276,6 +408,7 @@ impl Query {
                 .rule_tree
                 .add_to_bitset(node, state, class, n, len, &mut result);
         }
+        self.style.rule_tree.match_flags(&mut result, state, class, n, len);
 
         result
     }
@@ -20,7 +20,21 @@ pub enum TextWrap {
     WordWrap,
 }
 
+/// Lines that are drawn along text
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TextDecoration {
+    /// Draw a line below the text
+    pub underline: bool,
+    /// Draw a line through the text
+    pub strikethrough: bool,
+}
+
 /// A multi + true signed distance field font.
+///
+/// Fonts can have full color glyphs, which is useful for color emoji. They are detected from the color tables (`CBDT`,
+/// `sbix` or `COLR`) of the source font file when it is passed to
+/// [`StyleBuilder::load_color_font`](../style/builder/struct.StyleBuilder.html#method.load_color_font), and are drawn
+/// as is, without being multiplied by the text color.
 #[derive(Clone, Debug)]
 pub struct Font {
     atlas: ImageData,
@@ -90,6 +104,9 @@ pub struct Glyph {
     pub plane_bounds: Option<Rectangle>,
     /// Atlas bounds
     pub atlas_bounds: Option<Rectangle>,
+    /// Whether the atlas contains a full color bitmap for this glyph instead of a distance field.
+    /// Set for glyphs that are detected in the color tables of the source font.
+    pub color: bool,
 }
 
 /// A kerning pair in an MSDF font.
@@ -115,6 +132,14 @@ pub struct Text<'a> {
     pub wrap: TextWrap,
     /// Color to render the text with
     pub color: Color,
+    /// Runs of the text that are rendered with a different color, as the index of the character where the run starts
+    /// and the color of the run. The runs must be sorted by their start index. Characters before the first run are
+    /// rendered with [`color`](#structfield.color).
+    pub spans: Vec<(usize, Color)>,
+    /// Draw a line below the text, using the underline metrics of the font
+    pub underline: bool,
+    /// Draw a line through the text
+    pub strikethrough: bool,
 }
 
 /// Iterator over characters that have been layout by the rusttype engine.
@@ -154,7 +179,11 @@ struct WordWrapper<'a, 'b: 'a> {
 impl Font {
     /// Load mtsdf font from a json file and an atlas texture
     pub fn from_data(data: impl AsRef<[u8]>, atlas: ImageData) -> Result<Self> {
-        let mut data: FontData = serde_json::from_slice(data.as_ref())?;
+        Ok(Self::from_font_data(serde_json::from_slice(data.as_ref())?, atlas))
+    }
+
+    /// Constructs a font from parsed font data, converting the atlas bounds of the glyphs to texture coordinates.
+    pub(crate) fn from_font_data(mut data: FontData, atlas: ImageData) -> Self {
         for (_, g) in data.glyphs.iter_mut() {
             g.atlas_bounds = g.atlas_bounds.map(|b| match data.atlas.y_origin.as_str() {
                 "bottom" => atlas.texcoords.sub(Rectangle {
@@ -176,10 +205,10 @@ impl Font {
                 ..b
             });
         }
-        Ok(Self {
+        Self {
             atlas,
             data: Arc::new(data),
-        })
+        }
     }
 
     pub(crate) fn texture(&self) -> usize {
@@ -195,6 +224,26 @@ impl Deref for Font {
     }
 }
 
+impl FontData {
+    /// Returns the bounds of the full color glyphs in the atlas, in pixels from the top left.
+    pub(crate) fn color_glyph_bounds(&self) -> Vec<Rectangle> {
+        let height = self.atlas.height as f32;
+        self.glyphs
+            .values()
+            .filter(|g| g.color)
+            .filter_map(|g| g.atlas_bounds)
+            .map(|b| match self.atlas.y_origin.as_str() {
+                "bottom" => Rectangle {
+                    top: height - b.top,
+                    bottom: height - b.bottom,
+                    ..b
+                },
+                _ => b,
+            })
+            .collect()
+    }
+}
+
 impl From<FontDataSerialized> for FontData {
     fn from(val: FontDataSerialized) -> Self {
         let default_glyph = val.glyphs[0].clone();
@@ -233,6 +282,7 @@ impl Glyph {
             unicode: self.unicode,
             advance: self.advance * x,
             atlas_bounds: self.atlas_bounds.clone(),
+            color: self.color,
             plane_bounds: self.plane_bounds.clone().map(|b| Rectangle {
                 left: b.left * x,
                 top: b.top * y,
@@ -291,83 +341,113 @@ impl<'a, 'b: 'a> WordWrapper<'a, 'b> {
 }
 
 impl<'t> Text<'t> {
-    pub(crate) fn char_positions<'a, 'b>(&'b self) -> CharPositionIter<'a, 'b> {
+    fn char_positions<'a, 'b>(&'b self, line: &'a str) -> CharPositionIter<'a, 'b> {
         CharPositionIter {
             font: &*self.font.data,
             scale_x: self.size,
             scale_y: self.size,
             x: 0.0,
-            base: self.text.chars().peekable(),
+            base: line.chars().peekable(),
         }
     }
 
+    /// Lays out the glyphs of the text. `f` is called with each glyph, it's left and right x coordinate and the y
+    /// coordinate of it's baseline. Line breaks are passed to `f` as an empty glyph at the end of the line they break.
     pub(crate) fn layout<F: FnMut(Glyph, f32, f32, f32)>(&self, rect: Rectangle, mut f: F) {
         let line = self.font.data.metrics.scale(self.size);
 
         let width = rect.width();
         let height = /*-line.descender +*/ line.line_height /*+ line.ascender*/;
 
-        match self.wrap {
-            TextWrap::NoWrap => {
-                for (g, a, b) in self.char_positions() {
-                    f(g, a, b, line.ascender);
+        let mut y = line.ascender;
+        let mut lines = self.text.split('\n').peekable();
+        while let Some(text) = lines.next() {
+            // the position after the last glyph on the line, where the line break is placed.
+            let mut end = (0.0, y);
+            let mut emit = |g, a, b, y| {
+                end = (b, y);
+                f(g, a, b, y);
+            };
+
+            match self.wrap {
+                TextWrap::NoWrap => {
+                    for (g, a, b) in self.char_positions(text) {
+                        emit(g, a, b, y);
+                    }
                 }
-            }
 
-            TextWrap::Wrap => {
-                let mut x = 0.0;
-                let mut y = line.ascender;
+                TextWrap::Wrap => {
+                    let mut x = 0.0;
+                    let mut y = y;
+
+                    for (g, a, b) in self.char_positions(text) {
+                        if b - x > width {
+                            x = a;
+                            y += height;
+                        }
 
-                for (g, a, b) in self.char_positions() {
-                    if b - x > width {
-                        x = a;
-                        y += height;
+                        emit(g, a - x, b - x, y);
                     }
+                }
 
-                    f(g, a - x, b - x, y);
+                TextWrap::WordWrap => {
+                    let mut wrapper = WordWrapper {
+                        x: 0.0,
+                        y,
+                        final_x: 0.0,
+                        final_y: y,
+                        width,
+                        height,
+                        iter: self.char_positions(text),
+                        f: &mut emit,
+                    };
+
+                    while let Some((glyph, a, b)) = wrapper.iter.next() {
+                        let ch = unsafe { char::from_u32_unchecked(glyph.unicode) };
+                        wrapper.layout_word(glyph, a, a, b, ch.is_alphanumeric());
+                    }
                 }
             }
 
-            TextWrap::WordWrap => {
-                let mut wrapper = WordWrapper {
-                    x: 0.0,
-                    y: line.ascender,
-                    final_x: 0.0,
-                    final_y: line.ascender,
-                    width,
-                    height,
-                    iter: self.char_positions(),
-                    f: &mut f,
+            if lines.peek().is_some() {
+                let line_break = Glyph {
+                    unicode: '\n' as u32,
+                    ..Glyph::default()
                 };
-
-                while let Some((glyph, a, b)) = wrapper.iter.next() {
-                    let ch = unsafe { char::from_u32_unchecked(glyph.unicode) };
-                    wrapper.layout_word(glyph, a, a, b, ch.is_alphanumeric());
-                }
+                f(line_break, end.0, end.0, end.1);
+                y = end.1 + height;
             }
         }
     }
 
     /// Measure the size of the text. If a rectangle is supplied and the text wraps,
     /// the layout will stay within the width of the given rectangle.
+    /// Text that ends with a line break has an empty line at the end.
     pub fn measure(&self, rect: Option<Rectangle>) -> Rectangle {
         let line = self.font.data.metrics.scale(self.size);
 
+        let mut w: f32 = 0.0;
+        let mut h = line.ascender;
         match rect {
             None => {
-                let mut w = 0.0;
-                self.layout(Rectangle::from_wh(f32::INFINITY, 0.0), |_, _, new_w, _| w = new_w);
+                self.layout(Rectangle::from_wh(f32::INFINITY, 0.0), |g, _, new_w, new_h| {
+                    w = w.max(new_w);
+                    h = h.max(caret_after(&g, new_w, new_h, line.line_height).1);
+                });
 
-                Rectangle::from_wh(w.ceil(), (line.ascender - line.descender).ceil())
+                Rectangle::from_wh(w.ceil(), (h - line.descender).ceil())
             }
             Some(r) => {
-                let mut w = 0.0;
-                let mut h = line.ascender;
                 match self.wrap {
-                    TextWrap::NoWrap => self.layout(r, |_, _, new_w, _| w = new_w),
+                    TextWrap::NoWrap => self.layout(r, |g, _, new_w, new_h| {
+                        w = w.max(new_w);
+                        h = h.max(caret_after(&g, new_w, new_h, line.line_height).1);
+                    }),
                     TextWrap::Wrap | TextWrap::WordWrap => {
-                        w = rect.map_or(0.0, |r| r.width());
-                        self.layout(r, |_, _, _, new_h| h = new_h);
+                        w = r.width();
+                        self.layout(r, |g, _, new_w, new_h| {
+                            h = h.max(caret_after(&g, new_w, new_h, line.line_height).1)
+                        });
                     }
                 }
 
@@ -376,13 +456,32 @@ impl<'t> Text<'t> {
         }
     }
 
-    /// Measure the start and end coordinates of some selected glyphs
+    /// Measure the bounding box of the text when it's laid out in a column of `max_width` wide, honoring the
+    /// [`wrap`](#structfield.wrap) of the text. Unlike [`measure`](#method.measure), the width is the width of the
+    /// longest line after wrapping instead of `max_width`, so widgets can shrink to fit their wrapped text before it's
+    /// drawn. The bounding box only exceeds `max_width` if the text doesn't wrap, or if a single word doesn't fit.
+    pub fn measure_wrapped(&self, max_width: f32) -> Rectangle {
+        let line = self.font.data.metrics.scale(self.size);
+
+        let mut w: f32 = 0.0;
+        let mut h = line.ascender;
+        self.layout(Rectangle::from_wh(max_width, f32::INFINITY), |g, _, new_w, new_h| {
+            w = w.max(new_w);
+            h = h.max(caret_after(&g, new_w, new_h, line.line_height).1);
+        });
+
+        Rectangle::from_wh(w.ceil(), (h - line.descender).ceil())
+    }
+
+    /// Measure the start and end coordinates of some selected glyphs.
+    /// The y coordinates are the baselines of the lines that the start and end are on.
     pub fn measure_range(&self, from: usize, to: usize, rect: Rectangle) -> ((f32, f32), (f32, f32)) {
-        let mut from_result = (0.0, 0.0);
-        let mut to_result = (0.0, 0.0);
+        let line = self.font.data.metrics.scale(self.size);
+        let mut from_result = (0.0, line.ascender);
+        let mut to_result = (0.0, line.ascender);
 
         let mut index = 0;
-        self.layout(rect, |_, begin, end, y| {
+        self.layout(rect, |g, begin, end, y| {
             if index == from {
                 from_result = (begin, y)
             }
@@ -390,10 +489,10 @@ impl<'t> Text<'t> {
                 to_result = (begin, y)
             }
             if index + 1 == from {
-                from_result = (end, y)
+                from_result = caret_after(&g, end, y, line.line_height)
             }
             if index + 1 == to {
-                to_result = (end, y)
+                to_result = caret_after(&g, end, y, line.line_height)
             }
             index += 1;
         });
@@ -401,20 +500,32 @@ impl<'t> Text<'t> {
         (from_result, to_result)
     }
 
+    /// The distance between the baselines of two lines of the text.
+    pub(crate) fn line_height(&self) -> f32 {
+        self.font.data.metrics.scale(self.size).line_height
+    }
+
     /// Find out the index of a character where the mouse is.
+    /// The nearest line is found first, after which the nearest character on that line is returned.
     pub fn hitdetect(&self, cursor: (f32, f32), rect: Rectangle) -> usize {
-        let dist = |(x, y)| x * x + y * y;
+        let line = self.font.data.metrics.scale(self.size);
+        // vertical distance to the center of the line, followed by the horizontal distance to the character.
+        let dist = |(x, y): (f32, f32)| {
+            let center = y - (line.ascender + line.descender) * 0.5;
+            ((center - cursor.1).abs(), (x - cursor.0).abs())
+        };
 
-        let mut nearest = (dist(cursor), 0);
+        let mut nearest = ((f32::INFINITY, f32::INFINITY), 0);
         let mut index = 0;
 
-        self.layout(rect, |_, begin, end, y| {
-            if dist((begin - cursor.0, y - cursor.1)) < nearest.0 {
-                nearest.0 = dist((begin - cursor.0, y - cursor.1));
+        self.layout(rect, |g, begin, end, y| {
+            if dist((begin, y)) < nearest.0 {
+                nearest.0 = dist((begin, y));
                 nearest.1 = index;
             }
-            if dist((end - cursor.0, y - cursor.1)) < nearest.0 {
-                nearest.0 = dist((end - cursor.0, y - cursor.1));
+            let after = caret_after(&g, end, y, line.line_height);
+            if dist(after) < nearest.0 {
+                nearest.0 = dist(after);
                 nearest.1 = index + 1;
             }
 
@@ -424,16 +535,108 @@ impl<'t> Text<'t> {
         nearest.1
     }
 
-    pub(crate) fn draw<F: FnMut(Rectangle, Rectangle)>(&self, rect: Rectangle, mut place_glyph: F) {
-        self.layout(rect, |g, x, _, y| {
+    /// Lays out the glyphs of the text like [`layout`](#method.layout), but also passes the color of each glyph to `f`.
+    fn layout_colored<F: FnMut(Glyph, f32, f32, f32, Color)>(&self, rect: Rectangle, mut f: F) {
+        let mut color = self.color;
+        let mut spans = self.spans.iter().peekable();
+        let mut index = 0;
+        self.layout(rect, |g, a, b, y| {
+            while let Some(&&(_, span_color)) = spans.peek().filter(|(start, _)| *start <= index) {
+                color = span_color;
+                spans.next();
+            }
+            f(g, a, b, y, color);
+            index += 1;
+        });
+    }
+
+    pub(crate) fn draw<F: FnMut(Rectangle, Rectangle, bool, Color)>(&self, rect: Rectangle, mut place_glyph: F) {
+        self.layout_colored(rect, |g, x, _, y, color| {
             if let (Some(atlas), Some(plane)) = (g.atlas_bounds, g.plane_bounds) {
-                place_glyph(atlas, plane.translate(rect.left + x, rect.top + y));
+                place_glyph(atlas, plane.translate(rect.left + x, rect.top + y), g.color, color);
+            }
+        });
+    }
+
+    /// Returns the underline and strikethrough lines of the text, with the color of the glyphs they're drawn along.
+    pub(crate) fn decorations(&self, rect: Rectangle) -> Vec<(Rectangle, Color)> {
+        if !self.underline && !self.strikethrough {
+            return Vec::new();
+        }
+
+        // runs of glyphs on the same line that have the same color, as left, right, baseline and color.
+        let mut runs: Vec<(f32, f32, f32, Color)> = Vec::new();
+        self.layout_colored(rect, |g, begin, end, y, color| {
+            if g.unicode == '\n' as u32 {
+                return;
+            }
+            match runs.last_mut() {
+                Some(run) if run.2 == y && run.3 == color => run.1 = end,
+                _ => runs.push((begin, end, y, color)),
             }
         });
+
+        // the font metrics are y up, while the layout is y down.
+        let line = self.font.data.metrics.scale(self.size);
+        let mut offsets = Vec::new();
+        if self.underline {
+            offsets.push(-line.underline_y);
+        }
+        if self.strikethrough {
+            // roughly halfway the height of lowercase letters
+            offsets.push(-line.ascender * 0.3);
+        }
+
+        let half_thickness = line.underline_thickness * 0.5;
+        runs.into_iter()
+            .flat_map(|(left, right, y, color)| {
+                offsets.iter().map(move |offset| {
+                    let rect = Rectangle {
+                        left: rect.left + left,
+                        right: rect.left + right,
+                        top: rect.top + y + offset - half_thickness,
+                        bottom: rect.top + y + offset + half_thickness,
+                    };
+                    (rect, color)
+                })
+            })
+            .collect()
     }
 }
 
 impl<'a> Text<'a> {
+    /// Construct a new `Text` that is rendered in a single color without wrapping, a border or decorations.
+    /// The other fields are public, so they can be changed after construction.
+    pub fn new(text: impl Into<Cow<'a, str>>, font: Font, size: f32, color: Color) -> Self {
+        Text {
+            text: text.into(),
+            font,
+            size,
+            border: 0.0,
+            wrap: TextWrap::NoWrap,
+            color,
+            spans: Vec::new(),
+            underline: false,
+            strikethrough: false,
+        }
+    }
+
+    /// Replaces the text by the concatenation of `spans`, where each span is rendered with it's own color.
+    /// The spans share the font, size and layout of the text, so they wrap as a single paragraph.
+    pub fn with_spans<S: Into<Cow<'a, str>>>(mut self, spans: impl IntoIterator<Item = (S, Color)>) -> Self {
+        let mut text = String::new();
+        let mut index = 0;
+        self.spans.clear();
+        for (span, color) in spans {
+            let span = span.into();
+            self.spans.push((index, color));
+            index += span.chars().count();
+            text.push_str(&span);
+        }
+        self.text = Cow::Owned(text);
+        self
+    }
+
     /// Convert a borrowed to text to owned text.
     pub fn to_owned(&self) -> Text<'static> {
         Text {
@@ -443,6 +646,19 @@ impl<'a> Text<'a> {
             border: self.border,
             wrap: self.wrap,
             color: self.color,
+            spans: self.spans.clone(),
+            underline: self.underline,
+            strikethrough: self.strikethrough,
         }
     }
 }
+
+/// Returns the position of a caret placed after glyph `g`, which ends at `end` on the line with baseline `y`.
+/// A caret after a line break is placed at the start of the next line.
+fn caret_after(g: &Glyph, end: f32, y: f32, line_height: f32) -> (f32, f32) {
+    if g.unicode == '\n' as u32 {
+        (0.0, y + line_height)
+    } else {
+        (end, y)
+    }
+}
@@ -0,0 +1,119 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+/// The timer that wakes all pending delays. The thread is started when the first delay is polled.
+static TIMER: Lazy<Timer> = Lazy::new(Timer::start);
+
+/// A single thread that sleeps until the earliest deadline of all pending delays.
+struct Timer {
+    pending: Mutex<Pending>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct Pending {
+    deadlines: BinaryHeap<Reverse<(Instant, u64)>>,
+    wakers: HashMap<u64, Waker>,
+    next_id: u64,
+}
+
+/// A future that completes at a deadline. Dropping the future cancels the timer.
+pub(crate) struct Delay {
+    deadline: Instant,
+    id: Option<u64>,
+}
+
+impl Timer {
+    fn start() -> Self {
+        std::thread::Builder::new()
+            .name("pixel-widgets timer".into())
+            .spawn(|| TIMER.run())
+            .expect("failed to spawn the timer thread");
+        Self {
+            pending: Mutex::new(Pending::default()),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn run(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            let mut expired = Vec::new();
+            while let Some(&Reverse((deadline, id))) = pending.deadlines.peek() {
+                if deadline > now {
+                    break;
+                }
+                pending.deadlines.pop();
+                // cancelled delays have no waker anymore, so they are simply skipped.
+                expired.extend(pending.wakers.remove(&id));
+            }
+
+            if !expired.is_empty() {
+                drop(pending);
+                expired.into_iter().for_each(Waker::wake);
+                pending = self.pending.lock().unwrap();
+                continue;
+            }
+
+            pending = match pending.deadlines.peek() {
+                Some(&Reverse((deadline, _))) => self.changed.wait_timeout(pending, deadline - now).unwrap().0,
+                None => self.changed.wait(pending).unwrap(),
+            };
+        }
+    }
+}
+
+impl Delay {
+    pub(crate) fn new(duration: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + duration,
+            id: None,
+        }
+    }
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            if let Some(id) = self.id.take() {
+                TIMER.pending.lock().unwrap().wakers.remove(&id);
+            }
+            return Poll::Ready(());
+        }
+
+        let mut pending = TIMER.pending.lock().unwrap();
+        match self.id {
+            Some(id) => {
+                pending.wakers.insert(id, cx.waker().clone());
+            }
+            None => {
+                let id = pending.next_id;
+                pending.next_id += 1;
+                pending.deadlines.push(Reverse((self.deadline, id)));
+                pending.wakers.insert(id, cx.waker().clone());
+                self.id = Some(id);
+                TIMER.changed.notify_one();
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            TIMER.pending.lock().unwrap().wakers.remove(&id);
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use std::any::Any;
+#[cfg(debug_assertions)]
+use std::sync::{Arc, Weak};
 
 /// An [`Widget`](../widget/trait.Widget.html) state tracker.
 pub(crate) struct ManagedState {
@@ -6,10 +8,26 @@ pub(crate) struct ManagedState {
 }
 
 enum Tracked {
-    Begin { id: u64, state: Box<dyn Any + Send + Sync> },
+    Begin {
+        id: u64,
+        retained: bool,
+        state: Box<dyn Any + Send + Sync>,
+        #[cfg(debug_assertions)]
+        alive: Arc<()>,
+    },
     End,
 }
 
+/// A handle to a state object of a [`ManagedState`](struct.ManagedState.html), which can be used to check if the
+/// state object has not been dropped yet.
+/// Nodes hold on to their state using unchecked references, so using a node after it's state was dropped is undefined
+/// behaviour. In debug builds this is detected and turned into a panic, release builds don't do any checking.
+#[derive(Clone, Default)]
+pub(crate) struct StateToken {
+    #[cfg(debug_assertions)]
+    alive: Option<Weak<()>>,
+}
+
 #[doc(hidden)]
 pub struct ManagedStateTracker<'a> {
     tracker: &'a mut ManagedState,
@@ -34,6 +52,24 @@ impl Default for ManagedState {
     }
 }
 
+impl StateToken {
+    /// Panics if the state object has been dropped. Does nothing in release builds.
+    #[inline]
+    pub(crate) fn assert_alive(&self, owner: &str) {
+        #[cfg(debug_assertions)]
+        if let Some(alive) = self.alive.as_ref() {
+            assert!(
+                alive.strong_count() > 0,
+                "the state of {} was used after it was dropped. \
+                 This can happen when a node is kept around after the view that created it was replaced.",
+                owner,
+            );
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = owner;
+    }
+}
+
 impl Tracked {
     unsafe fn unchecked_mut_ref<'a, T: Any + Send + Sync>(&mut self) -> &'a mut T {
         match self {
@@ -52,6 +88,10 @@ impl Tracked {
 impl<'a> ManagedStateTracker<'a> {
     /// Get a state object for the given id. If such an object doesn't exist yet, it is constructed using the closure.
     /// The span of the widget that requests this state object should be closed using [`end`](#method.end).
+    ///
+    /// The state is searched for among the siblings that haven't been matched yet, so siblings that were reordered
+    /// find their state back. The siblings that are skipped are kept, so they can still be matched by later siblings,
+    /// until the parent span is closed.
     pub(crate) fn begin<'i, T, F>(&mut self, id: u64, default: F) -> &'i mut T
     where
         T: Any + Send + Sync,
@@ -68,8 +108,12 @@ impl<'a> ManagedStateTracker<'a> {
                     self.index = search_start;
                     break;
                 }
-                &Tracked::Begin { id: tid, state: _ } if level == 0 && tid == id => {
-                    self.tracker.state.splice(search_start..self.index, None);
+                &Tracked::Begin { id: tid, .. } if level == 0 && tid == id => {
+                    if self.index > search_start {
+                        // move the span of the found state in front of the skipped siblings.
+                        let span_end = self.span_end(self.index);
+                        self.tracker.state[search_start..=span_end].rotate_right(span_end + 1 - self.index);
+                    }
                     unsafe {
                         let i = search_start;
                         self.index = search_start + 1;
@@ -83,12 +127,56 @@ impl<'a> ManagedStateTracker<'a> {
 
         let i = self.index;
         let state = Box::new(default()) as Box<dyn Any + Send + Sync>;
-        self.tracker.state.insert(i, Tracked::Begin { id, state });
+        self.tracker.state.insert(
+            i,
+            Tracked::Begin {
+                id,
+                retained: false,
+                state,
+                #[cfg(debug_assertions)]
+                alive: Arc::new(()),
+            },
+        );
         self.tracker.state.insert(i + 1, Tracked::End);
         self.index += 1;
         unsafe { self.tracker.state[i].unchecked_mut_ref() }
     }
 
+    /// Returns the index of the `End` that closes the span that begins at `begin`.
+    fn span_end(&self, begin: usize) -> usize {
+        let mut level = 0;
+        for (index, tracked) in self.tracker.state.iter().enumerate().skip(begin + 1) {
+            match tracked {
+                Tracked::Begin { .. } => level += 1,
+                Tracked::End if level > 0 => level -= 1,
+                Tracked::End => return index,
+            }
+        }
+        unreachable!("did not find `End` at the end.");
+    }
+
+    /// Returns a [`StateToken`](struct.StateToken.html) for the state object that was returned by the last call to
+    /// [`begin`](#method.begin).
+    pub(crate) fn token(&self) -> StateToken {
+        match self.index.checked_sub(1).and_then(|i| self.tracker.state.get(i)) {
+            #[cfg(debug_assertions)]
+            Some(Tracked::Begin { alive, .. }) => StateToken {
+                alive: Some(Arc::downgrade(alive)),
+            },
+            _ => StateToken::default(),
+        }
+    }
+
+    /// Retains the state object that was returned by the last call to [`begin`](#method.begin) while it's not
+    /// requested, for as long as the span of it's parent is alive.
+    pub(crate) fn retain(&mut self) {
+        if let Some(Tracked::Begin { retained, .. }) =
+            self.index.checked_sub(1).and_then(|i| self.tracker.state.get_mut(i))
+        {
+            *retained = true;
+        }
+    }
+
     /// Ends the span of a widget.
     /// Should be called after all of it's children have been handled.
     pub(crate) fn end(&mut self) {
@@ -106,9 +194,18 @@ impl<'a> ManagedStateTracker<'a> {
                     level -= 1;
                 }
                 Tracked::End => {
-                    // found it! remove any widget states that were not matched.
-                    self.tracker.state.splice(search_start..self.index, None);
-                    self.index = search_start + 1;
+                    // found it! remove any widget states that were not matched, except for the retained ones.
+                    let mut index = search_start;
+                    while index < self.index {
+                        let span_end = self.span_end(index);
+                        if let Tracked::Begin { retained: true, .. } = self.tracker.state[index] {
+                            index = span_end + 1;
+                        } else {
+                            self.tracker.state.drain(index..=span_end);
+                            self.index -= span_end + 1 - index;
+                        }
+                    }
+                    self.index += 1;
                     return;
                 }
             }
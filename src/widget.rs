@@ -14,38 +14,59 @@ use std::hash::{Hash, Hasher};
 use smallvec::SmallVec;
 
 use crate::draw::Primitive;
-use crate::event::Event;
+use crate::event::{CursorIcon, Event};
 use crate::layout::*;
 use crate::node::GenericNode;
 use crate::style::*;
 
 /// Prelude widgets
 pub mod prelude {
+    pub use super::accordion::Accordion;
     pub use super::button::Button;
+    pub use super::canvas::Canvas;
+    pub use super::checkbox::Checkbox;
     pub use super::column::Column;
     pub use super::drag_drop::{Drag, Drop};
     pub use super::dropdown::Dropdown;
     pub use super::dummy::Dummy;
     pub use super::frame::Frame;
+    pub use super::grid::Grid;
     pub use super::image::Image;
     pub use super::input::Input;
+    pub use super::label::Label;
     pub use super::layers::Layers;
     pub use super::menu::Menu;
     pub use super::panel::Panel;
+    pub use super::portal::Portal;
+    pub use super::primitive_cache::PrimitiveCache;
     pub use super::progress::Progress;
+    pub use super::proximity::Proximity;
+    pub use super::radio::Radio;
+    pub use super::rating::Rating;
     pub use super::row::Row;
     pub use super::scroll::Scroll;
+    pub use super::scrollbar::ScrollBar;
+    pub use super::selection::SelectionArea;
     pub use super::slider::Slider;
     pub use super::spacer::Spacer;
+    pub use super::sprite_animation::SpriteAnimation;
     pub use super::text::Text;
     pub use super::toggle::Toggle;
+    pub use super::tooltip::Tooltip;
+    pub use super::transform::Transform;
     pub use super::window::Window;
 
     pub use super::{StateVec, Widget};
 }
 
+/// Sections that can be expanded and collapsed by clicking on their header
+pub mod accordion;
 /// A clickable button
 pub mod button;
+/// Draw custom primitives generated by a closure
+pub mod canvas;
+/// A box with a check mark that toggles some `bool`.
+pub mod checkbox;
 /// Layout child widgets vertically
 pub mod column;
 /// Drag and drop zones
@@ -56,30 +77,56 @@ pub mod dropdown;
 pub mod dummy;
 /// A widget that wraps around a content widget
 pub mod frame;
+/// Layout child widgets in a grid
+pub mod grid;
 /// Just an image
 pub mod image;
 /// Editable text input
 pub mod input;
+/// Read-only text that can be selected and copied
+pub mod label;
 /// Stack child widgets on top of each other, while only the topmost receives events.
 pub mod layers;
 /// A context menu with nestable items
 pub mod menu;
 /// A panel with a fixed size and location within it's parent
 pub mod panel;
+/// Draw content on top of the whole ui instead of in place
+pub mod portal;
+/// Cache the primitives of a content widget that rarely changes
+pub mod primitive_cache;
 /// A bar that fills up according to a value.
 pub mod progress;
+/// A widget that fades in it's background as the cursor approaches
+pub mod proximity;
+/// Pseudo elements that render content defined in the style
+pub mod pseudo;
+/// A radio button that selects one value out of a group of options.
+pub mod radio;
+/// Pick a rating by clicking on a number of stars
+pub mod rating;
 /// Layout child widgets horizontally
 pub mod row;
 /// View a small section of larger widget, with scrollbars.
 pub mod scroll;
+/// A scroll bar that can be attached to any scrollable view
+pub mod scrollbar;
+/// Drag a selection rectangle over a content widget
+pub mod selection;
 /// A slider for easily picking some number
 pub mod slider;
 /// Empty widget
 pub mod spacer;
+/// Play the frames of a sprite sheet
+pub mod sprite_animation;
 /// Widget that renders a paragraph of text.
 pub mod text;
 /// A clickable button that toggles some `bool`.
 pub mod toggle;
+/// Show a tooltip when a content widget is hovered for a while
+pub mod tooltip;
+/// Scale and pan a content widget
+pub mod transform;
 /// A window with a title and a content widget that can be moved by dragging the title.
 pub mod window;
 
@@ -161,6 +208,28 @@ pub trait Widget<'a, Message>: Send {
         false
     }
 
+    /// Returns whether the widget can be selected using directional navigation, such as with a gamepad.
+    /// Navigation moves the cursor to the center of the selected widget, so widgets that respond to clicks should
+    /// return `true`. See [`Event::Navigate`](../event/enum.Event.html#variant.Navigate).
+    fn focusable(&self, _state: &Self::State) -> bool {
+        false
+    }
+
+    /// Returns how the widget responds to cursor moves over it's layout. The [`Ui`](../struct.Ui.html) uses this to
+    /// skip cursor moves that can't change the state of any widget. The default of
+    /// [`CursorResponse::Track`](enum.CursorResponse.html#variant.Track) is always correct, but widgets that only
+    /// track hovering or ignore the cursor altogether can return a cheaper response.
+    fn cursor_response(&self, _state: &Self::State) -> CursorResponse {
+        CursorResponse::Track
+    }
+
+    /// Returns the layer to draw the widget on, relative to the layer of it's parent. This is added to the `z-index`
+    /// of the stylesheet, and is also used by [`Layers`](layers/struct.Layers.html) to decide which layer receives
+    /// events first. Defaults to `0`.
+    fn z_index(&self, _state: &Self::State) -> i32 {
+        0
+    }
+
     /// Handle an event. If an event changes the graphical appearance of an `Widget`,
     /// [`redraw`](struct.Context.html#method.redraw) should be called to let the [`Ui`](../struct.Ui.html) know that
     /// the ui should be redrawn.
@@ -183,6 +252,14 @@ pub trait Widget<'a, Message>: Send {
     ) {
     }
 
+    /// Handle an event for the [`Portal`s](portal/struct.Portal.html) among the descendants of the widget.
+    /// Portals are drawn on top of the whole ui, so the [`Ui`](../struct.Ui.html) passes events to them before the
+    /// other widgets, without the layout and clipping rect of their parents. The default implementation passes the
+    /// event on to all children, which is correct for every widget that doesn't keep a context of it's own.
+    fn portal_event(&mut self, _state: &mut Self::State, event: Event, context: &mut Context<Message>) {
+        self.visit_children(&mut |child| child.portal_event(event.clone(), context));
+    }
+
     /// Draw the widget. Returns a list of [`Primitive`s](../draw/enum.Primitive.html) that should be drawn.
     ///
     /// Arguments:
@@ -195,6 +272,32 @@ pub trait Widget<'a, Message>: Send {
         clip: Rectangle,
         style: &Stylesheet,
     ) -> Vec<Primitive<'a>>;
+
+    /// Collects the focusable rects and cursor regions of the children that were drawn during the last
+    /// [`draw`](#tymethod.draw). The default implementation asks all children, which is correct for every widget that
+    /// draws it's children. Widgets that reuse previously drawn primitives of their children instead, like
+    /// [`PrimitiveCache`](primitive_cache/struct.PrimitiveCache.html), should replay the rects that were collected
+    /// when the children were drawn.
+    fn drawn_rects(
+        &mut self,
+        _state: &mut Self::State,
+        focusable: &mut Vec<Rectangle>,
+        regions: &mut Vec<(Rectangle, CursorResponse)>,
+    ) {
+        self.visit_children(&mut |child| child.drawn_rects(&mut *focusable, &mut *regions));
+    }
+}
+
+/// How a widget responds to cursor moves over it's layout, see
+/// [`Widget::cursor_response`](trait.Widget.html#method.cursor_response).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CursorResponse {
+    /// The widget doesn't respond to the cursor itself, like a column. It's children still respond on their own.
+    Ignore,
+    /// The widget only responds to the cursor entering or leaving it's layout, like a button.
+    Hover,
+    /// The widget responds to every cursor move within it's layout, like a slider.
+    Track,
 }
 
 /// Storage for style states
@@ -205,6 +308,11 @@ pub struct Context<Message> {
     cursor: (f32, f32),
     redraw: bool,
     rebuild: bool,
+    animate: bool,
+    track_cursor: bool,
+    cursor_icon: Option<CursorIcon>,
+    scroll_consumed: bool,
+    portal_hovered: bool,
     messages: Vec<Message>,
 }
 
@@ -214,6 +322,11 @@ impl<Message> Context<Message> {
             cursor,
             redraw,
             rebuild,
+            animate: false,
+            track_cursor: false,
+            cursor_icon: None,
+            scroll_consumed: false,
+            portal_hovered: false,
             messages: Vec::new(),
         }
     }
@@ -223,6 +336,11 @@ impl<Message> Context<Message> {
             cursor: self.cursor,
             redraw: self.redraw,
             rebuild: self.rebuild,
+            animate: self.animate,
+            track_cursor: self.track_cursor,
+            cursor_icon: self.cursor_icon,
+            scroll_consumed: self.scroll_consumed,
+            portal_hovered: self.portal_hovered,
             messages: Vec::new(),
         }
     }
@@ -239,6 +357,10 @@ impl<Message> Context<Message> {
 
     /// Request a redraw of the ui.
     pub fn redraw(&mut self) {
+        #[cfg(feature = "tracing")]
+        if !self.redraw {
+            tracing::debug!("redraw");
+        }
         self.redraw = true;
     }
 
@@ -257,10 +379,82 @@ impl<Message> Context<Message> {
         self.rebuild
     }
 
+    /// Request another [`Event::Animate`](../event/enum.Event.html#variant.Animate) on the next frame.
+    /// Animating widgets should call this every time they receive `Event::Animate` until their animation is finished,
+    /// and only call [`redraw`](#method.redraw) when what they draw actually changed.
+    /// When no widget requests animation frames the ui stops sending `Event::Animate`, so it doesn't use any
+    /// resources while idle.
+    pub fn animate(&mut self) {
+        self.animate = true;
+    }
+
+    /// Returns the animate flag.
+    pub fn animation_requested(&self) -> bool {
+        self.animate
+    }
+
+    /// Request that the next cursor move is handled by the widgets, even if it doesn't enter or leave any widget.
+    /// Such cursor moves are normally skipped, since they can't change any hover state. Widgets that react to the
+    /// cursor while it's near them, like [`Proximity`](proximity/struct.Proximity.html), should call this every time
+    /// they handle a cursor move.
+    pub fn track_cursor(&mut self) {
+        self.track_cursor = true;
+    }
+
+    /// Returns the track cursor flag.
+    pub fn cursor_tracking_requested(&self) -> bool {
+        self.track_cursor
+    }
+
     /// Returns the cursor position
     pub fn cursor(&self) -> (f32, f32) {
         self.cursor
     }
+
+    /// Request an icon for the mouse cursor. Widgets should call this while handling an
+    /// [`Event::Cursor`](../event/enum.Event.html#variant.Cursor) that is inside of them. When multiple widgets
+    /// request an icon, the last request wins, so children take precedence over their parents.
+    /// If no widget requests an icon, the [default](../event/enum.CursorIcon.html#variant.Default) icon is used.
+    pub fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        self.cursor_icon = Some(icon);
+    }
+
+    /// Returns the cursor icon that was requested, if any.
+    pub fn cursor_icon_requested(&self) -> Option<CursorIcon> {
+        self.cursor_icon
+    }
+
+    /// Marks the [`Event::Scroll`](../event/enum.Event.html#variant.Scroll) that is being handled as consumed.
+    /// Widgets that scroll using the mouse wheel should call this when they actually scrolled, so the scrolling widgets
+    /// that contain them don't scroll as well.
+    pub fn consume_scroll(&mut self) {
+        self.scroll_consumed = true;
+    }
+
+    /// Returns whether the scroll event that is being handled was consumed by a descendant.
+    pub fn scroll_consumed(&self) -> bool {
+        self.scroll_consumed
+    }
+
+    /// Marks the cursor as being over the content of a [`Portal`](portal/struct.Portal.html), so the widgets that are
+    /// drawn below it don't see the cursor.
+    pub(crate) fn hover_portal(&mut self) {
+        self.portal_hovered = true;
+    }
+
+    /// Returns whether the cursor is over the content of a [`Portal`](portal/struct.Portal.html).
+    pub(crate) fn portal_hovered(&self) -> bool {
+        self.portal_hovered
+    }
+
+    pub(crate) fn set_cursor(&mut self, cursor: (f32, f32)) {
+        self.cursor = cursor;
+    }
+
+    #[cfg(feature = "tracing")]
+    pub(crate) fn messages(&self) -> &[Message] {
+        &self.messages
+    }
 }
 
 impl<Message> IntoIterator for Context<Message> {
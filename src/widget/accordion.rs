@@ -0,0 +1,464 @@
+use std::time::{Duration, Instant};
+
+use crate::draw::Primitive;
+use crate::event::{Event, Key};
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::{StyleState, Stylesheet};
+use crate::widget::{Context, StateVec, Widget};
+
+/// A list of sections that can be expanded and collapsed by clicking on their header.
+/// Child widgets are taken in pairs: the first widget of a pair is the header of a section, the second is it's content.
+///
+/// In exclusive mode, which is the default, expanding a section collapses all other sections.
+/// The content of collapsed sections is not drawn, but it does keep it's state.
+///
+/// Sections slide open and closed over the [`duration`](#method.duration). When switching sections, the collapsing
+/// section shrinks at the same pace as the expanding section grows. Switching again halfway reverses both from where
+/// they are.
+pub struct Accordion<'a, T, F> {
+    sections: Vec<(Node<'a, T>, Option<Node<'a, T>>)>,
+    open: Option<Option<usize>>,
+    exclusive: bool,
+    collapsible: bool,
+    duration: Duration,
+    on_change: F,
+}
+
+/// State for [`Accordion`](struct.Accordion.html)
+#[derive(Default)]
+pub struct State {
+    open: Vec<usize>,
+    hover: Option<usize>,
+    pressed: Option<usize>,
+    // how far each section is expanded, from `0.0` when it's collapsed to `1.0` when it's expanded.
+    expansion: Vec<f32>,
+    last_update: Option<Instant>,
+}
+
+impl<'a, T: 'a, F: 'a + Fn(usize) -> T> Accordion<'a, T, F> {
+    /// Construct a new `Accordion`
+    pub fn new(on_change: F) -> Self {
+        Self {
+            sections: Vec::new(),
+            open: None,
+            exclusive: true,
+            collapsible: true,
+            duration: Duration::from_millis(150),
+            on_change,
+        }
+    }
+
+    /// Adds a section with a header and content.
+    pub fn section(mut self, header: impl IntoNode<'a, T>, content: impl IntoNode<'a, T>) -> Self {
+        self.sections.push((header.into_node(), Some(content.into_node())));
+        self
+    }
+
+    /// Adds child widgets using an iterator. Child widgets are alternately used as header and content.
+    pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T> + 'a>(mut self, iter: I) -> Self {
+        for node in iter {
+            match self.sections.last_mut() {
+                Some((_, content @ None)) => *content = Some(node.into_node()),
+                _ => self.sections.push((node.into_node(), None)),
+            }
+        }
+        self
+    }
+
+    /// Sets the section that is expanded, instead of letting the accordion keep track of it.
+    /// When set, clicking a header only calls the on_change callback and it's up to the caller to update the
+    /// open section.
+    pub fn open(mut self, open: Option<usize>) -> Self {
+        self.open = Some(open);
+        self
+    }
+
+    /// Sets whether expanding a section collapses all other sections. Defaults to `true`.
+    pub fn exclusive(mut self, exclusive: bool) -> Self {
+        self.exclusive = exclusive;
+        self
+    }
+
+    /// Sets whether all sections are allowed to be collapsed at the same time. Defaults to `true`.
+    /// When `false`, the first section is expanded if no other section is.
+    pub fn collapsible(mut self, collapsible: bool) -> Self {
+        self.collapsible = collapsible;
+        self
+    }
+
+    /// Sets the time it takes for a section to expand or collapse. Defaults to 150 milliseconds.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Sets the on_change callback, which is called with the index of a section when it's expanded or collapsed.
+    pub fn on_change<N: Fn(usize) -> T>(self, on_change: N) -> Accordion<'a, T, N> {
+        Accordion {
+            sections: self.sections,
+            open: self.open,
+            exclusive: self.exclusive,
+            collapsible: self.collapsible,
+            duration: self.duration,
+            on_change,
+        }
+    }
+
+    fn node(&self, section: usize, header: bool) -> &Node<'a, T> {
+        match self.sections[section] {
+            (ref node, _) if header => node,
+            (_, ref content) => content.as_ref().unwrap(),
+        }
+    }
+
+    fn node_mut(&mut self, section: usize, header: bool) -> &mut Node<'a, T> {
+        match self.sections[section] {
+            (ref mut node, _) if header => node,
+            (_, ref mut content) => content.as_mut().unwrap(),
+        }
+    }
+
+    fn open_sections(&self, state: &State) -> Vec<usize> {
+        let mut open = match self.open {
+            Some(open) => open.into_iter().collect(),
+            None => state.open.clone(),
+        };
+        open.retain(|&section| section < self.sections.len());
+        if open.is_empty() && !self.collapsible && !self.sections.is_empty() {
+            open.push(0);
+        }
+        open
+    }
+
+    /// Returns how far a section is expanded, eased in and out so sections don't start and stop sliding abruptly.
+    fn expansion(&self, state: &State, open: &[usize], section: usize) -> f32 {
+        let expansion = state
+            .expansion
+            .get(section)
+            .copied()
+            .unwrap_or(if open.contains(&section) { 1.0 } else { 0.0 });
+        expansion * expansion * (3.0 - 2.0 * expansion)
+    }
+
+    /// Returns the section and layout of every visible child widget, whether the child is a header and the part of the
+    /// layout that is shown. Content of sections that are sliding open or closed is only partially shown.
+    fn layout(&self, state: &State, layout: Rectangle, style: &Stylesheet) -> Vec<(usize, bool, Rectangle, Rectangle)> {
+        let layout = style.background.content_rect(layout, style.padding);
+        let open = self.open_sections(state);
+        let visible = self
+            .sections
+            .iter()
+            .enumerate()
+            .flat_map(|(section, (_, content))| {
+                let expansion = self.expansion(state, &open, section);
+                let content = content
+                    .as_ref()
+                    .filter(|_| expansion > 0.0)
+                    .map(|_| (section, false, expansion));
+                std::iter::once((section, true, 1.0)).chain(content)
+            })
+            .collect::<Vec<_>>();
+
+        let heights = visible
+            .iter()
+            .map(|&(section, header, _)| {
+                let node = self.node(section, header);
+                (node.size().1, node.size_limits().1)
+            })
+            .collect::<Vec<_>>();
+        let heights = Size::resolve_stacked_limited(&heights, layout.height(), layout.height());
+
+        let mut cursor = 0.0;
+        visible
+            .into_iter()
+            .zip(heights)
+            .map(|((section, header, expansion), h)| {
+                let node = self.node(section, header);
+                let (w, _) = node.size();
+                let (min, max) = node.size_limits().0;
+                let w = w.resolve(layout.width(), w.parts()).min(max).max(min);
+                let h = h.min(layout.height() - cursor);
+                let x = style.align_horizontal.resolve_start(w, layout.width());
+                let y = cursor;
+                cursor += h * expansion;
+                (
+                    section,
+                    header,
+                    Rectangle::from_xywh(x, y, w, h).translate(layout.left, layout.top),
+                    Rectangle::from_xywh(x, y, w, h * expansion).translate(layout.left, layout.top),
+                )
+            })
+            .collect()
+    }
+
+    fn header_at(&self, state: &State, layout: Rectangle, style: &Stylesheet, x: f32, y: f32) -> Option<usize> {
+        self.layout(state, layout, style)
+            .into_iter()
+            .find(|&(_, header, layout, _)| header && layout.point_inside(x, y))
+            .map(|(section, _, _, _)| section)
+    }
+}
+
+impl<'a, T: 'a> Default for Accordion<'a, T, fn(usize) -> T> {
+    fn default() -> Self {
+        Self {
+            sections: Vec::new(),
+            open: None,
+            exclusive: true,
+            collapsible: true,
+            duration: Duration::from_millis(150),
+            on_change: |_| panic!("on_change of `Accordion` must be set"),
+        }
+    }
+}
+
+impl<'a, T: 'a + Send, F: 'a + Send + Fn(usize) -> T> Widget<'a, T> for Accordion<'a, T, F> {
+    type State = State;
+
+    fn mount(&self) -> State {
+        let mut state = State::default();
+        let open = self.open_sections(&state);
+        state.expansion = (0..self.sections.len())
+            .map(|section| if open.contains(&section) { 1.0 } else { 0.0 })
+            .collect();
+        state
+    }
+
+    fn widget(&self) -> &'static str {
+        "accordion"
+    }
+
+    fn state(&self, state: &State) -> StateVec {
+        let mut result = StateVec::new();
+        if state.hover.is_some() {
+            result.push(StyleState::Hover);
+        }
+        if state.pressed.is_some() {
+            result.push(StyleState::Pressed);
+        }
+        result
+    }
+
+    fn len(&self) -> usize {
+        self.sections.iter().map(|(_, content)| 1 + content.iter().len()).sum()
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        for (header, content) in self.sections.iter_mut() {
+            visitor(&mut **header);
+            if let Some(content) = content {
+                visitor(&mut **content);
+            }
+        }
+    }
+
+    fn size(&self, state: &State, style: &Stylesheet) -> (Size, Size) {
+        let open = &self.open_sections(state);
+        // every visible child with how far it's expanded, so a shrinking accordion grows and shrinks with it's sections.
+        let visible = || {
+            self.sections
+                .iter()
+                .enumerate()
+                .flat_map(|(section, (header, content))| {
+                    let expansion = self.expansion(state, open, section);
+                    let content = content.iter().map(move |content| (content, expansion));
+                    std::iter::once((header, 1.0)).chain(content.filter(|&(_, expansion)| expansion > 0.0))
+                })
+        };
+
+        let width = match style.width {
+            Size::Shrink => Size::Exact(visible().fold(0.0, |size, (child, _)| match child.size().0 {
+                Size::Exact(child_size) => size.max(child_size),
+                _ => size,
+            })),
+            other => other,
+        };
+        let height = match style.height {
+            Size::Shrink => Size::Exact(visible().fold(0.0, |size, (child, expansion)| match child.size().1 {
+                Size::Exact(child_size) => size + child_size * expansion,
+                _ => size,
+            })),
+            other => other,
+        };
+
+        style
+            .background
+            .resolve_size((style.width, style.height), (width, height), style.padding)
+    }
+
+    fn hit(
+        &self,
+        state: &State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        x: f32,
+        y: f32,
+        recursive: bool,
+    ) -> bool {
+        if layout.point_inside(x, y) && clip.point_inside(x, y) {
+            if recursive && !style.background.is_solid() {
+                self.layout(state, layout, style)
+                    .into_iter()
+                    .any(|(section, header, layout, shown)| {
+                        header
+                            || matches!(clip.intersect(&shown), Some(clip) if self.node(section, header).hit(layout, clip, x, y, recursive))
+                    })
+            } else {
+                true
+            }
+        } else {
+            false
+        }
+    }
+
+    fn focused(&self, _: &State) -> bool {
+        self.sections
+            .iter()
+            .any(|(header, content)| header.focused() || matches!(content, Some(content) if content.focused()))
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        let children = self.layout(state, layout, style);
+        let focused = children
+            .iter()
+            .find(|&&(section, header, _, _)| self.node(section, header).focused())
+            .map(|&(section, header, _, _)| (section, header));
+
+        for (section, header, child_layout, shown) in children {
+            if Some((section, header)) == focused {
+                self.node_mut(section, header)
+                    .event(child_layout, clip, event.clone(), context);
+            } else if focused.is_none() {
+                if let Some(clip) = clip.intersect(&shown) {
+                    self.node_mut(section, header)
+                        .event(child_layout, clip, event.clone(), context);
+                }
+            }
+        }
+
+        if let Event::Animate = event {
+            let open = self.open_sections(state);
+            let target = |section: usize| if open.contains(&section) { 1.0 } else { 0.0 };
+            let sections = self.sections.len();
+            if state.expansion.len() < sections {
+                let expanded = state.expansion.len();
+                state.expansion.extend((expanded..sections).map(target));
+            }
+
+            if (0..sections).any(|section| state.expansion[section] != target(section)) {
+                let now = Instant::now();
+                let step = match state.last_update.replace(now) {
+                    _ if self.duration.is_zero() => 1.0,
+                    Some(last_update) => (now - last_update).as_secs_f32() / self.duration.as_secs_f32(),
+                    None => 0.0,
+                };
+
+                // all sections move at the same pace, so a collapsing section makes room as fast as an
+                // expanding section takes it.
+                let mut done = true;
+                for (section, expansion) in state.expansion.iter_mut().enumerate().take(sections) {
+                    let target = target(section);
+                    if (target - *expansion).abs() <= step {
+                        *expansion = target;
+                    } else {
+                        *expansion += step.copysign(target - *expansion);
+                        done = false;
+                    }
+                }
+
+                if done {
+                    state.last_update = None;
+                } else {
+                    context.animate();
+                }
+                // the size of a shrinking accordion follows it's sections, so it has to be laid out again.
+                if style.width == Size::Shrink || style.height == Size::Shrink {
+                    context.rebuild();
+                }
+                context.redraw();
+            }
+        }
+
+        if focused.is_some() {
+            return;
+        }
+
+        match event {
+            Event::Cursor(x, y) => {
+                let hover = if clip.point_inside(x, y) {
+                    self.header_at(state, layout, style, x, y)
+                } else {
+                    None
+                };
+                if hover != state.hover {
+                    state.hover = hover;
+                    context.redraw();
+                }
+            }
+
+            Event::Press(Key::LeftMouseButton) if state.hover.is_some() => {
+                state.pressed = state.hover;
+                context.redraw();
+            }
+
+            Event::Release(Key::LeftMouseButton) => {
+                if let Some(pressed) = state.pressed.take() {
+                    context.redraw();
+                    if state.hover == Some(pressed) {
+                        let mut open = self.open_sections(state);
+                        if let Some(position) = open.iter().position(|&section| section == pressed) {
+                            if self.collapsible || open.len() > 1 {
+                                open.remove(position);
+                            } else {
+                                return;
+                            }
+                        } else if self.exclusive {
+                            open = vec![pressed];
+                        } else {
+                            open.push(pressed);
+                        }
+
+                        state.open = open;
+                        context.rebuild();
+                        context.push((self.on_change)(pressed));
+                    }
+                }
+            }
+
+            _ => (),
+        }
+    }
+
+    fn draw(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+    ) -> Vec<Primitive<'a>> {
+        let mut result = Vec::new();
+        result.extend(style.background.render(layout));
+        for (section, header, child_layout, shown) in self.layout(state, layout, style) {
+            if let Some(clip) = clip.intersect(&shown) {
+                result.extend(self.node_mut(section, header).draw(child_layout, clip));
+            }
+        }
+        result
+    }
+}
+
+impl<'a, T: 'a + Send, F: 'a + Send + Fn(usize) -> T> IntoNode<'a, T> for Accordion<'a, T, F> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
@@ -7,7 +7,7 @@ use crate::event::{Event, Key};
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
 use crate::style::{StyleState, Stylesheet};
-use crate::widget::{Context, StateVec, Widget};
+use crate::widget::{Context, CursorResponse, StateVec, Widget};
 
 /// A clickable button
 pub struct Button<'a, T> {
@@ -106,6 +106,14 @@ impl<'a, T: 'a + Send> Widget<'a, T> for Button<'a, T> {
             .resolve_size((style.width, style.height), self.content().size(), style.padding)
     }
 
+    fn focusable(&self, _: &State) -> bool {
+        true
+    }
+
+    fn cursor_response(&self, _: &State) -> CursorResponse {
+        CursorResponse::Hover
+    }
+
     fn event(
         &mut self,
         state: &mut State,
@@ -0,0 +1,94 @@
+use crate::draw::Primitive;
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::Stylesheet;
+use crate::widget::{CursorResponse, Widget};
+
+/// A widget that draws primitives generated by a closure, for custom drawing such as charts.
+///
+/// The closure is called with the content rect of the canvas every time the canvas is drawn, and the primitives it
+/// returns are drawn on top of the background of the canvas. The primitives are not clipped to the canvas, so
+/// primitives that should stay within the canvas should be wrapped in a
+/// [`Primitive::PushClip`](../../draw/enum.Primitive.html#variant.PushClip). Shapes other than rectangles can be
+/// drawn with [`Primitive::polygon`](../../draw/enum.Primitive.html#method.polygon).
+///
+/// The size of the canvas is taken from the `width` and `height` of the style. If they are `shrink`, the size
+/// returned by the [`measure`](#method.measure) closure is used instead.
+pub struct Canvas<'a> {
+    draw: Box<dyn 'a + Send + Fn(Rectangle) -> Vec<Primitive<'a>>>,
+    measure: Option<Box<dyn 'a + Send + Fn() -> (f32, f32)>>,
+}
+
+impl<'a> Canvas<'a> {
+    /// Construct a new `Canvas` that draws the primitives returned by `draw`.
+    pub fn new(draw: impl 'a + Send + Fn(Rectangle) -> Vec<Primitive<'a>>) -> Self {
+        Self {
+            draw: Box::new(draw),
+            measure: None,
+        }
+    }
+
+    /// Sets the closure that draws the canvas.
+    pub fn draw(mut self, draw: impl 'a + Send + Fn(Rectangle) -> Vec<Primitive<'a>>) -> Self {
+        self.draw = Box::new(draw);
+        self
+    }
+
+    /// Sets a closure that returns the preferred width and height of the content, which is used when the `width` or
+    /// `height` of the canvas is `shrink`.
+    pub fn measure(mut self, measure: impl 'a + Send + Fn() -> (f32, f32)) -> Self {
+        self.measure = Some(Box::new(measure));
+        self
+    }
+}
+
+impl<'a> Default for Canvas<'a> {
+    fn default() -> Self {
+        Self {
+            draw: Box::new(|_| Vec::new()),
+            measure: None,
+        }
+    }
+}
+
+impl<'a, T: 'a> Widget<'a, T> for Canvas<'a> {
+    type State = ();
+
+    fn mount(&self) {}
+
+    fn widget(&self) -> &'static str {
+        "canvas"
+    }
+
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
+    fn len(&self) -> usize {
+        0
+    }
+
+    fn visit_children(&mut self, _: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {}
+
+    fn size(&self, _: &(), style: &Stylesheet) -> (Size, Size) {
+        let (width, height) = self.measure.as_ref().map_or((0.0, 0.0), |measure| measure());
+        style.background.resolve_size(
+            (style.width, style.height),
+            (Size::Exact(width), Size::Exact(height)),
+            style.padding,
+        )
+    }
+
+    fn draw(&mut self, _: &mut (), layout: Rectangle, _: Rectangle, style: &Stylesheet) -> Vec<Primitive<'a>> {
+        let mut result = Vec::new();
+        result.extend(style.background.render(layout));
+        result.extend((self.draw)(style.background.content_rect(layout, style.padding)));
+        result
+    }
+}
+
+impl<'a, T: 'a> IntoNode<'a, T> for Canvas<'a> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
@@ -0,0 +1,237 @@
+use std::mem::replace;
+
+use smallvec::smallvec;
+
+use crate::draw::*;
+use crate::event::{Event, Key};
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::{StyleState, Stylesheet};
+use crate::widget::{pseudo::PseudoElement, Context, CursorResponse, StateVec, Widget};
+
+/// State for [`Checkbox`](struct.Checkbox.html) and [`Radio`](../radio/struct.Radio.html)
+#[allow(missing_docs)]
+pub enum State {
+    Idle,
+    Hover,
+    Pressed,
+}
+
+/// A box with a check mark that toggles some `bool` when clicked.
+/// The box is drawn using the background of the checkbox, and the check mark is drawn in the `color` of the checkbox
+/// inside of the padding. Checked checkboxes can be styled using the `:checked` selector state.
+///
+/// The checkbox supports the `::before` and `::after` pseudo elements, which are drawn on top of the check mark.
+pub struct Checkbox<'a, T, F: Fn(bool) -> T> {
+    checked: bool,
+    on_toggle: F,
+    before: Node<'a, T>,
+    after: Node<'a, T>,
+}
+
+impl<'a, T: 'a, F: Fn(bool) -> T> Checkbox<'a, T, F> {
+    /// Constructs a new `Checkbox`
+    pub fn new(checked: bool, on_toggle: F) -> Self {
+        Self {
+            checked,
+            on_toggle,
+            before: PseudoElement::before().into_node(),
+            after: PseudoElement::after().into_node(),
+        }
+    }
+
+    /// Sets the current checked state of the `Checkbox`.
+    pub fn val(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Sets the on_toggle callback for this `Checkbox`, which is called with the new checked state when it's clicked.
+    pub fn on_toggle<N: Fn(bool) -> T>(self, on_toggle: N) -> Checkbox<'a, T, N> {
+        Checkbox {
+            checked: self.checked,
+            on_toggle,
+            before: self.before,
+            after: self.after,
+        }
+    }
+}
+
+impl<'a, T: 'a> Default for Checkbox<'a, T, fn(bool) -> T> {
+    fn default() -> Self {
+        Self::new(false, |_| panic!("on_toggle of `Checkbox` must be set"))
+    }
+}
+
+impl State {
+    /// Returns the style states of a clickable widget in this state.
+    pub(crate) fn style(&self, checked: bool) -> StateVec {
+        let mut state = match self {
+            State::Idle => StateVec::new(),
+            State::Hover => smallvec![StyleState::Hover],
+            State::Pressed => smallvec![StyleState::Pressed],
+        };
+
+        if checked {
+            state.push(StyleState::Checked);
+        }
+
+        state
+    }
+
+    /// Handles an event for a clickable widget in this state. Returns `true` when the widget is clicked.
+    pub(crate) fn event<T>(
+        &mut self,
+        layout: Rectangle,
+        clip: Rectangle,
+        event: &Event,
+        context: &mut Context<T>,
+    ) -> bool {
+        match *event {
+            Event::Cursor(x, y) => {
+                let inside = layout.point_inside(x, y) && clip.point_inside(x, y);
+                *self = match replace(self, State::Idle) {
+                    State::Idle if inside => {
+                        context.redraw();
+                        State::Hover
+                    }
+                    State::Hover | State::Pressed if !inside => {
+                        context.redraw();
+                        State::Idle
+                    }
+                    other => other,
+                };
+                false
+            }
+
+            Event::Press(Key::LeftMouseButton) => {
+                *self = match replace(self, State::Idle) {
+                    State::Hover => {
+                        context.redraw();
+                        State::Pressed
+                    }
+                    other => other,
+                };
+                false
+            }
+
+            Event::Release(Key::LeftMouseButton) => match replace(self, State::Idle) {
+                State::Pressed => {
+                    context.redraw();
+                    *self = State::Hover;
+                    true
+                }
+                other => {
+                    *self = other;
+                    false
+                }
+            },
+
+            _ => false,
+        }
+    }
+}
+
+impl<'a, T: 'a, F: 'a + Send + Fn(bool) -> T> Widget<'a, T> for Checkbox<'a, T, F> {
+    type State = State;
+
+    fn mount(&self) -> Self::State {
+        State::Idle
+    }
+
+    fn widget(&self) -> &'static str {
+        "checkbox"
+    }
+
+    fn state(&self, state: &State) -> StateVec {
+        state.style(self.checked)
+    }
+
+    fn len(&self) -> usize {
+        2
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut *self.before);
+        visitor(&mut *self.after);
+    }
+
+    fn size(&self, _: &State, stylesheet: &Stylesheet) -> (Size, Size) {
+        (stylesheet.width, stylesheet.height)
+    }
+
+    fn focusable(&self, _: &State) -> bool {
+        true
+    }
+
+    fn cursor_response(&self, _: &State) -> CursorResponse {
+        CursorResponse::Hover
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        _: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        if state.event(layout, clip, &event, context) {
+            context.push((self.on_toggle)(!self.checked));
+        }
+    }
+
+    fn draw(
+        &mut self,
+        _: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        stylesheet: &Stylesheet,
+    ) -> Vec<Primitive<'a>> {
+        let mut result = Vec::new();
+        result.extend(stylesheet.background.render(layout));
+
+        if self.checked {
+            let content = stylesheet.background.content_rect(layout, stylesheet.padding);
+            let point = |x: f32, y: f32| [content.left + content.width() * x, content.top + content.height() * y];
+            let thickness = (content.width().min(content.height()) * 0.15).max(1.5);
+            let (a, b, c) = (point(0.1, 0.5), point(0.4, 0.8), point(0.9, 0.2));
+            result.extend(stroke(a, b, thickness, stylesheet.color));
+            result.extend(stroke(b, c, thickness, stylesheet.color));
+        }
+
+        let before = PseudoElement::layout(&self.before, layout, stylesheet);
+        let after = PseudoElement::layout(&self.after, layout, stylesheet);
+        result.extend(self.before.draw(before, clip));
+        result.extend(self.after.draw(after, clip));
+        result
+    }
+}
+
+impl<'a, T: 'a + Send, F: 'a + Send + Fn(bool) -> T> IntoNode<'a, T> for Checkbox<'a, T, F> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
+
+/// A line from `a` to `b` with square caps, made out of two triangles.
+fn stroke<'a>(a: [f32; 2], b: [f32; 2], thickness: f32, color: Color) -> Vec<Primitive<'a>> {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+    // extend the line by half the thickness on both ends, so the two strokes of the check mark join nicely.
+    let (ux, uy) = (dx / length * thickness * 0.5, dy / length * thickness * 0.5);
+    let (nx, ny) = (-uy, ux);
+    let a = [a[0] - ux, a[1] - uy];
+    let b = [b[0] + ux, b[1] + uy];
+    let corners = [
+        [a[0] + nx, a[1] + ny],
+        [b[0] + nx, b[1] + ny],
+        [b[0] - nx, b[1] - ny],
+        [a[0] - nx, a[1] - ny],
+    ];
+    vec![
+        Primitive::DrawTriangle([corners[0], corners[1], corners[2]], color),
+        Primitive::DrawTriangle([corners[0], corners[2], corners[3]], color),
+    ]
+}
@@ -5,11 +5,11 @@ use crate::event::Event;
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
 use crate::style::Stylesheet;
-use crate::widget::Context;
+use crate::widget::{Context, CursorResponse};
 
 use super::Widget;
 
-/// Layout child widgets vertically
+/// Layout child widgets vertically. The gap between the children can be set with the `spacing` style property.
 pub struct Column<'a, T> {
     children: Vec<Node<'a, T>>,
     layout: Vec<Rectangle>,
@@ -50,22 +50,28 @@ impl<'a, T: 'a> Column<'a, T> {
         let layout = style.background.content_rect(layout, style.padding);
         if self.layout.len() != self.children.len() {
             let align = style.align_horizontal;
-            let available_parts = self.children.iter().map(|c| c.size().1.parts()).sum();
-            let available_space = layout.height() - self.children.iter().map(|c| c.size().1.min_size()).sum::<f32>();
+            let spacing = style.spacing;
+            let gaps = spacing * self.children.len().saturating_sub(1) as f32;
+            let heights = self
+                .children
+                .iter()
+                .map(|c| (c.size().1, c.size_limits().1))
+                .collect::<Vec<_>>();
+            let heights = Size::resolve_stacked_limited(&heights, layout.height(), layout.height() - gaps);
             let mut cursor = 0.0;
             self.layout = self
                 .children
                 .iter()
-                .map(|child| {
-                    let (w, h) = child.size();
-                    let w = w.resolve(layout.width(), w.parts());
-                    let h = h
-                        .resolve(available_space, available_parts)
-                        .min(layout.height() - cursor);
+                .zip(heights)
+                .map(|(child, h)| {
+                    let (w, _) = child.size();
+                    let (min, max) = child.size_limits().0;
+                    let w = w.resolve(layout.width(), w.parts()).min(max).max(min);
+                    let h = h.min((layout.height() - cursor).max(0.0));
                     let x = align.resolve_start(w, layout.width());
                     let y = cursor;
 
-                    cursor += h;
+                    cursor += h + spacing;
                     Rectangle::from_xywh(x, y, w, h)
                 })
                 .collect();
@@ -102,6 +108,10 @@ impl<'a, T: 'a + Send> Widget<'a, T> for Column<'a, T> {
         "column"
     }
 
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
     fn len(&self) -> usize {
         self.children.len()
     }
@@ -119,10 +129,13 @@ impl<'a, T: 'a + Send> Widget<'a, T> for Column<'a, T> {
             other => other,
         };
         let height = match style.height {
-            Size::Shrink => Size::Exact(self.children.iter().fold(0.0, |size, child| match child.size().1 {
-                Size::Exact(child_size) => size + child_size,
-                _ => size,
-            })),
+            Size::Shrink => Size::Exact(self.children.iter().fold(
+                style.spacing * self.children.len().saturating_sub(1) as f32,
+                |size, child| match child.size().1 {
+                    Size::Exact(child_size) => size + child_size,
+                    _ => size,
+                },
+            )),
             other => other,
         };
 
@@ -170,10 +183,10 @@ impl<'a, T: 'a + Send> Widget<'a, T> for Column<'a, T> {
 
         for (index, (child, layout)) in self.layout_mut(layout, stylesheet).enumerate() {
             if Some(index) == focused {
-                child.event(layout, clip, event, context);
+                child.event(layout, clip, event.clone(), context);
             } else if focused.is_none() {
                 if let Some(clip) = clip.intersect(&layout) {
-                    child.event(layout, clip, event, context);
+                    child.event(layout, clip, event.clone(), context);
                 }
             }
         }
@@ -8,6 +8,11 @@ use crate::style::{StyleState, Stylesheet};
 use crate::widget::{Context, StateVec, Widget};
 
 /// Pick an item from a dropdown box
+///
+/// The dropdown opens when it's clicked, after which the items are shown in a list below the dropdown that overlaps
+/// the widgets that follow it. While the dropdown is open, the up and down keys move the highlighted item, enter
+/// selects the highlighted item and escape closes the dropdown without changing the selection. The dropdown has the
+/// `:open` state while it's open.
 pub struct Dropdown<'a, T, F> {
     items: Vec<Node<'a, T>>,
     default_selection: Option<usize>,
@@ -27,6 +32,35 @@ enum InnerState {
     Pressed { scroll: f32, hover_item: usize },
 }
 
+impl<'a, T: 'a, F: Fn(usize) -> T> Dropdown<'a, T, F> {
+    /// Construct a new `Dropdown` from it's items, the index of the item that is initially selected and a callback
+    /// that is called with the index of the item when an item is selected.
+    pub fn new<I: IntoNode<'a, T>>(
+        items: impl IntoIterator<Item = I>,
+        selected_index: Option<usize>,
+        on_select: F,
+    ) -> Self {
+        Self {
+            items: items.into_iter().map(IntoNode::into_node).collect(),
+            default_selection: selected_index,
+            on_select,
+        }
+    }
+}
+
+impl<'a, T: 'a> Dropdown<'a, T, fn(usize) -> T> {
+    /// Construct a new `Dropdown` from labeled values. The labels are shown as the items of the dropdown, and
+    /// `on_select` is called with a clone of the value of the item when an item is selected.
+    pub fn with_values<V: Clone, G: Fn(V) -> T>(
+        options: Vec<(String, V)>,
+        selected_index: Option<usize>,
+        on_select: G,
+    ) -> Dropdown<'a, T, impl Fn(usize) -> T> {
+        let (labels, values): (Vec<String>, Vec<V>) = options.into_iter().unzip();
+        Dropdown::new(labels, selected_index, move |index| on_select(values[index].clone()))
+    }
+}
+
 impl<'a, T: 'a, F> Dropdown<'a, T, F> {
     /// Set the default selected item
     pub fn default_selection(mut self, item_index: usize) -> Self {
@@ -71,7 +105,10 @@ impl<'a, T: Send + 'a, F: Send + Fn(usize) -> T> Widget<'a, T> for Dropdown<'a,
 
     fn mount(&self) -> Self::State {
         State {
-            selected_item: self.default_selection.map(|i| i.min(self.items.len() - 1)),
+            selected_item: self
+                .default_selection
+                .filter(|_| !self.items.is_empty())
+                .map(|i| i.min(self.items.len() - 1)),
             ..Default::default()
         }
     }
@@ -127,6 +164,10 @@ impl<'a, T: Send + 'a, F: Send + Fn(usize) -> T> Widget<'a, T> for Dropdown<'a,
         matches!(state.inner, InnerState::Open { .. } | InnerState::Pressed { .. })
     }
 
+    fn focusable(&self, _: &State) -> bool {
+        true
+    }
+
     fn event(
         &mut self,
         state: &mut State,
@@ -195,11 +236,11 @@ impl<'a, T: Send + 'a, F: Send + Fn(usize) -> T> Widget<'a, T> for Dropdown<'a,
             }
 
             (Event::Press(Key::LeftMouseButton), InnerState::Idle) => {
-                if state.hovered {
+                if state.hovered && !self.items.is_empty() {
                     context.redraw();
                     InnerState::Open {
                         scroll: 0.0,
-                        hover_item: 0,
+                        hover_item: state.selected_item.unwrap_or(0),
                     }
                 } else {
                     InnerState::Idle
@@ -222,6 +263,34 @@ impl<'a, T: Send + 'a, F: Send + Fn(usize) -> T> Widget<'a, T> for Dropdown<'a,
                 InnerState::Idle
             }
 
+            (Event::Press(Key::Up), InnerState::Open { scroll, hover_item }) => {
+                context.redraw();
+                InnerState::Open {
+                    scroll,
+                    hover_item: hover_item.saturating_sub(1),
+                }
+            }
+
+            (Event::Press(Key::Down), InnerState::Open { scroll, hover_item }) => {
+                context.redraw();
+                InnerState::Open {
+                    scroll,
+                    hover_item: (hover_item + 1).min(self.items.len() - 1),
+                }
+            }
+
+            (Event::Press(Key::Enter), InnerState::Open { hover_item, .. }) => {
+                context.redraw();
+                state.selected_item.replace(hover_item);
+                context.push((self.on_select)(hover_item));
+                InnerState::Idle
+            }
+
+            (Event::Press(Key::Escape), InnerState::Open { .. } | InnerState::Pressed { .. }) => {
+                context.redraw();
+                InnerState::Idle
+            }
+
             (_, state) => state,
         };
     }
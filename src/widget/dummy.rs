@@ -2,7 +2,7 @@ use crate::draw::Primitive;
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
 use crate::style::Stylesheet;
-use crate::widget::Widget;
+use crate::widget::{CursorResponse, Widget};
 
 /// Dummy widget that has a custom widget name
 pub struct Dummy {
@@ -37,6 +37,10 @@ impl<'a, T: 'a> Widget<'a, T> for Dummy {
         self.widget
     }
 
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
     fn len(&self) -> usize {
         0
     }
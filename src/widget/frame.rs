@@ -49,6 +49,10 @@ impl<'a, T: 'a> Widget<'a, T> for Frame<'a, T> {
         "frame"
     }
 
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
     fn len(&self) -> usize {
         1
     }
@@ -0,0 +1,347 @@
+use std::hash::{Hash, Hasher};
+
+use crate::draw::Primitive;
+use crate::event::Event;
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::Stylesheet;
+use crate::widget::{Context, CursorResponse};
+
+use super::Widget;
+
+/// Layout child widgets in a grid with a fixed number of columns.
+///
+/// Children are placed row by row, from left to right. The width of each column is determined by it's
+/// [`Size`](../../layout/enum.Size.html): `shrink` columns fit the widest child in the column, `exact` and `%` columns
+/// have a fixed width and `fill` columns divide the space that's left. Rows fit the tallest child in the row. The gap
+/// between rows and columns is taken from the `spacing` style property, unless it's overridden for either direction
+/// using [`row_spacing`](#method.row_spacing) or [`column_spacing`](#method.column_spacing). Children that are smaller
+/// than their cell are aligned using `align-horizontal` and `align-vertical`.
+///
+/// A settings form with labels on the left and inputs that fill the rest of the width:
+/// ```
+/// # use pixel_widgets::prelude::*;
+/// # use pixel_widgets::layout::Size;
+/// # struct Settings;
+/// # impl Component for Settings {
+/// #     type State = (String, String);
+/// #     type Message = (usize, String);
+/// #     type Output = ();
+/// #     fn mount(&self, _: &mut Runtime<(usize, String)>) -> Self::State {
+/// #         (String::new(), String::new())
+/// #     }
+/// fn view<'a>(&'a self, state: &'a Self::State) -> Node<'a, Self::Message> {
+///     view! {
+///         Grid { columns: [Size::Shrink, Size::Fill(1)] } => {
+///             Text { val: "Name" }
+///             Input { val: state.0.as_str(), on_change: |name| (0, name) }
+///             Text { val: "Email" }
+///             Input { val: state.1.as_str(), on_change: |email| (1, email) }
+///         }
+///     }
+/// }
+/// # }
+/// ```
+pub struct Grid<'a, T> {
+    children: Vec<Node<'a, T>>,
+    columns: Vec<Size>,
+    row_spacing: Option<f32>,
+    column_spacing: Option<f32>,
+    layout: Vec<Rectangle>,
+}
+
+impl<'a, T: 'a> Grid<'a, T> {
+    /// Construct a new Grid with a single `shrink` column
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the number of columns. All columns will fit the widest child in the column.
+    pub fn column_count(mut self, count: usize) -> Self {
+        self.columns = vec![Size::Shrink; count.max(1)];
+        self
+    }
+
+    /// Sets the number of columns and the width of each column.
+    pub fn columns(mut self, columns: impl IntoIterator<Item = Size>) -> Self {
+        self.columns = columns.into_iter().collect();
+        if self.columns.is_empty() {
+            self.columns.push(Size::Shrink);
+        }
+        self
+    }
+
+    /// Sets the vertical gap between rows, overriding the `spacing` style property.
+    pub fn row_spacing(mut self, spacing: f32) -> Self {
+        self.row_spacing = Some(spacing);
+        self
+    }
+
+    /// Sets the horizontal gap between columns, overriding the `spacing` style property.
+    pub fn column_spacing(mut self, spacing: f32) -> Self {
+        self.column_spacing = Some(spacing);
+        self
+    }
+
+    /// Adds a child widget to the grid
+    pub fn push<I: IntoNode<'a, T> + 'a>(mut self, item: I) -> Self {
+        self.children.push(item.into_node());
+        self
+    }
+
+    /// Adds child widgets using an iterator
+    pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T> + 'a>(mut self, iter: I) -> Self {
+        self.children.extend(iter.into_iter().map(IntoNode::into_node));
+        self
+    }
+
+    /// Returns the gap between columns and the gap between rows.
+    fn spacing(&self, style: &Stylesheet) -> (f32, f32) {
+        (
+            self.column_spacing.unwrap_or(style.spacing),
+            self.row_spacing.unwrap_or(style.spacing),
+        )
+    }
+
+    /// Returns the width of the widest `exact` sized child of each column.
+    fn content_widths(&self) -> Vec<f32> {
+        let mut widths = vec![0.0f32; self.columns.len()];
+        for (index, child) in self.children.iter().enumerate() {
+            let column = index % self.columns.len();
+            widths[column] = widths[column].max(child.size().0.min_size());
+        }
+        widths
+    }
+
+    /// Returns the height of the tallest `exact` sized child of each row.
+    fn content_heights(&self) -> Vec<f32> {
+        self.children
+            .chunks(self.columns.len())
+            .map(|row| {
+                row.iter()
+                    .fold(0.0f32, |height, child| height.max(child.size().1.min_size()))
+            })
+            .collect()
+    }
+
+    /// Resolves the width of each column within the content `width`.
+    fn column_widths(&self, width: f32, spacing: f32) -> Vec<f32> {
+        let content = self.content_widths();
+        let gaps = spacing * (self.columns.len() - 1) as f32;
+        let available_parts = self.columns.iter().map(Size::parts).sum();
+        let reserved = self
+            .columns
+            .iter()
+            .zip(content.iter())
+            .map(|(size, &content)| match size {
+                Size::Shrink => content,
+                other => other.reserved_size(width),
+            });
+        let available_space = width - gaps - reserved.sum::<f32>();
+        self.columns
+            .iter()
+            .zip(content)
+            .map(|(size, content)| match size {
+                Size::Shrink => content,
+                other => other.resolve_stacked(width, available_space, available_parts),
+            })
+            .collect()
+    }
+
+    fn layout(&self, layout: Rectangle, style: &Stylesheet) -> impl Iterator<Item = (&Node<'a, T>, Rectangle)> {
+        let layout = style.background.content_rect(layout, style.padding);
+        self.children.iter().zip(
+            self.layout
+                .iter()
+                .map(move |relative| relative.translate(layout.left, layout.top)),
+        )
+    }
+
+    fn layout_mut(
+        &mut self,
+        layout: Rectangle,
+        style: &Stylesheet,
+    ) -> impl Iterator<Item = (&mut Node<'a, T>, Rectangle)> {
+        let layout = style.background.content_rect(layout, style.padding);
+        if self.layout.len() != self.children.len() {
+            let (column_spacing, row_spacing) = self.spacing(style);
+            let widths = self.column_widths(layout.width(), column_spacing);
+            let heights = self.content_heights();
+
+            let mut x = 0.0;
+            let mut y = 0.0;
+            self.layout = self
+                .children
+                .iter()
+                .enumerate()
+                .map(|(index, child)| {
+                    let column = index % widths.len();
+                    let cell_width = widths[column];
+                    let cell_height = heights[index / widths.len()];
+                    if column == 0 {
+                        x = 0.0;
+                    }
+
+                    let (w, h) = child.size();
+                    let w = w.resolve(cell_width, w.parts()).min(cell_width);
+                    let h = h.resolve(cell_height, h.parts()).min(cell_height);
+                    let rect = Rectangle::from_xywh(
+                        x + style.align_horizontal.resolve_start(w, cell_width),
+                        y + style.align_vertical.resolve_start(h, cell_height),
+                        w,
+                        h,
+                    );
+
+                    x += cell_width + column_spacing;
+                    if column + 1 == widths.len() {
+                        y += cell_height + row_spacing;
+                    }
+                    rect
+                })
+                .collect();
+        }
+        self.children.iter_mut().zip(
+            self.layout
+                .iter()
+                .map(move |relative| relative.translate(layout.left, layout.top)),
+        )
+    }
+}
+
+impl<'a, T: 'a> Default for Grid<'a, T> {
+    fn default() -> Self {
+        Self {
+            children: Vec::new(),
+            columns: vec![Size::Shrink],
+            row_spacing: None,
+            column_spacing: None,
+            layout: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T> Hash for Grid<'a, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        "grid".hash(state)
+    }
+}
+
+impl<'a, T: 'a + Send> Widget<'a, T> for Grid<'a, T> {
+    type State = ();
+
+    fn mount(&self) {}
+
+    fn widget(&self) -> &'static str {
+        "grid"
+    }
+
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
+    fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        self.children.iter_mut().for_each(|child| visitor(&mut **child));
+    }
+
+    fn size(&self, _: &(), style: &Stylesheet) -> (Size, Size) {
+        let (column_spacing, row_spacing) = self.spacing(style);
+        let width = match style.width {
+            Size::Shrink => {
+                let gaps = column_spacing * (self.columns.len() - 1) as f32;
+                let columns = self.columns.iter().zip(self.content_widths());
+                Size::Exact(columns.fold(gaps, |width, (size, content)| match size {
+                    Size::Exact(size) => width + size,
+                    _ => width + content,
+                }))
+            }
+            other => other,
+        };
+        let height = match style.height {
+            Size::Shrink => {
+                let heights = self.content_heights();
+                let gaps = row_spacing * heights.len().saturating_sub(1) as f32;
+                Size::Exact(heights.into_iter().fold(gaps, |height, row| height + row))
+            }
+            other => other,
+        };
+
+        style
+            .background
+            .resolve_size((style.width, style.height), (width, height), style.padding)
+    }
+
+    fn hit(
+        &self,
+        _state: &Self::State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        x: f32,
+        y: f32,
+        recursive: bool,
+    ) -> bool {
+        if layout.point_inside(x, y) && clip.point_inside(x, y) {
+            if recursive && !style.background.is_solid() {
+                self.layout(layout, style)
+                    .any(|(child, layout)| child.hit(layout, clip, x, y, recursive))
+            } else {
+                true
+            }
+        } else {
+            false
+        }
+    }
+
+    fn focused(&self, _: &()) -> bool {
+        self.children.iter().any(|child| child.focused())
+    }
+
+    fn event(
+        &mut self,
+        _: &mut (),
+        layout: Rectangle,
+        clip: Rectangle,
+        stylesheet: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        let focused = self.children.iter().position(|child| child.focused());
+
+        for (index, (child, layout)) in self.layout_mut(layout, stylesheet).enumerate() {
+            if Some(index) == focused {
+                child.event(layout, clip, event.clone(), context);
+            } else if focused.is_none() {
+                if let Some(clip) = clip.intersect(&layout) {
+                    child.event(layout, clip, event.clone(), context);
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, _: &mut (), layout: Rectangle, clip: Rectangle, stylesheet: &Stylesheet) -> Vec<Primitive<'a>> {
+        let mut result = Vec::new();
+
+        result.extend(stylesheet.background.render(layout));
+
+        result = self
+            .layout_mut(layout, stylesheet)
+            .fold(result, |mut result, (child, layout)| {
+                if let Some(clip) = clip.intersect(&layout) {
+                    result.extend(child.draw(layout, clip));
+                }
+                result
+            });
+
+        result
+    }
+}
+
+impl<'a, T: 'a + Send> IntoNode<'a, T> for Grid<'a, T> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
@@ -1,35 +1,175 @@
 pub use crate::draw::ImageData;
-use crate::draw::Primitive;
+use crate::draw::{Color, Primitive};
+use crate::event::Event;
+use crate::graphics::ImageHandle;
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
 use crate::style::Stylesheet;
-use crate::widget::Widget;
-use std::marker::PhantomData;
+use crate::widget::{dummy::Dummy, Context, CursorResponse, Widget};
 
 /// A widget that display an image.
-pub struct Image<'a>(*const ImageData, PhantomData<&'a ()>);
+///
+/// Images that are loaded asynchronously, for example using
+/// [`Runtime::wait`](../../prelude/struct.Runtime.html#method.wait), can be passed to the `Image` using
+/// [`load`](#method.load), or using [`handle`](#method.handle) for images loaded with
+/// [`Graphics::load_image_async`](../../graphics/struct.Graphics.html#method.load_image_async). While the image is not loaded yet, the `placeholder` widget is shown in place of the image.
+/// If loading the image failed, the `error` widget is shown instead. Both widgets can be styled as children of the
+/// image, or replaced using the [`placeholder`](#method.placeholder) and [`error`](#method.error) methods.
+///
+/// To prevent the layout from jumping when the image is loaded, the size of the image can be reserved up front using
+/// [`size`](#method.size). Otherwise the size of the placeholder or error widget is used until the image is loaded.
+///
+/// By default the image is stretched to the layout rect of the widget. Use [`fit`](#method.fit) to preserve the aspect
+/// ratio of the image instead.
+///
+/// A part of the image, such as a single icon from a sprite sheet, can be displayed using
+/// [`source_rect`](#method.source_rect). The image is multiplied with the `color` of the style, or with the color set
+/// using [`tint`](#method.tint).
+pub struct Image<'a, T> {
+    content: Content,
+    size: Option<(f32, f32)>,
+    fit: ImageFit,
+    source_rect: Option<Rectangle>,
+    tint: Option<Color>,
+    placeholder: Node<'a, T>,
+    error: Node<'a, T>,
+}
+
+/// How an [`Image`](struct.Image.html) is scaled to fit it's layout rect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFit {
+    /// Stretch the image to fill the layout rect, ignoring the aspect ratio of the image.
+    Stretch,
+    /// Scale the image to fit entirely within the layout rect, preserving it's aspect ratio.
+    /// The image is centered in the layout rect.
+    Contain,
+    /// Scale the image to fill the layout rect entirely, preserving it's aspect ratio.
+    /// The image is centered in the layout rect, and the parts that don't fit are cut off.
+    Cover,
+    /// Draw the image at it's own size, centered in the layout rect. The parts that don't fit are cut off.
+    None,
+}
 
-impl<'a> Image<'a> {
+enum Content {
+    Pending,
+    Loaded(*const ImageData),
+    Failed,
+}
+
+impl<'a, T: 'a> Image<'a, T> {
     /// Sets the image to be displayed.
     pub fn image(mut self, image: &'a ImageData) -> Self {
-        self.0 = image as _;
+        self.content = Content::Loaded(image as _);
+        self
+    }
+
+    /// Sets the image to be displayed from the result of loading it, where `None` means the image is still loading.
+    pub fn load<E>(mut self, image: Option<&'a Result<ImageData, E>>) -> Self {
+        self.content = match image {
+            None => Content::Pending,
+            Some(Ok(image)) => Content::Loaded(image as _),
+            Some(Err(_)) => Content::Failed,
+        };
+        self
+    }
+
+    /// Sets the image to be displayed from a handle that is loaded using
+    /// [`Graphics::load_image_async`](../../graphics/struct.Graphics.html#method.load_image_async).
+    pub fn handle(mut self, handle: &'a ImageHandle) -> Self {
+        self.content = match handle.get() {
+            Some(image) => Content::Loaded(image as _),
+            None if handle.is_failed() => Content::Failed,
+            None => Content::Pending,
+        };
+        self
+    }
+
+    /// Marks the image as failed to load, so the error widget is shown.
+    pub fn failed(mut self) -> Self {
+        self.content = Content::Failed;
+        self
+    }
+
+    /// Reserves a size for the image, which is used when the `width` or `height` of the image is `shrink`,
+    /// regardless of whether the image is loaded yet.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Sets how the image is scaled to fit the layout rect. Defaults to [`ImageFit::Stretch`](enum.ImageFit.html).
+    pub fn fit(mut self, fit: ImageFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Only displays a region of the image, defined in pixels relative to the top left corner of the image.
+    /// The size of the region is used as the size of the image.
+    pub fn source_rect(mut self, source_rect: Rectangle) -> Self {
+        self.source_rect = Some(source_rect);
         self
     }
 
-    fn content(&self) -> &ImageData {
-        unsafe { self.0.as_ref().expect("image of `Image` must be set") }
+    /// Sets the color that the image is multiplied with. Defaults to the `color` of the style.
+    pub fn tint(mut self, tint: Color) -> Self {
+        self.tint = Some(tint);
+        self
+    }
+
+    /// Sets the widget that is shown while the image is loading.
+    pub fn placeholder(mut self, placeholder: impl IntoNode<'a, T>) -> Self {
+        self.placeholder = placeholder.into_node();
+        self
+    }
+
+    /// Sets the widget that is shown when the image failed to load.
+    pub fn error(mut self, error: impl IntoNode<'a, T>) -> Self {
+        self.error = error.into_node();
+        self
+    }
+
+    fn loaded(&self) -> Option<&ImageData> {
+        match self.content {
+            Content::Loaded(image) => unsafe { image.as_ref() },
+            _ => None,
+        }
+    }
+
+    /// The loaded image, cut to the source rect.
+    fn source(&self) -> Option<ImageData> {
+        self.loaded().map(|image| match self.source_rect {
+            Some(source_rect) => image.sub(source_rect),
+            None => image.clone(),
+        })
+    }
+
+    /// The widget that is shown in place of the image, if the image is not loaded.
+    fn fallback(&mut self) -> Option<&mut Node<'a, T>> {
+        match self.content {
+            Content::Pending => Some(&mut self.placeholder),
+            Content::Loaded(_) => None,
+            Content::Failed => Some(&mut self.error),
+        }
     }
 }
 
-impl<'a> Default for Image<'a> {
+impl<'a, T: 'a> Default for Image<'a, T> {
     fn default() -> Self {
-        Self(std::ptr::null(), PhantomData)
+        Self {
+            content: Content::Pending,
+            size: None,
+            fit: ImageFit::Stretch,
+            source_rect: None,
+            tint: None,
+            placeholder: Dummy::new("placeholder").into_node(),
+            error: Dummy::new("error").into_node(),
+        }
     }
 }
 
-unsafe impl<'a> Send for Image<'a> {}
+unsafe impl<'a, T> Send for Image<'a, T> {}
 
-impl<'a, T: 'a> Widget<'a, T> for Image<'a> {
+impl<'a, T: 'a> Widget<'a, T> for Image<'a, T> {
     type State = ();
 
     fn mount(&self) {}
@@ -38,31 +178,109 @@ impl<'a, T: 'a> Widget<'a, T> for Image<'a> {
         "image"
     }
 
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
     fn len(&self) -> usize {
-        0
+        2
     }
 
-    fn visit_children(&mut self, _: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {}
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut *self.placeholder);
+        visitor(&mut *self.error);
+    }
 
     fn size(&self, _: &(), style: &Stylesheet) -> (Size, Size) {
+        let (fallback_width, fallback_height) = match self.content {
+            Content::Pending => self.placeholder.size(),
+            Content::Loaded(_) => (Size::Shrink, Size::Shrink),
+            Content::Failed => self.error.size(),
+        };
+        let intrinsic = self.size.or_else(|| {
+            self.source_rect
+                .or_else(|| self.loaded().map(|image| image.size))
+                .map(|size| (size.width(), size.height()))
+        });
+
         let width = match style.width {
-            Size::Shrink => Size::Exact(self.content().size.width()),
+            Size::Shrink => intrinsic.map_or(fallback_width, |(width, _)| Size::Exact(width)),
             other => other,
         };
         let height = match style.height {
-            Size::Shrink => Size::Exact(self.content().size.height()),
+            Size::Shrink => intrinsic.map_or(fallback_height, |(_, height)| Size::Exact(height)),
             other => other,
         };
         (width, height)
     }
 
-    fn draw(&mut self, _: &mut (), layout: Rectangle, _: Rectangle, style: &Stylesheet) -> Vec<Primitive<'a>> {
-        vec![Primitive::DrawImage(self.content().clone(), layout, style.color)]
+    fn event(
+        &mut self,
+        _: &mut (),
+        layout: Rectangle,
+        clip: Rectangle,
+        _: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        if let Some(fallback) = self.fallback() {
+            fallback.event(layout, clip, event, context);
+        }
+    }
+
+    fn draw(&mut self, _: &mut (), layout: Rectangle, clip: Rectangle, style: &Stylesheet) -> Vec<Primitive<'a>> {
+        match self.source() {
+            Some(image) => fit_image(&image, layout, self.fit)
+                .map(|(image, rect)| Primitive::DrawImage(image, rect, self.tint.unwrap_or(style.color)))
+                .into_iter()
+                .collect(),
+            None => self
+                .fallback()
+                .map(|fallback| fallback.draw(layout, clip))
+                .unwrap_or_default(),
+        }
     }
 }
 
-impl<'a, T: 'a> IntoNode<'a, T> for Image<'a> {
+impl<'a, T: 'a> IntoNode<'a, T> for Image<'a, T> {
     fn into_node(self) -> Node<'a, T> {
         Node::from_widget(self)
     }
 }
+
+/// Computes the rect to draw an image in, and the image with it's texcoords cropped to the part of the image that is
+/// visible within `layout`.
+fn fit_image(image: &ImageData, layout: Rectangle, fit: ImageFit) -> Option<(ImageData, Rectangle)> {
+    let (image_width, image_height) = (image.size.width(), image.size.height());
+    if fit == ImageFit::Stretch || image_width <= 0.0 || image_height <= 0.0 {
+        return Some((image.clone(), layout));
+    }
+
+    let scale = match fit {
+        ImageFit::Contain => (layout.width() / image_width).min(layout.height() / image_height),
+        ImageFit::Cover => (layout.width() / image_width).max(layout.height() / image_height),
+        _ => 1.0,
+    };
+    let (width, height) = (image_width * scale, image_height * scale);
+    let rect = Rectangle::from_xywh(
+        layout.left + (layout.width() - width) * 0.5,
+        layout.top + (layout.height() - height) * 0.5,
+        width,
+        height,
+    );
+
+    let visible = rect.intersect(&layout)?;
+    let texcoords = image.texcoords;
+    let u = |x: f32| texcoords.left + (x - rect.left) / width * texcoords.width();
+    let v = |y: f32| texcoords.top + (y - rect.top) / height * texcoords.height();
+    let cropped = ImageData {
+        texcoords: Rectangle {
+            left: u(visible.left),
+            top: v(visible.top),
+            right: u(visible.right),
+            bottom: v(visible.bottom),
+        },
+        ..image.clone()
+    };
+    Some((cropped, visible))
+}
@@ -1,12 +1,12 @@
 use std::borrow::Cow;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "clipboard")]
 use clipboard::{ClipboardContext, ClipboardProvider};
 use smallvec::smallvec;
 
 use crate::draw::*;
-use crate::event::{Event, Key, Modifiers};
+use crate::event::{CursorIcon, Event, Key, Modifiers};
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
 use crate::style::{StyleState, Stylesheet};
@@ -24,13 +24,25 @@ const FORWARD_DELETE: char = '\x08';
 #[cfg(not(target_os = "macos"))]
 const FORWARD_DELETE: char = '\x7f';
 
+/// The time it takes a floating placeholder to move between the field and the label position.
+const FLOAT_DURATION: Duration = Duration::from_millis(150);
+/// The size of a floating placeholder in the label position, relative to the text size of the input.
+const FLOAT_SCALE: f32 = 0.75;
+
 /// State for [`Input`](struct.Input.html)
 pub struct State {
     scroll_x: f32,
     scroll_y: f32,
     modifiers: Modifiers,
     inner: InnerState,
+    extra_selections: Vec<(usize, usize)>,
+    // the corner where an alt+drag block selection started and the number of extra selections from before it.
+    block: Option<((f32, f32), usize)>,
     cursor: (f32, f32),
+    caret_visible: bool,
+    // whether the placeholder floats up, the progress it started from and when it started.
+    float: Option<(bool, f32, Instant)>,
+    float_drawn: f32,
 }
 
 #[derive(Clone, Copy)]
@@ -41,13 +53,32 @@ enum InnerState {
 }
 
 /// Editable text input
+///
+/// Holding ctrl (alt on macos) while pressing left or right moves the caret by words, and together with backspace or
+/// delete removes the previous or next word. Words are separated by whitespace and punctuation.
+///
+/// Holding alt while clicking adds an extra caret, and holding alt while dragging adds a block selection: every line
+/// between the press and the cursor is selected between the columns of both. Edits are applied to all carets at once,
+/// overlapping selections are merged and copying concatenates the selected fragments from left to right.
+/// Pressing escape collapses back to a single caret.
+///
+/// The input loses focus when the user clicks outside of it, presses tab or submits using the enter key. Losing the
+/// focus of the window only unfocuses the input if [`blur_with_window`](#method.blur_with_window) is set.
+/// While the input has focus it can be styled using the `:focused` selector state.
 pub struct Input<'a, T, F, S> {
     placeholder: &'a str,
     password: bool,
     value: S,
     on_change: F,
     on_submit: Option<T>,
+    on_blur: Option<Box<dyn 'a + Send + Fn(String) -> T>>,
+    blur_with_window: bool,
+    multiline: bool,
+    max_length: Option<usize>,
+    filter: Option<Box<dyn 'a + Send + Fn(char) -> bool>>,
+    numeric: bool,
     trigger: Option<Key>,
+    floating_placeholder: bool,
 }
 
 impl<'a, T, F, S> Input<'a, T, F, S>
@@ -64,7 +95,14 @@ where
             value,
             on_change,
             on_submit: None,
+            on_blur: None,
+            blur_with_window: false,
+            multiline: false,
+            max_length: None,
+            filter: None,
+            numeric: false,
             trigger: None,
+            floating_placeholder: false,
         }
     }
 
@@ -88,7 +126,14 @@ where
             value,
             on_change: self.on_change,
             on_submit: self.on_submit,
+            on_blur: self.on_blur,
+            blur_with_window: self.blur_with_window,
+            multiline: self.multiline,
+            max_length: self.max_length,
+            filter: self.filter,
+            numeric: self.numeric,
             trigger: self.trigger,
+            floating_placeholder: self.floating_placeholder,
         }
     }
 
@@ -100,7 +145,14 @@ where
             value: self.value,
             on_change,
             on_submit: self.on_submit,
+            on_blur: self.on_blur,
+            blur_with_window: self.blur_with_window,
+            multiline: self.multiline,
+            max_length: self.max_length,
+            filter: self.filter,
+            numeric: self.numeric,
             trigger: self.trigger,
+            floating_placeholder: self.floating_placeholder,
         }
     }
 
@@ -110,20 +162,68 @@ where
         self
     }
 
+    /// Sets the message to post with the final text value when the input loses focus.
+    /// This is posted exactly once each time the input goes from focused to unfocused, after any `on_change` or
+    /// `on_submit` message, which makes it a good place to validate or commit the value.
+    pub fn on_blur<B: 'a + Send + Fn(String) -> T>(mut self, on_blur: B) -> Self {
+        self.on_blur = Some(Box::new(on_blur));
+        self
+    }
+
+    /// Sets whether the input loses focus when the window loses focus. Defaults to `false`.
+    pub fn blur_with_window(mut self, blur_with_window: bool) -> Self {
+        self.blur_with_window = blur_with_window;
+        self
+    }
+
+    /// Sets whether the input accepts multiple lines of text. Defaults to `false`.
+    /// A multi-line input inserts a line break when enter is pressed, and submits when command+enter is pressed instead.
+    /// The text is wrapped according to the `text-wrap` of the input, and the up and down keys move the caret between
+    /// lines.
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Sets the maximum number of characters of the value. Typing is ignored when the value is at the limit, and pasted
+    /// text is cut off at the limit. Values that are already longer than the limit are not shortened.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Sets a filter that decides which characters can be entered. Typed characters that are rejected by the filter are
+    /// ignored, and rejected characters are removed from pasted text.
+    pub fn filter<P: 'a + Send + Fn(char) -> bool>(mut self, filter: P) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
     /// Sets a keyboard key that will trigger input focus
     pub fn trigger_key(mut self, key: Key) -> Self {
         self.trigger.replace(key);
         self
     }
 
+    /// Sets whether the placeholder floats up into a label above the text when the input is focused or has a value.
+    /// Defaults to `false`. The placeholder animates between the two positions, and room for the label is reserved
+    /// above the text.
+    pub fn floating_placeholder(mut self, floating_placeholder: bool) -> Self {
+        self.floating_placeholder = floating_placeholder;
+        self
+    }
+
     fn text(&self, stylesheet: &Stylesheet) -> Text {
         Text {
             text: Cow::Borrowed(self.value.as_ref()),
             font: stylesheet.font.clone(),
             size: stylesheet.text_size,
             border: stylesheet.text_border,
-            wrap: TextWrap::NoWrap,
+            wrap: self.wrap(stylesheet),
             color: stylesheet.color,
+            spans: Vec::new(),
+            underline: stylesheet.text_decoration.underline,
+            strikethrough: stylesheet.text_decoration.strikethrough,
         }
     }
 
@@ -133,13 +233,205 @@ where
             font: stylesheet.font.clone(),
             size: stylesheet.text_size,
             border: stylesheet.text_border,
-            wrap: TextWrap::NoWrap,
+            wrap: self.wrap(stylesheet),
             color: stylesheet.color.with_alpha(0.5),
+            spans: Vec::new(),
+            underline: stylesheet.text_decoration.underline,
+            strikethrough: stylesheet.text_decoration.strikethrough,
+        }
+    }
+
+    fn wrap(&self, stylesheet: &Stylesheet) -> TextWrap {
+        if self.multiline {
+            stylesheet.text_wrap
+        } else {
+            TextWrap::NoWrap
+        }
+    }
+
+    /// The height of the unwrapped text, which is a single line unless the input is multi-line.
+    fn text_height(&self, stylesheet: &Stylesheet) -> f32 {
+        if self.multiline {
+            self.text(stylesheet).measure(None).height()
+        } else {
+            let metrics = stylesheet.font.metrics.scale(stylesheet.text_size);
+            metrics.ascender - metrics.descender
+        }
+    }
+
+    /// The height of the floating placeholder in the label position, which is reserved above the text.
+    fn label_height(&self, stylesheet: &Stylesheet) -> f32 {
+        if self.floating_placeholder {
+            let metrics = stylesheet.font.metrics.scale(stylesheet.text_size * FLOAT_SCALE);
+            metrics.ascender - metrics.descender
+        } else {
+            0.0
         }
     }
 
     fn content_rect(&self, layout: Rectangle, stylesheet: &Stylesheet) -> Rectangle {
-        layout.after_padding(stylesheet.padding)
+        let content_rect = layout.after_padding(stylesheet.padding);
+        Rectangle {
+            top: content_rect.top + self.label_height(stylesheet),
+            ..content_rect
+        }
+    }
+
+    /// Clamps the scroll state so that the caret is visible and no space is wasted after the end of the text.
+    /// If `new_text` is `Some`, it is used instead of the current value.
+    /// Returns `true` if the scroll state was changed.
+    fn follow_caret(
+        &self,
+        state: &mut State,
+        content_rect: Rectangle,
+        stylesheet: &Stylesheet,
+        new_text: Option<&str>,
+    ) -> bool {
+        let mut measure_text = Text {
+            text: Cow::Borrowed(new_text.unwrap_or_else(|| self.value.as_ref())),
+            font: stylesheet.font.clone(),
+            size: stylesheet.text_size,
+            border: stylesheet.text_border,
+            wrap: self.wrap(stylesheet),
+            color: stylesheet.color,
+            spans: Vec::new(),
+            underline: false,
+            strikethrough: false,
+        };
+
+        let measure_text_len = measure_text.text.chars().count();
+
+        if self.password {
+            measure_text.text = Cow::Owned("\u{25cf}".repeat(measure_text_len));
+        }
+
+        let (scroll_x, scroll_y) = (state.scroll_x, state.scroll_y);
+
+        let pos = match state.inner {
+            InnerState::Dragging(_, pos, _) | InnerState::Focused(_, pos, _) => pos.min(measure_text_len),
+            InnerState::Idle => 0,
+        };
+        let (caret, range) = measure_text.measure_range(pos, measure_text_len, content_rect);
+
+        if self.multiline {
+            let size = measure_text.measure(Some(content_rect));
+            state.scroll_x = state.scroll_x.min((size.width() - content_rect.width() + 2.0).max(0.0));
+            state.scroll_y = state.scroll_y.min((size.height() - content_rect.height()).max(0.0));
+        } else if state.scroll_x + content_rect.width() > range.0 + 2.0 {
+            state.scroll_x = (range.0 - content_rect.width() + 2.0).max(0.0);
+        }
+        if let InnerState::Dragging(_, _, _) | InnerState::Focused(_, _, _) = state.inner {
+            if caret.0 - state.scroll_x > content_rect.width() - 2.0 {
+                state.scroll_x = caret.0 - content_rect.width() + 2.0;
+            }
+            if caret.0 - state.scroll_x < 0.0 {
+                state.scroll_x = caret.0;
+            }
+            if self.multiline {
+                // keep the whole line of the caret in view
+                let metrics = stylesheet.font.metrics.scale(stylesheet.text_size);
+                let (top, bottom) = (caret.1 - metrics.ascender, caret.1 - metrics.descender);
+                if bottom - state.scroll_y > content_rect.height() {
+                    state.scroll_y = bottom - content_rect.height();
+                }
+                if top - state.scroll_y < 0.0 {
+                    state.scroll_y = top;
+                }
+            } else {
+                if caret.1 - state.scroll_y > content_rect.height() - 2.0 {
+                    state.scroll_y = caret.1 - content_rect.height() + 2.0;
+                }
+                if caret.1 - state.scroll_y < 0.0 {
+                    state.scroll_y = caret.1;
+                }
+            }
+        } else if !self.multiline {
+            state.scroll_y = 0.0;
+        }
+
+        scroll_x != state.scroll_x || scroll_y != state.scroll_y
+    }
+
+    /// Returns whether the character passes the filter of the input.
+    // `Option::is_none_or` requires rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    fn accepts(&self, c: char) -> bool {
+        self.filter.as_ref().map_or(true, |filter| filter(c))
+    }
+
+    /// Replaces a range of the text for every selection and places the carets after the replacements.
+    /// `edit` receives each selection ordered from left to right, and returns the replaced range and the replacement.
+    /// Returns the new text if any selection was edited.
+    fn edit<'s>(
+        &self,
+        state: &mut State,
+        primary: (usize, usize),
+        edit: impl Fn(usize, usize) -> Option<(usize, usize, &'s str)>,
+    ) -> Option<String> {
+        let mut selections = vec![primary];
+        selections.extend(state.extra_selections.iter().copied());
+
+        let mut order = (0..selections.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| selections[i].0.min(selections[i].1));
+
+        let chars = self.value.as_ref().chars().collect::<Vec<_>>();
+        let mut result = String::new();
+        let mut carets = vec![0; selections.len()];
+        let mut edited = false;
+        let mut consumed = 0;
+        let mut written = 0;
+        // the number of characters that can still be inserted without exceeding the maximum length
+        let mut available = self.max_length.map(|max| max.saturating_sub(chars.len()));
+        for i in order {
+            let (from, to) = selections[i];
+            let (start, end, mut replacement) = edit(from.min(to), from.max(to)).unwrap_or((from, from, ""));
+            let start = start.clamp(consumed, chars.len());
+            let end = end.clamp(start, chars.len());
+
+            if let Some(available) = available.as_mut() {
+                *available += end - start;
+                let fits = replacement.chars().count().min(*available);
+                replacement = &replacement[..codepoint(replacement, fits)];
+                *available -= fits;
+            }
+            edited |= end > start || !replacement.is_empty();
+
+            result.extend(&chars[consumed..start]);
+            result.push_str(replacement);
+            written += start - consumed + replacement.chars().count();
+            carets[i] = written;
+            consumed = end;
+        }
+        result.extend(&chars[consumed..]);
+
+        // don't turn a number into something else, but don't prevent fixing a value that isn't a number either.
+        if self.numeric && !is_numeric(&result) && is_numeric(self.value.as_ref()) {
+            return None;
+        }
+
+        state.extra_selections = carets[1..].iter().map(|&caret| (caret, caret)).collect();
+        let (from, to) = merge_selections((carets[0], carets[0]), &mut state.extra_selections);
+        state.inner = InnerState::Focused(from, to, Instant::now());
+
+        if edited {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the selected text of all selections, concatenated from left to right.
+    #[cfg(feature = "clipboard")]
+    fn selected_text(&self, state: &State, primary: (usize, usize)) -> String {
+        let mut selections = vec![primary];
+        selections.extend(state.extra_selections.iter().copied());
+        selections.sort_by_key(|&(from, to)| from.min(to));
+
+        let value = self.value.as_ref();
+        selections
+            .into_iter()
+            .map(|(from, to)| &value[codepoint(value, from.min(to))..codepoint(value, from.max(to))])
+            .collect()
     }
 }
 
@@ -151,11 +443,27 @@ impl<'a, T> Default for Input<'a, T, fn(String) -> T, &'static str> {
             value: "",
             on_change: |_| panic!("on_change of `Input` must be set"),
             on_submit: None,
+            on_blur: None,
+            blur_with_window: false,
+            multiline: false,
+            max_length: None,
+            filter: None,
+            numeric: false,
             trigger: None,
+            floating_placeholder: false,
         }
     }
 }
 
+impl<'a, T: 'a + Send> Input<'a, T, fn(String) -> T, &'static str> {
+    /// Construct a new `Input` that only accepts numbers: digits, a single decimal point and a leading minus sign.
+    pub fn numeric() -> Self {
+        let mut input = Self::default().filter(|c| c.is_ascii_digit() || c == '.' || c == '-');
+        input.numeric = true;
+        input
+    }
+}
+
 impl<'a, T, F, S> Widget<'a, T> for Input<'a, T, F, S>
 where
     T: 'a + Send,
@@ -192,8 +500,10 @@ where
                 let width = self.placeholder_text(stylesheet).measure(None).width()
                     + stylesheet.padding.left
                     + stylesheet.padding.right;
-                let metrics = stylesheet.font.metrics.scale(stylesheet.text_size);
-                let height = metrics.ascender - metrics.descender + stylesheet.padding.top + stylesheet.padding.bottom;
+                let height = self.text_height(stylesheet)
+                    + self.label_height(stylesheet)
+                    + stylesheet.padding.top
+                    + stylesheet.padding.bottom;
                 (Size::Exact(width), Size::Exact(height))
             }
 
@@ -205,8 +515,10 @@ where
             }
 
             (other, Size::Shrink) => {
-                let metrics = stylesheet.font.metrics.scale(stylesheet.text_size);
-                let height = metrics.ascender - metrics.descender + stylesheet.padding.top + stylesheet.padding.bottom;
+                let height = self.text_height(stylesheet)
+                    + self.label_height(stylesheet)
+                    + stylesheet.padding.top
+                    + stylesheet.padding.bottom;
                 (other, Size::Exact(height))
             }
 
@@ -214,6 +526,10 @@ where
         }
     }
 
+    fn focusable(&self, _: &State) -> bool {
+        true
+    }
+
     fn event(
         &mut self,
         state: &mut State,
@@ -227,6 +543,12 @@ where
         let value_len = self.value.as_ref().chars().count();
         let mut new_text = None;
 
+        // passwords don't reveal their words, so they are treated as a single word.
+        let chars = self.value.as_ref().chars().collect::<Vec<_>>();
+        let password = self.password;
+        let previous_word = |caret: usize| if password { 0 } else { word_left(&chars, caret) };
+        let next_word = |caret: usize| if password { value_len } else { word_right(&chars, caret) };
+
         // sanity check on the state
         state.inner = match state.inner {
             InnerState::Dragging(mut from, mut to, since) => {
@@ -249,23 +571,50 @@ where
             }
             InnerState::Idle => InnerState::Idle,
         };
-
-        //if context.cursor.inside(&current) {
-        //    context.style = MouseStyle::Text;
-        //}
+        for (from, to) in state.extra_selections.iter_mut() {
+            *from = (*from).min(value_len);
+            *to = (*to).min(value_len);
+        }
+        let was_focused = !matches!(state.inner, InnerState::Idle);
 
         // event related state update
         match event {
             Event::Cursor(x, y) => {
                 state.cursor = (x, y);
+                let dragging = matches!(state.inner, InnerState::Dragging(_, _, _));
+                if dragging || (content_rect.point_inside(x, y) && clip.point_inside(x, y)) {
+                    context.set_cursor_icon(CursorIcon::Text);
+                }
                 if let InnerState::Dragging(from, _, _) = state.inner {
                     let relative_cursor = (
                         state.cursor.0 - content_rect.left + state.scroll_x,
                         state.cursor.1 - content_rect.top + state.scroll_y,
                     );
-                    let hit =
-                        text_display(self.text(stylesheet), self.password).hitdetect(relative_cursor, content_rect);
-                    state.inner = InnerState::Dragging(from, hit, Instant::now());
+                    let text = text_display(self.text(stylesheet), self.password);
+                    state.inner = match state.block {
+                        Some((corner, kept)) => {
+                            // one selection per line from the corner to the cursor, the cursor's line is the primary.
+                            let line_height = text.line_height();
+                            let lines = ((relative_cursor.1 - corner.1) / line_height).round() as i32;
+                            let select = |line: i32| {
+                                let y = corner.1 + line as f32 * line_height;
+                                (
+                                    text.hitdetect((corner.0, y), content_rect),
+                                    text.hitdetect((relative_cursor.0, y), content_rect),
+                                )
+                            };
+                            state.extra_selections.truncate(kept);
+                            let step = lines.signum();
+                            state
+                                .extra_selections
+                                .extend((0..lines.abs()).map(|i| select(i * step)));
+                            let (from, to) = select(lines);
+                            InnerState::Dragging(from, to, Instant::now())
+                        }
+                        None => {
+                            InnerState::Dragging(from, text.hitdetect(relative_cursor, content_rect), Instant::now())
+                        }
+                    };
                     context.redraw();
                 }
             }
@@ -274,6 +623,22 @@ where
                 state.modifiers = modifiers;
             }
 
+            Event::Animate => {
+                // keep animating while focused, but only redraw when the caret blinks.
+                if let InnerState::Dragging(_, _, since) | InnerState::Focused(_, _, since) = state.inner {
+                    if caret_visible(since) != state.caret_visible {
+                        context.redraw();
+                    }
+                    context.animate();
+                }
+                if state.float_progress() != state.float_drawn {
+                    context.redraw();
+                }
+                if state.floating() {
+                    context.animate();
+                }
+            }
+
             Event::Press(Key::LeftMouseButton) => {
                 context.redraw();
                 if layout.point_inside(state.cursor.0, state.cursor.1)
@@ -285,9 +650,25 @@ where
                     );
                     let hit =
                         text_display(self.text(stylesheet), self.password).hitdetect(relative_cursor, content_rect);
+                    match state.inner {
+                        InnerState::Focused(from, to, _) if state.modifiers.alt => {
+                            state.extra_selections.push((from, to));
+                        }
+                        _ => state.extra_selections.clear(),
+                    }
+                    state.block = Some((relative_cursor, state.extra_selections.len())).filter(|_| state.modifiers.alt);
                     state.inner = InnerState::Dragging(hit, hit, Instant::now());
                 } else {
                     state.inner = InnerState::Idle;
+                    state.extra_selections.clear();
+                }
+            }
+
+            Event::Focus(false) if self.blur_with_window => {
+                if was_focused {
+                    context.redraw();
+                    state.inner = InnerState::Idle;
+                    state.extra_selections.clear();
                 }
             }
 
@@ -295,6 +676,8 @@ where
                 state.inner = match state.inner {
                     InnerState::Dragging(from, to, since) => {
                         context.redraw();
+                        state.block = None;
+                        let (from, to) = merge_selections((from, to), &mut state.extra_selections);
                         InnerState::Focused(from, to, since)
                     }
                     other => other,
@@ -304,7 +687,7 @@ where
             event => match state.inner {
                 InnerState::Idle => match event {
                     Event::Press(key) if Some(key) == self.trigger => {
-                        state.inner = InnerState::Focused(0, self.value.as_ref().len(), Instant::now());
+                        state.inner = InnerState::Focused(0, value_len, Instant::now());
                         context.redraw();
                     }
                     _ => (),
@@ -312,70 +695,84 @@ where
 
                 InnerState::Focused(from, to, _) => match event {
                     Event::Text(c) => match c {
+                        // word deletion is handled by the key press
+                        BACKWARDS_DELETE | FORWARD_DELETE if word_modifier(state.modifiers) => (),
                         BACKWARDS_DELETE => {
                             context.redraw();
-                            let (from, to) = (from.min(to), from.max(to));
-
-                            if to > from {
-                                state.inner = InnerState::Focused(from, from, Instant::now());
-                                let (head, tail) = self.value.as_ref().split_at(codepoint(self.value.as_ref(), from));
-                                new_text.replace(format!("{}{}", head, tail.split_at(codepoint(tail, to - from)).1));
-                            } else if from > 0 {
-                                state.inner = InnerState::Focused(from - 1, from - 1, Instant::now());
-                                let (head, tail) =
-                                    self.value.as_ref().split_at(codepoint(self.value.as_ref(), from - 1));
-                                new_text.replace(format!("{}{}", head, tail.split_at(codepoint(tail, 1)).1));
-                            }
+                            new_text = self.edit(state, (from, to), |from, to| {
+                                if to > from {
+                                    Some((from, to, ""))
+                                } else if from > 0 {
+                                    Some((from - 1, from, ""))
+                                } else {
+                                    None
+                                }
+                            });
                         }
                         FORWARD_DELETE => {
                             context.redraw();
-                            let (from, to) = (from.min(to), from.max(to));
-                            state.inner = InnerState::Focused(from, from, Instant::now());
-
-                            let (head, tail) = self.value.as_ref().split_at(codepoint(self.value.as_ref(), from));
-                            if to > from {
-                                new_text.replace(format!("{}{}", head, tail.split_at(codepoint(tail, to - from)).1));
-                            } else if !tail.is_empty() {
-                                new_text.replace(format!("{}{}", head, tail.split_at(codepoint(tail, 1)).1));
-                            }
-                        }
-                        c => {
-                            if !c.is_control() {
-                                context.redraw();
-                                let (from, to) = (from.min(to), from.max(to));
-                                state.inner = InnerState::Focused(from + 1, from + 1, Instant::now());
-
-                                let (head, tail) = self.value.as_ref().split_at(codepoint(self.value.as_ref(), from));
+                            new_text = self.edit(state, (from, to), |from, to| {
                                 if to > from {
-                                    new_text.replace(format!(
-                                        "{}{}{}",
-                                        head,
-                                        c,
-                                        tail.split_at(codepoint(tail, to - from)).1
-                                    ));
+                                    Some((from, to, ""))
+                                } else if from < value_len {
+                                    Some((from, from + 1, ""))
                                 } else {
-                                    new_text.replace(format!("{}{}{}", head, c, tail));
+                                    None
                                 }
+                            });
+                        }
+                        c => {
+                            if self.accepts(c) && !c.is_control() {
+                                context.redraw();
+                                let insert = c.to_string();
+                                new_text = self.edit(state, (from, to), |from, to| Some((from, to, insert.as_str())));
                             }
                         }
                     },
 
+                    Event::InsertText(text) => {
+                        let multiline = self.multiline;
+                        let text = text
+                            .replace("\r\n", "\n")
+                            .chars()
+                            .filter(|&c| self.accepts(c))
+                            .filter(|&c| !c.is_control() || (multiline && c == '\n'))
+                            .collect::<String>();
+                        if !text.is_empty() {
+                            context.redraw();
+                            new_text = self.edit(state, (from, to), |from, to| Some((from, to, text.as_str())));
+                        }
+                    }
+
+                    Event::Press(Key::Enter) if self.multiline && !state.modifiers.command => {
+                        context.redraw();
+                        new_text = self.edit(state, (from, to), |from, to| Some((from, to, "\n")));
+                    }
+
                     Event::Press(Key::Enter) if self.on_submit.is_some() => {
                         if !state.modifiers.shift {
                             context.redraw();
                             context.extend(self.on_submit.take());
                             state.inner = InnerState::Idle;
+                            state.extra_selections.clear();
                         }
                     }
 
+                    Event::Press(Key::Tab) => {
+                        context.redraw();
+                        state.inner = InnerState::Idle;
+                        state.extra_selections.clear();
+                    }
+
+                    Event::Press(Key::Escape) if !state.extra_selections.is_empty() => {
+                        context.redraw();
+                        state.extra_selections.clear();
+                    }
+
                     #[cfg(feature = "clipboard")]
                     Event::Press(Key::C) => {
                         if state.modifiers.command {
-                            let (a, b) = (
-                                codepoint(self.value.as_ref(), from.min(to)),
-                                codepoint(self.value.as_ref(), from.max(to)),
-                            );
-                            let copy_text = self.value.as_ref()[a..b].to_string();
+                            let copy_text = self.selected_text(state, (from, to));
                             ClipboardContext::new()
                                 .and_then(|mut cc| cc.set_contents(copy_text))
                                 .ok();
@@ -386,20 +783,20 @@ where
                     Event::Press(Key::X) => {
                         if state.modifiers.command {
                             context.redraw();
-                            let (from, to) = (from.min(to), from.max(to));
-                            let (a, b) = (codepoint(self.value.as_ref(), from), codepoint(self.value.as_ref(), to));
-                            let cut_text = self.value.as_ref()[a..b].to_string();
+                            let cut_text = self.selected_text(state, (from, to));
                             ClipboardContext::new()
                                 .and_then(|mut cc| cc.set_contents(cut_text))
                                 .ok();
 
-                            state.inner = InnerState::Focused(from, from, Instant::now());
-                            let (head, tail) = self.value.as_ref().split_at(codepoint(self.value.as_ref(), from));
-                            if to > from {
-                                new_text.replace(format!("{}{}", head, tail.split_at(codepoint(tail, to - from)).1));
-                            } else if !tail.is_empty() {
-                                new_text.replace(format!("{}{}", head, tail.split_at(codepoint(tail, 1)).1));
-                            }
+                            new_text = self.edit(state, (from, to), |from, to| {
+                                if to > from {
+                                    Some((from, to, ""))
+                                } else if from < value_len {
+                                    Some((from, from + 1, ""))
+                                } else {
+                                    None
+                                }
+                            });
                         }
                     }
 
@@ -407,86 +804,144 @@ where
                     Event::Press(Key::V) => {
                         if state.modifiers.command {
                             context.redraw();
-                            let (from, to) = (from.min(to), from.max(to));
                             let paste_text = ClipboardContext::new().and_then(|mut cc| cc.get_contents()).ok();
 
                             if let Some(paste_text) = paste_text {
-                                let (head, tail) = self.value.as_ref().split_at(codepoint(self.value.as_ref(), from));
-                                state.inner = InnerState::Focused(
-                                    from + paste_text.len(),
-                                    from + paste_text.len(),
-                                    Instant::now(),
-                                );
-                                if to > from {
-                                    new_text.replace(format!(
-                                        "{}{}{}",
-                                        head,
-                                        paste_text,
-                                        tail.split_at(codepoint(tail, to - from)).1
-                                    ));
-                                } else {
-                                    new_text.replace(format!("{}{}{}", head, paste_text, tail));
-                                }
+                                let paste_text = paste_text.chars().filter(|&c| self.accepts(c)).collect::<String>();
+                                new_text =
+                                    self.edit(state, (from, to), |from, to| Some((from, to, paste_text.as_str())));
                             }
                         }
                     }
 
-                    Event::Press(Key::Left) => {
+                    Event::Press(Key::Backspace) if word_modifier(state.modifiers) => {
                         context.redraw();
-                        if state.modifiers.command {
-                            if state.modifiers.shift {
-                                state.inner = InnerState::Focused(from, 0, Instant::now());
+                        new_text = self.edit(state, (from, to), |from, to| {
+                            if to > from {
+                                Some((from, to, ""))
+                            } else if from > 0 {
+                                Some((previous_word(from), from, ""))
                             } else {
-                                state.inner = InnerState::Focused(0, 0, Instant::now());
+                                None
                             }
-                        } else if state.modifiers.shift {
-                            state.inner = InnerState::Focused(from, if to > 0 { to - 1 } else { 0 }, Instant::now());
-                        } else {
-                            let (from, to) = (from.min(to), from.max(to));
-                            if from != to || from == 0 {
-                                state.inner = InnerState::Focused(from, from, Instant::now());
+                        });
+                    }
+
+                    Event::Press(Key::Delete) if word_modifier(state.modifiers) => {
+                        context.redraw();
+                        new_text = self.edit(state, (from, to), |from, to| {
+                            if to > from {
+                                Some((from, to, ""))
+                            } else if from < value_len {
+                                Some((from, next_word(from), ""))
                             } else {
-                                state.inner = InnerState::Focused(from - 1, from - 1, Instant::now());
+                                None
                             }
-                        }
+                        });
+                    }
+
+                    Event::Press(Key::Left) => {
+                        context.redraw();
+                        let modifiers = state.modifiers;
+                        move_selections(state, (from, to), |from, to| {
+                            if word_modifier(modifiers) {
+                                if modifiers.shift {
+                                    (from, previous_word(to))
+                                } else {
+                                    (previous_word(to), previous_word(to))
+                                }
+                            } else if modifiers.command {
+                                if modifiers.shift {
+                                    (from, 0)
+                                } else {
+                                    (0, 0)
+                                }
+                            } else if modifiers.shift {
+                                (from, to.saturating_sub(1))
+                            } else {
+                                let (from, to) = (from.min(to), from.max(to));
+                                if from != to || from == 0 {
+                                    (from, from)
+                                } else {
+                                    (from - 1, from - 1)
+                                }
+                            }
+                        });
                     }
 
                     Event::Press(Key::Right) => {
                         context.redraw();
-                        if state.modifiers.command {
-                            if state.modifiers.shift {
-                                state.inner = InnerState::Focused(from, value_len, Instant::now());
+                        let modifiers = state.modifiers;
+                        move_selections(state, (from, to), |from, to| {
+                            if word_modifier(modifiers) {
+                                if modifiers.shift {
+                                    (from, next_word(to))
+                                } else {
+                                    (next_word(to), next_word(to))
+                                }
+                            } else if modifiers.command {
+                                if modifiers.shift {
+                                    (from, value_len)
+                                } else {
+                                    (value_len, value_len)
+                                }
+                            } else if modifiers.shift {
+                                (from, (to + 1).min(value_len))
+                            } else {
+                                let (from, to) = (from.min(to), from.max(to));
+                                if from != to || to >= value_len {
+                                    (to, to)
+                                } else {
+                                    (to + 1, to + 1)
+                                }
+                            }
+                        });
+                    }
+
+                    Event::Press(Key::Up) if self.multiline => {
+                        context.redraw();
+                        let text = text_display(self.text(stylesheet), self.password);
+                        let shift = state.modifiers.shift;
+                        move_selections(state, (from, to), |from, to| {
+                            let caret = line_caret(&text, to, -1.0, content_rect);
+                            if shift {
+                                (from, caret)
                             } else {
-                                state.inner = InnerState::Focused(value_len, value_len, Instant::now());
+                                (caret, caret)
                             }
-                        } else if state.modifiers.shift {
-                            state.inner = InnerState::Focused(from, (to + 1).min(value_len), Instant::now());
-                        } else {
-                            let (from, to) = (from.min(to), from.max(to));
-                            if from != to || to >= value_len {
-                                state.inner = InnerState::Focused(to, to, Instant::now());
+                        });
+                    }
+
+                    Event::Press(Key::Down) if self.multiline => {
+                        context.redraw();
+                        let text = text_display(self.text(stylesheet), self.password);
+                        let shift = state.modifiers.shift;
+                        move_selections(state, (from, to), |from, to| {
+                            let caret = line_caret(&text, to, 1.0, content_rect);
+                            if shift {
+                                (from, caret)
                             } else {
-                                state.inner = InnerState::Focused(to + 1, to + 1, Instant::now());
+                                (caret, caret)
                             }
-                        }
+                        });
                     }
 
                     Event::Press(Key::Home) => {
                         context.redraw();
-                        if state.modifiers.shift {
-                            state.inner = InnerState::Focused(from, 0, Instant::now());
-                        } else {
-                            state.inner = InnerState::Focused(0, 0, Instant::now());
-                        }
+                        let shift = state.modifiers.shift;
+                        move_selections(state, (from, to), |from, _| if shift { (from, 0) } else { (0, 0) });
                     }
 
                     Event::Press(Key::End) => {
                         context.redraw();
-                        if state.modifiers.shift {
-                            state.inner = InnerState::Focused(from, value_len, Instant::now());
-                        } else {
-                            state.inner = InnerState::Focused(value_len, value_len, Instant::now());
-                        }
+                        let shift = state.modifiers.shift;
+                        move_selections(state, (from, to), |from, _| {
+                            if shift {
+                                (from, value_len)
+                            } else {
+                                (value_len, value_len)
+                            }
+                        });
                     }
 
                     _ => (),
@@ -497,52 +952,31 @@ where
         }
 
         // update scroll state for current text and caret position
-        match state.inner {
-            InnerState::Dragging(_, pos, _) | InnerState::Focused(_, pos, _) => {
-                let mut measure_text = Text {
-                    text: Cow::Borrowed(new_text.as_deref().unwrap_or_else(|| self.value.as_ref())),
-                    font: stylesheet.font.clone(),
-                    size: stylesheet.text_size,
-                    border: stylesheet.text_border,
-                    wrap: TextWrap::NoWrap,
-                    color: stylesheet.color,
-                };
-
-                let measure_text_len = measure_text.text.chars().count();
-
-                if self.password {
-                    measure_text.text = Cow::Owned("\u{25cf}".repeat(measure_text_len));
-                }
-
-                let (caret, range) = measure_text.measure_range(pos, measure_text_len, content_rect);
+        if self.follow_caret(state, content_rect, stylesheet, new_text.as_deref()) {
+            context.redraw();
+        }
 
-                if state.scroll_x + content_rect.width() > range.0 + 2.0 {
-                    context.redraw();
-                    state.scroll_x = (range.0 - content_rect.width() + 2.0).max(0.0);
-                }
-                if caret.0 - state.scroll_x > content_rect.width() - 2.0 {
-                    context.redraw();
-                    state.scroll_x = caret.0 - content_rect.width() + 2.0;
-                }
-                if caret.0 - state.scroll_x < 0.0 {
-                    context.redraw();
-                    state.scroll_x = caret.0;
-                }
-                if caret.1 - state.scroll_y > content_rect.height() - 2.0 {
-                    context.redraw();
-                    state.scroll_y = caret.1 - content_rect.height() + 2.0;
-                }
-                if caret.1 - state.scroll_y < 0.0 {
-                    context.redraw();
-                    state.scroll_y = caret.1;
-                }
+        if self.floating_placeholder {
+            let empty = new_text.as_deref().unwrap_or_else(|| self.value.as_ref()).is_empty();
+            if state.float_to(!empty || !matches!(state.inner, InnerState::Idle)) {
+                context.redraw();
+                context.animate();
             }
-            _ => (),
+        }
+
+        let blurred = was_focused && matches!(state.inner, InnerState::Idle);
+        let blur_text = match self.on_blur {
+            Some(_) if blurred => Some(new_text.clone().unwrap_or_else(|| self.value.as_ref().to_string())),
+            _ => None,
         };
 
         if let Some(new_text) = new_text {
             context.push((self.on_change)(new_text));
         }
+
+        if let (Some(on_blur), Some(text)) = (self.on_blur.as_ref(), blur_text) {
+            context.push(on_blur(text));
+        }
     }
 
     fn draw(
@@ -555,65 +989,106 @@ where
         let mut result = Vec::new();
 
         let content_rect = self.content_rect(layout, stylesheet);
+        // the value may have changed since the last event, so the caret has to be followed again.
+        self.follow_caret(state, content_rect, stylesheet, None);
         let text_rect = content_rect.translate(-state.scroll_x, -state.scroll_y);
         let text = text_display(self.text(stylesheet), self.password);
+        let metrics = stylesheet.font.metrics.scale(stylesheet.text_size);
+        let multiline = self.multiline;
+        // the vertical extent of the line with baseline `y`, which is the whole input if it is not multi-line.
+        let line = |y: f32| {
+            if multiline {
+                let baseline = text_rect.top + y;
+                (baseline - metrics.ascender, baseline - metrics.descender)
+            } else {
+                (text_rect.top, text_rect.bottom)
+            }
+        };
 
         result.extend(stylesheet.background.render(layout).into_iter());
         if let Some(clip) = content_rect.intersect(&clip) {
             result.push(Primitive::PushClip(clip));
             match state.inner {
                 InnerState::Dragging(from, to, since) | InnerState::Focused(from, to, since) => {
-                    let range = text.measure_range(from.min(to), from.max(to), text_rect);
-
-                    if to != from {
-                        result.push(Primitive::DrawRect(
-                            Rectangle {
-                                left: text_rect.left + (range.0).0,
-                                right: text_rect.left + (range.1).0,
-                                top: text_rect.top,
-                                bottom: text_rect.bottom,
-                            },
-                            Color {
-                                r: 0.0,
-                                g: 0.0,
-                                b: 0.5,
-                                a: 0.5,
-                            },
-                        ));
-                    }
+                    for (from, to) in std::iter::once((from, to)).chain(state.extra_selections.iter().copied()) {
+                        if to != from {
+                            for (left, right, y) in selection_spans(&text, from.min(to), from.max(to), text_rect) {
+                                let (top, bottom) = line(y);
+                                result.push(Primitive::DrawRect(
+                                    Rectangle {
+                                        left: text_rect.left + left,
+                                        right: text_rect.left + right,
+                                        top,
+                                        bottom,
+                                    },
+                                    Color {
+                                        r: 0.0,
+                                        g: 0.0,
+                                        b: 0.5,
+                                        a: 0.5,
+                                    },
+                                ));
+                            }
+                        }
 
-                    if since.elapsed().subsec_nanos() < 500_000_000 {
-                        let caret = if to > from { range.1 } else { range.0 };
-
-                        result.push(Primitive::DrawRect(
-                            Rectangle {
-                                left: text_rect.left + caret.0,
-                                right: text_rect.left + caret.0 + 1.0,
-                                top: text_rect.top,
-                                bottom: text_rect.bottom,
-                            },
-                            Color {
-                                r: 0.0,
-                                g: 0.0,
-                                b: 0.0,
-                                a: 1.0,
-                            },
-                        ));
+                        state.caret_visible = caret_visible(since);
+                        if state.caret_visible {
+                            let (caret, _) = text.measure_range(to, to, text_rect);
+                            let (top, bottom) = line(caret.1);
+
+                            result.push(Primitive::DrawRect(
+                                Rectangle {
+                                    left: text_rect.left + caret.0,
+                                    right: text_rect.left + caret.0 + 1.0,
+                                    top,
+                                    bottom,
+                                },
+                                Color {
+                                    r: 0.0,
+                                    g: 0.0,
+                                    b: 0.0,
+                                    a: 1.0,
+                                },
+                            ));
+                        }
                     }
                 }
                 _ => (),
             }
-            if self.value.as_ref().is_empty() {
+            if !self.value.as_ref().is_empty() {
+                result.push(Primitive::DrawText(text, text_rect));
+            } else if !self.floating_placeholder {
                 result.push(Primitive::DrawText(
                     self.placeholder_text(stylesheet).to_owned(),
                     text_rect,
                 ));
-            } else {
-                result.push(Primitive::DrawText(text, text_rect));
             }
             result.push(Primitive::PopClip);
         }
 
+        if self.floating_placeholder {
+            // the value may have been set without an event, so the floating state is updated without animating.
+            if !state.floating() {
+                let empty = self.value.as_ref().is_empty();
+                state.float = None;
+                state.float_to(!empty || !matches!(state.inner, InnerState::Idle));
+            }
+            state.float_drawn = state.float_progress();
+
+            // ease in and out between the placeholder position in the field and the label position above the text.
+            let t = state.float_drawn * state.float_drawn * (3.0 - 2.0 * state.float_drawn);
+            let label_top = layout.top + stylesheet.padding.top;
+            let top = content_rect.top + (label_top - content_rect.top) * t;
+            let mut placeholder = self.placeholder_text(stylesheet).to_owned();
+            placeholder.size *= 1.0 + (FLOAT_SCALE - 1.0) * t;
+            placeholder.wrap = TextWrap::NoWrap;
+            if let Some(clip) = layout.intersect(&clip) {
+                result.push(Primitive::PushClip(clip));
+                result.push(Primitive::DrawText(placeholder, Rectangle { top, ..content_rect }));
+                result.push(Primitive::PopClip);
+            }
+        }
+
         result
     }
 }
@@ -642,7 +1117,12 @@ impl Default for State {
                 command: false,
             },
             inner: InnerState::Idle,
+            extra_selections: Vec::new(),
+            block: None,
             cursor: (0.0, 0.0),
+            caret_visible: false,
+            float: None,
+            float_drawn: 0.0,
         }
     }
 }
@@ -652,6 +1132,40 @@ impl State {
     pub fn is_focused(&self) -> bool {
         matches!(self.inner, InnerState::Focused(_, _, _))
     }
+
+    /// Returns how far the placeholder has floated up, from `0.0` in the field to `1.0` in the label position.
+    fn float_progress(&self) -> f32 {
+        match self.float {
+            Some((up, from, since)) => {
+                let to = if up { 1.0 } else { 0.0 };
+                let t = (since.elapsed().as_secs_f32() / FLOAT_DURATION.as_secs_f32()).min(1.0);
+                from + (to - from) * t
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Returns whether the placeholder is moving between the field and the label position.
+    fn floating(&self) -> bool {
+        matches!(self.float, Some((_, _, since)) if since.elapsed() < FLOAT_DURATION)
+    }
+
+    /// Starts moving the placeholder up or down from where it currently is, which reverses a running animation.
+    /// The first time this is called the placeholder is placed without animating.
+    /// Returns `true` if the placeholder starts moving.
+    fn float_to(&mut self, up: bool) -> bool {
+        match self.float {
+            Some((current, _, _)) if current == up => false,
+            Some(_) => {
+                self.float = Some((up, self.float_progress(), Instant::now()));
+                true
+            }
+            None => {
+                self.float = Some((up, if up { 1.0 } else { 0.0 }, Instant::now()));
+                false
+            }
+        }
+    }
 }
 
 fn text_display(buffer: Text<'_>, password: bool) -> Text<'static> {
@@ -663,12 +1177,161 @@ fn text_display(buffer: Text<'_>, password: bool) -> Text<'static> {
             border: buffer.border,
             color: buffer.color,
             wrap: buffer.wrap,
+            spans: buffer.spans.clone(),
+            underline: buffer.underline,
+            strikethrough: buffer.strikethrough,
         }
     } else {
         buffer.to_owned()
     }
 }
 
-fn codepoint(s: &str, char_index: usize) -> usize {
+/// Finds the caret on the visual line above (`lines` < 0) or below (`lines` > 0) the line of `caret`, as close as
+/// possible to the x coordinate of `caret`. Moves to the start or end of the text if there is no such line.
+fn line_caret(text: &Text, caret: usize, lines: f32, rect: Rectangle) -> usize {
+    let metrics = text.font.metrics.scale(text.size);
+    let ((x, y), _) = text.measure_range(caret, caret, rect);
+    let center = y + lines * metrics.line_height - (metrics.ascender + metrics.descender) * 0.5;
+    let hit = text.hitdetect((x, center), rect);
+    let ((_, hit_y), _) = text.measure_range(hit, hit, rect);
+    if (hit_y - y).abs() < metrics.line_height * 0.5 {
+        if lines < 0.0 {
+            0
+        } else {
+            text.text.chars().count()
+        }
+    } else {
+        hit
+    }
+}
+
+/// Returns the left and right x coordinate and the baseline of the selected glyphs `from..to` on each line.
+pub(super) fn selection_spans(text: &Text, from: usize, to: usize, rect: Rectangle) -> Vec<(f32, f32, f32)> {
+    let mut spans: Vec<(f32, f32, f32)> = Vec::new();
+    let mut index = 0;
+    text.layout(rect, |_, begin, end, y| {
+        if index >= from && index < to {
+            match spans.last_mut() {
+                Some(span) if span.2 == y => span.1 = end,
+                _ => spans.push((begin, end, y)),
+            }
+        }
+        index += 1;
+    });
+    spans
+}
+
+/// Returns whether the text is a number that may still be incomplete: an optional leading minus sign, followed by
+/// digits with at most one decimal point.
+fn is_numeric(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    digits.chars().all(|c| c.is_ascii_digit() || c == '.') && digits.matches('.').count() <= 1
+}
+
+/// Returns whether the caret is visible in the blink cycle that started at `since`.
+fn caret_visible(since: Instant) -> bool {
+    since.elapsed().subsec_nanos() < 500_000_000
+}
+
+/// Returns whether the modifier for moving and deleting by words is held. This is alt on macos and ctrl elsewhere.
+fn word_modifier(modifiers: Modifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        modifiers.alt
+    } else {
+        modifiers.ctrl
+    }
+}
+
+#[derive(PartialEq)]
+enum CharClass {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// Finds the start of the word before `caret`, skipping any whitespace in between.
+fn word_left(chars: &[char], caret: usize) -> usize {
+    let mut i = caret.min(chars.len());
+    while i > 0 && char_class(chars[i - 1]) == CharClass::Whitespace {
+        i -= 1;
+    }
+    if i > 0 {
+        let class = char_class(chars[i - 1]);
+        while i > 0 && char_class(chars[i - 1]) == class {
+            i -= 1;
+        }
+    }
+    i
+}
+
+/// Finds the end of the word after `caret`, skipping any whitespace in between.
+fn word_right(chars: &[char], caret: usize) -> usize {
+    let mut i = caret.min(chars.len());
+    while i < chars.len() && char_class(chars[i]) == CharClass::Whitespace {
+        i += 1;
+    }
+    if i < chars.len() {
+        let class = char_class(chars[i]);
+        while i < chars.len() && char_class(chars[i]) == class {
+            i += 1;
+        }
+    }
+    i
+}
+
+pub(super) fn codepoint(s: &str, char_index: usize) -> usize {
     s.char_indices().nth(char_index).map_or(s.len(), |(i, _)| i)
 }
+
+/// Applies `f` to the primary selection and all extra selections, merging selections that end up overlapping.
+fn move_selections(state: &mut State, primary: (usize, usize), f: impl Fn(usize, usize) -> (usize, usize)) {
+    let primary = f(primary.0, primary.1);
+    for selection in state.extra_selections.iter_mut() {
+        *selection = f(selection.0, selection.1);
+    }
+    let (from, to) = merge_selections(primary, &mut state.extra_selections);
+    state.inner = InnerState::Focused(from, to, Instant::now());
+}
+
+/// Merges overlapping or touching selections. Returns the primary selection, which absorbs any selection it touches.
+fn merge_selections(mut primary: (usize, usize), extra: &mut Vec<(usize, usize)>) -> (usize, usize) {
+    let touches = |a: (usize, usize), b: (usize, usize)| a.0.min(a.1) <= b.0.max(b.1) && b.0.min(b.1) <= a.0.max(a.1);
+    let union = |a: (usize, usize), b: (usize, usize)| {
+        let (left, right) = (a.0.min(a.1).min(b.0.min(b.1)), a.0.max(a.1).max(b.0.max(b.1)));
+        if a.0 <= a.1 {
+            (left, right)
+        } else {
+            (right, left)
+        }
+    };
+
+    extra.sort_by_key(|&(from, to)| from.min(to));
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(extra.len());
+    for selection in extra.drain(..) {
+        match merged.last_mut() {
+            Some(last) if touches(*last, selection) => *last = union(*last, selection),
+            _ => merged.push(selection),
+        }
+    }
+    merged.retain(|&selection| {
+        if touches(primary, selection) {
+            primary = union(primary, selection);
+            false
+        } else {
+            true
+        }
+    });
+    *extra = merged;
+
+    primary
+}
@@ -0,0 +1,182 @@
+#[cfg(feature = "clipboard")]
+use clipboard::{ClipboardContext, ClipboardProvider};
+
+use crate::draw::{Color, Primitive};
+use crate::event::{Event, Key, Modifiers};
+use crate::layout::{Rectangle, Size};
+use crate::node::{IntoNode, Node};
+use crate::style::Stylesheet;
+#[cfg(feature = "clipboard")]
+use crate::widget::input::codepoint;
+use crate::widget::input::selection_spans;
+use crate::widget::text::{paragraph, paragraph_size};
+use crate::widget::*;
+
+/// Widget that renders a paragraph of read-only text that can be selected and copied.
+///
+/// The text is laid out like a [`Text`](../text/struct.Text.html), using the `text-wrap` of the stylesheet, and is
+/// styled using the same `text` selector. Dragging over the text with the mouse selects it, and the selection can be
+/// copied to the clipboard by pressing command+c (ctrl+c on windows and linux). Clicking anywhere else clears the
+/// selection.
+#[derive(Default)]
+pub struct Label {
+    text: String,
+}
+
+/// State for [`Label`](struct.Label.html)
+pub struct State {
+    selection: Option<(usize, usize)>,
+    dragging: bool,
+    cursor: (f32, f32),
+    modifiers: Modifiers,
+}
+
+impl Label {
+    /// Constructs a new `Label`
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self { text: text.into() }
+    }
+
+    /// Sets the text value.
+    pub fn val(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+}
+
+impl State {
+    /// Returns the range of selected characters, if any text is selected.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        self.selection
+            .filter(|(from, to)| from != to)
+            .map(|(from, to)| (from.min(to), from.max(to)))
+    }
+}
+
+impl<'a, T> Widget<'a, T> for Label {
+    type State = State;
+
+    fn mount(&self) -> State {
+        State::default()
+    }
+
+    fn widget(&self) -> &'static str {
+        "text"
+    }
+
+    fn len(&self) -> usize {
+        0
+    }
+
+    fn visit_children(&mut self, _: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {}
+
+    fn size(&self, _: &State, style: &Stylesheet) -> (Size, Size) {
+        paragraph_size(self.text.as_str(), style)
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        let content_rect = style.background.content_rect(layout, style.padding);
+        let hit = |(x, y): (f32, f32)| {
+            paragraph(self.text.as_str(), style).hitdetect((x - content_rect.left, y - content_rect.top), content_rect)
+        };
+
+        match event {
+            Event::Cursor(x, y) => {
+                state.cursor = (x, y);
+                if let (true, Some((from, to))) = (state.dragging, state.selection) {
+                    let hit = hit(state.cursor);
+                    if hit != to {
+                        state.selection = Some((from, hit));
+                        context.redraw();
+                    }
+                }
+            }
+
+            Event::Modifiers(modifiers) => {
+                state.modifiers = modifiers;
+            }
+
+            Event::Press(Key::LeftMouseButton) => {
+                let (x, y) = state.cursor;
+                if layout.point_inside(x, y) && clip.point_inside(x, y) {
+                    let hit = hit(state.cursor);
+                    state.selection = Some((hit, hit));
+                    state.dragging = true;
+                    context.redraw();
+                } else if state.selection.take().is_some() {
+                    context.redraw();
+                }
+            }
+
+            Event::Release(Key::LeftMouseButton) => {
+                state.dragging = false;
+            }
+
+            #[cfg(feature = "clipboard")]
+            Event::Press(Key::C) if state.modifiers.command => {
+                if let Some((from, to)) = state.selection() {
+                    let text = &self.text[codepoint(&self.text, from)..codepoint(&self.text, to)];
+                    ClipboardContext::new()
+                        .and_then(|mut cc| cc.set_contents(text.to_string()))
+                        .ok();
+                }
+            }
+
+            _ => (),
+        }
+    }
+
+    fn draw(&mut self, state: &mut State, layout: Rectangle, _: Rectangle, style: &Stylesheet) -> Vec<Primitive<'a>> {
+        let content_rect = style.background.content_rect(layout, style.padding);
+        let text = paragraph(self.text.clone(), style);
+
+        let mut result = Vec::new();
+        result.extend(style.background.render(layout));
+        if let Some((from, to)) = state.selection() {
+            let metrics = style.font.metrics.scale(style.text_size);
+            for (left, right, y) in selection_spans(&text, from, to, content_rect) {
+                result.push(Primitive::DrawRect(
+                    Rectangle {
+                        left: content_rect.left + left,
+                        right: content_rect.left + right,
+                        top: content_rect.top + y - metrics.ascender,
+                        bottom: content_rect.top + y - metrics.descender,
+                    },
+                    Color {
+                        r: 0.0,
+                        g: 0.0,
+                        b: 0.5,
+                        a: 0.5,
+                    },
+                ));
+            }
+        }
+        result.push(Primitive::DrawText(text, content_rect));
+        result
+    }
+}
+
+impl<'a, T: 'a> IntoNode<'a, T> for Label {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            selection: None,
+            dragging: false,
+            cursor: (0.0, 0.0),
+            modifiers: Modifiers::none(),
+        }
+    }
+}
@@ -6,6 +6,9 @@ use crate::style::Stylesheet;
 use crate::widget::{Context, Widget};
 
 /// Stack child widgets on top of each other, while only the topmost receives events.
+///
+/// Clicking a layer brings it to the front, but layers with a higher z-index, set using the `z-index` style property
+/// or [`Panel::z_index`](../panel/struct.Panel.html#method.z_index), always stay in front of layers with a lower one.
 pub struct Layers<'a, T> {
     layers: Vec<Layer<'a, T>>,
     background: Option<Node<'a, T>>,
@@ -70,11 +73,18 @@ impl<'a, T: 'a> Layers<'a, T> {
             }
         }
 
+        Self::sort_by_z_index(&mut result);
         state.order.clear();
         state.order.extend(result.iter().map(|l| l.id));
 
         result
     }
+
+    /// Layers with a higher z-index stay in front of layers with a lower z-index, regardless of the order they were
+    /// activated in. This keeps the event order the same as the draw order.
+    fn sort_by_z_index(layers: &mut [&mut Layer<'a, T>]) {
+        layers.sort_by_key(|layer| std::cmp::Reverse(layer.node.z_index()));
+    }
 }
 
 impl<'a, T: 'a> Default for Layers<'a, T> {
@@ -195,16 +205,20 @@ impl<'a, T: 'a + Send> Widget<'a, T> for Layers<'a, T> {
                     if hit_index != 0 || state.background_focused {
                         state.background_focused = false;
                         if hit_index != 0 {
-                            ordered_layers[0].node.event(layout, clip, event, context);
+                            ordered_layers[0].node.event(layout, clip, event.clone(), context);
                         }
                         let rm = ordered_layers.remove(hit_index);
                         ordered_layers.insert(0, rm);
-                        ordered_layers[0].node.event(layout, clip, Event::Cursor(x, y), context);
+                        Self::sort_by_z_index(&mut ordered_layers);
+                        let hit_layer = ordered_layers.iter_mut().find(|l| l.node.hit(layout, clip, x, y, false));
+                        if let Some(layer) = hit_layer {
+                            layer.node.event(layout, clip, Event::Cursor(x, y), context);
+                        }
                     }
                 } else if !state.background_focused {
                     state.background_focused = true;
                     if !ordered_layers.is_empty() {
-                        ordered_layers[0].node.event(layout, clip, event, context);
+                        ordered_layers[0].node.event(layout, clip, event.clone(), context);
                     }
                     if let Some(bg) = self.background.as_mut() {
                         bg.event(layout, clip, Event::Cursor(x, y), context)
@@ -215,10 +229,10 @@ impl<'a, T: 'a + Send> Widget<'a, T> for Layers<'a, T> {
         }
 
         if let Some(bg) = self.background.as_mut() {
-            bg.event(layout, clip, event, context)
+            bg.event(layout, clip, event.clone(), context)
         }
         for layer in ordered_layers.iter_mut() {
-            layer.node.event(layout, clip, event, context);
+            layer.node.event(layout, clip, event.clone(), context);
         }
 
         state.order.clear();
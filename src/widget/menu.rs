@@ -1,13 +1,23 @@
 use std::marker::PhantomData;
 
+use smallvec::smallvec;
+
 use crate::draw::Primitive;
 use crate::event::{Event, Key};
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
-use crate::style::Stylesheet;
-use crate::widget::{Context, Widget};
+use crate::style::{StyleState, Stylesheet};
+use crate::widget::{dummy::Dummy, Context, StateVec, Widget};
 
 /// A (context) menu with nestable items
+///
+/// Besides items and sub menus, a menu can contain separators, headers and disabled items. These can't be hovered or
+/// selected. Separators are drawn using the `separator` child widget, which is stretched to the width of the menu.
+/// The content of disabled items is wrapped in an `item` widget with the `:disabled` state, so it can be greyed out.
+///
+/// The menu can also be navigated using the keyboard. The up and down keys move between the items, enter selects the
+/// highlighted item and escape closes the menu. Sub menus are opened using the right key or enter, and closed using
+/// the left key. Key presses are handled by the deepest sub menu that is open.
 pub struct Menu<'a, T: 'a, S: AsMut<[MenuItem<'a, T>]>> {
     items: S,
     x: f32,
@@ -41,6 +51,8 @@ pub enum MenuItem<'a, T> {
         content: Node<'a, T>,
         /// Message to send when the item is clicked
         on_select: Option<T>,
+        /// Label that is shown at the right side of the item, such as a keyboard shortcut
+        accelerator: Option<Node<'a, T>>,
     },
     /// Sub menu
     Menu {
@@ -49,6 +61,26 @@ pub enum MenuItem<'a, T> {
         /// MenuItems to show when this item is hovered
         items: Vec<MenuItem<'a, T>>,
     },
+    /// A divider between items that can't be hovered or selected
+    Separator {
+        /// The separator widget
+        content: Node<'a, T>,
+    },
+    /// A section header that can't be hovered or selected
+    Header {
+        /// The content of the header
+        content: Node<'a, T>,
+    },
+    /// An item that is unavailable, so it can't be hovered or selected
+    Disabled {
+        /// The content of the item, wrapped in a widget with the `:disabled` state
+        content: Node<'a, T>,
+    },
+}
+
+/// Wraps the content of a disabled item, so the content can be styled using the `:disabled` state.
+struct DisabledItem<'a, T> {
+    content: Node<'a, T>,
 }
 
 impl<'a, T: 'a> Menu<'a, T, Vec<MenuItem<'a, T>>> {
@@ -113,11 +145,13 @@ impl<'a, T: 'a + Send, S: Send + AsRef<[MenuItem<'a, T>]> + AsMut<[MenuItem<'a,
         let width = match width {
             Size::Exact(width) => width,
             Size::Fill(_) => viewport.width() - state.right,
+            relative @ (Size::Percent(_) | Size::Calc(..)) => relative.reserved_size(viewport.width()),
             Size::Shrink => 0.0,
         };
         let height = match height {
             Size::Exact(height) => height,
             Size::Fill(_) => viewport.height() - state.top,
+            relative @ (Size::Percent(_) | Size::Calc(..)) => relative.reserved_size(viewport.height()),
             Size::Shrink => 0.0,
         };
 
@@ -149,21 +183,22 @@ impl<'a, T: 'a + Send, S: Send + AsRef<[MenuItem<'a, T>]> + AsMut<[MenuItem<'a,
     ) -> impl Iterator<Item = (&mut MenuItem<'a, T>, Rectangle)> {
         let layout = style.background.content_rect(layout, style.padding);
         let align = style.align_horizontal;
-        let available_parts = self.items.as_mut().iter().map(|i| i.content().size().1.parts()).sum();
-        let available_space = layout.height()
-            - self
-                .items
-                .as_mut()
-                .iter()
-                .map(|i| i.content().size().1.min_size())
-                .sum::<f32>();
+        let heights = self
+            .items
+            .as_mut()
+            .iter()
+            .map(|i| (i.content().size().1, i.content().size_limits().1))
+            .collect::<Vec<_>>();
+        let heights = Size::resolve_stacked_limited(&heights, layout.height(), layout.height());
         let mut cursor = 0.0;
-        self.items.as_mut().iter_mut().map(move |item| {
-            let (w, h) = item.content().size();
-            let w = w.resolve(layout.width(), w.parts());
-            let h = h
-                .resolve(available_space, available_parts)
-                .min(layout.height() - cursor);
+        self.items.as_mut().iter_mut().zip(heights).map(move |(item, h)| {
+            let (w, _) = item.content().size();
+            let (min, max) = item.content().size_limits().0;
+            let w = match item {
+                MenuItem::Separator { .. } => layout.width(),
+                _ => w.resolve(layout.width(), w.parts()).min(max).max(min),
+            };
+            let h = h.min(layout.height() - cursor);
             let x = align.resolve_start(w, layout.width());
             let y = cursor;
 
@@ -201,14 +236,11 @@ impl<'a, T: 'a + Send, S: Send + AsRef<[MenuItem<'a, T>]> + AsMut<[MenuItem<'a,
                         MenuItem::Item { .. } => InnerState::HoverItem { index },
                         MenuItem::Menu { .. } => InnerState::HoverSubMenu {
                             index,
-                            sub_state: Box::new(MenuState {
-                                inner: InnerState::Idle,
-                                right: layout.right - style.padding.right - style.padding.left,
-                                left: layout.left + style.padding.left + style.padding.right,
-                                top: item_layout.top - style.padding.top,
-                                bottom: item_layout.bottom + style.padding.bottom,
-                            }),
+                            sub_state: Box::new(sub_menu_state(InnerState::Idle, layout, item_layout, style)),
                         },
+                        MenuItem::Separator { .. } | MenuItem::Header { .. } | MenuItem::Disabled { .. } => {
+                            InnerState::Idle
+                        }
                     };
                 }
             }
@@ -217,12 +249,121 @@ impl<'a, T: 'a + Send, S: Send + AsRef<[MenuItem<'a, T>]> + AsMut<[MenuItem<'a,
             current
         }
     }
+
+    /// Handles a navigation key for this menu, which is the deepest menu that is open.
+    fn navigate(
+        &mut self,
+        key: Key,
+        current: InnerState,
+        layout: Rectangle,
+        style: &Stylesheet,
+        context: &mut Context<T>,
+    ) -> InnerState {
+        let index = match current {
+            InnerState::HoverItem { index } | InnerState::HoverSubMenu { index, .. } => Some(index),
+            InnerState::Idle => None,
+            InnerState::Closed | InnerState::Pressed { .. } => return current,
+        };
+
+        match key {
+            Key::Up | Key::Down => {
+                let items = self.items.as_ref();
+                let len = items.len();
+                if len == 0 {
+                    return current;
+                }
+                let step = |i: usize| {
+                    if key == Key::Down {
+                        (i + 1) % len
+                    } else {
+                        (i + len - 1) % len
+                    }
+                };
+                let start = index.unwrap_or(if key == Key::Down { len - 1 } else { 0 });
+                let mut next = step(start);
+                while !items[next].selectable() {
+                    if next == start {
+                        return current;
+                    }
+                    next = step(next);
+                }
+                context.redraw();
+                InnerState::HoverItem { index: next }
+            }
+
+            Key::Left => match current {
+                InnerState::HoverSubMenu { index, .. } => {
+                    context.redraw();
+                    InnerState::HoverItem { index }
+                }
+                other => other,
+            },
+
+            Key::Right | Key::Enter => match (index, current) {
+                (Some(index), InnerState::HoverItem { .. }) => {
+                    let item_layout = self.item_layouts(layout, style).nth(index).map(|(_, rect)| rect);
+                    match (self.items.as_mut().get_mut(index), item_layout) {
+                        (Some(MenuItem::Menu { items, .. }), Some(item_layout)) => {
+                            let inner = items
+                                .iter()
+                                .position(MenuItem::selectable)
+                                .map_or(InnerState::Idle, |index| InnerState::HoverItem { index });
+                            context.redraw();
+                            InnerState::HoverSubMenu {
+                                index,
+                                sub_state: Box::new(sub_menu_state(inner, layout, item_layout, style)),
+                            }
+                        }
+                        (Some(MenuItem::Item { on_select, .. }), _) if key == Key::Enter => {
+                            context.redraw();
+                            context.extend(on_select.take());
+                            context.extend(self.on_close.take());
+                            InnerState::Closed
+                        }
+                        _ => InnerState::HoverItem { index },
+                    }
+                }
+                (_, other) => other,
+            },
+
+            Key::Escape => {
+                context.redraw();
+                context.extend(self.on_close.take());
+                InnerState::Closed
+            }
+
+            _ => current,
+        }
+    }
+}
+
+/// Returns the state of a sub menu that opens next to the item at `item_layout` in a menu at `layout`.
+fn sub_menu_state(inner: InnerState, layout: Rectangle, item_layout: Rectangle, style: &Stylesheet) -> MenuState {
+    MenuState {
+        inner,
+        right: layout.right - style.padding.right - style.padding.left,
+        left: layout.left + style.padding.left + style.padding.right,
+        top: item_layout.top - style.padding.top,
+        bottom: item_layout.bottom + style.padding.bottom,
+    }
 }
 
 fn visit<'a, T>(items: &mut [MenuItem<'a, T>], visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
     for item in items.iter_mut() {
         match item {
-            MenuItem::Item { ref mut content, .. } => visitor(&mut **content),
+            MenuItem::Item {
+                ref mut content,
+                ref mut accelerator,
+                ..
+            } => {
+                visitor(&mut **content);
+                if let Some(accelerator) = accelerator {
+                    visitor(&mut **accelerator);
+                }
+            }
+            MenuItem::Separator { ref mut content }
+            | MenuItem::Header { ref mut content }
+            | MenuItem::Disabled { ref mut content } => visitor(&mut **content),
             MenuItem::Menu {
                 ref mut content,
                 ref mut items,
@@ -267,7 +408,7 @@ impl<'a, T: 'a + Send, S: Send + AsRef<[MenuItem<'a, T>]> + AsMut<[MenuItem<'a,
                         .as_ref()
                         .iter()
                         .fold(0.0, |size, child| match child.content().size().0 {
-                            Size::Exact(child_size) => size.max(child_size),
+                            Size::Exact(child_size) => size.max(child_size + child.accelerator_width()),
                             _ => size,
                         }),
                 )
@@ -317,49 +458,73 @@ impl<'a, T: 'a + Send, S: Send + AsRef<[MenuItem<'a, T>]> + AsMut<[MenuItem<'a,
 
         let layout = self.layout(state, viewport, style);
 
-        state.inner = match (event, std::mem::replace(&mut state.inner, InnerState::Idle)) {
-            (Event::Cursor(x, y), InnerState::HoverSubMenu { index, sub_state }) => self.hover(
-                InnerState::HoverSubMenu { index, sub_state },
-                x,
-                y,
-                layout,
-                clip,
-                style,
-                context,
-            ),
-
-            (Event::Cursor(x, y), InnerState::Pressed { index }) => {
-                match self.hover(InnerState::Idle, x, y, layout, clip, style, context) {
-                    InnerState::HoverItem { index: hover_index } if hover_index == index => {
-                        InnerState::Pressed { index }
-                    }
-                    other => other,
-                }
+        // navigation keys are handled by the deepest sub menu that is open, except for the left key which is handled
+        // by the menu that contains the deepest sub menu, so it can close the sub menu.
+        let key = match event {
+            Event::Press(key @ (Key::Up | Key::Down | Key::Left | Key::Right | Key::Enter | Key::Escape)) => Some(key),
+            _ => None,
+        };
+        let delegate_key = match (&state.inner, key) {
+            (InnerState::HoverSubMenu { sub_state, .. }, Some(key)) => {
+                key != Key::Left || matches!(sub_state.inner, InnerState::HoverSubMenu { .. })
             }
+            _ => false,
+        };
 
-            (Event::Cursor(x, y), _) => self.hover(InnerState::Idle, x, y, layout, clip, style, context),
+        let current = std::mem::replace(&mut state.inner, InnerState::Idle);
+        state.inner = match key {
+            Some(_) if delegate_key => current,
+            Some(key) => self.navigate(key, current, layout, style, context),
+            None => match (event.clone(), current) {
+                (Event::Cursor(x, y), InnerState::HoverSubMenu { index, sub_state }) => self.hover(
+                    InnerState::HoverSubMenu { index, sub_state },
+                    x,
+                    y,
+                    layout,
+                    clip,
+                    style,
+                    context,
+                ),
+
+                (Event::Cursor(x, y), InnerState::Pressed { index }) => {
+                    match self.hover(InnerState::Idle, x, y, layout, clip, style, context) {
+                        InnerState::HoverItem { index: hover_index } if hover_index == index => {
+                            InnerState::Pressed { index }
+                        }
+                        other => other,
+                    }
+                }
 
-            (Event::Press(Key::LeftMouseButton), InnerState::Idle) => {
-                context.redraw();
-                context.extend(self.on_close.take());
-                InnerState::Closed
-            }
+                (Event::Cursor(x, y), _) => self.hover(InnerState::Idle, x, y, layout, clip, style, context),
+
+                (Event::Press(Key::LeftMouseButton), InnerState::Idle) => {
+                    let (x, y) = context.cursor();
+                    if layout.point_inside(x, y) && clip.point_inside(x, y) {
+                        // clicks on items that can't be selected are ignored
+                        InnerState::Idle
+                    } else {
+                        context.redraw();
+                        context.extend(self.on_close.take());
+                        InnerState::Closed
+                    }
+                }
 
-            (Event::Press(Key::LeftMouseButton), InnerState::HoverItem { index }) => {
-                context.redraw();
-                InnerState::Pressed { index }
-            }
+                (Event::Press(Key::LeftMouseButton), InnerState::HoverItem { index }) => {
+                    context.redraw();
+                    InnerState::Pressed { index }
+                }
 
-            (Event::Release(Key::LeftMouseButton), InnerState::Pressed { index }) => {
-                context.redraw();
-                if let Some(MenuItem::Item { on_select, .. }) = self.items.as_mut().get_mut(index) {
-                    context.extend(on_select.take());
+                (Event::Release(Key::LeftMouseButton), InnerState::Pressed { index }) => {
+                    context.redraw();
+                    if let Some(MenuItem::Item { on_select, .. }) = self.items.as_mut().get_mut(index) {
+                        context.extend(on_select.take());
+                    }
+                    context.extend(self.on_close.take());
+                    InnerState::Closed
                 }
-                context.extend(self.on_close.take());
-                InnerState::Closed
-            }
 
-            (_, unhandled) => unhandled,
+                (_, unhandled) => unhandled,
+            },
         };
 
         let mut close = false;
@@ -369,7 +534,9 @@ impl<'a, T: 'a + Send, S: Send + AsRef<[MenuItem<'a, T>]> + AsMut<[MenuItem<'a,
             ref mut sub_state,
         } = state.inner
         {
-            if let Some(&mut MenuItem::Menu { ref mut items, .. }) = self.items.as_mut().get_mut(index) {
+            if let (Some(&mut MenuItem::Menu { ref mut items, .. }), true) =
+                (self.items.as_mut().get_mut(index), key.is_none() || delegate_key)
+            {
                 let mut sub_menu = Menu {
                     items: items.as_mut_slice(),
                     x: 0.0,
@@ -406,6 +573,7 @@ impl<'a, T: 'a + Send, S: Send + AsRef<[MenuItem<'a, T>]> + AsMut<[MenuItem<'a,
         let mut result = vec![Primitive::LayerUp];
 
         let layout = self.layout(state, viewport, style);
+        let content = style.background.content_rect(layout, style.padding);
 
         result.extend(style.background.render(layout));
 
@@ -449,6 +617,19 @@ impl<'a, T: 'a + Send, S: Send + AsRef<[MenuItem<'a, T>]> + AsMut<[MenuItem<'a,
                         ));
                     }
                     result.extend(item.content_mut().draw(item_layout, clip));
+                    if let MenuItem::Item {
+                        accelerator: Some(ref mut accelerator),
+                        ..
+                    } = item
+                    {
+                        let width = accelerator.size().0.min_size();
+                        let accelerator_layout = Rectangle {
+                            left: content.right - width,
+                            right: content.right,
+                            ..item_layout
+                        };
+                        result.extend(accelerator.draw(accelerator_layout, clip));
+                    }
                     result
                 });
 
@@ -501,6 +682,22 @@ impl<'a, T: 'a> MenuItem<'a, T> {
         Self::Item {
             content: content.into_node(),
             on_select: on_select.into(),
+            accelerator: None,
+        }
+    }
+
+    /// Sets the accelerator label of this item, which is shown at the right side of the item.
+    /// The accelerator is only a label, the shortcut it shows should be handled by the application.
+    /// Will panic if this is not an item.
+    pub fn accelerator(self, label: impl IntoNode<'a, T>) -> Self {
+        if let Self::Item { content, on_select, .. } = self {
+            Self::Item {
+                content,
+                on_select,
+                accelerator: Some(label.into_node()),
+            }
+        } else {
+            panic!("accelerator may only be called on items")
         }
     }
 
@@ -513,6 +710,31 @@ impl<'a, T: 'a> MenuItem<'a, T> {
         }
     }
 
+    /// Construct a new `MenuItem` of the separator type.
+    /// The separator can be styled using the `separator` widget name.
+    pub fn separator() -> Self {
+        Self::Separator {
+            content: Dummy::new("separator").into_node(),
+        }
+    }
+
+    /// Construct a new `MenuItem` of the disabled type, with a content node. Disabled items can't be selected.
+    /// The content is wrapped in an `item` widget with the `:disabled` state, so it can be styled to look unavailable.
+    pub fn disabled(content: impl IntoNode<'a, T>) -> Self {
+        Self::Disabled {
+            content: Node::from_widget(DisabledItem {
+                content: content.into_node(),
+            }),
+        }
+    }
+
+    /// Construct a new `MenuItem` of the header type, with a content node.
+    pub fn header(content: impl IntoNode<'a, T>) -> Self {
+        Self::Header {
+            content: content.into_node(),
+        }
+    }
+
     /// Adds a sub `MenuItem` to this menu.
     /// Will panic if this is an item instead of a submenu.
     pub fn push(self, item: Self) -> Self {
@@ -535,10 +757,27 @@ impl<'a, T: 'a> MenuItem<'a, T> {
         }
     }
 
+    fn selectable(&self) -> bool {
+        matches!(self, MenuItem::Item { .. } | MenuItem::Menu { .. })
+    }
+
+    fn accelerator_width(&self) -> f32 {
+        match self {
+            MenuItem::Item {
+                accelerator: Some(accelerator),
+                ..
+            } => accelerator.size().0.min_size(),
+            _ => 0.0,
+        }
+    }
+
     fn content(&self) -> &Node<'a, T> {
         match self {
             MenuItem::Item { ref content, .. } => content,
             MenuItem::Menu { ref content, .. } => content,
+            MenuItem::Separator { ref content } => content,
+            MenuItem::Header { ref content } => content,
+            MenuItem::Disabled { ref content } => content,
         }
     }
 
@@ -546,6 +785,39 @@ impl<'a, T: 'a> MenuItem<'a, T> {
         match self {
             MenuItem::Item { ref mut content, .. } => content,
             MenuItem::Menu { ref mut content, .. } => content,
+            MenuItem::Separator { ref mut content } => content,
+            MenuItem::Header { ref mut content } => content,
+            MenuItem::Disabled { ref mut content } => content,
         }
     }
 }
+
+impl<'a, T: 'a> Widget<'a, T> for DisabledItem<'a, T> {
+    type State = ();
+
+    fn mount(&self) {}
+
+    fn widget(&self) -> &'static str {
+        "item"
+    }
+
+    fn state(&self, _: &()) -> StateVec {
+        smallvec![StyleState::Disabled]
+    }
+
+    fn len(&self) -> usize {
+        1
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut *self.content);
+    }
+
+    fn size(&self, _: &(), _: &Stylesheet) -> (Size, Size) {
+        self.content.size()
+    }
+
+    fn draw(&mut self, _: &mut (), layout: Rectangle, clip: Rectangle, _: &Stylesheet) -> Vec<Primitive<'a>> {
+        self.content.draw(layout, clip)
+    }
+}
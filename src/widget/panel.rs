@@ -3,26 +3,42 @@ use crate::event::Event;
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
 use crate::style::Stylesheet;
-use crate::widget::{Context, Widget};
+use crate::widget::{Context, CursorResponse, Widget};
 
-/// The anchor from which to apply the offset of a `Panel`
-#[allow(missing_docs)]
+/// The anchor from which to apply the offset of a `Panel`.
+///
+/// Offsets point away from the anchored edge, so a positive offset from a right or bottom anchor moves the panel to
+/// the left or up. On centered axes a positive offset moves the panel to the right or down.
 pub enum Anchor {
+    /// Pin the panel to the top left corner of the parent
     TopLeft,
+    /// Pin the panel to the center of the top edge of the parent
     TopCenter,
+    /// Pin the panel to the top right corner of the parent
     TopRight,
+    /// Pin the panel to the center of the left edge of the parent
     CenterLeft,
+    /// Pin the panel to the center of the parent
     Center,
+    /// Pin the panel to the center of the right edge of the parent
     CenterRight,
+    /// Pin the panel to the bottom left corner of the parent
     BottomLeft,
+    /// Pin the panel to the center of the bottom edge of the parent
     BottomCenter,
+    /// Pin the panel to the bottom right corner of the parent
     BottomRight,
 }
 
 /// A panel with a fixed size and location within it's parent
+///
+/// Panels can be stacked inside [`Layers`](../layers/struct.Layers.html). Normally overlapping panels are drawn in the
+/// order of the layers, but panels with a higher [`z_index`](#method.z_index) are always drawn in front of panels with
+/// a lower z-index.
 pub struct Panel<'a, T> {
-    offset: (f32, f32),
+    offset: (Size, Size),
     anchor: Anchor,
+    z_index: i32,
     content: Option<Node<'a, T>>,
 }
 
@@ -30,15 +46,30 @@ impl<'a, T: 'a> Panel<'a, T> {
     /// Construct a new `Panel`, with an offset from an anchor
     pub fn new(offset: (f32, f32), anchor: Anchor, content: impl IntoNode<'a, T>) -> Self {
         Self {
-            offset,
+            offset: (Size::Exact(offset.0), Size::Exact(offset.1)),
             anchor,
+            z_index: 0,
             content: Some(content.into_node()),
         }
     }
 
     /// Sets the (x, y) offset from the anchor.
     pub fn offset(mut self, offset: (f32, f32)) -> Self {
-        self.offset = offset;
+        self.offset = (Size::Exact(offset.0), Size::Exact(offset.1));
+        self
+    }
+
+    /// Sets the (x, y) offset from the anchor as a `Size`, so it can be relative to the size of the parent using
+    /// `Size::Percent` or `Size::Calc`. `Shrink` and `Fill` offsets are treated as zero.
+    pub fn position(mut self, position: (Size, Size)) -> Self {
+        self.offset = position;
+        self
+    }
+
+    /// Sets the layer to draw the panel on, relative to the layer of the parent. Works the same as the `z-index`
+    /// style property, but is specified per panel. Defaults to `0`.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
         self
     }
 
@@ -58,6 +89,10 @@ impl<'a, T: 'a> Panel<'a, T> {
 
     fn layout(&self, layout: Rectangle) -> Option<Rectangle> {
         let (content_width, content_height) = self.content().size();
+        let offset = (
+            self.offset.0.reserved_size(layout.width()),
+            self.offset.1.reserved_size(layout.height()),
+        );
         let (h, v) = match self.anchor {
             Anchor::TopLeft => (0, 0),
             Anchor::TopCenter => (1, 0),
@@ -71,28 +106,34 @@ impl<'a, T: 'a> Panel<'a, T> {
         };
 
         let h_available = match h {
-            0 => (layout.left + self.offset.0, layout.right),
-            1 if self.offset.0 > 0.0 => (layout.left + self.offset.0 * 2.0, layout.right),
-            1 => (layout.left, layout.right + self.offset.0 * 2.0),
-            _ => (layout.left, layout.right - self.offset.0),
+            0 => (layout.left + offset.0, layout.right),
+            1 if offset.0 > 0.0 => (layout.left + offset.0 * 2.0, layout.right),
+            1 => (layout.left, layout.right + offset.0 * 2.0),
+            _ => (layout.left, layout.right - offset.0),
         };
 
         let v_available = match v {
-            0 => (layout.top + self.offset.1, layout.bottom),
-            1 if self.offset.1 > 0.0 => (layout.top + self.offset.1 * 2.0, layout.bottom),
-            1 => (layout.top, layout.bottom + self.offset.1 * 2.0),
-            _ => (layout.top, layout.bottom - self.offset.1),
+            0 => (layout.top + offset.1, layout.bottom),
+            1 if offset.1 > 0.0 => (layout.top + offset.1 * 2.0, layout.bottom),
+            1 => (layout.top, layout.bottom + offset.1 * 2.0),
+            _ => (layout.top, layout.bottom - offset.1),
         };
 
         if h_available.0 < h_available.1 && v_available.0 < v_available.1 {
             let width = match content_width {
                 Size::Exact(width) => width.min(h_available.1 - h_available.0),
                 Size::Fill(_) => h_available.1 - h_available.0,
+                relative @ (Size::Percent(_) | Size::Calc(..)) => relative
+                    .reserved_size(layout.width())
+                    .min(h_available.1 - h_available.0),
                 Size::Shrink => 0.0,
             };
             let height = match content_height {
                 Size::Exact(height) => height.min(v_available.1 - v_available.0),
                 Size::Fill(_) => v_available.1 - v_available.0,
+                relative @ (Size::Percent(_) | Size::Calc(..)) => relative
+                    .reserved_size(layout.height())
+                    .min(v_available.1 - v_available.0),
                 Size::Shrink => 0.0,
             };
 
@@ -137,8 +178,9 @@ impl<'a, T: 'a> Panel<'a, T> {
 impl<'a, T: 'a> Default for Panel<'a, T> {
     fn default() -> Self {
         Self {
-            offset: (0.0, 0.0),
+            offset: (Size::Exact(0.0), Size::Exact(0.0)),
             anchor: Anchor::TopLeft,
+            z_index: 0,
             content: None,
         }
     }
@@ -153,6 +195,10 @@ impl<'a, T: 'a> Widget<'a, T> for Panel<'a, T> {
         "panel"
     }
 
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
     fn len(&self) -> usize {
         1
     }
@@ -189,6 +235,10 @@ impl<'a, T: 'a> Widget<'a, T> for Panel<'a, T> {
         self.content().focused()
     }
 
+    fn z_index(&self, _: &()) -> i32 {
+        self.z_index
+    }
+
     fn event(
         &mut self,
         _: &mut (),
@@ -204,10 +254,9 @@ impl<'a, T: 'a> Widget<'a, T> for Panel<'a, T> {
     }
 
     fn draw(&mut self, _: &mut (), layout: Rectangle, clip: Rectangle, _: &Stylesheet) -> Vec<Primitive<'a>> {
-        if let Some(layout) = self.layout(layout) {
-            self.content_mut().draw(layout, clip)
-        } else {
-            Vec::new()
+        match self.layout(layout) {
+            Some(layout) => self.content_mut().draw(layout, clip),
+            None => Vec::new(),
         }
     }
 }
@@ -0,0 +1,152 @@
+use crate::draw::Primitive;
+use crate::layout::{Rectangle, Size};
+use crate::node::{IntoNode, Node};
+use crate::style::Stylesheet;
+use crate::widget::*;
+
+/// A widget that draws it's content on top of the whole ui, instead of in place.
+///
+/// The content is laid out as if it was a [`Frame`](../frame/struct.Frame.html), and it's state lives with the
+/// component that declared the portal. Only the drawing is moved to a layer above all other widgets, without being
+/// clipped by any of the parent widgets. This is useful for tooltips and modals.
+///
+/// Since the content is drawn on top, it also receives events before all other widgets, using the rect it was drawn at
+/// instead of the layout and clipping rect of the parents. Widgets below the content don't see the cursor while it's
+/// over the content.
+pub struct Portal<'a, T> {
+    content: Option<Node<'a, T>>,
+    drawn: Option<(Rectangle, Rectangle)>,
+}
+
+impl<'a, T: 'a> Portal<'a, T> {
+    /// Construct a new `Portal` with content
+    pub fn new(content: impl IntoNode<'a, T>) -> Self {
+        Self {
+            content: Some(content.into_node()),
+            drawn: None,
+        }
+    }
+
+    /// Sets the content widget from the first element of an iterator.
+    pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T>>(mut self, iter: I) -> Self {
+        if self.content.is_none() {
+            self.content = iter.into_iter().next().map(IntoNode::into_node);
+        }
+        self
+    }
+
+    fn content(&self) -> &Node<'a, T> {
+        self.content.as_ref().expect("content of `Portal` must be set")
+    }
+
+    fn content_mut(&mut self) -> &mut Node<'a, T> {
+        self.content.as_mut().expect("content of `Portal` must be set")
+    }
+}
+
+impl<'a, T: 'a> Default for Portal<'a, T> {
+    fn default() -> Self {
+        Self {
+            content: None,
+            drawn: None,
+        }
+    }
+}
+
+impl<'a, T: 'a> Widget<'a, T> for Portal<'a, T> {
+    type State = ();
+
+    fn mount(&self) {}
+
+    fn widget(&self) -> &'static str {
+        "portal"
+    }
+
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
+    fn len(&self) -> usize {
+        1
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut **self.content_mut());
+    }
+
+    fn size(&self, _: &(), style: &Stylesheet) -> (Size, Size) {
+        style
+            .background
+            .resolve_size((style.width, style.height), self.content().size(), style.padding)
+    }
+
+    fn hit(
+        &self,
+        _state: &Self::State,
+        layout: Rectangle,
+        _clip: Rectangle,
+        style: &Stylesheet,
+        x: f32,
+        y: f32,
+        recursive: bool,
+    ) -> bool {
+        if layout.point_inside(x, y) {
+            if recursive && !style.background.is_solid() {
+                self.content().hit(
+                    style.background.content_rect(layout, style.padding),
+                    layout,
+                    x,
+                    y,
+                    recursive,
+                )
+            } else {
+                true
+            }
+        } else {
+            false
+        }
+    }
+
+    fn focused(&self, _: &()) -> bool {
+        self.content().focused()
+    }
+
+    fn portal_event(&mut self, _: &mut (), event: Event, context: &mut Context<T>) {
+        // portals inside of the content are drawn on top of the content, so they get the event first.
+        self.content_mut().portal_event(event.clone(), context);
+
+        if let Some((content_rect, layout)) = self.drawn {
+            let event = match event {
+                Event::Cursor(_, _) if context.portal_hovered() => Event::Cursor(f32::INFINITY, f32::INFINITY),
+                Event::Cursor(x, y) => {
+                    if layout.point_inside(x, y) {
+                        context.hover_portal();
+                    }
+                    Event::Cursor(x, y)
+                }
+                other => other,
+            };
+            self.content_mut().event(content_rect, layout, event, context);
+        }
+    }
+
+    fn draw(&mut self, _: &mut (), layout: Rectangle, _clip: Rectangle, style: &Stylesheet) -> Vec<Primitive<'a>> {
+        let content_rect = style.background.content_rect(layout, style.padding);
+        self.drawn = Some((content_rect, layout));
+
+        vec![Primitive::Portal(
+            style
+                .background
+                .render(layout)
+                .into_iter()
+                .chain(self.content_mut().draw(content_rect, layout).into_iter())
+                .collect(),
+        )]
+    }
+}
+
+impl<'a, T: 'a> IntoNode<'a, T> for Portal<'a, T> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
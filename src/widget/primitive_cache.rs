@@ -0,0 +1,269 @@
+use crate::draw::Primitive;
+use crate::event::Event;
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::Stylesheet;
+use crate::widget::{Context, CursorResponse, Widget};
+
+/// A widget that wraps around a content widget and caches the primitives it draws.
+/// This is useful for content that is expensive to draw, but rarely changes, such as a rendered chart.
+///
+/// The cache is invalidated when the layout or clip of the widget changes, when the content requests a redraw while
+/// handling an event, or when the [`version`](#method.version) changes. Changes that are not picked up by any of
+/// these, like new props that change what the content looks like, should be signalled by changing the version.
+///
+/// Only the primitives are cached, the content is not rendered to a texture. This skips drawing the content, but the
+/// primitives are still converted to vertices and rendered every frame.
+pub struct PrimitiveCache<'a, T> {
+    content: Option<Node<'a, T>>,
+    version: u64,
+    interactive: bool,
+}
+
+/// State for [`PrimitiveCache`](struct.PrimitiveCache.html)
+#[derive(Default)]
+pub struct State {
+    cache: Option<Cache>,
+}
+
+struct Cache {
+    layout: Rectangle,
+    clip: Rectangle,
+    version: u64,
+    primitives: Vec<Primitive<'static>>,
+    focusable: Vec<Rectangle>,
+    regions: Vec<(Rectangle, CursorResponse)>,
+}
+
+impl<'a, T: 'a> PrimitiveCache<'a, T> {
+    /// Construct a new `PrimitiveCache` with content
+    pub fn new(content: impl IntoNode<'a, T>) -> Self {
+        Self {
+            content: Some(content.into_node()),
+            version: 0,
+            interactive: true,
+        }
+    }
+
+    /// Sets the content widget from the first element of an iterator.
+    pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T>>(mut self, iter: I) -> Self {
+        if self.content.is_none() {
+            self.content = iter.into_iter().next().map(IntoNode::into_node);
+        }
+        self
+    }
+
+    /// Sets the version of the content. Whenever the version is different from the version of the cached primitives,
+    /// the content is drawn again.
+    pub fn version(mut self, version: u64) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Sets whether the content receives events. Defaults to `true`.
+    /// Non interactive content can only change through the version, so the cache is invalidated less often.
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    fn content(&self) -> &Node<'a, T> {
+        self.content.as_ref().expect("content of `PrimitiveCache` must be set")
+    }
+
+    fn content_mut(&mut self) -> &mut Node<'a, T> {
+        self.content.as_mut().expect("content of `PrimitiveCache` must be set")
+    }
+
+    /// Passes an event to the content, and forwards the requests of the content to the parent context. The cache is
+    /// cleared when the content requests a redraw or rebuild.
+    fn forward(
+        &mut self,
+        state: &mut State,
+        context: &mut Context<T>,
+        event: impl FnOnce(&mut Node<'a, T>, &mut Context<T>),
+    ) {
+        let mut sub_context = Context::new(false, false, context.cursor());
+        if context.portal_hovered() {
+            sub_context.hover_portal();
+        }
+        event(self.content_mut(), &mut sub_context);
+
+        if sub_context.redraw_requested() {
+            state.cache = None;
+            context.redraw();
+        }
+        if sub_context.rebuild_requested() {
+            state.cache = None;
+            context.rebuild();
+        }
+        if sub_context.animation_requested() {
+            context.animate();
+        }
+        if sub_context.cursor_tracking_requested() {
+            context.track_cursor();
+        }
+        if let Some(icon) = sub_context.cursor_icon_requested() {
+            context.set_cursor_icon(icon);
+        }
+        if sub_context.scroll_consumed() {
+            context.consume_scroll();
+        }
+        if sub_context.portal_hovered() {
+            context.hover_portal();
+        }
+        context.extend(sub_context);
+    }
+}
+
+impl<'a, T: 'a> Default for PrimitiveCache<'a, T> {
+    fn default() -> Self {
+        Self {
+            content: None,
+            version: 0,
+            interactive: true,
+        }
+    }
+}
+
+impl<'a, T: 'a> Widget<'a, T> for PrimitiveCache<'a, T> {
+    type State = State;
+
+    fn mount(&self) -> State {
+        State::default()
+    }
+
+    fn widget(&self) -> &'static str {
+        "primitive-cache"
+    }
+
+    fn len(&self) -> usize {
+        1
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut **self.content_mut());
+    }
+
+    fn size(&self, _: &State, style: &Stylesheet) -> (Size, Size) {
+        style
+            .background
+            .resolve_size((style.width, style.height), self.content().size(), style.padding)
+    }
+
+    fn hit(
+        &self,
+        _: &State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        x: f32,
+        y: f32,
+        recursive: bool,
+    ) -> bool {
+        if layout.point_inside(x, y) && clip.point_inside(x, y) {
+            if recursive && self.interactive && !style.background.is_solid() {
+                self.content().hit(
+                    style.background.content_rect(layout, style.padding),
+                    clip,
+                    x,
+                    y,
+                    recursive,
+                )
+            } else {
+                true
+            }
+        } else {
+            false
+        }
+    }
+
+    fn focused(&self, _: &State) -> bool {
+        self.interactive && self.content().focused()
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        if !self.interactive {
+            return;
+        }
+
+        let content_rect = style.background.content_rect(layout, style.padding);
+        self.forward(state, context, |content, sub_context| {
+            content.event(content_rect, clip, event, sub_context)
+        });
+    }
+
+    fn portal_event(&mut self, state: &mut State, event: Event, context: &mut Context<T>) {
+        if self.interactive {
+            self.forward(state, context, |content, sub_context| {
+                content.portal_event(event, sub_context)
+            });
+        }
+    }
+
+    fn draw(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+    ) -> Vec<Primitive<'a>> {
+        if let Some(cache) = state.cache.as_ref() {
+            if cache.layout == layout && cache.clip == clip && cache.version == self.version {
+                return cache.primitives.clone();
+            }
+        }
+
+        let content_rect = style.background.content_rect(layout, style.padding);
+        let primitives = style
+            .background
+            .render(layout)
+            .into_iter()
+            .chain(self.content_mut().draw(content_rect, clip))
+            .collect::<Vec<_>>();
+
+        // the rects of the content are only recorded when it's drawn, so they are kept to be replayed on a cache hit.
+        let mut focusable = Vec::new();
+        let mut regions = Vec::new();
+        self.content_mut().drawn_rects(&mut focusable, &mut regions);
+
+        state.cache = Some(Cache {
+            layout,
+            clip,
+            version: self.version,
+            primitives: primitives.iter().map(Primitive::to_owned).collect(),
+            focusable,
+            regions,
+        });
+
+        primitives
+    }
+
+    fn drawn_rects(
+        &mut self,
+        state: &mut State,
+        focusable: &mut Vec<Rectangle>,
+        regions: &mut Vec<(Rectangle, CursorResponse)>,
+    ) {
+        if let Some(cache) = state.cache.as_ref() {
+            if self.interactive {
+                focusable.extend_from_slice(&cache.focusable);
+                regions.extend_from_slice(&cache.regions);
+            }
+        }
+    }
+}
+
+impl<'a, T: 'a> IntoNode<'a, T> for PrimitiveCache<'a, T> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
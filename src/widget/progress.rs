@@ -10,6 +10,8 @@ use crate::widget::{dummy::Dummy, Context, Widget};
 /// Progress accepts the `clip-bar` flag for it's style. When the `clip-bar` flag is set, the bar is always rendered
 /// at full size and then clipped according to the progress. When `clip-bar` is not set, the bar itself is rendered
 /// with a size that matches the progress.
+/// Progress values outside of the range [0.0, 1.0] are clamped. When no `height` is set, the progress bar is one line
+/// of text high.
 pub struct Progress<'a, T> {
     progress: ProgressValue<'a>,
     fill: Node<'a, T>,
@@ -71,7 +73,11 @@ impl<'a, T: 'a> Widget<'a, T> for Progress<'a, T> {
     }
 
     fn size(&self, _: &(), style: &Stylesheet) -> (Size, Size) {
-        (style.width, style.height)
+        let height = match style.height {
+            Size::Shrink => Size::Exact(style.text_size + style.padding.top + style.padding.bottom),
+            other => other,
+        };
+        (style.width, height)
     }
 
     fn hit(&self, _: &Self::State, _: Rectangle, _: Rectangle, _: &Stylesheet, _: f32, _: f32, _: bool) -> bool {
@@ -88,7 +94,8 @@ impl<'a, T: 'a> Widget<'a, T> for Progress<'a, T> {
         let progress = match &mut self.progress {
             &mut ProgressValue::Static(value) => value,
             ProgressValue::Dynamic(dynamic) => dynamic(),
-        };
+        }
+        .clamp(0.0, 1.0);
 
         let mut result = Vec::new();
         result.extend(style.background.render(layout));
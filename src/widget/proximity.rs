@@ -0,0 +1,164 @@
+use crate::draw::{Color, Primitive};
+use crate::layout::{Rectangle, Size};
+use crate::node::{IntoNode, Node};
+use crate::style::Stylesheet;
+use crate::widget::*;
+
+/// A widget that wraps around a content widget and reacts to the cursor approaching it.
+///
+/// The proximity of the cursor ranges from `0.0` when the cursor is `radius` or more logical pixels away from the
+/// widget, to `1.0` when the cursor is over the widget. The background of the widget is drawn with it's opacity
+/// multiplied by the proximity, so the background fades in as the cursor approaches. The content is drawn on top of
+/// the background as usual.
+///
+/// A redraw is only requested when the proximity changes, so cursor moves outside of the radius don't cause redraws.
+/// Custom widgets can implement the same behaviour using
+/// [`Rectangle::proximity`](../../layout/struct.Rectangle.html#method.proximity) and
+/// [`Context::track_cursor`](../struct.Context.html#method.track_cursor).
+pub struct Proximity<'a, T> {
+    content: Option<Node<'a, T>>,
+    radius: f32,
+}
+
+/// State for [`Proximity`](struct.Proximity.html)
+#[derive(Default)]
+pub struct State {
+    proximity: f32,
+}
+
+impl<'a, T: 'a> Proximity<'a, T> {
+    /// Construct a new `Proximity` with content
+    pub fn new(radius: f32, content: impl IntoNode<'a, T>) -> Self {
+        Self {
+            content: Some(content.into_node()),
+            radius,
+        }
+    }
+
+    /// Sets the distance in logical pixels from which the widget starts reacting to the cursor. Defaults to `48.0`.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the content widget from the first element of an iterator.
+    pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T>>(mut self, iter: I) -> Self {
+        if self.content.is_none() {
+            self.content = iter.into_iter().next().map(IntoNode::into_node);
+        }
+        self
+    }
+
+    fn content(&self) -> &Node<'a, T> {
+        self.content.as_ref().expect("content of `Proximity` must be set")
+    }
+
+    fn content_mut(&mut self) -> &mut Node<'a, T> {
+        self.content.as_mut().expect("content of `Proximity` must be set")
+    }
+}
+
+impl<'a, T: 'a> Default for Proximity<'a, T> {
+    fn default() -> Self {
+        Self {
+            content: None,
+            radius: 48.0,
+        }
+    }
+}
+
+impl State {
+    /// Returns the proximity of the cursor at the last cursor move, ranging from `0.0` to `1.0`.
+    pub fn proximity(&self) -> f32 {
+        self.proximity
+    }
+}
+
+impl<'a, T: 'a> Widget<'a, T> for Proximity<'a, T> {
+    type State = State;
+
+    fn mount(&self) -> State {
+        State::default()
+    }
+
+    fn widget(&self) -> &'static str {
+        "proximity"
+    }
+
+    fn len(&self) -> usize {
+        1
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut **self.content_mut());
+    }
+
+    fn size(&self, _: &State, style: &Stylesheet) -> (Size, Size) {
+        style
+            .background
+            .resolve_size((style.width, style.height), self.content().size(), style.padding)
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        if let Event::Cursor(x, y) = event {
+            let proximity = layout.proximity(x, y, self.radius);
+            if proximity != state.proximity {
+                state.proximity = proximity;
+                context.redraw();
+            }
+            // the cursor can approach from outside of every widget, so all cursor moves are needed.
+            context.track_cursor();
+        }
+
+        self.content_mut().event(
+            style.background.content_rect(layout, style.padding),
+            clip,
+            event,
+            context,
+        );
+    }
+
+    fn draw(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+    ) -> Vec<Primitive<'a>> {
+        let content_rect = style.background.content_rect(layout, style.padding);
+        let fade = |color: Color| color.with_alpha(color.a * state.proximity);
+
+        let background = style
+            .background
+            .render(layout)
+            .filter(|_| state.proximity > 0.0)
+            .map(|primitive| match primitive {
+                Primitive::DrawRect(rect, color) => Primitive::DrawRect(rect, fade(color)),
+                Primitive::DrawRoundedRect(rect, corners, color) => {
+                    Primitive::DrawRoundedRect(rect, corners, fade(color))
+                }
+                Primitive::Draw9(patch, rect, color) => Primitive::Draw9(patch, rect, fade(color)),
+                Primitive::DrawImage(image, rect, color) => Primitive::DrawImage(image, rect, fade(color)),
+                other => other,
+            });
+
+        background
+            .into_iter()
+            .chain(self.content_mut().draw(content_rect, clip))
+            .collect()
+    }
+}
+
+impl<'a, T: 'a> IntoNode<'a, T> for Proximity<'a, T> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
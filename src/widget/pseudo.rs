@@ -0,0 +1,120 @@
+use std::borrow::Cow;
+
+use crate::draw::Primitive;
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::{Content, Stylesheet};
+use crate::text;
+use crate::widget::{CursorResponse, Widget};
+
+/// A pseudo element that renders the `content` of it's own stylesheet.
+/// Widgets that support pseudo elements add them as child widgets,
+/// so they can be styled using the `::before` and `::after` selectors.
+/// ```ignore
+/// toggle:checked::before {
+///     content: "x";
+///     color: #fff;
+/// }
+/// ```
+pub struct PseudoElement {
+    widget: &'static str,
+}
+
+impl PseudoElement {
+    /// Construct a new `::before` pseudo element
+    pub fn before() -> Self {
+        Self { widget: "::before" }
+    }
+
+    /// Construct a new `::after` pseudo element
+    pub fn after() -> Self {
+        Self { widget: "::after" }
+    }
+
+    /// Returns the layout of a pseudo element within the content rect of it's parent, aligned using the alignment of
+    /// the parent's stylesheet.
+    pub(crate) fn layout<'a, T>(node: &Node<'a, T>, layout: Rectangle, stylesheet: &Stylesheet) -> Rectangle {
+        let content = stylesheet.background.content_rect(layout, stylesheet.padding);
+        let (width, height) = node.size();
+        let width = width.resolve(content.width(), width.parts()).min(content.width());
+        let height = height.resolve(content.height(), height.parts()).min(content.height());
+        Rectangle::from_xywh(
+            content.left + stylesheet.align_horizontal.resolve_start(width, content.width()),
+            content.top + stylesheet.align_vertical.resolve_start(height, content.height()),
+            width,
+            height,
+        )
+    }
+
+    fn text(content: &str, style: &Stylesheet) -> text::Text<'static> {
+        text::Text {
+            text: Cow::Owned(content.to_string()),
+            font: style.font.clone(),
+            size: style.text_size,
+            border: style.text_border,
+            wrap: style.text_wrap,
+            color: style.color,
+            spans: Vec::new(),
+            underline: style.text_decoration.underline,
+            strikethrough: style.text_decoration.strikethrough,
+        }
+    }
+}
+
+impl Default for PseudoElement {
+    fn default() -> Self {
+        Self::before()
+    }
+}
+
+impl<'a, T: 'a> Widget<'a, T> for PseudoElement {
+    type State = ();
+
+    fn mount(&self) {}
+
+    fn widget(&self) -> &'static str {
+        self.widget
+    }
+
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
+    fn len(&self) -> usize {
+        0
+    }
+
+    fn visit_children(&mut self, _: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {}
+
+    fn size(&self, _: &(), style: &Stylesheet) -> (Size, Size) {
+        let content = match style.content {
+            Content::None => (Size::Exact(0.0), Size::Exact(0.0)),
+            Content::Text(ref content) => {
+                let measured = Self::text(content, style).measure(None);
+                (Size::Exact(measured.width()), Size::Exact(measured.height()))
+            }
+            Content::Image(ref image) => (Size::Exact(image.size.width()), Size::Exact(image.size.height())),
+        };
+        style
+            .background
+            .resolve_size((style.width, style.height), content, style.padding)
+    }
+
+    fn draw(&mut self, _: &mut (), layout: Rectangle, _: Rectangle, style: &Stylesheet) -> Vec<Primitive<'a>> {
+        let content_rect = style.background.content_rect(layout, style.padding);
+        let mut result = Vec::new();
+        result.extend(style.background.render(layout));
+        match style.content {
+            Content::None => (),
+            Content::Text(ref content) => result.push(Primitive::DrawText(Self::text(content, style), content_rect)),
+            Content::Image(ref image) => result.push(Primitive::DrawImage(image.clone(), content_rect, style.color)),
+        }
+        result
+    }
+}
+
+impl<'a, T: 'a> IntoNode<'a, T> for PseudoElement {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
@@ -0,0 +1,160 @@
+use crate::draw::*;
+use crate::event::Event;
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::Stylesheet;
+use crate::widget::{pseudo::PseudoElement, Context, CursorResponse, StateVec, Widget};
+
+pub use crate::widget::checkbox::State;
+
+/// A radio button that selects one value out of a group of options when clicked.
+/// The radio button doesn't know about the other options in it's group, the parent component is responsible for
+/// deselecting them when a new value is selected. Use [`group`](#method.group) to construct a radio button for every
+/// option at once.
+///
+/// The button is drawn using the background of the radio, and the dot of a selected radio is drawn in the `color` of the
+/// radio inside of the padding. Selected radio buttons can be styled using the `:checked` selector state.
+///
+/// The radio supports the `::before` and `::after` pseudo elements, which are drawn on top of the dot.
+pub struct Radio<'a, V, T, F: Fn(V) -> T> {
+    selected: bool,
+    value: V,
+    on_select: F,
+    before: Node<'a, T>,
+    after: Node<'a, T>,
+}
+
+impl<'a, V, T: 'a, F: Fn(V) -> T> Radio<'a, V, T, F> {
+    /// Constructs a new `Radio`
+    pub fn new(selected: bool, value: V, on_select: F) -> Self {
+        Self {
+            selected,
+            value,
+            on_select,
+            before: PseudoElement::before().into_node(),
+            after: PseudoElement::after().into_node(),
+        }
+    }
+
+    /// Sets whether the `Radio` is currently selected.
+    pub fn val(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Sets the value that is passed to the on_select callback when the `Radio` is selected.
+    pub fn value(mut self, value: V) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Sets the on_select callback for this `Radio`, which is called with the value of the radio when it's selected.
+    pub fn on_select<N: Fn(V) -> T>(self, on_select: N) -> Radio<'a, V, T, N> {
+        Radio {
+            selected: self.selected,
+            value: self.value,
+            on_select,
+            before: self.before,
+            after: self.after,
+        }
+    }
+}
+
+impl<'a, V: Clone + PartialEq, T: 'a, F: Clone + Fn(V) -> T> Radio<'a, V, T, F> {
+    /// Constructs a `Radio` for each of the `options`, where the option equal to `selected` is selected.
+    /// Clicking one of the radios will call `on_select` with the option of that radio.
+    /// The resulting radios can be added to a layout widget such as [`Row`](../row/struct.Row.html) using `extend`.
+    pub fn group<I: IntoIterator<Item = V>>(options: I, selected: &V, on_select: F) -> Vec<Self> {
+        options
+            .into_iter()
+            .map(|value| Self::new(value == *selected, value, on_select.clone()))
+            .collect()
+    }
+}
+
+impl<'a, V: Default, T: 'a> Default for Radio<'a, V, T, fn(V) -> T> {
+    fn default() -> Self {
+        Self::new(false, V::default(), |_| panic!("on_select of `Radio` must be set"))
+    }
+}
+
+impl<'a, V: 'a + Send + Clone, T: 'a, F: 'a + Send + Fn(V) -> T> Widget<'a, T> for Radio<'a, V, T, F> {
+    type State = State;
+
+    fn mount(&self) -> Self::State {
+        State::Idle
+    }
+
+    fn widget(&self) -> &'static str {
+        "radio"
+    }
+
+    fn state(&self, state: &State) -> StateVec {
+        state.style(self.selected)
+    }
+
+    fn len(&self) -> usize {
+        2
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut *self.before);
+        visitor(&mut *self.after);
+    }
+
+    fn size(&self, _: &State, stylesheet: &Stylesheet) -> (Size, Size) {
+        (stylesheet.width, stylesheet.height)
+    }
+
+    fn focusable(&self, _: &State) -> bool {
+        true
+    }
+
+    fn cursor_response(&self, _: &State) -> CursorResponse {
+        CursorResponse::Hover
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        _: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        if state.event(layout, clip, &event, context) && !self.selected {
+            context.push((self.on_select)(self.value.clone()));
+        }
+    }
+
+    fn draw(
+        &mut self,
+        _: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        stylesheet: &Stylesheet,
+    ) -> Vec<Primitive<'a>> {
+        let mut result = Vec::new();
+        result.extend(stylesheet.background.render(layout));
+
+        if self.selected {
+            let content = stylesheet.background.content_rect(layout, stylesheet.padding);
+            let dot = Corners::all(content.width().min(content.height()) * 0.5);
+            result.push(Primitive::DrawRoundedRect(content, dot, stylesheet.color));
+        }
+
+        let before = PseudoElement::layout(&self.before, layout, stylesheet);
+        let after = PseudoElement::layout(&self.after, layout, stylesheet);
+        result.extend(self.before.draw(before, clip));
+        result.extend(self.after.draw(after, clip));
+
+        result
+    }
+}
+
+impl<'a, V: 'a + Send + Clone, T: 'a + Send, F: 'a + Send + Fn(V) -> T> IntoNode<'a, T> for Radio<'a, V, T, F> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
@@ -0,0 +1,276 @@
+use crate::draw::*;
+use crate::event::{Event, Key};
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::{StyleState, Stylesheet};
+use crate::widget::{dummy::Dummy, Context, StateVec, Widget};
+
+/// Pick a rating by clicking on one of a number of stars.
+/// The stars can be styled using the `star`, `star-filled` and `star-preview` child widgets of this widget.
+/// `star` is drawn for every star, `star-filled` is drawn on top of it for the stars up to the current value and
+/// `star-preview` is drawn instead of `star-filled` while the mouse hovers over the widget.
+///
+/// When half star precision is enabled, the value counts half stars. A value of 7 means three and a half stars.
+pub struct Rating<'a, T, F> {
+    star: Node<'a, T>,
+    filled: Node<'a, T>,
+    preview: Node<'a, T>,
+    stars: u32,
+    value: u32,
+    half_stars: bool,
+    on_rate: F,
+}
+
+/// State for [`Rating`](struct.Rating.html)
+#[derive(Default)]
+pub struct State {
+    hover: Option<u32>,
+    focused: bool,
+}
+
+impl<'a, T: 'a, F: 'a + Fn(u32) -> T> Rating<'a, T, F> {
+    /// Construct a new `Rating`
+    pub fn new(stars: u32, value: u32, on_rate: F) -> Self {
+        Self {
+            star: Dummy::new("star").into_node(),
+            filled: Dummy::new("star-filled").into_node(),
+            preview: Dummy::new("star-preview").into_node(),
+            stars,
+            value,
+            half_stars: false,
+            on_rate,
+        }
+    }
+
+    /// Sets the number of stars to display.
+    pub fn stars(mut self, stars: u32) -> Self {
+        self.stars = stars;
+        self
+    }
+
+    /// Sets the current value of the rating.
+    pub fn val(mut self, value: u32) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Enables half star precision. When enabled, values count half stars instead of stars.
+    pub fn half_stars(mut self, half_stars: bool) -> Self {
+        self.half_stars = half_stars;
+        self
+    }
+
+    /// Sets the on_rate callback of the rating, which is called when the user picks a new value.
+    pub fn on_rate<N: Fn(u32) -> T>(self, on_rate: N) -> Rating<'a, T, N> {
+        Rating {
+            star: self.star,
+            filled: self.filled,
+            preview: self.preview,
+            stars: self.stars,
+            value: self.value,
+            half_stars: self.half_stars,
+            on_rate,
+        }
+    }
+
+    fn max_value(&self) -> u32 {
+        if self.half_stars {
+            self.stars * 2
+        } else {
+            self.stars
+        }
+    }
+
+    fn star_size(&self, style: &Stylesheet) -> (f32, f32) {
+        let (width, height) = self.star.size();
+        let width = match width {
+            Size::Exact(width) => width,
+            _ => style.text_size,
+        };
+        let height = match height {
+            Size::Exact(height) => height,
+            _ => style.text_size,
+        };
+        (width, height)
+    }
+
+    fn star_rect(&self, index: u32, layout: Rectangle, style: &Stylesheet) -> Rectangle {
+        let content = style.background.content_rect(layout, style.padding);
+        let (width, height) = self.star_size(style);
+        Rectangle::from_xywh(content.left + width * index as f32, content.top, width, height)
+    }
+
+    fn value_at(&self, x: f32, y: f32, layout: Rectangle, style: &Stylesheet) -> Option<u32> {
+        (0..self.stars).find_map(|index| {
+            let rect = self.star_rect(index, layout, style);
+            if rect.point_inside(x, y) {
+                if self.half_stars {
+                    Some(if x < rect.left + rect.width() * 0.5 {
+                        index * 2 + 1
+                    } else {
+                        index * 2 + 2
+                    })
+                } else {
+                    Some(index + 1)
+                }
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl<'a, T: 'a> Default for Rating<'a, T, fn(u32) -> T> {
+    fn default() -> Self {
+        Self {
+            star: Dummy::new("star").into_node(),
+            filled: Dummy::new("star-filled").into_node(),
+            preview: Dummy::new("star-preview").into_node(),
+            stars: 5,
+            value: 0,
+            half_stars: false,
+            on_rate: |_| panic!("on_rate of `Rating` must be set"),
+        }
+    }
+}
+
+impl<'a, T: 'a, F: 'a + Send + Fn(u32) -> T> Widget<'a, T> for Rating<'a, T, F> {
+    type State = State;
+
+    fn mount(&self) -> Self::State {
+        State::default()
+    }
+
+    fn widget(&self) -> &'static str {
+        "rating"
+    }
+
+    fn state(&self, state: &State) -> StateVec {
+        let mut result = StateVec::new();
+        if state.hover.is_some() {
+            result.push(StyleState::Hover);
+        }
+        if state.focused {
+            result.push(StyleState::Focused);
+        }
+        result
+    }
+
+    fn len(&self) -> usize {
+        3
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut *self.star);
+        visitor(&mut *self.filled);
+        visitor(&mut *self.preview);
+    }
+
+    fn size(&self, _: &State, style: &Stylesheet) -> (Size, Size) {
+        let (width, height) = self.star_size(style);
+        style.background.resolve_size(
+            (style.width, style.height),
+            (Size::Exact(width * self.stars as f32), Size::Exact(height)),
+            style.padding,
+        )
+    }
+
+    fn focused(&self, state: &State) -> bool {
+        state.focused
+    }
+
+    fn focusable(&self, _: &State) -> bool {
+        true
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        match event {
+            Event::Cursor(x, y) => {
+                let hover = if clip.point_inside(x, y) {
+                    self.value_at(x, y, layout, style)
+                } else {
+                    None
+                };
+                if hover != state.hover {
+                    state.hover = hover;
+                    context.redraw();
+                }
+            }
+            Event::Press(Key::LeftMouseButton) => {
+                if let Some(value) = state.hover {
+                    context.push((self.on_rate)(value));
+                    state.focused = true;
+                    context.redraw();
+                } else if state.focused {
+                    state.focused = false;
+                    context.redraw();
+                }
+            }
+            Event::Press(Key::Left) if state.focused && self.value > 0 => {
+                context.push((self.on_rate)(self.value.min(self.max_value()) - 1));
+                context.redraw();
+            }
+            Event::Press(Key::Right) if state.focused && self.value < self.max_value() => {
+                context.push((self.on_rate)(self.value + 1));
+                context.redraw();
+            }
+            _ => (),
+        }
+    }
+
+    fn draw(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+    ) -> Vec<Primitive<'a>> {
+        let mut result = Vec::new();
+        result.extend(style.background.render(layout));
+
+        let value = state.hover.unwrap_or(self.value);
+        let step = if self.half_stars { 2 } else { 1 };
+
+        for index in 0..self.stars {
+            let rect = self.star_rect(index, layout, style);
+            result.extend(self.star.draw(rect, clip));
+
+            let fill = if state.hover.is_some() {
+                &mut self.preview
+            } else {
+                &mut self.filled
+            };
+
+            let filled = value.saturating_sub(index * step).min(step);
+            if filled == step {
+                result.extend(fill.draw(rect, clip));
+            } else if filled > 0 {
+                let half = Rectangle {
+                    right: rect.left + rect.width() * 0.5,
+                    ..rect
+                };
+                if let Some(half_clip) = half.intersect(&clip) {
+                    result.push(Primitive::PushClip(half_clip));
+                    result.extend(fill.draw(rect, half_clip));
+                    result.push(Primitive::PopClip);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<'a, T: 'a, F: 'a + Send + Fn(u32) -> T> IntoNode<'a, T> for Rating<'a, T, F> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
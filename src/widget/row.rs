@@ -3,11 +3,11 @@ use crate::event::Event;
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
 use crate::style::Stylesheet;
-use crate::widget::Context;
+use crate::widget::{Context, CursorResponse};
 
 use super::Widget;
 
-/// Layout child widgets horizontally
+/// Layout child widgets horizontally. The gap between the children can be set with the `spacing` style property.
 pub struct Row<'a, T> {
     children: Vec<Node<'a, T>>,
     layout: Vec<Rectangle>,
@@ -48,20 +48,28 @@ impl<'a, T: 'a> Row<'a, T> {
         let layout = style.background.content_rect(layout, style.padding);
         if self.layout.len() != self.children.len() {
             let align = style.align_vertical;
-            let available_parts = self.children.iter().map(|c| c.size().0.parts()).sum();
-            let available_space = layout.width() - self.children.iter().map(|c| c.size().0.min_size()).sum::<f32>();
+            let spacing = style.spacing;
+            let gaps = spacing * self.children.len().saturating_sub(1) as f32;
+            let widths = self
+                .children
+                .iter()
+                .map(|c| (c.size().0, c.size_limits().0))
+                .collect::<Vec<_>>();
+            let widths = Size::resolve_stacked_limited(&widths, layout.width(), layout.width() - gaps);
             let mut cursor = 0.0;
             self.layout = self
                 .children
                 .iter()
-                .map(|child| {
-                    let (w, h) = child.size();
-                    let w = w.resolve(available_space, available_parts).min(layout.width() - cursor);
-                    let h = h.resolve(layout.height(), h.parts());
+                .zip(widths)
+                .map(|(child, w)| {
+                    let (_, h) = child.size();
+                    let (min, max) = child.size_limits().1;
+                    let w = w.min((layout.width() - cursor).max(0.0));
+                    let h = h.resolve(layout.height(), h.parts()).min(max).max(min);
                     let x = cursor;
                     let y = align.resolve_start(h, layout.height());
 
-                    cursor += w;
+                    cursor += w + spacing;
                     Rectangle::from_xywh(x, y, w, h)
                 })
                 .collect();
@@ -92,6 +100,10 @@ impl<'a, T: 'a> Widget<'a, T> for Row<'a, T> {
         "row"
     }
 
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
     fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
         self.children.iter_mut().for_each(|child| visitor(&mut **child));
     }
@@ -102,10 +114,13 @@ impl<'a, T: 'a> Widget<'a, T> for Row<'a, T> {
 
     fn size(&self, _: &(), style: &Stylesheet) -> (Size, Size) {
         let width = match style.width {
-            Size::Shrink => Size::Exact(self.children.iter().fold(0.0, |size, child| match child.size().0 {
-                Size::Exact(child_size) => size + child_size,
-                _ => size,
-            })),
+            Size::Shrink => Size::Exact(self.children.iter().fold(
+                style.spacing * self.children.len().saturating_sub(1) as f32,
+                |size, child| match child.size().0 {
+                    Size::Exact(child_size) => size + child_size,
+                    _ => size,
+                },
+            )),
             other => other,
         };
         let height = match style.height {
@@ -159,10 +174,10 @@ impl<'a, T: 'a> Widget<'a, T> for Row<'a, T> {
 
         for (index, (child, layout)) in self.layout_mut(layout, stylesheet).enumerate() {
             if Some(index) == focused {
-                child.event(layout, clip, event, context);
+                child.event(layout, clip, event.clone(), context);
             } else if focused.is_none() {
                 if let Some(clip) = clip.intersect(&layout) {
-                    child.event(layout, clip, event, context);
+                    child.event(layout, clip, event.clone(), context);
                 }
             }
         }
@@ -1,17 +1,67 @@
 use crate::draw::*;
 use crate::event::{Event, Key};
-use crate::layout::{Rectangle, Size};
+use crate::layout::{Direction, Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
-use crate::style::Stylesheet;
-use crate::widget::{dummy::Dummy, Context, Widget};
+use crate::style::{StyleState, Stylesheet};
+use crate::widget::{dummy::Dummy, Context, StateVec, Widget};
 
 /// View a small section of larger widget, with scrollbars.
 /// The scrollbars are only rendered if the content is larger than the view in that direction.
-/// The scrollbars can be styled using the `scrollbar-horizontal` and `scrollbar-vertical` child widgets of this widget.
+///
+/// The scrollbars are drawn in the padding of the scroll: the vertical scrollbar in the right padding and the
+/// horizontal scrollbar in the bottom padding, so the padding determines how thick they are. The tracks are part of
+/// the background of the scroll, while the thumbs can be styled using the `scrollbar-horizontal` and
+/// `scrollbar-vertical` child widgets of this widget. The scroll is `:hover` while a thumb is hovered and `:pressed`
+/// while a thumb is dragged, so a thumb can be highlighted with a selector like `scroll:pressed > scrollbar-vertical`.
+/// Dragging a thumb scrolls the content, and clicking a track outside of the thumb scrolls by one page towards the
+/// click.
+///
+/// The content can be scrolled using the mouse wheel or touchpad while the cursor is over the scroll.
+///
+/// By default the content can be scrolled in both directions, whenever it's larger than the view. Use
+/// [`direction`](#method.direction) to only scroll horizontally or vertically, in which case the content is fit to the
+/// view in the other direction.
+///
+/// Clicking inside the scroll gives it keyboard focus, until something outside of it is clicked.
+/// While it has keyboard focus, the view can be scrolled using the arrow keys, page up, page down, home and end.
+/// Keys are not handled while a child widget is focused, so they don't interfere with child inputs.
+///
+/// Inside a widget that shrinks to fit it's content, like a `shrink` column, a `fill` size of the scroll resolves to
+/// it's minimum size instead of growing to fit the content that it views. The scroll still fills the space that the
+/// column shrinks to, so in the example below it's as wide as the widest sibling and as tall as it's exact height:
+/// ```
+/// # use pixel_widgets::prelude::*;
+/// # struct Log;
+/// # impl Component for Log {
+/// #     type State = ();
+/// #     type Message = ();
+/// #     type Output = ();
+/// #     fn mount(&self, _: &mut Runtime<()>) {}
+/// fn view<'a>(&'a self, _: &'a ()) -> Node<'a, ()> {
+///     view! {
+///         Column => {
+///             Canvas { draw: |_| vec![], measure: || (120.0, 20.0) }
+///             Scroll => {
+///                 Canvas { draw: |_| vec![], measure: || (80.0, 300.0) }
+///             }
+///         }
+///     }
+/// }
+/// # }
+/// # let read = |_: &std::path::Path| async { Ok::<_, std::io::Error>(Vec::new()) };
+/// let style = "column { width: shrink; height: shrink; } scroll { width: fill(1); height: 50; }";
+/// let style = StyleBuilder::from_string_with(style, read).unwrap();
+/// let mut ui = Ui::new(Log, Rectangle::from_wh(200.0, 100.0), 1.0, style).unwrap();
+///
+/// let column = ui.layout_snapshot();
+/// assert_eq!(column.layout, Some(Rectangle::from_xywh(0.0, 0.0, 120.0, 70.0)));
+/// assert_eq!(column.children[1].layout, Some(Rectangle::from_xywh(0.0, 20.0, 120.0, 50.0)));
+/// ```
 pub struct Scroll<'a, T> {
     content: Option<Node<'a, T>>,
     scrollbar_h: Node<'a, T>,
     scrollbar_v: Node<'a, T>,
+    direction: Option<Direction>,
 }
 
 /// State for [`Scroll`](struct.Scroll.html)
@@ -21,6 +71,7 @@ pub struct State {
     scroll_y: f32,
     cursor_x: f32,
     cursor_y: f32,
+    focused: bool,
 }
 
 #[derive(Clone, Copy)]
@@ -39,9 +90,57 @@ impl<'a, T: 'a> Scroll<'a, T> {
             content: Some(content.into_node()),
             scrollbar_h: Dummy::new("scrollbar-horizontal").into_node(),
             scrollbar_v: Dummy::new("scrollbar-vertical").into_node(),
+            direction: None,
         }
     }
 
+    /// Only scroll in one direction. `LeftToRight` and `RightToLeft` scroll horizontally, `TopToBottom` and
+    /// `BottomToTop` scroll vertically. The mouse wheel scrolls in this direction, and page up, page down, home and end
+    /// move along it.
+    ///
+    /// `RightToLeft` and `BottomToTop` start scrolled to the right or bottom end of the content, and stay at the same
+    /// distance from that end when the content grows, which is useful for logs and chat messages.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Returns whether the content can be scrolled horizontally and vertically.
+    fn axes(&self) -> (bool, bool) {
+        match self.direction {
+            None => (true, true),
+            Some(Direction::LeftToRight | Direction::RightToLeft) => (true, false),
+            Some(Direction::TopToBottom | Direction::BottomToTop) => (false, true),
+        }
+    }
+
+    /// Returns whether the horizontal and vertical scroll positions are measured from the right and bottom end.
+    fn reversed(&self) -> (bool, bool) {
+        match self.direction {
+            Some(Direction::RightToLeft) => (true, false),
+            Some(Direction::BottomToTop) => (false, true),
+            _ => (false, false),
+        }
+    }
+
+    /// Converts between the scroll position in the state and the offset of the view from the top left of the content,
+    /// given the maximum offset. The conversion is the same in both ways.
+    fn mirror(&self, (x, y): (f32, f32), (max_x, max_y): (f32, f32)) -> (f32, f32) {
+        let (reverse_x, reverse_y) = self.reversed();
+        (
+            if reverse_x { max_x - x } else { x },
+            if reverse_y { max_y - y } else { y },
+        )
+    }
+
+    /// Scrolls the view to an offset from the top left of the content, which is clamped to the maximum offset.
+    fn scroll_to(&self, state: &mut State, (x, y): (f32, f32), (max_x, max_y): (f32, f32)) {
+        let offset = (x.max(0.0).min(max_x), y.max(0.0).min(max_y));
+        let (x, y) = self.mirror(offset, (max_x, max_y));
+        state.scroll_x = x;
+        state.scroll_y = y;
+    }
+
     /// Sets the content widget from the first element of an iterator.
     pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T>>(mut self, iter: I) -> Self {
         if self.content.is_none() {
@@ -50,25 +149,33 @@ impl<'a, T: 'a> Scroll<'a, T> {
         self
     }
 
-    fn scrollbars(
-        &self,
-        state: &State,
-        layout: Rectangle,
-        content: Rectangle,
-        style: &Stylesheet,
-    ) -> (Rectangle, Rectangle) {
+    /// Returns the vertical and horizontal track that the thumbs move along.
+    fn tracks(&self, layout: Rectangle, content_rect: Rectangle) -> (Rectangle, Rectangle) {
+        let vertical = Rectangle {
+            left: content_rect.right,
+            top: layout.top,
+            right: layout.right,
+            bottom: content_rect.bottom,
+        };
+        let horizontal = Rectangle {
+            left: layout.left,
+            top: content_rect.bottom,
+            right: content_rect.right,
+            bottom: layout.bottom,
+        };
+        (vertical, horizontal)
+    }
+
+    /// Returns the vertical and horizontal thumb.
+    fn scrollbars(&self, layout: Rectangle, content: Rectangle, style: &Stylesheet) -> (Rectangle, Rectangle) {
         let content_rect = style.background.content_rect(layout, style.padding);
+        let (vertical_track, horizontal_track) = self.tracks(layout, content_rect);
 
         let vertical_rect = {
-            let mut bar = Rectangle {
-                left: content_rect.right,
-                top: layout.top,
-                right: layout.right,
-                bottom: content_rect.bottom,
-            };
+            let mut bar = vertical_track;
             let handle_range = handle_range(
                 bar.top,
-                state.scroll_y,
+                content_rect.top - content.top,
                 bar.height(),
                 content.height() - content_rect.height(),
             );
@@ -78,15 +185,10 @@ impl<'a, T: 'a> Scroll<'a, T> {
         };
 
         let horizontal_rect = {
-            let mut bar = Rectangle {
-                left: layout.left,
-                top: content_rect.bottom,
-                right: content_rect.right,
-                bottom: layout.bottom,
-            };
+            let mut bar = horizontal_track;
             let handle_range = handle_range(
                 bar.left,
-                state.scroll_x,
+                content_rect.left - content.left,
                 bar.width(),
                 content.width() - content_rect.width(),
             );
@@ -100,18 +202,61 @@ impl<'a, T: 'a> Scroll<'a, T> {
 
     fn content_layout(&self, state: &State, content_rect: &Rectangle) -> Rectangle {
         let content_size = self.content().size();
-        Rectangle::from_xywh(
-            content_rect.left - state.scroll_x,
-            content_rect.top - state.scroll_y,
-            content_size
-                .0
-                .resolve(content_rect.width(), content_size.0.parts())
-                .max(content_size.0.min_size()),
-            content_size
-                .1
-                .resolve(content_rect.height(), content_size.1.parts())
-                .max(content_size.1.min_size()),
-        )
+        let (horizontal, vertical) = self.axes();
+        // the content is only allowed to overflow in the directions that can be scrolled.
+        let resolve = |size: Size, available: f32, overflow: bool| {
+            let resolved = size.resolve(available, size.parts());
+            if overflow {
+                resolved.max(size.min_size())
+            } else {
+                resolved
+            }
+        };
+        let width = resolve(content_size.0, content_rect.width(), horizontal);
+        let height = resolve(content_size.1, content_rect.height(), vertical);
+        let max = (
+            (width - content_rect.width()).max(0.0),
+            (height - content_rect.height()).max(0.0),
+        );
+        let (x, y) = self.mirror((state.scroll_x, state.scroll_y), max);
+        Rectangle::from_xywh(content_rect.left - x, content_rect.top - y, width, height)
+    }
+
+    /// Handles a key press while the scroll has keyboard focus. Returns `false` if the key is not a navigation key.
+    fn navigate(
+        &self,
+        state: &mut State,
+        key: Key,
+        content_layout: Rectangle,
+        content_rect: Rectangle,
+        line: f32,
+    ) -> bool {
+        let max_x = (content_layout.width() - content_rect.width()).max(0.0);
+        let max_y = (content_layout.height() - content_rect.height()).max(0.0);
+        let line_x = (content_rect.width() * 0.1).max(line);
+        let line_y = (content_rect.height() * 0.1).max(line);
+        let horizontal = self.axes() == (true, false);
+        let scroll_x = content_rect.left - content_layout.left;
+        let scroll_y = content_rect.top - content_layout.top;
+
+        let (x, y) = match key {
+            Key::Left => (scroll_x - line_x, scroll_y),
+            Key::Right => (scroll_x + line_x, scroll_y),
+            Key::Up => (scroll_x, scroll_y - line_y),
+            Key::Down => (scroll_x, scroll_y + line_y),
+            Key::PageUp if horizontal => (scroll_x - content_rect.width(), scroll_y),
+            Key::PageDown if horizontal => (scroll_x + content_rect.width(), scroll_y),
+            Key::Home if horizontal => (0.0, scroll_y),
+            Key::End if horizontal => (max_x, scroll_y),
+            Key::PageUp => (scroll_x, scroll_y - content_rect.height()),
+            Key::PageDown => (scroll_x, scroll_y + content_rect.height()),
+            Key::Home => (scroll_x, 0.0),
+            Key::End => (scroll_x, max_y),
+            _ => return false,
+        };
+
+        self.scroll_to(state, (x.floor(), y.floor()), (max_x, max_y));
+        true
     }
 
     fn content(&self) -> &Node<'a, T> {
@@ -129,6 +274,7 @@ impl<'a, T: 'a> Default for Scroll<'a, T> {
             content: None,
             scrollbar_h: Dummy::new("scrollbar-horizontal").into_node(),
             scrollbar_v: Dummy::new("scrollbar-vertical").into_node(),
+            direction: None,
         }
     }
 }
@@ -160,6 +306,19 @@ impl<'a, T: 'a> Widget<'a, T> for Scroll<'a, T> {
             .resolve_size((style.width, style.height), self.content().size(), style.padding)
     }
 
+    fn state(&self, state: &State) -> StateVec {
+        let mut result = StateVec::new();
+        if state.focused {
+            result.push(StyleState::Focused);
+        }
+        match state.inner {
+            InnerState::HoverHorizontalBar | InnerState::HoverVerticalBar => result.push(StyleState::Hover),
+            InnerState::DragHorizontalBar(_) | InnerState::DragVerticalBar(_) => result.push(StyleState::Pressed),
+            InnerState::Idle => (),
+        }
+        result
+    }
+
     fn focused(&self, _: &State) -> bool {
         self.content().focused()
     }
@@ -175,49 +334,97 @@ impl<'a, T: 'a> Widget<'a, T> for Scroll<'a, T> {
     ) {
         let content_rect = style.background.content_rect(layout, style.padding);
         let content_layout = self.content_layout(&*state, &content_rect);
-        let (vbar, hbar) = self.scrollbars(&*state, layout, content_layout, style);
+        let (vbar, hbar) = self.scrollbars(layout, content_layout, style);
+        let (vtrack, htrack) = self.tracks(layout, content_rect);
+        let (scroll_h, scroll_v) = (
+            content_layout.width() > layout.width(),
+            content_layout.height() > layout.height(),
+        );
+        let scroll = (
+            content_rect.left - content_layout.left,
+            content_rect.top - content_layout.top,
+        );
+        let max = (
+            (content_layout.width() - content_rect.width()).max(0.0),
+            (content_layout.height() - content_rect.height()).max(0.0),
+        );
 
         if self.content().focused() {
             self.content_mut().event(content_layout, content_rect, event, context);
             return;
         }
 
-        match (event, state.inner) {
+        match event {
+            Event::Press(Key::LeftMouseButton) => {
+                let inside = layout.point_inside(state.cursor_x, state.cursor_y)
+                    && clip.point_inside(state.cursor_x, state.cursor_y);
+                if inside != state.focused {
+                    state.focused = inside;
+                    context.redraw();
+                }
+            }
+            Event::Press(key)
+                if state.focused && self.navigate(state, key, content_layout, content_rect, style.text_size) =>
+            {
+                context.redraw();
+                return;
+            }
+            Event::Scroll(dx, dy)
+                if layout.point_inside(state.cursor_x, state.cursor_y)
+                    && clip.point_inside(state.cursor_x, state.cursor_y) =>
+            {
+                // the content is scrolled first, so nested scrolls only scroll the outer scroll when the inner one
+                // can't scroll any further.
+                if let (Some(clip), InnerState::Idle) = (clip.intersect(&content_rect), state.inner) {
+                    self.content_mut().event(content_layout, clip, event, context);
+                }
+                if context.scroll_consumed() {
+                    return;
+                }
+
+                // a mouse wheel only scrolls vertically, so it scrolls horizontally when that's the only direction.
+                let (dx, dy) = if self.axes() == (true, false) && dx == 0.0 {
+                    (dy, 0.0)
+                } else {
+                    (dx, dy)
+                };
+                let (x, y) = (state.scroll_x, state.scroll_y);
+                self.scroll_to(state, (scroll.0 - dx, scroll.1 - dy), max);
+                if (x, y) != (state.scroll_x, state.scroll_y) {
+                    context.redraw();
+                    context.consume_scroll();
+                }
+                return;
+            }
+            _ => (),
+        }
+
+        match (event.clone(), state.inner) {
             (Event::Cursor(cx, cy), InnerState::DragHorizontalBar(x)) => {
                 context.redraw();
                 state.cursor_x = cx;
                 state.cursor_y = cy;
 
-                let bar = Rectangle {
-                    left: layout.left,
-                    top: content_rect.bottom,
-                    right: content_rect.right,
-                    bottom: layout.bottom,
-                };
-                state.scroll_x = handle_to_scroll(
-                    bar.left,
+                let x = handle_to_scroll(
+                    htrack.left,
                     cx - x,
-                    bar.width(),
+                    htrack.width(),
                     content_layout.width() - content_rect.width(),
                 );
+                self.scroll_to(state, (x, scroll.1), max);
             }
             (Event::Cursor(cx, cy), InnerState::DragVerticalBar(y)) => {
                 context.redraw();
                 state.cursor_x = cx;
                 state.cursor_y = cy;
 
-                let bar = Rectangle {
-                    left: content_rect.right,
-                    top: layout.top,
-                    right: layout.right,
-                    bottom: content_rect.bottom,
-                };
-                state.scroll_y = handle_to_scroll(
-                    bar.top,
+                let y = handle_to_scroll(
+                    vtrack.top,
                     cy - y,
-                    bar.height(),
+                    vtrack.height(),
                     content_layout.height() - content_rect.height(),
                 );
+                self.scroll_to(state, (scroll.0, y), max);
             }
             (Event::Cursor(x, y), _) => {
                 if let Some(clip) = clip.intersect(&content_rect) {
@@ -225,22 +432,52 @@ impl<'a, T: 'a> Widget<'a, T> for Scroll<'a, T> {
                 }
                 state.cursor_x = x;
                 state.cursor_y = y;
-                if hbar.point_inside(x, y) && clip.point_inside(x, y) {
+                let hovered = state.inner;
+                if scroll_h && hbar.point_inside(x, y) && clip.point_inside(x, y) {
                     state.inner = InnerState::HoverHorizontalBar;
-                } else if vbar.point_inside(x, y) && clip.point_inside(x, y) {
+                } else if scroll_v && vbar.point_inside(x, y) && clip.point_inside(x, y) {
                     state.inner = InnerState::HoverVerticalBar;
                 } else {
                     state.inner = InnerState::Idle;
                 }
+                if std::mem::discriminant(&hovered) != std::mem::discriminant(&state.inner) {
+                    context.redraw();
+                }
             }
             (Event::Press(Key::LeftMouseButton), InnerState::HoverHorizontalBar) => {
+                context.redraw();
                 state.inner = InnerState::DragHorizontalBar(state.cursor_x - hbar.left);
             }
             (Event::Press(Key::LeftMouseButton), InnerState::HoverVerticalBar) => {
+                context.redraw();
                 state.inner = InnerState::DragVerticalBar(state.cursor_y - vbar.top);
             }
+            (Event::Press(Key::LeftMouseButton), InnerState::Idle)
+                if clip.point_inside(state.cursor_x, state.cursor_y)
+                    && ((scroll_h && htrack.point_inside(state.cursor_x, state.cursor_y))
+                        || (scroll_v && vtrack.point_inside(state.cursor_x, state.cursor_y))) =>
+            {
+                // the thumb is not hovered, so the track was clicked next to the thumb.
+                context.redraw();
+                if htrack.point_inside(state.cursor_x, state.cursor_y) {
+                    let page = if state.cursor_x < hbar.left {
+                        -content_rect.width()
+                    } else {
+                        content_rect.width()
+                    };
+                    self.scroll_to(state, ((scroll.0 + page).floor(), scroll.1), max);
+                } else {
+                    let page = if state.cursor_y < vbar.top {
+                        -content_rect.height()
+                    } else {
+                        content_rect.height()
+                    };
+                    self.scroll_to(state, (scroll.0, (scroll.1 + page).floor()), max);
+                }
+            }
             (Event::Release(Key::LeftMouseButton), InnerState::DragHorizontalBar(_))
             | (Event::Release(Key::LeftMouseButton), InnerState::DragVerticalBar(_)) => {
+                context.redraw();
                 if hbar.point_inside(state.cursor_x, state.cursor_y)
                     && clip.point_inside(state.cursor_x, state.cursor_y)
                 {
@@ -271,7 +508,7 @@ impl<'a, T: 'a> Widget<'a, T> for Scroll<'a, T> {
     ) -> Vec<Primitive<'a>> {
         let content_rect = style.background.content_rect(layout, style.padding);
         let content_layout = self.content_layout(&*state, &content_rect);
-        let (vbar, hbar) = self.scrollbars(&*state, layout, content_layout, style);
+        let (vbar, hbar) = self.scrollbars(layout, content_layout, style);
 
         let mut result = Vec::new();
         result.extend(style.background.render(layout));
@@ -304,6 +541,7 @@ impl Default for State {
             scroll_y: 0.0,
             cursor_x: 0.0,
             cursor_y: 0.0,
+            focused: false,
         }
     }
 }
@@ -0,0 +1,301 @@
+use crate::draw::*;
+use crate::event::{Event, Key};
+use crate::layout::{Direction, Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::{StyleState, Stylesheet};
+use crate::widget::{dummy::Dummy, Context, StateVec, Widget};
+
+/// A scroll bar with a draggable thumb, for scrolling through content that is larger than what fits in view.
+/// The scroll bar doesn't own the content it scrolls, so it can be attached to any view, such as a virtualized list.
+///
+/// The `value` is the scroll offset, which ranges from 0 to `total - page_size`. The length of the thumb reflects the
+/// part of the content that is in view, `page_size / total`. Clicking the track outside of the thumb scrolls by one
+/// page towards the click. New values are reported to the `on_scroll` callback.
+///
+/// The orientation follows the `layout-direction` of the scroll bar: `left-to-right` and `right-to-left` scroll bars
+/// are horizontal, `top-to-bottom` and `bottom-to-top` scroll bars are vertical. The default is horizontal.
+/// The size of the scroll bar is taken from it's `width` and `height`.
+///
+/// The thumb can be styled using the `thumb` child widget of this widget. The scroll bar is `:hover` while the thumb is
+/// hovered and `:pressed` while the thumb is dragged.
+pub struct ScrollBar<'a, T, F> {
+    thumb: Node<'a, T>,
+    value: f32,
+    page_size: f32,
+    total: f32,
+    on_scroll: F,
+}
+
+/// State for [`ScrollBar`](struct.ScrollBar.html)
+pub struct State {
+    inner: InnerState,
+    cursor: (f32, f32),
+}
+
+#[derive(Clone, Copy)]
+enum InnerState {
+    Idle,
+    Hover,
+    Drag { value: f32, cursor: f32 },
+}
+
+impl<'a, T: 'a, F: 'a + Fn(f32) -> T> ScrollBar<'a, T, F> {
+    /// Construct a new `ScrollBar`
+    pub fn new(value: f32, page_size: f32, total: f32, on_scroll: F) -> Self {
+        Self {
+            thumb: Dummy::new("thumb").into_node(),
+            value,
+            page_size,
+            total,
+            on_scroll,
+        }
+    }
+
+    /// Sets the current scroll offset.
+    pub fn val(mut self, value: f32) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Sets the size of the part of the content that is in view.
+    pub fn page_size(mut self, page_size: f32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Sets the total size of the content.
+    pub fn total(mut self, total: f32) -> Self {
+        self.total = total;
+        self
+    }
+
+    /// Sets the on_scroll callback, which is called with the new scroll offset when the user scrolls.
+    pub fn on_scroll<N: Fn(f32) -> T>(self, on_scroll: N) -> ScrollBar<'a, T, N> {
+        ScrollBar {
+            thumb: self.thumb,
+            value: self.value,
+            page_size: self.page_size,
+            total: self.total,
+            on_scroll,
+        }
+    }
+
+    /// The largest possible scroll offset.
+    fn max_value(&self) -> f32 {
+        (self.total - self.page_size).max(0.0)
+    }
+
+    /// Returns the thumb rectangle and the length of the track that the thumb can move along.
+    fn thumb(&self, layout: Rectangle, style: &Stylesheet) -> (Rectangle, f32) {
+        let content = style.background.content_rect(layout, style.padding);
+        let (horizontal, reversed) = axis(style.direction);
+        let (start, end, thickness) = if horizontal {
+            (content.left, content.right, content.height())
+        } else {
+            (content.top, content.bottom, content.width())
+        };
+
+        let track = end - start;
+        let ratio = if self.total > 0.0 {
+            (self.page_size / self.total).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        // keep the thumb at least as long as the bar is thick, so it can always be grabbed.
+        let length = (track * ratio).max(thickness.min(track));
+        let range = track - length;
+
+        let max_value = self.max_value();
+        let t = if max_value > 0.0 {
+            (self.value / max_value).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let offset = range * t;
+        let (a, b) = if reversed {
+            (end - offset - length, end - offset)
+        } else {
+            (start + offset, start + offset + length)
+        };
+
+        let rect = if horizontal {
+            Rectangle {
+                left: a,
+                right: b,
+                ..content
+            }
+        } else {
+            Rectangle {
+                top: a,
+                bottom: b,
+                ..content
+            }
+        };
+        (rect, range)
+    }
+
+    fn scroll_to(&mut self, value: f32, context: &mut Context<T>) {
+        let value = value.clamp(0.0, self.max_value());
+        if value != self.value {
+            self.value = value;
+            context.redraw();
+            context.push((self.on_scroll)(value));
+        }
+    }
+}
+
+impl<'a, T: 'a> Default for ScrollBar<'a, T, fn(f32) -> T> {
+    fn default() -> Self {
+        Self {
+            thumb: Dummy::new("thumb").into_node(),
+            value: 0.0,
+            page_size: 0.0,
+            total: 0.0,
+            on_scroll: |_| panic!("on_scroll of `ScrollBar` must be set"),
+        }
+    }
+}
+
+impl<'a, T: 'a, F: 'a + Send + Fn(f32) -> T> Widget<'a, T> for ScrollBar<'a, T, F> {
+    type State = State;
+
+    fn mount(&self) -> State {
+        State::default()
+    }
+
+    fn widget(&self) -> &'static str {
+        "scrollbar"
+    }
+
+    fn state(&self, state: &State) -> StateVec {
+        let mut result = StateVec::new();
+        match state.inner {
+            InnerState::Hover => result.push(StyleState::Hover),
+            InnerState::Drag { .. } => result.push(StyleState::Pressed),
+            InnerState::Idle => (),
+        }
+        result
+    }
+
+    fn len(&self) -> usize {
+        1
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut *self.thumb);
+    }
+
+    fn size(&self, _: &State, style: &Stylesheet) -> (Size, Size) {
+        (style.width, style.height)
+    }
+
+    fn focused(&self, state: &State) -> bool {
+        matches!(state.inner, InnerState::Drag { .. })
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        let (thumb, range) = self.thumb(layout, style);
+        let (horizontal, reversed) = axis(style.direction);
+        let main = |(x, y): (f32, f32)| if horizontal { x } else { y };
+
+        match (event, state.inner) {
+            (Event::Cursor(x, y), InnerState::Drag { value, cursor }) => {
+                state.cursor = (x, y);
+                if range > 0.0 {
+                    let delta = if reversed {
+                        cursor - main(state.cursor)
+                    } else {
+                        main(state.cursor) - cursor
+                    };
+                    self.scroll_to(value + delta / range * self.max_value(), context);
+                }
+            }
+            (Event::Cursor(x, y), _) => {
+                state.cursor = (x, y);
+                let hover = thumb.point_inside(x, y) && clip.point_inside(x, y);
+                let next = if hover { InnerState::Hover } else { InnerState::Idle };
+                if matches!(state.inner, InnerState::Hover) != hover {
+                    context.redraw();
+                }
+                state.inner = next;
+            }
+            (Event::Press(Key::LeftMouseButton), InnerState::Hover) => {
+                context.redraw();
+                state.inner = InnerState::Drag {
+                    value: self.value,
+                    cursor: main(state.cursor),
+                };
+            }
+            (Event::Press(Key::LeftMouseButton), InnerState::Idle) => {
+                let (x, y) = state.cursor;
+                let track = style.background.content_rect(layout, style.padding);
+                if track.point_inside(x, y) && clip.point_inside(x, y) {
+                    let (thumb_start, thumb_end) = if horizontal {
+                        (thumb.left, thumb.right)
+                    } else {
+                        (thumb.top, thumb.bottom)
+                    };
+                    let before = if reversed {
+                        main(state.cursor) > thumb_end
+                    } else {
+                        main(state.cursor) < thumb_start
+                    };
+                    let page = if before { -self.page_size } else { self.page_size };
+                    self.scroll_to(self.value + page, context);
+                }
+            }
+            (Event::Release(Key::LeftMouseButton), InnerState::Drag { .. }) => {
+                context.redraw();
+                let (x, y) = state.cursor;
+                let (thumb, _) = self.thumb(layout, style);
+                state.inner = if thumb.point_inside(x, y) && clip.point_inside(x, y) {
+                    InnerState::Hover
+                } else {
+                    InnerState::Idle
+                };
+            }
+            _ => (),
+        }
+    }
+
+    fn draw(&mut self, _: &mut State, layout: Rectangle, clip: Rectangle, style: &Stylesheet) -> Vec<Primitive<'a>> {
+        let mut result = Vec::new();
+        result.extend(style.background.render(layout));
+        let (thumb, _) = self.thumb(layout, style);
+        result.extend(self.thumb.draw(thumb, clip));
+        result
+    }
+}
+
+impl<'a, T: 'a, F: 'a + Send + Fn(f32) -> T> IntoNode<'a, T> for ScrollBar<'a, T, F> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
+
+impl Default for State {
+    fn default() -> State {
+        State {
+            inner: InnerState::Idle,
+            cursor: (0.0, 0.0),
+        }
+    }
+}
+
+/// Returns whether the direction is horizontal and whether it's reversed.
+fn axis(direction: Direction) -> (bool, bool) {
+    match direction {
+        Direction::LeftToRight => (true, false),
+        Direction::RightToLeft => (true, true),
+        Direction::TopToBottom => (false, false),
+        Direction::BottomToTop => (false, true),
+    }
+}
@@ -0,0 +1,215 @@
+use crate::draw::Primitive;
+use crate::event::{Event, Key};
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::{StyleState, Stylesheet};
+use crate::widget::{dummy::Dummy, Context, StateVec, Widget};
+
+/// A widget that wraps around a content widget and lets the user drag a selection rectangle over it.
+/// Dragging starts when the left mouse button is pressed on empty space, that is, on a part of the content that is
+/// not hit by any child widget. Drags that start on a child widget are left to that child.
+///
+/// While dragging, the area captures the cursor, so the drag continues even if the cursor leaves the area.
+/// When the button is released the selection rectangle is reported to the on_select callback, relative to the top left
+/// of the content and clipped to it's bounds. A click without dragging reports an empty rectangle, which can be used
+/// to clear the selection. Pressing escape cancels the drag.
+///
+/// The selection rectangle can be styled using the `selection` child widget of this widget.
+pub struct SelectionArea<'a, T, F> {
+    content: Option<Node<'a, T>>,
+    selection: Node<'a, T>,
+    on_select: F,
+}
+
+/// State for [`SelectionArea`](struct.SelectionArea.html)
+#[derive(Default)]
+pub struct State {
+    cursor: (f32, f32),
+    drag: Option<(f32, f32)>,
+}
+
+impl<'a, T: 'a, F: 'a + Fn(Rectangle) -> T> SelectionArea<'a, T, F> {
+    /// Construct a new `SelectionArea` with content
+    pub fn new(content: impl IntoNode<'a, T>, on_select: F) -> Self {
+        Self {
+            content: Some(content.into_node()),
+            selection: Dummy::new("selection").into_node(),
+            on_select,
+        }
+    }
+
+    /// Sets the content widget from the first element of an iterator.
+    pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T>>(mut self, iter: I) -> Self {
+        if self.content.is_none() {
+            self.content = iter.into_iter().next().map(IntoNode::into_node);
+        }
+        self
+    }
+
+    /// Sets the on_select callback, which is called with the selection rectangle when a drag is finished.
+    pub fn on_select<N: Fn(Rectangle) -> T>(self, on_select: N) -> SelectionArea<'a, T, N> {
+        SelectionArea {
+            content: self.content,
+            selection: self.selection,
+            on_select,
+        }
+    }
+
+    fn content(&self) -> &Node<'a, T> {
+        self.content.as_ref().expect("content of `SelectionArea` must be set")
+    }
+
+    fn content_mut(&mut self) -> &mut Node<'a, T> {
+        self.content.as_mut().expect("content of `SelectionArea` must be set")
+    }
+
+    /// The selection rectangle in absolute coordinates, clipped to the content rect.
+    fn selection_rect(state: &State, content_rect: Rectangle) -> Option<Rectangle> {
+        state.drag.map(|(x, y)| {
+            let (cx, cy) = state.cursor;
+            let clamp_x = |x: f32| x.max(content_rect.left).min(content_rect.right);
+            let clamp_y = |y: f32| y.max(content_rect.top).min(content_rect.bottom);
+            Rectangle {
+                left: clamp_x(x.min(cx)),
+                top: clamp_y(y.min(cy)),
+                right: clamp_x(x.max(cx)),
+                bottom: clamp_y(y.max(cy)),
+            }
+        })
+    }
+}
+
+impl<'a, T: 'a> Default for SelectionArea<'a, T, fn(Rectangle) -> T> {
+    fn default() -> Self {
+        Self {
+            content: None,
+            selection: Dummy::new("selection").into_node(),
+            on_select: |_| panic!("on_select of `SelectionArea` must be set"),
+        }
+    }
+}
+
+impl<'a, T: 'a + Send, F: 'a + Send + Fn(Rectangle) -> T> Widget<'a, T> for SelectionArea<'a, T, F> {
+    type State = State;
+
+    fn mount(&self) -> State {
+        State::default()
+    }
+
+    fn widget(&self) -> &'static str {
+        "selection-area"
+    }
+
+    fn state(&self, state: &State) -> StateVec {
+        let mut result = StateVec::new();
+        if state.drag.is_some() {
+            result.push(StyleState::Pressed);
+        }
+        result
+    }
+
+    fn len(&self) -> usize {
+        2
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut **self.content_mut());
+        visitor(&mut *self.selection);
+    }
+
+    fn size(&self, _: &State, style: &Stylesheet) -> (Size, Size) {
+        style
+            .background
+            .resolve_size((style.width, style.height), self.content().size(), style.padding)
+    }
+
+    fn hit(&self, _: &State, layout: Rectangle, clip: Rectangle, _: &Stylesheet, x: f32, y: f32, _: bool) -> bool {
+        // empty space is part of the selection area, so anything inside the layout is a hit.
+        layout.point_inside(x, y) && clip.point_inside(x, y)
+    }
+
+    fn focused(&self, state: &State) -> bool {
+        state.drag.is_some() || self.content().focused()
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        let content_rect = style.background.content_rect(layout, style.padding);
+
+        if state.drag.is_some() {
+            match event {
+                Event::Cursor(x, y) => {
+                    state.cursor = (x, y);
+                    context.redraw();
+                }
+                Event::Release(Key::LeftMouseButton) => {
+                    let selection = Self::selection_rect(state, content_rect).unwrap();
+                    state.drag = None;
+                    context.redraw();
+                    context.push((self.on_select)(
+                        selection.translate(-content_rect.left, -content_rect.top),
+                    ));
+                }
+                Event::Press(Key::Escape) => {
+                    state.drag = None;
+                    context.redraw();
+                }
+                _ => (),
+            }
+            return;
+        }
+
+        if let Event::Cursor(x, y) = event {
+            state.cursor = (x, y);
+        }
+
+        let start_drag = match event {
+            Event::Press(Key::LeftMouseButton) => {
+                let (x, y) = state.cursor;
+                !self.content().focused()
+                    && layout.point_inside(x, y)
+                    && clip.point_inside(x, y)
+                    && !self.content().hit(content_rect, clip, x, y, true)
+            }
+            _ => false,
+        };
+
+        self.content_mut().event(content_rect, clip, event, context);
+
+        if start_drag {
+            state.drag = Some(state.cursor);
+            context.redraw();
+        }
+    }
+
+    fn draw(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+    ) -> Vec<Primitive<'a>> {
+        let content_rect = style.background.content_rect(layout, style.padding);
+
+        let mut result = Vec::new();
+        result.extend(style.background.render(layout));
+        result.extend(self.content_mut().draw(content_rect, clip));
+        if let Some(selection) = Self::selection_rect(state, content_rect) {
+            result.extend(self.selection.draw(selection, clip));
+        }
+        result
+    }
+}
+
+impl<'a, T: 'a + Send, F: 'a + Send + Fn(Rectangle) -> T> IntoNode<'a, T> for SelectionArea<'a, T, F> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
@@ -2,15 +2,17 @@ use crate::draw::*;
 use crate::event::{Event, Key};
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
-use crate::style::Stylesheet;
-use crate::widget::{dummy::Dummy, Context, Widget};
+use crate::style::{StyleState, Stylesheet};
+use crate::widget::{dummy::Dummy, Context, StateVec, Widget};
 
 /// Select a number using a sliding handle
 /// The handle can be styled using the `handle` child widget of this widget.
+/// The slider is `:hover` while the handle is hovered and `:pressed` while the handle is dragged.
 pub struct Slider<'a, T, F> {
     scrollbar: Node<'a, T>,
     min: f32,
     max: f32,
+    step: Option<f32>,
     value: f32,
     on_slide: F,
 }
@@ -36,6 +38,7 @@ impl<'a, T: 'a, F: 'a + Fn(f32) -> T> Slider<'a, T, F> {
             scrollbar: Dummy::new("handle").into_node(),
             min,
             max,
+            step: None,
             value: value.max(min).min(max),
             on_slide,
         }
@@ -57,7 +60,13 @@ impl<'a, T: 'a, F: 'a + Fn(f32) -> T> Slider<'a, T, F> {
 
     /// Sets the current value of the slider.
     pub fn val(mut self, value: f32) -> Self {
-        self.value = value.min(self.max).min(self.min);
+        self.value = value.max(self.min).min(self.max);
+        self
+    }
+
+    /// Sets the step size of the slider. When set, values picked by the user snap to `min + n * step`.
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = Some(step).filter(|&step| step > 0.0);
         self
     }
 
@@ -67,11 +76,19 @@ impl<'a, T: 'a, F: 'a + Fn(f32) -> T> Slider<'a, T, F> {
             scrollbar: self.scrollbar,
             min: self.min,
             max: self.max,
+            step: self.step,
             value: self.value,
             on_slide,
         }
     }
 
+    fn snap(&self, value: f32) -> f32 {
+        match self.step {
+            Some(step) => (self.min + ((value - self.min) / step).round() * step).min(self.max),
+            None => value,
+        }
+    }
+
     fn scrollbar(&self, layout: Rectangle, style: &Stylesheet) -> Rectangle {
         let content = style.background.content_rect(layout, style.padding);
 
@@ -80,6 +97,7 @@ impl<'a, T: 'a, F: 'a + Fn(f32) -> T> Slider<'a, T, F> {
             Size::Shrink => content.width() * 0.1,
             Size::Exact(x) => x,
             Size::Fill(_) => content.width() * 0.1,
+            relative @ (Size::Percent(_) | Size::Calc(..)) => relative.reserved_size(content.width()),
         };
 
         let mut t = (self.value - self.min) / (self.max - self.min);
@@ -99,6 +117,7 @@ impl<'a, T: 'a> Default for Slider<'a, T, fn(f32) -> T> {
             scrollbar: Dummy::new("handle").into_node(),
             min: 0.0,
             max: 1.0,
+            step: None,
             value: 0.0,
             on_slide: |_| panic!("on_slide of `Slider` must be set"),
         }
@@ -116,6 +135,16 @@ impl<'a, T: 'a, F: 'a + Send + Fn(f32) -> T> Widget<'a, T> for Slider<'a, T, F>
         "slider"
     }
 
+    fn state(&self, state: &State) -> StateVec {
+        let mut result = StateVec::new();
+        match state.inner {
+            InnerState::Hover => result.push(StyleState::Hover),
+            InnerState::Drag(_) => result.push(StyleState::Pressed),
+            InnerState::Idle => (),
+        }
+        result
+    }
+
     fn len(&self) -> usize {
         1
     }
@@ -130,6 +159,10 @@ impl<'a, T: 'a, F: 'a + Send + Fn(f32) -> T> Widget<'a, T> for Slider<'a, T, F>
             .resolve_size((style.width, style.height), self.scrollbar.size(), style.padding)
     }
 
+    fn focusable(&self, _: &State) -> bool {
+        true
+    }
+
     fn event(
         &mut self,
         state: &mut State,
@@ -153,22 +186,31 @@ impl<'a, T: 'a, F: 'a + Send + Fn(f32) -> T> Widget<'a, T> for Slider<'a, T, F>
                 let next_bar_left = (cx - x).max(begin).min(end);
                 let t = (next_bar_left - begin) / (end - begin);
 
-                self.value = self.min + t * (self.max - self.min);
-                context.push((self.on_slide)(self.value));
+                let value = self.snap(self.min + t * (self.max - self.min));
+                if value != self.value {
+                    self.value = value;
+                    context.push((self.on_slide)(self.value));
+                }
             }
             (Event::Cursor(x, y), _) => {
                 state.cursor_x = x;
                 state.cursor_y = y;
-                if bar.point_inside(x, y) && clip.point_inside(x, y) {
+                let hover = bar.point_inside(x, y) && clip.point_inside(x, y);
+                if hover != matches!(state.inner, InnerState::Hover) {
+                    context.redraw();
+                }
+                if hover {
                     state.inner = InnerState::Hover;
                 } else {
                     state.inner = InnerState::Idle;
                 }
             }
             (Event::Press(Key::LeftMouseButton), InnerState::Hover) => {
+                context.redraw();
                 state.inner = InnerState::Drag(state.cursor_x - bar.left);
             }
             (Event::Release(Key::LeftMouseButton), InnerState::Drag(_)) => {
+                context.redraw();
                 if bar.point_inside(state.cursor_x, state.cursor_y) && clip.point_inside(state.cursor_x, state.cursor_y)
                 {
                     state.inner = InnerState::Hover;
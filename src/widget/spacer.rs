@@ -17,6 +17,10 @@ impl<'a, T> Widget<'a, T> for Spacer {
         "spacer"
     }
 
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
     fn len(&self) -> usize {
         0
     }
@@ -0,0 +1,153 @@
+use std::time::{Duration, Instant};
+
+use crate::draw::Primitive;
+use crate::event::Event;
+use crate::graphics::{Playback, SpriteSheet};
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::Stylesheet;
+use crate::widget::{Context, Widget};
+
+/// A widget that plays the frames of a [`SpriteSheet`](../../graphics/struct.SpriteSheet.html).
+/// Frames are advanced on [`Event::Animate`](../../event/enum.Event.html#variant.Animate), based on the time that
+/// has passed since the animation started. If the sprite sheet has
+/// [`delays`](../../graphics/struct.SpriteSheet.html#method.with_delays) for it's frames, like animations that are
+/// loaded using [`Graphics::load_animation`](../../graphics/struct.Graphics.html#method.load_animation), they are
+/// used instead of the `fps`.
+pub struct SpriteAnimation<'a> {
+    sheet: Option<&'a SpriteSheet>,
+    fps: f32,
+    playback: Playback,
+}
+
+/// State for [`SpriteAnimation`](struct.SpriteAnimation.html)
+#[derive(Default)]
+pub struct State {
+    elapsed: Duration,
+    last_update: Option<Instant>,
+    frame: usize,
+}
+
+impl<'a> SpriteAnimation<'a> {
+    /// Construct a new `SpriteAnimation`
+    pub fn new(sheet: &'a SpriteSheet, fps: f32) -> Self {
+        Self {
+            sheet: Some(sheet),
+            fps,
+            playback: Playback::Loop,
+        }
+    }
+
+    /// Sets the sprite sheet to play.
+    pub fn sheet(mut self, sheet: &'a SpriteSheet) -> Self {
+        self.sheet = Some(sheet);
+        self
+    }
+
+    /// Sets the number of frames per second.
+    pub fn fps(mut self, fps: f32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Sets what happens after the last frame has been played. Defaults to `Playback::Loop`.
+    pub fn playback(mut self, playback: Playback) -> Self {
+        self.playback = playback;
+        self
+    }
+
+    /// Sets whether the animation starts over after the last frame, or stops at the last frame.
+    /// This is a shorthand for setting the [`playback`](#method.playback) to `Playback::Loop` or `Playback::Once`.
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.playback = if looping { Playback::Loop } else { Playback::Once };
+        self
+    }
+
+    fn content(&self) -> &'a SpriteSheet {
+        self.sheet.expect("sheet of `SpriteAnimation` must be set")
+    }
+}
+
+impl<'a> Default for SpriteAnimation<'a> {
+    fn default() -> Self {
+        Self {
+            sheet: None,
+            fps: 10.0,
+            playback: Playback::Loop,
+        }
+    }
+}
+
+impl<'a, T: 'a> Widget<'a, T> for SpriteAnimation<'a> {
+    type State = State;
+
+    fn mount(&self) -> State {
+        State::default()
+    }
+
+    fn widget(&self) -> &'static str {
+        "sprite-animation"
+    }
+
+    fn len(&self) -> usize {
+        0
+    }
+
+    fn visit_children(&mut self, _: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {}
+
+    fn size(&self, _: &State, style: &Stylesheet) -> (Size, Size) {
+        let frame = self.content().frames().first();
+        let width = match style.width {
+            Size::Shrink => Size::Exact(frame.map_or(0.0, |f| f.size.width())),
+            other => other,
+        };
+        let height = match style.height {
+            Size::Shrink => Size::Exact(frame.map_or(0.0, |f| f.size.height())),
+            other => other,
+        };
+        (width, height)
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        _: Rectangle,
+        _: Rectangle,
+        _: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        if let Event::Animate = event {
+            let sheet = self.content();
+            let now = Instant::now();
+            if let Some(last_update) = state.last_update.replace(now) {
+                state.elapsed += now - last_update;
+            }
+
+            let played = sheet.frames_played(state.elapsed, self.fps, self.playback);
+            let frame = self.playback.frame_index(played, sheet.len());
+            if frame != state.frame {
+                state.frame = frame;
+                context.redraw();
+            }
+            if !self.playback.finished(played, sheet.len()) {
+                context.animate();
+            }
+        }
+    }
+
+    fn draw(&mut self, state: &mut State, layout: Rectangle, _: Rectangle, style: &Stylesheet) -> Vec<Primitive<'a>> {
+        self.content()
+            .frames()
+            .get(state.frame)
+            .map(|frame| Primitive::DrawImage(frame.clone(), layout, style.color))
+            .into_iter()
+            .collect()
+    }
+}
+
+impl<'a, T: 'a> IntoNode<'a, T> for SpriteAnimation<'a> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use crate::draw::Primitive;
+use crate::draw::{Color, Primitive};
 use crate::event::Event;
 use crate::layout::{Rectangle, Size};
 use crate::node::{IntoNode, Node};
@@ -9,20 +9,41 @@ use crate::text;
 use crate::widget::*;
 
 /// Widget that renders a paragraph of text.
+///
+/// The text is rendered in the `color` of the stylesheet, unless it's built from [`spans`](#method.spans) that each
+/// have their own color, which is useful for syntax highlighting or mentions in a chat.
 #[derive(Default)]
 pub struct Text {
     text: String,
+    spans: Vec<(usize, Color)>,
 }
 
 impl Text {
     /// Constructs a new `Text`
     pub fn new<S: Into<String>>(text: S) -> Self {
-        Self { text: text.into() }
+        Self {
+            text: text.into(),
+            spans: Vec::new(),
+        }
     }
 
     /// Sets the text value.
     pub fn val(mut self, text: impl Into<String>) -> Self {
         self.text = text.into();
+        self.spans.clear();
+        self
+    }
+
+    /// Sets the text value to the concatenation of `spans`, where each span is rendered with it's own color.
+    pub fn spans<S: AsRef<str>>(mut self, spans: impl IntoIterator<Item = (S, Color)>) -> Self {
+        self.text.clear();
+        self.spans.clear();
+        let mut index = 0;
+        for (span, color) in spans {
+            self.spans.push((index, color));
+            index += span.as_ref().chars().count();
+            self.text.push_str(span.as_ref());
+        }
         self
     }
 }
@@ -36,6 +57,10 @@ impl<'a, T> Widget<'a, T> for Text {
         "text"
     }
 
+    fn cursor_response(&self, _: &()) -> CursorResponse {
+        CursorResponse::Ignore
+    }
+
     fn len(&self) -> usize {
         0
     }
@@ -43,34 +68,7 @@ impl<'a, T> Widget<'a, T> for Text {
     fn visit_children(&mut self, _: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {}
 
     fn size(&self, _: &(), style: &Stylesheet) -> (Size, Size) {
-        let width = style.width;
-        let height = style.height;
-        let text = text::Text {
-            text: Cow::Borrowed(self.text.as_str()),
-            font: style.font.clone(),
-            size: style.text_size,
-            border: style.text_border,
-            wrap: style.text_wrap,
-            color: style.color,
-        };
-        let content = match (width, height) {
-            (Size::Shrink, Size::Shrink) => {
-                let measured = text.measure(None);
-                (Size::Exact(measured.width()), Size::Exact(measured.height()))
-            }
-            (Size::Shrink, height) => {
-                let measured = text.measure(None);
-                (Size::Exact(measured.width()), height)
-            }
-            (Size::Exact(size), Size::Shrink) => {
-                let measured = text.measure(Some(Rectangle::from_wh(size, std::f32::INFINITY)));
-                (Size::Exact(size), Size::Exact(measured.height()))
-            }
-            (width, height) => (width, height),
-        };
-        style
-            .background
-            .resolve_size((style.width, style.height), content, style.padding)
+        paragraph_size(self.text.as_str(), style)
     }
 
     fn event(&mut self, _: &mut (), _: Rectangle, _: Rectangle, _: &Stylesheet, _: Event, _: &mut Context<T>) {}
@@ -80,12 +78,8 @@ impl<'a, T> Widget<'a, T> for Text {
         result.extend(style.background.render(layout));
         result.push(Primitive::DrawText(
             text::Text {
-                text: Cow::Owned(self.text.clone()),
-                font: style.font.clone(),
-                size: style.text_size,
-                border: style.text_border,
-                wrap: style.text_wrap,
-                color: style.color,
+                spans: self.spans.clone(),
+                ..paragraph(self.text.clone(), style)
             },
             style.background.content_rect(layout, style.padding),
         ));
@@ -104,3 +98,41 @@ impl<'a, T: 'a, S: 'a + Into<String>> IntoNode<'a, T> for S {
         Node::from_widget(Text::new(self))
     }
 }
+
+/// Returns the size of a widget that shows `text` as a paragraph using the stylesheet.
+pub(super) fn paragraph_size(text: &str, style: &Stylesheet) -> (Size, Size) {
+    let text = paragraph(text, style);
+    let content = match (style.width, style.height) {
+        (Size::Shrink, Size::Shrink) => {
+            let measured = text.measure(None);
+            (Size::Exact(measured.width()), Size::Exact(measured.height()))
+        }
+        (Size::Shrink, height) => {
+            let measured = text.measure(None);
+            (Size::Exact(measured.width()), height)
+        }
+        (Size::Exact(size), Size::Shrink) => {
+            let measured = text.measure_wrapped(size);
+            (Size::Exact(size), Size::Exact(measured.height()))
+        }
+        (width, height) => (width, height),
+    };
+    style
+        .background
+        .resolve_size((style.width, style.height), content, style.padding)
+}
+
+/// Returns the `Text` that shows `text` as a paragraph using the stylesheet.
+pub(super) fn paragraph<'a>(text: impl Into<Cow<'a, str>>, style: &Stylesheet) -> text::Text<'a> {
+    text::Text {
+        text: text.into(),
+        font: style.font.clone(),
+        size: style.text_size,
+        border: style.text_border,
+        wrap: style.text_wrap,
+        color: style.color,
+        spans: Vec::new(),
+        underline: style.text_decoration.underline,
+        strikethrough: style.text_decoration.strikethrough,
+    }
+}
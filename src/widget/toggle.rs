@@ -1,33 +1,46 @@
-use std::mem::replace;
-
-use smallvec::smallvec;
+use std::time::{Duration, Instant};
 
 use crate::draw::*;
-use crate::event::{Event, Key};
+use crate::event::Event;
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
-use crate::style::{StyleState, Stylesheet};
-use crate::widget::{Context, StateVec, Widget};
+use crate::style::Stylesheet;
+use crate::widget::{checkbox, dummy::Dummy, pseudo::PseudoElement, Context, CursorResponse, StateVec, Widget};
 
 /// State for [`Toggle`](struct.Toggle.html)
-#[allow(missing_docs)]
-pub enum State {
-    Idle,
-    Hover,
-    Pressed,
-    Disabled,
+pub struct State {
+    inner: checkbox::State,
+    progress: f32,
+    last_update: Option<Instant>,
 }
 
 /// A clickable button that toggles some `bool`.
-pub struct Toggle<T, F: Fn(bool) -> T> {
+/// The toggle supports the `::before` and `::after` pseudo elements, which can be used to draw a check mark.
+///
+/// The toggle also has a knob, which can be styled by selecting the child widget `knob`. The knob is placed at the left
+/// of the content rect when the toggle is off, and at the right when it's on. When the value changes, the knob slides
+/// to it's new position over the [`duration`](#method.duration). The knob has no size unless it's styled, so it can be
+/// left out by not styling it.
+pub struct Toggle<'a, T, F: Fn(bool) -> T> {
     checked: bool,
     on_toggle: F,
+    duration: Duration,
+    before: Node<'a, T>,
+    knob: Node<'a, T>,
+    after: Node<'a, T>,
 }
 
-impl<'a, T: 'a, F: 'a + Fn(bool) -> T> Toggle<T, F> {
+impl<'a, T: 'a, F: 'a + Fn(bool) -> T> Toggle<'a, T, F> {
     /// Constructs a new `Toggle`
-    pub fn new<C: IntoNode<'a, T> + 'a>(checked: bool, on_toggle: F) -> Self {
-        Self { checked, on_toggle }
+    pub fn new(checked: bool, on_toggle: F) -> Self {
+        Self {
+            checked,
+            on_toggle,
+            duration: Duration::from_millis(150),
+            before: PseudoElement::before().into_node(),
+            knob: Dummy::new("knob").into_node(),
+            after: PseudoElement::after().into_node(),
+        }
     }
 
     /// Sets the current toggle state of the `Toggle`.
@@ -36,29 +49,63 @@ impl<'a, T: 'a, F: 'a + Fn(bool) -> T> Toggle<T, F> {
         self
     }
 
+    /// Sets the time it takes for the knob to slide from one side to the other. Defaults to 150 milliseconds.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
     /// Sets the on_toggle callback for this `Toggle`, which is called when the toggle state changes.
-    pub fn on_toggle<N: Fn(bool) -> T>(self, on_toggle: N) -> Toggle<T, N> {
+    pub fn on_toggle<N: Fn(bool) -> T>(self, on_toggle: N) -> Toggle<'a, T, N> {
         Toggle {
             checked: self.checked,
             on_toggle,
+            duration: self.duration,
+            before: self.before,
+            knob: self.knob,
+            after: self.after,
+        }
+    }
+
+    fn target(&self) -> f32 {
+        if self.checked {
+            1.0
+        } else {
+            0.0
         }
     }
 }
 
-impl<'a, T: 'a> Default for Toggle<T, fn(bool) -> T> {
+impl<'a, T: 'a> Default for Toggle<'a, T, fn(bool) -> T> {
     fn default() -> Self {
         Self {
             checked: false,
             on_toggle: |_| panic!("on_toggle of `Toggle` must be set"),
+            duration: Duration::from_millis(150),
+            before: PseudoElement::before().into_node(),
+            knob: Dummy::new("knob").into_node(),
+            after: PseudoElement::after().into_node(),
         }
     }
 }
 
-impl<'a, T, F: Send + Fn(bool) -> T> Widget<'a, T> for Toggle<T, F> {
+impl State {
+    /// Returns the position of the knob, ranging from `0.0` when it's at the off position to `1.0` when it's at the on
+    /// position.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+}
+
+impl<'a, T: 'a, F: 'a + Send + Fn(bool) -> T> Widget<'a, T> for Toggle<'a, T, F> {
     type State = State;
 
     fn mount(&self) -> Self::State {
-        State::Idle
+        State {
+            inner: checkbox::State::Idle,
+            progress: self.target(),
+            last_update: None,
+        }
     }
 
     fn widget(&self) -> &'static str {
@@ -66,25 +113,18 @@ impl<'a, T, F: Send + Fn(bool) -> T> Widget<'a, T> for Toggle<T, F> {
     }
 
     fn state(&self, state: &State) -> StateVec {
-        let mut state = match state {
-            State::Idle => StateVec::new(),
-            State::Hover => smallvec![StyleState::Hover],
-            State::Pressed => smallvec![StyleState::Pressed],
-            State::Disabled => smallvec![StyleState::Disabled],
-        };
-
-        if self.checked {
-            state.push(StyleState::Checked);
-        }
-
-        state
+        state.inner.style(self.checked)
     }
 
     fn len(&self) -> usize {
-        0
+        3
     }
 
-    fn visit_children(&mut self, _: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {}
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut *self.before);
+        visitor(&mut *self.knob);
+        visitor(&mut *self.after);
+    }
 
     fn size(&self, _: &State, stylesheet: &Stylesheet) -> (Size, Size) {
         match stylesheet.background {
@@ -97,6 +137,14 @@ impl<'a, T, F: Send + Fn(bool) -> T> Widget<'a, T> for Toggle<T, F> {
         }
     }
 
+    fn focusable(&self, _: &State) -> bool {
+        true
+    }
+
+    fn cursor_response(&self, _: &State) -> CursorResponse {
+        CursorResponse::Hover
+    }
+
     fn event(
         &mut self,
         state: &mut State,
@@ -106,68 +154,66 @@ impl<'a, T, F: Send + Fn(bool) -> T> Widget<'a, T> for Toggle<T, F> {
         event: Event,
         context: &mut Context<T>,
     ) {
-        match event {
-            Event::Cursor(x, y) => {
-                *state = match replace(state, State::Idle) {
-                    State::Idle => {
-                        if layout.point_inside(x, y) && clip.point_inside(x, y) {
-                            context.redraw();
-                            State::Hover
-                        } else {
-                            State::Idle
-                        }
-                    }
-                    State::Hover => {
-                        if layout.point_inside(x, y) && clip.point_inside(x, y) {
-                            State::Hover
-                        } else {
-                            context.redraw();
-                            State::Idle
-                        }
-                    }
-                    State::Pressed => {
-                        if layout.point_inside(x, y) && clip.point_inside(x, y) {
-                            State::Pressed
-                        } else {
-                            context.redraw();
-                            State::Idle
-                        }
-                    }
-                    State::Disabled => State::Disabled,
-                };
-            }
+        if state.inner.event(layout, clip, &event, context) {
+            context.push((self.on_toggle)(!self.checked));
+        }
 
-            Event::Press(Key::LeftMouseButton) => {
-                *state = match replace(state, State::Idle) {
-                    State::Hover => {
-                        context.redraw();
-                        State::Pressed
-                    }
-                    other => other,
+        if let Event::Animate = event {
+            let target = self.target();
+            if state.progress != target {
+                let now = Instant::now();
+                let step = match state.last_update.replace(now) {
+                    _ if self.duration.is_zero() => 1.0,
+                    Some(last_update) => (now - last_update).as_secs_f32() / self.duration.as_secs_f32(),
+                    None => 0.0,
                 };
-            }
 
-            Event::Release(Key::LeftMouseButton) => {
-                *state = match replace(state, State::Idle) {
-                    State::Pressed => {
-                        context.redraw();
-                        context.push((self.on_toggle)(!self.checked));
-                        State::Hover
-                    }
-                    other => other,
-                };
+                if (target - state.progress).abs() <= step {
+                    state.progress = target;
+                    state.last_update = None;
+                } else {
+                    state.progress += step.copysign(target - state.progress);
+                    context.animate();
+                }
+                context.redraw();
             }
-
-            _ => (),
         }
     }
 
-    fn draw(&mut self, _: &mut State, layout: Rectangle, _: Rectangle, stylesheet: &Stylesheet) -> Vec<Primitive<'a>> {
-        stylesheet.background.render(layout).into_iter().collect()
+    fn draw(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        stylesheet: &Stylesheet,
+    ) -> Vec<Primitive<'a>> {
+        let before = PseudoElement::layout(&self.before, layout, stylesheet);
+        let after = PseudoElement::layout(&self.after, layout, stylesheet);
+
+        let content = stylesheet.background.content_rect(layout, stylesheet.padding);
+        let (width, height) = self.knob.size();
+        let width = width.resolve(content.width(), width.parts()).min(content.width());
+        let height = height.resolve(content.height(), height.parts()).min(content.height());
+
+        // ease in and out, so the knob doesn't start and stop abruptly.
+        let progress = state.progress * state.progress * (3.0 - 2.0 * state.progress);
+        let knob = Rectangle::from_xywh(
+            content.left + (content.width() - width) * progress,
+            content.top + (content.height() - height) * 0.5,
+            width,
+            height,
+        );
+
+        let mut result = Vec::new();
+        result.extend(stylesheet.background.render(layout));
+        result.extend(self.before.draw(before, clip));
+        result.extend(self.knob.draw(knob, clip));
+        result.extend(self.after.draw(after, clip));
+        result
     }
 }
 
-impl<'a, T: 'a + Send, F: 'a + Send + Fn(bool) -> T> IntoNode<'a, T> for Toggle<T, F> {
+impl<'a, T: 'a + Send, F: 'a + Send + Fn(bool) -> T> IntoNode<'a, T> for Toggle<'a, T, F> {
     fn into_node(self) -> Node<'a, T> {
         Node::from_widget(self)
     }
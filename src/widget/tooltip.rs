@@ -0,0 +1,243 @@
+use std::time::{Duration, Instant};
+
+use crate::draw::Primitive;
+use crate::event::Event;
+use crate::layout::{Rectangle, Size};
+use crate::node::{GenericNode, IntoNode, Node};
+use crate::style::Stylesheet;
+use crate::widget::{Context, Widget};
+
+/// A widget that wraps around a content widget, and shows a tooltip when the content is hovered for a while.
+///
+/// The tooltip appears near the cursor after the cursor rested on the content for the [`delay`](#method.delay), and
+/// moving the cursor over the content starts the delay over. The delay can also be styled using the `delay` property
+/// of the `tooltip`, which is used unless the delay is set using the method. The tooltip is dismissed when the cursor leaves the content or when a mouse button is pressed. It's drawn on a layer above the
+/// rest of the ui, so it's never covered by sibling widgets.
+///
+/// The `background` and `padding` of the `tooltip` are used for the box around the tooltip, while the content is drawn
+/// as is. In the `view!` macro, the first child is used as the content and the second child as the tooltip.
+pub struct Tooltip<'a, T> {
+    content: Option<Node<'a, T>>,
+    tip: Option<Node<'a, T>>,
+    delay: Option<Duration>,
+}
+
+/// State for [`Tooltip`](struct.Tooltip.html)
+pub struct State {
+    inner: InnerState,
+    cursor: (f32, f32),
+}
+
+#[derive(Clone, Copy)]
+enum InnerState {
+    Idle,
+    Waiting(Instant),
+    Visible(f32, f32),
+    Dismissed,
+}
+
+/// The offset of the tooltip from the cursor, so the tooltip isn't covered by the cursor.
+const CURSOR_OFFSET: (f32, f32) = (12.0, 16.0);
+
+/// The delay before the tooltip appears, if it's not set on the tooltip or in the style.
+const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+
+impl<'a, T: 'a> Tooltip<'a, T> {
+    /// Construct a new `Tooltip` that shows `tip` when `content` is hovered.
+    pub fn new(content: impl IntoNode<'a, T>, tip: impl IntoNode<'a, T>) -> Self {
+        Self {
+            content: Some(content.into_node()),
+            tip: Some(tip.into_node()),
+            delay: None,
+        }
+    }
+
+    /// Sets the widget that is shown in the tooltip.
+    pub fn tip(mut self, tip: impl IntoNode<'a, T>) -> Self {
+        self.tip = Some(tip.into_node());
+        self
+    }
+
+    /// Sets the time the cursor has to rest on the content before the tooltip appears. Defaults to the `delay` of the
+    /// style, or 500 milliseconds if the style has no delay.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Sets the content widget from the first element of an iterator, and the tooltip from the second element.
+    pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T>>(mut self, iter: I) -> Self {
+        let mut iter = iter.into_iter();
+        if self.content.is_none() {
+            self.content = iter.next().map(IntoNode::into_node);
+        }
+        if self.tip.is_none() {
+            self.tip = iter.next().map(IntoNode::into_node);
+        }
+        self
+    }
+
+    fn content(&self) -> &Node<'a, T> {
+        self.content.as_ref().expect("content of `Tooltip` must be set")
+    }
+
+    fn content_mut(&mut self) -> &mut Node<'a, T> {
+        self.content.as_mut().expect("content of `Tooltip` must be set")
+    }
+
+    fn tip_mut(&mut self) -> &mut Node<'a, T> {
+        self.tip.as_mut().expect("tip of `Tooltip` must be set")
+    }
+}
+
+impl<'a, T: 'a> Default for Tooltip<'a, T> {
+    fn default() -> Self {
+        Self {
+            content: None,
+            tip: None,
+            delay: None,
+        }
+    }
+}
+
+impl<'a, T: 'a> Widget<'a, T> for Tooltip<'a, T> {
+    type State = State;
+
+    fn mount(&self) -> State {
+        State::default()
+    }
+
+    fn widget(&self) -> &'static str {
+        "tooltip"
+    }
+
+    fn len(&self) -> usize {
+        2
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut **self.content_mut());
+        visitor(&mut **self.tip_mut());
+    }
+
+    fn size(&self, _: &State, _: &Stylesheet) -> (Size, Size) {
+        self.content().size()
+    }
+
+    fn hit(
+        &self,
+        _: &State,
+        layout: Rectangle,
+        clip: Rectangle,
+        _: &Stylesheet,
+        x: f32,
+        y: f32,
+        recursive: bool,
+    ) -> bool {
+        self.content().hit(layout, clip, x, y, recursive)
+    }
+
+    fn focused(&self, _: &State) -> bool {
+        self.content().focused()
+    }
+
+    fn event(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        match event {
+            Event::Cursor(x, y) => {
+                state.cursor = (x, y);
+                let inside = layout.point_inside(x, y) && clip.point_inside(x, y);
+                state.inner = match state.inner {
+                    // the delay starts over whenever the cursor moves, so it only runs out once the cursor rests.
+                    InnerState::Idle | InnerState::Waiting(_) if inside => {
+                        context.animate();
+                        InnerState::Waiting(Instant::now())
+                    }
+                    InnerState::Waiting(_) | InnerState::Dismissed if !inside => InnerState::Idle,
+                    InnerState::Visible(_, _) if !inside => {
+                        context.redraw();
+                        InnerState::Idle
+                    }
+                    other => other,
+                };
+            }
+
+            Event::Animate => {
+                if let InnerState::Waiting(since) = state.inner {
+                    if since.elapsed() >= self.delay.or(style.delay).unwrap_or(DEFAULT_DELAY) {
+                        context.redraw();
+                        state.inner = InnerState::Visible(state.cursor.0, state.cursor.1);
+                    } else {
+                        context.animate();
+                    }
+                }
+            }
+
+            Event::Press(_) => {
+                if let InnerState::Visible(_, _) = state.inner {
+                    context.redraw();
+                }
+                if !matches!(state.inner, InnerState::Idle) {
+                    state.inner = InnerState::Dismissed;
+                }
+            }
+
+            _ => (),
+        }
+
+        self.content_mut().event(layout, clip, event, context);
+    }
+
+    fn draw(
+        &mut self,
+        state: &mut State,
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+    ) -> Vec<Primitive<'a>> {
+        let mut result = self.content_mut().draw(layout, clip);
+
+        if let InnerState::Visible(x, y) = state.inner {
+            let (width, height) = self.tip_mut().size();
+            let content = Rectangle::from_xywh(
+                x + CURSOR_OFFSET.0 + style.padding.left,
+                y + CURSOR_OFFSET.1 + style.padding.top,
+                width.min_size(),
+                height.min_size(),
+            );
+            let bubble = style.background.layout_rect(content, style.padding);
+
+            // the tooltip is not clipped by the parent widgets, so it only clips to it's own box.
+            result.push(Primitive::LayerUp);
+            result.push(Primitive::PushClip(bubble));
+            result.extend(style.background.render(bubble));
+            result.extend(self.tip_mut().draw(content, bubble));
+            result.push(Primitive::PopClip);
+            result.push(Primitive::LayerDown);
+        }
+
+        result
+    }
+}
+
+impl<'a, T: 'a> IntoNode<'a, T> for Tooltip<'a, T> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            inner: InnerState::Idle,
+            cursor: (0.0, 0.0),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::draw::Primitive;
+use crate::layout::{Rectangle, Size};
+use crate::node::{IntoNode, Node};
+use crate::style::Stylesheet;
+use crate::widget::*;
+
+/// A widget that scales and pans it's content, for zoomable canvases.
+///
+/// The content is laid out in the content rect of the `Transform` as usual, after which it's scaled around the top
+/// left corner of the content rect and then moved by the translation. Cursor events and hit tests are transformed back
+/// to the coordinates of the content, so the content behaves as if it wasn't transformed. The content is clipped to
+/// the layout of the `Transform`.
+///
+/// The `Transform` doesn't keep track of panning and zooming itself, the scale and translation are usually stored in
+/// the state of a [`Component`](../../component/trait.Component.html) and updated from events.
+pub struct Transform<'a, T> {
+    content: Option<Node<'a, T>>,
+    scale: f32,
+    translate: (f32, f32),
+    drawn: Option<(Rectangle, (f32, f32))>,
+}
+
+impl<'a, T: 'a> Transform<'a, T> {
+    /// Construct a new `Transform` with content
+    pub fn new(content: impl IntoNode<'a, T>) -> Self {
+        Self {
+            content: Some(content.into_node()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the scale of the content. Values above `1.0` zoom in, values below `1.0` zoom out. Defaults to `1.0`.
+    /// The scale must be positive.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the translation of the content in logical pixels, which is applied after scaling. Defaults to `(0.0, 0.0)`.
+    pub fn translate(mut self, x: f32, y: f32) -> Self {
+        self.translate = (x, y);
+        self
+    }
+
+    /// Sets the content widget from the first element of an iterator.
+    pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T>>(mut self, iter: I) -> Self {
+        if self.content.is_none() {
+            self.content = iter.into_iter().next().map(IntoNode::into_node);
+        }
+        self
+    }
+
+    fn content(&self) -> &Node<'a, T> {
+        self.content.as_ref().expect("content of `Transform` must be set")
+    }
+
+    fn content_mut(&mut self) -> &mut Node<'a, T> {
+        self.content.as_mut().expect("content of `Transform` must be set")
+    }
+
+    /// The translation that scales around the top left corner of `content_rect` and then applies `self.translate`.
+    fn offset(&self, content_rect: Rectangle) -> (f32, f32) {
+        (
+            content_rect.left * (1.0 - self.scale) + self.translate.0,
+            content_rect.top * (1.0 - self.scale) + self.translate.1,
+        )
+    }
+
+    /// Transforms a point from the coordinates of the ui to the coordinates of the content.
+    fn inverse(&self, content_rect: Rectangle, x: f32, y: f32) -> (f32, f32) {
+        let (dx, dy) = self.offset(content_rect);
+        ((x - dx) / self.scale, (y - dy) / self.scale)
+    }
+
+    /// Transforms a clipping rectangle from the coordinates of the ui to the coordinates of the content.
+    fn inverse_clip(&self, content_rect: Rectangle, clip: Option<Rectangle>) -> Rectangle {
+        let (dx, dy) = self.offset(content_rect);
+        clip.map_or(Rectangle::zero(), |clip| {
+            clip.translate(-dx, -dy).scale(self.scale.recip())
+        })
+    }
+}
+
+impl<'a, T: 'a> Default for Transform<'a, T> {
+    fn default() -> Self {
+        Self {
+            content: None,
+            scale: 1.0,
+            translate: (0.0, 0.0),
+            drawn: None,
+        }
+    }
+}
+
+impl<'a, T: 'a> Widget<'a, T> for Transform<'a, T> {
+    type State = ();
+
+    fn mount(&self) {}
+
+    fn widget(&self) -> &'static str {
+        "transform"
+    }
+
+    fn len(&self) -> usize {
+        1
+    }
+
+    fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
+        visitor(&mut **self.content_mut());
+    }
+
+    fn size(&self, _: &(), style: &Stylesheet) -> (Size, Size) {
+        style
+            .background
+            .resolve_size((style.width, style.height), self.content().size(), style.padding)
+    }
+
+    fn hit(
+        &self,
+        _: &(),
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        x: f32,
+        y: f32,
+        recursive: bool,
+    ) -> bool {
+        if layout.point_inside(x, y) && clip.point_inside(x, y) {
+            if recursive && !style.background.is_solid() {
+                let content_rect = style.background.content_rect(layout, style.padding);
+                let (x, y) = self.inverse(content_rect, x, y);
+                self.content().hit(
+                    content_rect,
+                    self.inverse_clip(content_rect, clip.intersect(&layout)),
+                    x,
+                    y,
+                    recursive,
+                )
+            } else {
+                true
+            }
+        } else {
+            false
+        }
+    }
+
+    fn focused(&self, _: &()) -> bool {
+        self.content().focused()
+    }
+
+    fn event(
+        &mut self,
+        _: &mut (),
+        layout: Rectangle,
+        clip: Rectangle,
+        style: &Stylesheet,
+        event: Event,
+        context: &mut Context<T>,
+    ) {
+        let content_rect = style.background.content_rect(layout, style.padding);
+        let content_clip = self.inverse_clip(content_rect, clip.intersect(&layout));
+        let event = match event {
+            Event::Cursor(x, y) => {
+                let (x, y) = self.inverse(content_rect, x, y);
+                Event::Cursor(x, y)
+            }
+            other => other,
+        };
+
+        // widgets that read the cursor from the context should see it in the coordinates of the content too.
+        let (x, y) = context.cursor;
+        let cursor = std::mem::replace(&mut context.cursor, self.inverse(content_rect, x, y));
+        self.content_mut().event(content_rect, content_clip, event, context);
+        context.cursor = cursor;
+    }
+
+    fn portal_event(&mut self, _: &mut (), event: Event, context: &mut Context<T>) {
+        // portals are drawn with the transform of their parents, so the cursor is transformed the same way as in
+        // `event`.
+        if let Some((_, (dx, dy))) = self.drawn {
+            let inverse = |(x, y): (f32, f32)| ((x - dx) / self.scale, (y - dy) / self.scale);
+            let event = match event {
+                Event::Cursor(x, y) => {
+                    let (x, y) = inverse((x, y));
+                    Event::Cursor(x, y)
+                }
+                other => other,
+            };
+            let cursor = context.cursor;
+            context.cursor = inverse(cursor);
+            self.content_mut().portal_event(event, context);
+            context.cursor = cursor;
+        }
+    }
+
+    fn draw(&mut self, _: &mut (), layout: Rectangle, clip: Rectangle, style: &Stylesheet) -> Vec<Primitive<'a>> {
+        let content_rect = style.background.content_rect(layout, style.padding);
+        let (dx, dy) = self.offset(content_rect);
+
+        let mut result = Vec::new();
+        result.extend(style.background.render(layout));
+        if let Some(clip) = clip.intersect(&layout) {
+            let content_clip = self.inverse_clip(content_rect, Some(clip));
+            self.drawn = Some((clip, (dx, dy)));
+            result.push(Primitive::PushClip(clip));
+            result.push(Primitive::PushTransform(self.scale, (dx, dy)));
+            result.extend(self.content_mut().draw(content_rect, content_clip));
+            result.push(Primitive::PopTransform);
+            result.push(Primitive::PopClip);
+        }
+        result
+    }
+
+    fn drawn_rects(
+        &mut self,
+        _: &mut (),
+        focusable: &mut Vec<Rectangle>,
+        regions: &mut Vec<(Rectangle, CursorResponse)>,
+    ) {
+        if let Some((clip, (dx, dy))) = self.drawn {
+            // the content recorded it's rects in the coordinates of the content, so they need the same transform as
+            // the primitives of the content.
+            let scale = self.scale;
+            let transform = |rect: Rectangle| rect.scale(scale).translate(dx, dy).intersect(&clip);
+            let mut content_focusable = Vec::new();
+            let mut content_regions = Vec::new();
+            self.content_mut()
+                .drawn_rects(&mut content_focusable, &mut content_regions);
+            focusable.extend(content_focusable.into_iter().filter_map(transform));
+            regions.extend(
+                content_regions
+                    .into_iter()
+                    .filter_map(|(rect, response)| transform(rect).map(|rect| (rect, response))),
+            );
+        }
+    }
+}
+
+impl<'a, T: 'a> IntoNode<'a, T> for Transform<'a, T> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::from_widget(self)
+    }
+}
@@ -3,18 +3,34 @@ use crate::event::{Event, Key};
 use crate::layout::{Rectangle, Size};
 use crate::node::{GenericNode, IntoNode, Node};
 use crate::style::Stylesheet;
-use crate::widget::{Context, Widget};
+use crate::widget::{dummy::Dummy, Context, Widget};
 
 /// A window with a title and a content widget that can be moved by dragging the title.
+///
+/// The window is kept inside of the viewport while it's dragged, although the content may extend beyond the bottom of
+/// the viewport, as long as the title bar is visible. The window is drawn on a layer above it's siblings while it's
+/// focused or dragged.
+///
+/// If an [`on_close`](#method.on_close) message is set, a close button is shown at the right side of the title bar.
+/// Like a [`Button`](../button/struct.Button.html), the close button is clicked when it's released. The close button
+/// is a `close` child widget, which can be styled using the `window > close` selector.
+///
+/// A [`resizable`](#method.resizable) window can be resized by dragging it's edges or corners. The window can't be
+/// made smaller than the minimum size of the title and content.
 pub struct Window<'a, T> {
     title: Option<Node<'a, T>>,
     content: Option<Node<'a, T>>,
+    close: Option<Node<'a, T>>,
+    on_close: Option<T>,
+    resizable: bool,
 }
 
 /// State for [`Window`](struct.Window.html)
 pub struct State {
     x: f32,
     y: f32,
+    width: Option<f32>,
+    height: Option<f32>,
     cursor_x: f32,
     cursor_y: f32,
     inner: InnerState,
@@ -23,18 +39,53 @@ pub struct State {
 #[derive(Clone, Copy)]
 enum InnerState {
     Idle,
+    // the close button is pressed, the window is closed when it's released over the close button.
+    Closing,
     Dragging(f32, f32),
+    Resizing {
+        edges: Edges,
+        start: Rectangle,
+        anchor: (f32, f32),
+    },
 }
 
+/// The edges of a window that are moved while resizing it.
+#[derive(Clone, Copy)]
+struct Edges {
+    left: bool,
+    top: bool,
+    right: bool,
+    bottom: bool,
+}
+
+/// The distance in logical pixels from the edges of the window at which resizing starts.
+const RESIZE_MARGIN: f32 = 4.0;
+
 impl<'a, T: 'a> Window<'a, T> {
     /// Constructs a new `Window`
     pub fn new(title: impl IntoNode<'a, T>, content: impl IntoNode<'a, T>) -> Self {
         Self {
             title: Some(title.into_node()),
             content: Some(content.into_node()),
+            close: None,
+            on_close: None,
+            resizable: false,
         }
     }
 
+    /// Sets whether the window can be resized by dragging it's edges. Defaults to `false`.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Sets the message to post when the close button of the window is clicked, which shows the close button.
+    pub fn on_close(mut self, message: T) -> Self {
+        self.close = Some(Dummy::new("close").into_node());
+        self.on_close = Some(message);
+        self
+    }
+
     /// Sets the title bar widget from the first element of the iterator.
     /// Sets the content widget from the second element of the iterator.
     pub fn extend<I: IntoIterator<Item = N>, N: IntoNode<'a, T>>(mut self, iter: I) -> Self {
@@ -48,10 +99,21 @@ impl<'a, T: 'a> Window<'a, T> {
         self
     }
 
-    fn layout(&self, state: &State, viewport: Rectangle, style: &Stylesheet) -> (Rectangle, Rectangle, Rectangle) {
+    /// Returns the layout of the window, followed by the layout of the title, the content and the close button.
+    fn layout(
+        &self,
+        state: &State,
+        viewport: Rectangle,
+        style: &Stylesheet,
+    ) -> (Rectangle, Rectangle, Rectangle, Rectangle) {
         let title_size = self.title().size();
-        let title_width = title_size.0.min_size();
-        let title_height = title_size.1.min_size();
+        let close_size = self
+            .close
+            .as_ref()
+            .map_or((Size::Exact(0.0), Size::Exact(0.0)), |close| close.size());
+        let close_width = close_size.0.min_size();
+        let title_width = title_size.0.min_size() + close_width;
+        let title_height = title_size.1.min_size().max(close_size.1.min_size());
         let content_size = self.content().size();
         let content_width = content_size.0.min_size();
         let content_height = content_size.1.min_size();
@@ -64,24 +126,38 @@ impl<'a, T: 'a> Window<'a, T> {
             top: padding.top + style.padding.top,
             bottom: padding.bottom + style.padding.bottom,
         };
+        // a resized window can't be smaller than the minimum size of it's title and content.
+        let min_width = width + padding.left + padding.right;
+        let min_height = height + padding.top + padding.bottom;
         let layout = Rectangle::from_xywh(
             viewport.left + state.x,
             viewport.top + state.y,
-            width + padding.left + padding.right,
-            height + padding.top + padding.bottom,
+            state.width.map_or(min_width, |width| width.max(min_width)),
+            state.height.map_or(min_height, |height| height.max(min_height)),
         );
         let title_content = layout.after_padding(padding);
         let title = Rectangle::from_xywh(
             title_content.left,
             title_content.top,
-            title_size.0.resolve(title_content.width(), title_size.0.parts()),
+            title_size
+                .0
+                .resolve(title_content.width() - close_width, title_size.0.parts()),
             title_height,
         );
+        let close = Rectangle::from_xywh(
+            title_content.right - close_width,
+            title_content.top,
+            close_width,
+            close_size.1.min_size(),
+        );
         let content = Rectangle::from_xywh(
             title_content.left,
             title_content.top + title_height,
             content_size.0.resolve(title_content.width(), content_size.0.parts()),
-            content_height,
+            content_size
+                .1
+                .resolve(title_content.height() - title_height, content_size.1.parts())
+                .max(content_height),
         );
         let align = |rect: Rectangle| {
             rect.translate(
@@ -91,7 +167,7 @@ impl<'a, T: 'a> Window<'a, T> {
                 0.0,
             )
         };
-        (layout, align(title), align(content))
+        (layout, align(title), align(content), close)
     }
 
     fn content(&self) -> &Node<'a, T> {
@@ -116,11 +192,30 @@ impl<'a, T: 'a> Default for Window<'a, T> {
         Self {
             title: None,
             content: None,
+            close: None,
+            on_close: None,
+            resizable: false,
         }
     }
 }
 
-impl<'a, T: 'a> Widget<'a, T> for Window<'a, T> {
+impl Edges {
+    /// Returns the edges of `layout` that are within resizing distance of the cursor, if any.
+    fn at(layout: Rectangle, x: f32, y: f32) -> Option<Self> {
+        if !layout.point_inside(x, y) {
+            return None;
+        }
+        let edges = Edges {
+            left: x < layout.left + RESIZE_MARGIN,
+            top: y < layout.top + RESIZE_MARGIN,
+            right: x >= layout.right - RESIZE_MARGIN,
+            bottom: y >= layout.bottom - RESIZE_MARGIN,
+        };
+        Some(edges).filter(|edges| edges.left || edges.top || edges.right || edges.bottom)
+    }
+}
+
+impl<'a, T: 'a + Send> Widget<'a, T> for Window<'a, T> {
     type State = State;
 
     fn mount(&self) -> Self::State {
@@ -132,12 +227,19 @@ impl<'a, T: 'a> Widget<'a, T> for Window<'a, T> {
     }
 
     fn len(&self) -> usize {
-        2
+        if self.close.is_some() {
+            3
+        } else {
+            2
+        }
     }
 
     fn visit_children(&mut self, visitor: &mut dyn FnMut(&mut dyn GenericNode<'a, T>)) {
         visitor(&mut **self.title_mut());
         visitor(&mut **self.content_mut());
+        if let Some(close) = self.close.as_mut() {
+            visitor(&mut **close);
+        }
     }
 
     fn size(&self, _: &State, _: &Stylesheet) -> (Size, Size) {
@@ -146,7 +248,7 @@ impl<'a, T: 'a> Widget<'a, T> for Window<'a, T> {
 
     fn hit(&self, state: &State, viewport: Rectangle, clip: Rectangle, style: &Stylesheet, x: f32, y: f32, _recursive: bool) -> bool {
         if clip.point_inside(x, y) {
-            let (layout, _, _) = self.layout(state, viewport, style);
+            let (layout, _, _, _) = self.layout(state, viewport, style);
             layout.point_inside(x, y)
         } else {
             false
@@ -166,7 +268,7 @@ impl<'a, T: 'a> Widget<'a, T> for Window<'a, T> {
         event: Event,
         context: &mut Context<T>,
     ) {
-        let (layout, title, content) = self.layout(&*state, viewport, style);
+        let (layout, title, content, close) = self.layout(&*state, viewport, style);
 
         if self.title().focused() {
             self.title_mut().event(title, clip, event, context);
@@ -178,14 +280,28 @@ impl<'a, T: 'a> Widget<'a, T> for Window<'a, T> {
             return;
         }
 
-        match (event, state.inner) {
-            (Event::Cursor(x, y), InnerState::Idle) => {
+        match (event.clone(), state.inner) {
+            (Event::Cursor(x, y), InnerState::Idle | InnerState::Closing) => {
                 state.cursor_x = x;
                 state.cursor_y = y;
             }
 
             (Event::Press(Key::LeftMouseButton), InnerState::Idle) => {
-                if clip.point_inside(state.cursor_x, state.cursor_y)
+                if self.close.is_some()
+                    && clip.point_inside(state.cursor_x, state.cursor_y)
+                    && close.point_inside(state.cursor_x, state.cursor_y)
+                {
+                    state.inner = InnerState::Closing;
+                } else if let Some(edges) = Edges::at(layout, state.cursor_x, state.cursor_y)
+                    .filter(|_| self.resizable && clip.point_inside(state.cursor_x, state.cursor_y))
+                {
+                    context.redraw();
+                    state.inner = InnerState::Resizing {
+                        edges,
+                        start: layout,
+                        anchor: (state.cursor_x, state.cursor_y),
+                    };
+                } else if clip.point_inside(state.cursor_x, state.cursor_y)
                     && title.point_inside(state.cursor_x, state.cursor_y)
                 {
                     context.redraw();
@@ -197,18 +313,68 @@ impl<'a, T: 'a> Widget<'a, T> for Window<'a, T> {
                 context.redraw();
                 state.cursor_x = x;
                 state.cursor_y = y;
+                // only the title bar has to stay inside of the viewport vertically, so the window can be moved down
+                // to reveal what's behind it.
+                let title_bar_height = title.bottom - layout.top;
                 state.x = (x - anchor_x).max(0.0).min(viewport.width() - layout.width());
-                state.y = (y - anchor_y).max(0.0).min(viewport.height() - layout.height());
+                state.y = (y - anchor_y).max(0.0).min(viewport.height() - title_bar_height);
+            }
+
+            (Event::Cursor(x, y), InnerState::Resizing { edges, start, anchor }) => {
+                context.redraw();
+                state.cursor_x = x;
+                state.cursor_y = y;
+                let dx = x - anchor.0;
+                let dy = y - anchor.1;
+                // the edges that are dragged are kept inside of the viewport. The minimum size is enforced by
+                // `layout`, so the edges that are dragged towards the opposite edge stop at the minimum size.
+                let mut rect = start;
+                if edges.left {
+                    rect.left = (start.left + dx).max(viewport.left).min(start.right);
+                }
+                if edges.top {
+                    rect.top = (start.top + dy).max(viewport.top).min(start.bottom);
+                }
+                if edges.right {
+                    rect.right = (start.right + dx).min(viewport.right).max(start.left);
+                }
+                if edges.bottom {
+                    rect.bottom = (start.bottom + dy).min(viewport.bottom).max(start.top);
+                }
+                state.width = Some(rect.width());
+                state.height = Some(rect.height());
+
+                // when the left or top edge is dragged past the minimum size, the opposite edge stays in place.
+                let (resized, _, _, _) = self.layout(&*state, viewport, style);
+                state.x = if edges.left {
+                    start.right - resized.width()
+                } else {
+                    start.left
+                } - viewport.left;
+                state.y = if edges.top {
+                    start.bottom - resized.height()
+                } else {
+                    start.top
+                } - viewport.top;
             }
 
-            (Event::Release(Key::LeftMouseButton), InnerState::Dragging(_, _)) => {
+            (Event::Release(Key::LeftMouseButton), InnerState::Closing) => {
+                state.inner = InnerState::Idle;
+                if clip.point_inside(state.cursor_x, state.cursor_y)
+                    && close.point_inside(state.cursor_x, state.cursor_y)
+                {
+                    context.extend(self.on_close.take());
+                }
+            }
+
+            (Event::Release(Key::LeftMouseButton), InnerState::Dragging(_, _) | InnerState::Resizing { .. }) => {
                 state.inner = InnerState::Idle;
             }
 
             _ => (),
         }
 
-        self.title_mut().event(title, clip, event, context);
+        self.title_mut().event(title, clip, event.clone(), context);
         self.content_mut().event(content, clip, event, context);
     }
 
@@ -219,17 +385,27 @@ impl<'a, T: 'a> Widget<'a, T> for Window<'a, T> {
         clip: Rectangle,
         style: &Stylesheet,
     ) -> Vec<Primitive<'a>> {
-        let (layout, title, content) = self.layout(&*state, viewport, style);
+        let (layout, title, content, close) = self.layout(&*state, viewport, style);
+        let raised = self.focused(state) || matches!(state.inner, InnerState::Dragging(_, _));
 
         let mut result = Vec::new();
+        if raised {
+            result.push(Primitive::LayerUp);
+        }
         result.extend(style.background.render(layout));
         result.extend(self.title_mut().draw(title, clip));
         result.extend(self.content_mut().draw(content, clip));
+        if let Some(close_button) = self.close.as_mut() {
+            result.extend(close_button.draw(close, clip));
+        }
+        if raised {
+            result.push(Primitive::LayerDown);
+        }
         result
     }
 }
 
-impl<'a, T: 'a> IntoNode<'a, T> for Window<'a, T> {
+impl<'a, T: 'a + Send> IntoNode<'a, T> for Window<'a, T> {
     fn into_node(self) -> Node<'a, T> {
         Node::from_widget(self)
     }
@@ -240,6 +416,8 @@ impl Default for State {
         Self {
             x: 0.0,
             y: 0.0,
+            width: None,
+            height: None,
             cursor_x: 0.0,
             cursor_y: 0.0,
             inner: InnerState::Idle,